@@ -3,11 +3,16 @@
  * Verifies that entries are correctly sorted by date for display.
  */
 import { describe, it, expect } from 'vitest';
-import type { ChronologyEntry } from '../types/ai';
+import type { ChronoDate, ChronologyEntry } from '../types/ai';
+import { chronoDateInput, compareChronoDates, formatChronoDate } from '../lib/chronoDate';
 
-// Replicate the sort logic from ChronologyView
+// Same sort as ChronologyView
 function sortChronology(entries: ChronologyEntry[]): ChronologyEntry[] {
-    return [...entries].sort((a, b) => a.date.localeCompare(b.date));
+    return [...entries].sort((a, b) => compareChronoDates(a.date, b.date));
+}
+
+function structured(precision: ChronoDate['precision'], start: string | null, end: string | null, text: string | null = null): ChronoDate {
+    return { precision, start, end, time: null, timezone: null, text };
 }
 
 describe('chronology sorting', () => {
@@ -90,4 +95,35 @@ describe('chronology sorting', () => {
         expect(entries[0].description).toBe('Later');  // Original unchanged
         expect(sorted[0].description).toBe('Earlier'); // Sorted copy
     });
+
+    it('puts a day before the month or year that contains it, and undated entries last', () => {
+        const entries: ChronologyEntry[] = [
+            { id: '1', date: structured('approximate', null, null, 'sometime'), description: 'Undated', source: 'manual', significance: 'key' },
+            { id: '2', date: structured('year', '2024-01-01', '2024-12-31'), description: 'Year', source: 'manual', significance: 'key' },
+            { id: '3', date: structured('month', '2024-01-01', '2024-01-31'), description: 'Month', source: 'manual', significance: 'key' },
+            { id: '4', date: structured('day', '2024-01-01', '2024-01-01'), description: 'Day', source: 'manual', significance: 'key' },
+        ];
+
+        const sorted = sortChronology(entries);
+
+        expect(sorted.map((e) => e.description)).toEqual(['Day', 'Month', 'Year', 'Undated']);
+    });
+});
+
+describe('chronology date display', () => {
+    it('formats each precision', () => {
+        expect(formatChronoDate('2024-03-05')).toBe('5 Mar 2024');
+        expect(formatChronoDate('2024-09')).toBe('Sep 2024');
+        expect(formatChronoDate(structured('range', '2024-03-05', '2024-03-09'))).toBe('5–9 Mar 2024');
+        expect(formatChronoDate(structured('range', '2024-03-28', '2024-04-02'))).toBe('28 Mar – 2 Apr 2024');
+        expect(formatChronoDate(structured('approximate', '2022-09-01', '2022-12-31', 'late 2022'))).toBe('late 2022');
+        expect(formatChronoDate(structured('approximate', '2024-03-05', '2024-03-05'))).toBe('c. 5 Mar 2024');
+    });
+
+    it('round-trips to an editable string', () => {
+        expect(chronoDateInput(structured('month', '2024-09-01', '2024-09-30'))).toBe('2024-09');
+        expect(chronoDateInput(structured('range', '2024-03-05', '2024-03-09'))).toBe('2024-03-05/2024-03-09');
+        expect(chronoDateInput({ ...structured('day', '2024-03-05', '2024-03-05'), time: '14:30', timezone: 'Europe/London' }))
+            .toBe('2024-03-05 14:30 Europe/London');
+    });
 });
@@ -23,7 +23,7 @@ const MOCK_CASE: CaseMetadata = {
     product_service_type: 'goods',
     issues: ['faulty_goods'],
     desired_outcome: ['refund'],
-    claim_value: 850,
+    claim_value: 85000,
     date_of_purchase: '2024-06-15',
     date_problem_discovered: '2024-07-01',
     date_first_complained: '2024-07-05',
@@ -1,8 +1,9 @@
 import { useState, useEffect } from 'react';
 import { Link } from 'react-router-dom';
 import { useCaseStore } from '../../hooks/useCase';
-import { saveAiCall } from '../../lib/tauri-commands';
-import type { AiCallType, AssembledContext, AiCallRecord, ParsedMeritsResponse, DraftingInstructions } from '../../types/ai';
+import { checkAiResponse, listAiKeys, previewAiRequest, redactTexts, sendAiRequest } from '../../lib/tauri-commands';
+import { formatChronoDate } from '../../lib/chronoDate';
+import type { AiCallType, AssembledContext, AiCallRecord, AiProvider, AiRequest, AiStreamEvent, ParsedMeritsResponse, DraftingInstructions, PreparedRequest, RedactionLog } from '../../types/ai';
 import type { UserRole } from '../../types/case';
 
 /* ─── Analysis types, filtered by user role ─── */
@@ -78,7 +79,7 @@ Case: ${ctx.parties.claimant.name} v ${ctx.parties.defendant.name} (${ctx.partie
 Case type: ${ctx.caseType === 'cra_goods' ? 'Consumer Rights Act 2015 — Goods' : 'Consumer Rights Act 2015 — Services'}
 
 Timeline:
-${ctx.timeline.map((e) => `- ${e.date}: ${e.description}`).join('\n')}
+${ctx.timeline.map((e) => `- ${formatChronoDate(e.date)}: ${e.description}`).join('\n')}
 
 Documents provided:
 ${ctx.selectedDocuments
@@ -218,7 +219,12 @@ Insert [USER TO CONFIRM: ...] for any facts not in the documents. Output only th
 
 export default function AiReviewPanel() {
     const [savedMsg, setSavedMsg] = useState('');
-    const hasApiKey = !!localStorage.getItem('casekit_api_key');
+    /* Which providers have a key saved in the backend */
+    const [storedKeys, setStoredKeys] = useState<AiProvider[]>([]);
+    useEffect(() => {
+        listAiKeys().then((keys) => setStoredKeys(keys.map((k) => k.provider))).catch(() => setStoredKeys([]));
+    }, []);
+    const hasApiKey = storedKeys.length > 0;
     const cases = useCaseStore((s) => s.cases);
     const currentCase = useCaseStore((s) => s.currentCase);
     const selectCase = useCaseStore((s) => s.selectCase);
@@ -243,6 +249,10 @@ export default function AiReviewPanel() {
     const [selectedModel, setSelectedModel] = useState(defaultModel);
     const [result, setResult] = useState<AiCallRecord | null>(null);
     const [error, setError] = useState<string | null>(null);
+    /* The exact request awaiting confirmation, and the reply as it streams in */
+    const [preview, setPreview] = useState<{ request: AiRequest; prepared: PreparedRequest } | null>(null);
+    const [streamText, setStreamText] = useState('');
+    const [checking, setChecking] = useState(false);
 
     /* Ensure documents are loaded when panel mounts */
     const loadDocuments = useCaseStore((s) => s.loadDocuments);
@@ -254,6 +264,15 @@ export default function AiReviewPanel() {
 
     /* Editable document texts — initialised with auto-redaction applied */
     const [editedTexts, setEditedTexts] = useState<Record<string, string>>({});
+    /* The auto-redacted texts, for "Re-run auto-redact", and the backend's record of what it removed */
+    const [autoTexts, setAutoTexts] = useState<Record<string, string>>({});
+    const [redactionLog, setRedactionLog] = useState<RedactionLog | null>(null);
+    /* Nothing is sent while the backend redaction is running, or after it has
+       failed unless the user accepts the built-in patterns alone */
+    const [redactionPending, setRedactionPending] = useState(false);
+    const [redactionError, setRedactionError] = useState<string | null>(null);
+    const [basicRedactionAccepted, setBasicRedactionAccepted] = useState(false);
+    const redactionBlocked = redactionPending || (redactionError !== null && !basicRedactionAccepted);
     const [expandedDoc, setExpandedDoc] = useState<string | null>(null);
 
     /* Drafting instruction fields */
@@ -266,6 +285,12 @@ export default function AiReviewPanel() {
     const [instExactAmount, setInstExactAmount] = useState('');
     const [instInterest, setInstInterest] = useState(false);
 
+    /* A preview is only good for the inputs it was built from */
+    useEffect(() => {
+        setPreview(null);
+    }, [editedTexts, selectedDocs, selectedModel, selectedType, userQuestion, instRemedySought, instDeadline,
+        instKeyPoints, instTone, instPriorOffers, instAdditionalContext, instExactAmount, instInterest]);
+
     const assembleContext = (): AssembledContext => {
         const instructions: DraftingInstructions | undefined =
             selectedType && NEEDS_INSTRUCTIONS.includes(selectedType)
@@ -303,105 +328,59 @@ export default function AiReviewPanel() {
         };
     };
 
+    /* Build the exact request and show it before anything is sent */
     const handleAnalyse = async () => {
-        if (!selectedType) return;
-        setLoading(true);
+        if (!selectedType || !currentCase || redactionBlocked) return;
         setError(null);
-
-        const ctx = assembleContext();
-        const prompt = buildPrompt(selectedType, ctx);
-        const apiKey = localStorage.getItem('casekit_api_key');
-        const provider = localStorage.getItem('casekit_api_provider') || 'anthropic';
+        setPreview(null);
+
+        const chosenModel = MODEL_OPTIONS.find((m) => m.id === selectedModel);
+        const request: AiRequest = {
+            provider: (chosenModel?.provider || storedProvider) as AiProvider,
+            model: selectedModel,
+            prompt: buildPrompt(selectedType, assembleContext()),
+            // Force JSON output for merits assessment, where the model supports it
+            json: selectedType === 'merits_assessment',
+        };
 
         try {
-            let responseText = '';
-            let model = '';
-            let inputTokens = 0;
-            let outputTokens = 0;
-
-            const chosenModel = MODEL_OPTIONS.find((m) => m.id === selectedModel);
-            const chosenProvider = chosenModel?.provider || provider;
-
-            if (chosenProvider === 'anthropic') {
-                const { default: Anthropic } = await import('@anthropic-ai/sdk');
-                const client = new Anthropic({ apiKey: apiKey!, dangerouslyAllowBrowser: true });
-                model = selectedModel;
-                const response = await client.messages.create({
-                    model,
-                    max_tokens: 16000,
-                    messages: [{ role: 'user', content: prompt }],
-                });
-                // Opus 4.6+ may return thinking blocks before text blocks.
-                // Extract text from ALL text-type content blocks, skipping thinking blocks.
-                responseText = response.content
-                    .filter((block: { type: string }) => block.type === 'text')
-                    .map((block: { type: string; text?: string }) => block.text || '')
-                    .join('\n\n');
-                inputTokens = response.usage.input_tokens;
-                outputTokens = response.usage.output_tokens;
-            } else if (chosenProvider === 'openai') {
-                model = selectedModel;
-                const isReasoning = model.startsWith('o3') || model.startsWith('o4');
-                const body: Record<string, unknown> = {
-                    model,
-                    // Reasoning models (o3, o4-mini) require max_completion_tokens; GPT models use max_tokens
-                    ...(isReasoning
-                        ? { max_completion_tokens: 16000 }
-                        : { max_tokens: 16000 }),
-                    messages: [{ role: 'user', content: prompt }],
-                };
-                // Force JSON output for merits assessment (not supported on reasoning models)
-                if (selectedType === 'merits_assessment' && !isReasoning) {
-                    body.response_format = { type: 'json_object' };
-                }
-                const res = await fetch('https://api.openai.com/v1/chat/completions', {
-                    method: 'POST',
-                    headers: { 'Content-Type': 'application/json', 'Authorization': `Bearer ${apiKey}` },
-                    body: JSON.stringify(body),
-                });
-                if (!res.ok) { const err = await res.text(); throw new Error(`OpenAI error: ${err}`); }
-                const data = await res.json();
-                responseText = data.choices?.[0]?.message?.content || '';
-                inputTokens = data.usage?.prompt_tokens || 0;
-                outputTokens = data.usage?.completion_tokens || 0;
-            } else if (chosenProvider === 'gemini') {
-                model = selectedModel;
-                const genConfig: Record<string, unknown> = { maxOutputTokens: 16000 };
-                // Force JSON output for merits assessment
-                if (selectedType === 'merits_assessment') {
-                    genConfig.responseMimeType = 'application/json';
-                }
-                // Gemini 2.5+ and 3 models have thinking enabled by default.
-                // Set thinkingConfig to avoid thinking tokens eating into output token limit.
-                const thinkingConfig: Record<string, unknown> = { thinkingBudget: 0 };
-                const res = await fetch(
-                    `https://generativelanguage.googleapis.com/v1beta/models/${model}:generateContent?key=${apiKey}`,
-                    {
-                        method: 'POST',
-                        headers: { 'Content-Type': 'application/json' },
-                        body: JSON.stringify({
-                            contents: [{ parts: [{ text: prompt }] }],
-                            generationConfig: genConfig,
-                            thinkingConfig,
-                        }),
-                    },
-                );
-                if (!res.ok) { const err = await res.text(); throw new Error(`Gemini error: ${err}`); }
-                const data = await res.json();
-                // Gemini may return multiple parts including thinking parts.
-                // Extract only non-thought text parts.
-                const parts = data.candidates?.[0]?.content?.parts || [];
-                responseText = parts
-                    .filter((p: { thought?: boolean; text?: string }) => !p.thought && p.text)
-                    .map((p: { text: string }) => p.text)
-                    .join('\n\n') || '';
-                inputTokens = data.usageMetadata?.promptTokenCount || 0;
-                outputTokens = data.usageMetadata?.candidatesTokenCount || 0;
-            } else {
-                setError(`Unknown provider: ${chosenProvider}. Please select a model from the dropdown.`);
-                setLoading(false);
+            const prepared = await previewAiRequest(currentCase.name, request, redactionLog?.map_id);
+            if (!prepared.key_stored) {
+                setError(`No API key saved for ${prepared.provider}. Add one in API Key Setup.`);
                 return;
             }
+            setPreview({ request, prepared });
+        } catch (e) {
+            setError(String(e));
+        }
+    };
+
+    /* Send the previewed request; the backend refuses it if the payload has changed */
+    const handleSend = async () => {
+        if (!selectedType || !preview || !currentCase || redactionBlocked) return;
+        setLoading(true);
+        setError(null);
+        setStreamText('');
+
+        const streamId = crypto.randomUUID();
+        const { listen } = await import('@tauri-apps/api/event');
+        const unlisten = await listen<AiStreamEvent>('ai-stream', (event) => {
+            if (event.payload.stream_id === streamId && event.payload.type === 'delta') {
+                const text = event.payload.text;
+                setStreamText((t) => t + text);
+            }
+        });
+
+        try {
+            const saved = await sendAiRequest(
+                currentCase.name,
+                preview.request,
+                selectedType,
+                streamId,
+                preview.prepared.payload_hash,
+                redactionLog?.map_id,
+            );
+            const responseText = saved.response;
 
             let parsedResponse: ParsedMeritsResponse | undefined;
             if (selectedType === 'merits_assessment') {
@@ -437,50 +416,42 @@ export default function AiReviewPanel() {
                 }
             }
 
-            const record: AiCallRecord = {
-                id: `ai-${Date.now()}`,
+            setResult({
+                id: saved.id,
                 callType: selectedType,
-                timestamp: new Date().toISOString(),
-                inputTokens,
-                outputTokens,
-                model,
-                response: responseText,
+                timestamp: saved.timestamp,
+                inputTokens: saved.inputTokens,
+                outputTokens: saved.outputTokens,
+                model: saved.model,
+                response: saved.response,
                 parsedResponse,
-            };
-            setResult(record);
-
-            // Persist to ai-history.json
-            if (currentCase) {
-                saveAiCall(currentCase.name, {
-                    id: record.id,
-                    callType: record.callType,
-                    timestamp: record.timestamp,
-                    inputTokens: record.inputTokens,
-                    outputTokens: record.outputTokens,
-                    model: record.model,
-                    response: record.response,
-                    summary: parsedResponse?.factualSummary?.slice(0, 200) || record.response.slice(0, 200),
-                }).then(() => {
-                    setSavedMsg(`Saved to ${currentCase.name}/ai-history.json`);
-                    setTimeout(() => setSavedMsg(''), 5000);
-                }).catch(() => {
-                    setSavedMsg('⚠ Could not save — check case folder permissions.');
-                    setTimeout(() => setSavedMsg(''), 5000);
-                });
-            }
-        } catch (e: any) {
-            if (e.status === 401) {
-                setError('Authentication failed. Check your API key is correct.');
-            } else if (e.status === 429) {
-                setError('Rate limit reached. Please wait a moment and try again.');
-            } else if (e.status === 400) {
-                setError('Request error. Your documents may be too large. Try selecting fewer documents.');
-            } else {
-                setError(e.message || String(e));
-            }
+                verification: saved.verification,
+            });
+            setSavedMsg(`Saved to ${currentCase.name}/ai-history.json`);
+            setTimeout(() => setSavedMsg(''), 5000);
+            setShowConfirm(false);
+        } catch (e) {
+            setError(String(e));
         } finally {
+            unlisten();
             setLoading(false);
-            setShowConfirm(false);
+            setStreamText('');
+            setPreview(null);
+        }
+    };
+
+    /* Re-run the source check, e.g. after adding documents or chronology entries */
+    const handleRecheck = async () => {
+        if (!result || !currentCase) return;
+        setChecking(true);
+        try {
+            const verification = await checkAiResponse(currentCase.name, result.id);
+            setResult({ ...result, verification });
+        } catch (e) {
+            setSavedMsg(`⚠ Source check failed: ${e}`);
+            setTimeout(() => setSavedMsg(''), 5000);
+        } finally {
+            setChecking(false);
         }
     };
 
@@ -648,7 +619,7 @@ export default function AiReviewPanel() {
                     </Link>
                     <p style={{ color: 'var(--text-muted)', fontSize: '0.75rem', marginTop: '0.75rem' }}>
                         CaseKit has no internet connection by default. AI features send data only when you
-                        explicitly press "Analyse". Citation verification sends only citation strings to
+                        explicitly press "Send Now". Citation verification sends only citation strings to
                         BAILII/National Archives — never client data.
                     </p>
                 </div>
@@ -859,6 +830,60 @@ export default function AiReviewPanel() {
                         </div>
                     )}
 
+                    {/* Source check — is each statement backed by the case documents? */}
+                    <div className="card" style={{ fontSize: '0.8rem' }}>
+                        <div style={{ display: 'flex', alignItems: 'center', justifyContent: 'space-between', marginBottom: '0.5rem' }}>
+                            <p style={{ fontWeight: 600 }}>Source check</p>
+                            <button className="btn btn-secondary" onClick={handleRecheck} disabled={checking} style={{ fontSize: '0.75rem' }}>
+                                {checking ? 'Checking...' : result.verification ? 'Check again' : 'Check sources'}
+                            </button>
+                        </div>
+                        {result.verification ? (() => {
+                            const v = result.verification;
+                            const count = (status: string) => v.claims.filter((c) => c.status === status).length;
+                            return (
+                                <>
+                                    <div style={{ display: 'flex', gap: '1rem', flexWrap: 'wrap', color: 'var(--text-muted)', marginBottom: '0.5rem' }}>
+                                        <span>{count('supported')} supported</span>
+                                        <span>{count('partial')} partly supported</span>
+                                        <span style={{ color: count('no_source') ? '#991b1b' : undefined }}>{count('no_source')} with no source</span>
+                                        <span>{count('legal')} statements of law</span>
+                                        <span>{v.quotes.length} quotations</span>
+                                        <span>{v.dates.length} dates</span>
+                                        <span>{v.documents_checked} documents checked</span>
+                                    </div>
+                                    {(v.warnings ?? []).map((w, i) => (
+                                        <p key={i} style={{ color: '#92400e', marginBottom: '0.5rem' }}>{w}.</p>
+                                    ))}
+                                    {v.documents_checked === 0 && (
+                                        <p style={{ color: '#92400e', marginBottom: '0.5rem' }}>
+                                            No documents have extracted text, so nothing could be checked. Extract text from your documents first.
+                                        </p>
+                                    )}
+                                    {v.issues.length === 0 ? (
+                                        <p style={{ color: '#166534' }}>✓ Every statement, quotation and date was found in the case documents or chronology.</p>
+                                    ) : (
+                                        <ul style={{ paddingLeft: '1.25rem', display: 'flex', flexDirection: 'column', gap: '0.375rem' }}>
+                                            {v.issues.map((issue, i) => (
+                                                <li key={i}>
+                                                    <span style={{ fontWeight: 600, color: issue.kind === 'date_not_in_chronology' ? '#92400e' : '#991b1b' }}>
+                                                        {issue.kind === 'no_source' ? 'No source' : issue.kind === 'quote_not_found' ? 'Quote not found' : 'Date not in chronology'}:
+                                                    </span>{' '}
+                                                    {issue.kind === 'quote_not_found' ? `"${issue.text}"` : issue.kind === 'no_source' ? issue.text : issue.message}
+                                                </li>
+                                            ))}
+                                        </ul>
+                                    )}
+                                    <p style={{ fontSize: '0.7rem', color: '#94a3b8', marginTop: '0.5rem' }}>
+                                        Statements are matched to the documents by their wording, not their meaning. A flag means check it yourself, not that it is wrong.
+                                    </p>
+                                </>
+                            );
+                        })() : (
+                            <p style={{ color: 'var(--text-muted)' }}>This response has not been checked against the case documents.</p>
+                        )}
+                    </div>
+
                     {/* Usage & cost */}
                     <div style={{ fontSize: '0.75rem', color: 'var(--text-muted)', display: 'flex', gap: '1rem' }}>
                         <span>Model: {result.model}</span>
@@ -901,10 +926,28 @@ export default function AiReviewPanel() {
                                         setShowConfirm(true);
                                         setError(null);
                                         setSelectedDocs(new Set(documents.map(d => d.filename)));
-                                        // Initialise edited texts with auto-redaction
+                                        // Initialise edited texts with auto-redaction: the built-in
+                                        // patterns straight away, then the backend's fuller redaction
                                         const texts: Record<string, string> = {};
                                         documents.forEach(d => { texts[d.filename] = autoRedact(d.extracted_text || ''); });
                                         setEditedTexts(texts);
+                                        setAutoTexts(texts);
+                                        setRedactionLog(null);
+                                        setRedactionError(null);
+                                        setBasicRedactionAccepted(false);
+                                        if (currentCase) {
+                                            setRedactionPending(true);
+                                            redactTexts(currentCase.name, documents.map(d => d.extracted_text || ''))
+                                                .then((r) => {
+                                                    const redacted: Record<string, string> = {};
+                                                    documents.forEach((d, i) => { redacted[d.filename] = r.texts[i]; });
+                                                    setEditedTexts(redacted);
+                                                    setAutoTexts(redacted);
+                                                    setRedactionLog(r.log);
+                                                })
+                                                .catch((e) => setRedactionError(String(e)))
+                                                .finally(() => setRedactionPending(false));
+                                        }
                                         setExpandedDoc(null);
                                     }}
                                     style={{
@@ -943,7 +986,7 @@ export default function AiReviewPanel() {
                                             const isSelected = selectedDocs.has(d.filename);
                                             const text = editedTexts[d.filename] ?? d.extracted_text ?? '';
                                             const isExpanded = expandedDoc === d.filename;
-                                            const redactedCount = (text.match(/\[.+? REDACTED\]/g) || []).length;
+                                            const redactedCount = (text.match(/\[REDACTED [A-Z ]+ \d+\]|\[.+? REDACTED\]/g) || []).length;
                                             return (
                                                 <div key={d.filename} style={{
                                                     border: `1px solid ${isSelected ? '#86efac' : '#e2e8f0'}`,
@@ -996,7 +1039,7 @@ export default function AiReviewPanel() {
                                                             <div style={{ display: 'flex', gap: '0.5rem', marginBottom: '0.375rem' }}>
                                                                 <button
                                                                     type="button"
-                                                                    onClick={() => setEditedTexts(prev => ({ ...prev, [d.filename]: autoRedact(d.extracted_text || '') }))}
+                                                                    onClick={() => setEditedTexts(prev => ({ ...prev, [d.filename]: autoTexts[d.filename] ?? autoRedact(d.extracted_text || '') }))}
                                                                     style={{ fontSize: '0.7rem', padding: '2px 8px', border: '1px solid #e2e8f0', borderRadius: 3, background: '#fff7ed', cursor: 'pointer', color: '#9a3412' }}
                                                                 >
                                                                     Re-run auto-redact
@@ -1155,11 +1198,69 @@ export default function AiReviewPanel() {
                                 style={{ marginBottom: '0.75rem' }}
                             />
 
+                            {preview ? (
+                                <div style={{ border: '1px solid var(--border)', borderRadius: '0.375rem', padding: '0.75rem', marginBottom: '0.75rem', fontSize: '0.78rem' }}>
+                                    <p style={{ fontWeight: 600, marginBottom: '0.375rem' }}>Exactly what will be sent</p>
+                                    <p style={{ fontFamily: 'monospace', wordBreak: 'break-all' }}>{preview.prepared.method} {preview.prepared.url}</p>
+                                    {preview.prepared.headers.map(([name, value]) => (
+                                        <p key={name} style={{ fontFamily: 'monospace', color: 'var(--text-muted)' }}>{name}: {value}</p>
+                                    ))}
+                                    <div style={{ display: 'flex', gap: '1rem', flexWrap: 'wrap', margin: '0.375rem 0', color: 'var(--text-muted)' }}>
+                                        <span>{preview.prepared.payload_bytes.toLocaleString()} bytes</span>
+                                        <span>~{preview.prepared.estimated_input_tokens.toLocaleString()} tokens</span>
+                                        <span title={preview.prepared.payload_hash}>SHA-256 {preview.prepared.payload_hash.slice(0, 12)}…</span>
+                                    </div>
+                                    <details>
+                                        <summary style={{ cursor: 'pointer' }}>Show request body</summary>
+                                        <pre style={{ whiteSpace: 'pre-wrap', wordBreak: 'break-word', maxHeight: 320, overflow: 'auto', background: '#f8fafc', padding: '0.5rem', marginTop: '0.375rem' }}>
+                                            {JSON.stringify(preview.prepared.body, null, 2)}
+                                        </pre>
+                                    </details>
+                                </div>
+                            ) : null}
+
+                            {loading && (
+                                <div className="card" style={{ marginBottom: '0.75rem' }}>
+                                    <pre style={{ whiteSpace: 'pre-wrap', fontFamily: 'var(--font-body)', fontSize: '0.8rem', lineHeight: 1.5, margin: 0, maxHeight: 320, overflow: 'auto' }}>
+                                        {streamText || 'Waiting for the first words of the response...'}
+                                    </pre>
+                                </div>
+                            )}
+
+                            {redactionPending && (
+                                <p style={{ fontSize: '0.8rem', color: 'var(--text-muted)', marginBottom: '0.75rem' }}>
+                                    Redacting documents…
+                                </p>
+                            )}
+                            {redactionError && (
+                                <div style={{ marginBottom: '0.75rem', padding: '0.5rem 0.75rem', background: '#fef2f2', border: '1px solid #fca5a5', borderRadius: '0.375rem', fontSize: '0.8rem', color: '#991b1b' }}>
+                                    <p style={{ marginBottom: '0.375rem' }}>
+                                        Could not redact the documents: {redactionError}. Only the built-in patterns
+                                        (emails, phone numbers, bank details and similar) have been removed. Names, addresses
+                                        and this case's redaction terms may still be in the text.
+                                    </p>
+                                    <label style={{ display: 'flex', gap: '0.375rem', alignItems: 'center' }}>
+                                        <input
+                                            type="checkbox"
+                                            checked={basicRedactionAccepted}
+                                            onChange={(e) => setBasicRedactionAccepted(e.target.checked)}
+                                        />
+                                        I have checked the text and want to send it with the built-in patterns only
+                                    </label>
+                                </div>
+                            )}
+
                             <div style={{ display: 'flex', gap: '0.5rem' }}>
-                                <button className="btn btn-primary" onClick={handleAnalyse} disabled={loading || selectedDocs.size === 0}>
-                                    {loading ? 'Analysing...' : 'Analyse Now'}
-                                </button>
-                                <button className="btn btn-secondary" onClick={() => { setShowConfirm(false); setSelectedType(null); }}>
+                                {preview ? (
+                                    <button className="btn btn-primary" onClick={handleSend} disabled={loading || redactionBlocked}>
+                                        {loading ? 'Analysing...' : 'Send Now'}
+                                    </button>
+                                ) : (
+                                    <button className="btn btn-primary" onClick={handleAnalyse} disabled={loading || redactionBlocked || selectedDocs.size === 0}>
+                                        Review Request
+                                    </button>
+                                )}
+                                <button className="btn btn-secondary" disabled={loading} onClick={() => { setPreview(null); setShowConfirm(false); setSelectedType(null); }}>
                                     Cancel
                                 </button>
                             </div>
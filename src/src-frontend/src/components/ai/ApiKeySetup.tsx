@@ -1,5 +1,15 @@
-import { useState } from 'react';
+import { useEffect, useState } from 'react';
 import { Link } from 'react-router-dom';
+import {
+    hasCompaniesHouseKey,
+    listAiKeys,
+    removeAiKey,
+    removeCompaniesHouseKey,
+    setAiKey,
+    setCompaniesHouseKey,
+    testAiConnection,
+} from '../../lib/tauri-commands';
+import type { AiProvider } from '../../types/ai';
 
 const PROVIDERS = [
     {
@@ -48,28 +58,127 @@ const PROVIDERS = [
     },
 ];
 
+/** Small, cheap models used only to check a key works */
+const TEST_MODELS: Record<string, string> = {
+    anthropic: 'claude-sonnet-4-5-20250929',
+    openai: 'gpt-4o-mini',
+    gemini: 'gemini-2.5-flash',
+};
+
+/** The optional Companies House key, used for live checks on a defendant company */
+function CompaniesHouseKey() {
+    const [saved, setSaved] = useState(false);
+    const [key, setKey] = useState('');
+    const [message, setMessage] = useState<string | null>(null);
+
+    useEffect(() => {
+        hasCompaniesHouseKey()
+            .then(setSaved)
+            .catch((e) => setMessage(`✗ Could not read saved keys: ${e}`));
+    }, []);
+
+    const handleSave = async () => {
+        try {
+            await setCompaniesHouseKey(key.trim());
+            setSaved(true);
+            setKey('');
+            setMessage(null);
+        } catch (e) {
+            setMessage(`✗ Could not save key: ${e}`);
+        }
+    };
+
+    const handleRemove = async () => {
+        try {
+            await removeCompaniesHouseKey();
+            setSaved(false);
+            setMessage(null);
+        } catch (e) {
+            setMessage(`✗ Could not remove key: ${e}`);
+        }
+    };
+
+    return (
+        <div className="card" style={{ marginTop: '1rem' }}>
+            <h2 style={{ fontSize: '1rem', fontWeight: 600, marginBottom: '0.5rem' }}>Companies House (optional)</h2>
+            <p style={{ fontSize: '0.85rem', marginBottom: '0.75rem', color: 'var(--text-muted)' }}>
+                A free key from the Companies House developer hub lets CaseKit check a company on the live register
+                instead of a downloaded snapshot. Only the company name or number you look up is sent, to api.company-information.service.gov.uk.
+            </p>
+            {saved ? (
+                <div style={{ display: 'flex', gap: '0.5rem', alignItems: 'center' }}>
+                    <span className="badge badge-green">✓ Key Saved</span>
+                    <button className="btn btn-danger" onClick={handleRemove}>Remove Key</button>
+                </div>
+            ) : (
+                <div style={{ display: 'flex', gap: '0.5rem' }}>
+                    <input
+                        type="password"
+                        className="input"
+                        value={key}
+                        onChange={(e) => setKey(e.target.value)}
+                        aria-label="Companies House API key"
+                    />
+                    <button className="btn btn-primary" onClick={handleSave} disabled={!key.trim()}>
+                        Save Key
+                    </button>
+                </div>
+            )}
+            {message && <p style={{ fontSize: '0.85rem', marginTop: '0.5rem' }}>{message}</p>}
+        </div>
+    );
+}
+
 export default function ApiKeySetup() {
     const [apiKey, setApiKey] = useState('');
     const storedProvider = localStorage.getItem('casekit_api_provider') || 'anthropic';
     const [provider, setProvider] = useState(storedProvider);
-    const [saved, setSaved] = useState(!!localStorage.getItem('casekit_api_key'));
+    const [saved, setSaved] = useState(false);
     const [testing, setTesting] = useState(false);
     const [testResult, setTestResult] = useState<string | null>(null);
-    const [step, setStep] = useState(saved ? 0 : 1);
+    const [step, setStep] = useState(1);
 
     const currentProvider = PROVIDERS.find((p) => p.id === provider) || PROVIDERS[0];
 
-    const handleSave = () => {
+    // Keys are held by the backend. Move any key left in local storage by
+    // earlier versions across, then delete it from the webview.
+    useEffect(() => {
+        const legacyKey = localStorage.getItem('casekit_api_key');
+        const migrate = legacyKey
+            ? setAiKey(storedProvider as AiProvider, legacyKey).then(() => localStorage.removeItem('casekit_api_key'))
+            : Promise.resolve();
+        migrate
+            .then(() => listAiKeys())
+            .then((keys) => {
+                const has = keys.some((k) => k.provider === storedProvider);
+                setSaved(has);
+                setStep(has ? 0 : 1);
+            })
+            .catch((e) => setTestResult(`✗ Could not read saved keys: ${e}`));
+    }, [storedProvider]);
+
+    const handleSave = async () => {
         if (apiKey.trim()) {
-            localStorage.setItem('casekit_api_key', apiKey.trim());
-            localStorage.setItem('casekit_api_provider', provider);
-            setSaved(true);
-            setTestResult(null);
+            try {
+                await setAiKey(provider as AiProvider, apiKey.trim());
+                localStorage.setItem('casekit_api_provider', provider);
+                setSaved(true);
+                setApiKey('');
+                setTestResult(null);
+                setStep(0);
+            } catch (e) {
+                setTestResult(`✗ Could not save key: ${e}`);
+            }
         }
     };
 
-    const handleRemove = () => {
-        localStorage.removeItem('casekit_api_key');
+    const handleRemove = async () => {
+        try {
+            await removeAiKey(storedProvider as AiProvider);
+        } catch (e) {
+            setTestResult(`✗ Could not remove key: ${e}`);
+            return;
+        }
         localStorage.removeItem('casekit_api_provider');
         setSaved(false);
         setApiKey('');
@@ -78,8 +187,7 @@ export default function ApiKeySetup() {
     };
 
     const handleTest = async () => {
-        const key = localStorage.getItem('casekit_api_key');
-        if (!key) {
+        if (!saved) {
             setTestResult('No API key saved. Please enter and save your key first.');
             return;
         }
@@ -88,49 +196,10 @@ export default function ApiKeySetup() {
         setTestResult(null);
 
         try {
-            if (provider === 'anthropic') {
-                const { default: Anthropic } = await import('@anthropic-ai/sdk');
-                const client = new Anthropic({ apiKey: key, dangerouslyAllowBrowser: true });
-                const response = await client.messages.create({
-                    model: 'claude-sonnet-4-5-20250929',
-                    max_tokens: 100,
-                    messages: [{ role: 'user', content: 'Reply with exactly: "CaseKit connection successful."' }],
-                });
-                // Handle thinking blocks (Opus 4.6+) — extract text from text-type blocks
-                const textBlocks = response.content.filter((b: { type: string }) => b.type === 'text');
-                const text = textBlocks.length > 0 ? (textBlocks[0] as { type: string; text: string }).text : 'OK';
-                setTestResult(`✓ Connection successful: ${text}`);
-            } else if (provider === 'openai') {
-                const res = await fetch('https://api.openai.com/v1/models', {
-                    headers: { 'Authorization': `Bearer ${key}` },
-                });
-                if (res.ok) {
-                    setTestResult(`✓ Connection successful — OpenAI key is valid.`);
-                } else if (res.status === 401) {
-                    throw { status: 401 };
-                } else {
-                    setTestResult(`⚠ OpenAI responded with status ${res.status}. Key may still be valid.`);
-                }
-            } else if (provider === 'gemini') {
-                const res = await fetch(
-                    `https://generativelanguage.googleapis.com/v1beta/models?key=${key}`
-                );
-                if (res.ok) {
-                    setTestResult(`✓ Connection successful — Gemini key is valid.`);
-                } else if (res.status === 400 || res.status === 403) {
-                    throw { status: 401, message: 'Invalid API key' };
-                } else {
-                    setTestResult(`⚠ Google responded with status ${res.status}. Key may still be valid.`);
-                }
-            }
-        } catch (e: any) {
-            if (e.status === 401) {
-                setTestResult('✗ Authentication failed. Please check your API key is correct.');
-            } else if (e.status === 429) {
-                setTestResult('⚠ Rate limit reached. Your key works but you have hit the rate limit. Wait and try again.');
-            } else {
-                setTestResult(`✗ Error: ${e.message || String(e)}`);
-            }
+            const reply = await testAiConnection(provider as AiProvider, TEST_MODELS[provider]);
+            setTestResult(`✓ Connection successful: ${reply || 'OK'}`);
+        } catch (e) {
+            setTestResult(`✗ ${e}`);
         } finally {
             setTesting(false);
         }
@@ -155,7 +224,7 @@ export default function ApiKeySetup() {
                         </span>
                     </div>
                     <p style={{ fontSize: '0.85rem', marginBottom: '1rem' }}>
-                        Your API key is stored locally and ready to use.
+                        Your API key is saved in an encrypted file on this computer and ready to use.
                     </p>
                     <div style={{ display: 'flex', gap: '0.5rem', flexWrap: 'wrap' }}>
                         <button className="btn btn-primary" onClick={handleTest} disabled={testing}>
@@ -293,7 +362,7 @@ export default function ApiKeySetup() {
                             </div>
                         </div>
                         <p style={{ fontSize: '0.75rem', color: 'var(--text-muted)', marginTop: '0.5rem' }}>
-                            Your key is stored in your browser's local storage on this computer only. It is never written to any file or sent to any server other than the provider's API endpoint.
+                            Your key is saved in an encrypted file on this computer, outside the app window, and is only ever sent to the provider's API endpoint. Anyone with access to your user account on this computer could still recover it.
                         </p>
                     </div>
 
@@ -314,12 +383,14 @@ export default function ApiKeySetup() {
                             <strong>CaseKit has no internet connection by default.</strong> Your API key is only used
                             when you explicitly press "Analyse" on an AI feature. The only other network
                             activity is citation verification, which sends only citation strings (e.g. "[2020] UKSC 42")
-                            to BAILII and the National Archives — never any client data.
+                            to BAILII and the National Archives, and live Companies House checks, which send only a company name or number — never any client data.
                             There is no background connectivity, no analytics, and no data collection.
                         </div>
                     </div>
                 </div>
             )}
+
+            <CompaniesHouseKey />
         </div>
     );
 }
@@ -1,9 +1,9 @@
 import { useState, useEffect } from 'react';
 import { Link, useSearchParams } from 'react-router-dom';
 import { useCaseStore } from '../../hooks/useCase';
-import { loadAiHistory } from '../../lib/tauri-commands';
+import { checkLimitation, checkPartyCompany, loadAiHistory } from '../../lib/tauri-commands';
 import type { AiHistoryRecord } from '../../lib/tauri-commands';
-import type { UserRole } from '../../types/case';
+import type { CompanyCheck, LimitationReport, UserRole } from '../../types/case';
 import type { ParsedMeritsResponse } from '../../types/ai';
 
 const AI_TYPE_LABELS: Record<string, string> = {
@@ -33,6 +33,12 @@ export default function CaseOverview() {
     const [createError, setCreateError] = useState<string | null>(null);
     const [aiHistory, setAiHistory] = useState<AiHistoryRecord[]>([]);
     const [expandedAi, setExpandedAi] = useState<Set<string>>(new Set());
+    const [limitation, setLimitation] = useState<LimitationReport | null>(null);
+    const [companyNumber, setCompanyNumber] = useState('');
+    const [companyLive, setCompanyLive] = useState(false);
+    const [companyCheck, setCompanyCheck] = useState<CompanyCheck | null>(null);
+    const [companyError, setCompanyError] = useState<string | null>(null);
+    const [checkingCompany, setCheckingCompany] = useState(false);
 
     // Cases are loaded centrally in AppShell — no need to reload here
 
@@ -55,6 +61,14 @@ export default function CaseOverview() {
             .catch(() => setAiHistory([]));
     }, [currentCase?.name]);
 
+    // Recheck limitation whenever the case's dates change
+    useEffect(() => {
+        if (!currentCase) { setLimitation(null); return; }
+        checkLimitation(currentCase.name)
+            .then(setLimitation)
+            .catch(() => setLimitation(null));
+    }, [currentCase?.name, currentCase?.updated_at]);
+
     const handleCreate = async () => {
         if (!newName.trim() || !newClaimant.trim() || !newDefendant.trim()) {
             setCreateError('All fields are required.');
@@ -73,6 +87,11 @@ export default function CaseOverview() {
     };
 
     if (currentCase) {
+        // Cases from before parties were recorded have a single implied defendant
+        const defendantParty: { id: string; company_number?: string | null } | undefined =
+            currentCase.parties?.find((p) => p.role === 'defendant')
+            ?? (currentCase.defendant_name ? { id: 'defendant' } : undefined);
+        const registered = currentCase.parties?.find((p) => p.role === 'defendant')?.companies_house;
 
         const statusLabels: Record<string, string> = {
             intake: 'Case Intake',
@@ -122,7 +141,64 @@ export default function CaseOverview() {
                                 <span className="badge badge-grey" style={{ marginTop: '0.25rem', textTransform: 'capitalize' }}>
                                     {currentCase.defendant_type.replace('_', ' ')}
                                 </span>
+                                {registered && (
+                                    <span
+                                        className={`badge ${registered.status === 'active' ? 'badge-green' : registered.status === 'proposal_to_strike_off' ? 'badge-amber' : 'badge-red'}`}
+                                        style={{ marginTop: '0.25rem', marginLeft: 6 }}
+                                        title={`Companies House, checked ${registered.retrieved_at.slice(0, 10)}`}
+                                    >
+                                        {registered.company_number} · {registered.status_text || registered.status}
+                                    </span>
+                                )}
                             </div>
+                            {currentCase.defendant_type === 'company' && defendantParty && (
+                                <div>
+                                    <span style={{ fontSize: '0.75rem', color: 'var(--text-muted)' }}>Companies House</span>
+                                    <div style={{ display: 'flex', gap: '0.5rem', alignItems: 'center', flexWrap: 'wrap', marginTop: '0.25rem' }}>
+                                        <input
+                                            value={companyNumber}
+                                            onChange={(e) => setCompanyNumber(e.target.value)}
+                                            placeholder={defendantParty.company_number || 'Company number'}
+                                            style={{ width: 140, fontSize: '0.8rem' }}
+                                        />
+                                        <label style={{ fontSize: '0.75rem', display: 'flex', gap: 4, alignItems: 'center' }}>
+                                            <input type="checkbox" checked={companyLive} onChange={(e) => setCompanyLive(e.target.checked)} />
+                                            Live register
+                                        </label>
+                                        <button
+                                            className="btn btn-secondary"
+                                            style={{ fontSize: '0.75rem' }}
+                                            disabled={checkingCompany || !(companyNumber || defendantParty.company_number)}
+                                            onClick={async () => {
+                                                setCheckingCompany(true);
+                                                setCompanyError(null);
+                                                try {
+                                                    const check = await checkPartyCompany(
+                                                        currentCase.name,
+                                                        defendantParty.id,
+                                                        companyNumber || defendantParty.company_number || '',
+                                                        companyLive
+                                                    );
+                                                    setCompanyCheck(check);
+                                                    await selectCase(currentCase.name);
+                                                } catch (e) {
+                                                    setCompanyError(String(e));
+                                                } finally {
+                                                    setCheckingCompany(false);
+                                                }
+                                            }}
+                                        >
+                                            {checkingCompany ? 'Checking…' : 'Check'}
+                                        </button>
+                                    </div>
+                                    {companyError && <p style={{ fontSize: '0.75rem', color: 'var(--red)', marginTop: '0.25rem' }}>{companyError}</p>}
+                                    {companyCheck && (
+                                        <ul style={{ paddingLeft: '1.25rem', marginTop: '0.25rem', fontSize: '0.75rem', color: companyCheck.red_flag ? 'var(--red)' : 'var(--text-muted)' }}>
+                                            {companyCheck.warnings.map((w, i) => <li key={i}>{w}</li>)}
+                                        </ul>
+                                    )}
+                                </div>
+                            )}
                         </div>
                     </div>
 
@@ -136,7 +212,7 @@ export default function CaseOverview() {
                             </div>
                             <div>
                                 <span style={{ fontSize: '0.75rem', color: 'var(--text-muted)' }}>Claim Value</span>
-                                <p style={{ fontWeight: 500 }}>£{currentCase.claim_value.toLocaleString()}</p>
+                                <p style={{ fontWeight: 500 }}>£{(currentCase.claim_value / 100).toLocaleString('en-GB', { minimumFractionDigits: 2 })}</p>
                             </div>
                             <div>
                                 <span style={{ fontSize: '0.75rem', color: 'var(--text-muted)' }}>Risk Assessment</span>
@@ -155,6 +231,33 @@ export default function CaseOverview() {
                                             : 'Seek Advice'}
                                 </span>
                             </div>
+                            {limitation?.primary && (
+                                <div>
+                                    <span style={{ fontSize: '0.75rem', color: 'var(--text-muted)' }}>Limitation</span>
+                                    <p style={{ fontWeight: 500 }}>
+                                        <span
+                                            className={`badge ${limitation.red_flag
+                                                ? 'badge-red'
+                                                : limitation.status === 'warning'
+                                                    ? 'badge-amber'
+                                                    : 'badge-green'
+                                                }`}
+                                        >
+                                            {limitation.status === 'expired'
+                                                ? 'Expired'
+                                                : limitation.status === 'issued_in_time'
+                                                    ? 'Issued in time'
+                                                    : `${limitation.primary.days_remaining} days left`}
+                                        </span>
+                                    </p>
+                                    <span
+                                        style={{ fontSize: '0.75rem', color: 'var(--text-muted)' }}
+                                        title={limitation.assumptions.join('\n')}
+                                    >
+                                        Expires {limitation.primary.expiry_date} ({limitation.primary.section})
+                                    </span>
+                                </div>
+                            )}
                         </div>
                     </div>
 
@@ -2,7 +2,9 @@ import { useState } from 'react';
 import { invoke } from '@tauri-apps/api/core';
 import { useCaseStore } from '../../hooks/useCase';
 import { Link } from 'react-router-dom';
-import type { ChronologyEntry } from '../../types/ai';
+import type { ChronologyEntry, ChronologyIssue } from '../../types/ai';
+import { checkChronology } from '../../lib/tauri-commands';
+import { chronoDateInput, compareChronoDates, formatChronoDate } from '../../lib/chronoDate';
 
 /* ── colour tokens ── */
 const SIG_COLORS: Record<string, { dot: string; badge: string; badgeText: string }> = {
@@ -17,18 +19,6 @@ const CONF_DOT: Record<string, string> = {
     low: '#dc2626',
 };
 
-/* ── helpers ── */
-function formatDate(iso: string): string {
-    const parts = iso.split('-');
-    if (parts.length < 2) return iso;
-    const months = ['Jan', 'Feb', 'Mar', 'Apr', 'May', 'Jun', 'Jul', 'Aug', 'Sep', 'Oct', 'Nov', 'Dec'];
-    const y = parts[0];
-    const m = months[parseInt(parts[1], 10) - 1] || parts[1];
-    if (parts.length === 2) return `${m} ${y}`;
-    const d = parseInt(parts[2], 10);
-    return `${d} ${m} ${y}`;
-}
-
 const SOURCE_ICON: Record<string, string> = {
     manual: '✏️',
     document: '📄',
@@ -72,6 +62,10 @@ export default function ChronologyView() {
     const [addSig, setAddSig] = useState<'key' | 'supporting' | 'background'>('supporting');
     const [addingSaving, setAddingSaving] = useState(false);
 
+    // Consistency check state
+    const [checking, setChecking] = useState(false);
+    const [issues, setIssues] = useState<ChronologyIssue[] | null>(null);
+
     if (!currentCase) {
         return (
             <div className="page" style={{ textAlign: 'center', padding: '3rem' }}>
@@ -124,7 +118,7 @@ export default function ChronologyView() {
     /* ── Edit handlers ── */
     const startEdit = (entry: ChronologyEntry) => {
         setEditingId(entry.id);
-        setEditDate(entry.date);
+        setEditDate(chronoDateInput(entry.date));
         setEditDesc(entry.description);
         setEditSig(entry.significance);
         if (sourceTextId !== entry.id) {
@@ -190,8 +184,19 @@ export default function ChronologyView() {
         setAddingSaving(false);
     };
 
+    /* ── Consistency check ── */
+    const handleCheck = async () => {
+        setChecking(true);
+        try {
+            setIssues(await checkChronology(caseName));
+        } catch (e) {
+            console.error(e);
+        }
+        setChecking(false);
+    };
+
     /* ── Sort chronology by date ── */
-    const sortedChronology = [...chronology].sort((a, b) => a.date.localeCompare(b.date));
+    const sortedChronology = [...chronology].sort((a, b) => compareChronoDates(a.date, b.date));
 
     /* ── render ── */
     return (
@@ -217,8 +222,50 @@ export default function ChronologyView() {
                 >
                     {showAdd ? 'Cancel' : '+ Add Entry'}
                 </button>
+                <button className="btn btn-secondary" onClick={handleCheck} disabled={checking}>
+                    {checking ? 'Checking…' : 'Check Consistency'}
+                </button>
             </div>
 
+            {/* ── Consistency issues ── */}
+            {issues && (
+                <div className="card" style={{ marginBottom: 'var(--space-4)', borderLeft: '3px solid #d97706' }}>
+                    <div style={{ display: 'flex', justifyContent: 'space-between', alignItems: 'center', marginBottom: 'var(--space-2)' }}>
+                        <h3 style={{ fontSize: '0.95rem', margin: 0 }}>
+                            {issues.length === 0 ? 'No inconsistencies found' : `${issues.length} thing${issues.length === 1 ? '' : 's'} to check`}
+                        </h3>
+                        <button className="btn btn-secondary" style={{ fontSize: '0.8rem' }} onClick={() => setIssues(null)}>
+                            Dismiss
+                        </button>
+                    </div>
+                    {issues.map((issue, i) => (
+                        <div key={i} style={{ padding: '6px 0', borderTop: i > 0 ? '1px solid var(--border)' : 'none', fontSize: '0.8rem' }}>
+                            <span style={{
+                                width: 6, height: 6, borderRadius: '50%', display: 'inline-block', marginRight: 6,
+                                background: issue.severity === 'warning' ? '#d97706' : '#94a3b8',
+                            }} />
+                            {issue.message}
+                            <div style={{ display: 'flex', gap: 6, flexWrap: 'wrap', marginTop: 4 }}>
+                                {issue.entry_ids.map((id) => {
+                                    const entry = chronology.find((e) => e.id === id);
+                                    if (!entry) return null;
+                                    return (
+                                        <button
+                                            key={id}
+                                            className="badge badge-accent"
+                                            style={{ cursor: 'pointer', border: 'none' }}
+                                            onClick={() => startEdit(entry)}
+                                        >
+                                            {formatChronoDate(entry.date)}
+                                        </button>
+                                    );
+                                })}
+                            </div>
+                        </div>
+                    ))}
+                </div>
+            )}
+
             {/* ── Manual add form ── */}
             {showAdd && (
                 <div className="card" style={{ marginBottom: 'var(--space-4)', borderLeft: '3px solid var(--accent)' }}>
@@ -227,7 +274,8 @@ export default function ChronologyView() {
                         <div>
                             <label style={{ fontSize: '0.75rem', fontWeight: 500, color: 'var(--text-muted)', display: 'block', marginBottom: 2 }}>Date</label>
                             <input
-                                type="date"
+                                type="text"
+                                placeholder="e.g. 2024-03-05, March 2024, late 2022"
                                 value={addDate}
                                 onChange={(e) => setAddDate(e.target.value)}
                                 style={{
@@ -319,7 +367,7 @@ export default function ChronologyView() {
                                         />
                                         <div style={{ flex: 1, minWidth: 0 }}>
                                             <div style={{ display: 'flex', gap: 8, alignItems: 'center' }}>
-                                                <span style={{ fontWeight: 600, fontSize: '0.8rem' }}>{formatDate(entry.date)}</span>
+                                                <span style={{ fontWeight: 600, fontSize: '0.8rem' }}>{formatChronoDate(entry.date)}</span>
                                                 {entry.confidence && (
                                                     <span style={{
                                                         width: 6, height: 6, borderRadius: '50%',
@@ -332,6 +380,16 @@ export default function ChronologyView() {
                                                         from {entry.source_document_path.split(/[/\\]/).pop()}
                                                     </span>
                                                 )}
+                                                {entry.trigger && (
+                                                    <span style={{ fontSize: '0.75rem', color: 'var(--text-muted)' }} title="The phrase that identified this event">
+                                                        · matched “{entry.trigger}”
+                                                    </span>
+                                                )}
+                                                {entry.relative && (
+                                                    <span style={{ fontSize: '0.75rem', color: 'var(--text-muted)' }} title={entry.relative.reading}>
+                                                        · “{entry.relative.phrase}”, counted from {entry.relative.anchor_label} ({entry.relative.anchor})
+                                                    </span>
+                                                )}
                                             </div>
                                             <p style={{ fontSize: '0.8rem', color: '#475569', margin: '2px 0 0', lineHeight: 1.4 }}>
                                                 {entry.description}
@@ -391,8 +449,8 @@ export default function ChronologyView() {
 
                     {sortedChronology.map((entry, i) => {
                         const sig = SIG_COLORS[entry.significance] || SIG_COLORS.background;
-                        const prevDate = i > 0 ? sortedChronology[i - 1].date : null;
-                        const showDate = entry.date !== prevDate;
+                        const prevDate = i > 0 ? formatChronoDate(sortedChronology[i - 1].date) : null;
+                        const showDate = formatChronoDate(entry.date) !== prevDate;
                         const sourceDoc = entry.source_document_path?.split(/[/\\]/).pop();
                         const isEditing = editingId === entry.id;
                         const isShowingSource = sourceTextId === entry.id;
@@ -431,7 +489,7 @@ export default function ChronologyView() {
                                                 fontWeight: 600, fontSize: '0.8rem',
                                                 color: 'var(--text)', whiteSpace: 'nowrap',
                                             }}>
-                                                {formatDate(entry.date)}
+                                                {formatChronoDate(entry.date)}
                                             </span>
                                         )}
                                     </div>
@@ -520,7 +578,8 @@ export default function ChronologyView() {
                                             <div>
                                                 <label style={{ fontSize: '0.75rem', fontWeight: 500, color: 'var(--text-muted)', display: 'block', marginBottom: 2 }}>Date</label>
                                                 <input
-                                                    type="date"
+                                                    type="text"
+                                                    placeholder="e.g. 2024-03-05, March 2024, late 2022"
                                                     value={editDate}
                                                     onChange={(e) => setEditDate(e.target.value)}
                                                     style={{
@@ -8,6 +8,8 @@ interface DependencyStatus {
     required: boolean;
     install_url: string;
     description: string;
+    path: string | null;
+    languages: string[];
 }
 
 export default function SetupCheck() {
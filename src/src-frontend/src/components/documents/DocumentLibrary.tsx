@@ -3,6 +3,9 @@ import { invoke } from '@tauri-apps/api/core';
 import { open } from '@tauri-apps/plugin-dialog';
 import { Link } from 'react-router-dom';
 import { useCaseStore } from '../../hooks/useCase';
+import { copyFileToCase, linkDocumentParties, listParties, redactPdf } from '../../lib/tauri-commands';
+import type { DocumentParties, Party } from '../../types/case';
+import type { ExtractedContent, Job } from '../../types/document';
 
 interface DocumentEntry {
     id: string;
@@ -16,6 +19,8 @@ interface DocumentEntry {
     description: string;
     tags: string[];
     extracted_text: string | null;
+    /** An email's sent date from its headers */
+    metadata_date?: string | null;
     added_at: string;
 }
 
@@ -43,7 +48,18 @@ export default function DocumentLibrary() {
     const [dragOver, setDragOver] = useState(false);
     const [importProgress, setImportProgress] = useState<{ current: number; total: number } | null>(null);
     const [hasUnsavedText, setHasUnsavedText] = useState(false);
+    const [parties, setParties] = useState<Party[]>([]);
+    const [partyLinks, setPartyLinks] = useState<Record<string, DocumentParties>>({});
+    const [partyFilter, setPartyFilter] = useState('');
+    const [redactTerms, setRedactTerms] = useState('');
+    const [redacting, setRedacting] = useState(false);
+    const [redactResult, setRedactResult] = useState<string | null>(null);
+    const [extracting, setExtracting] = useState<Record<string, string>>({});
     const dropRef = useRef<HTMLDivElement>(null);
+    // Extraction jobs for imported files: job id -> document id, and jobs that
+    // finished before their document was saved
+    const extractionJobs = useRef(new Map<string, string>());
+    const finishedJobs = useRef(new Map<string, Job>());
 
     // Load documents when case changes
     useEffect(() => {
@@ -54,6 +70,24 @@ export default function DocumentLibrary() {
             .catch((e) => { setError(String(e)); setLoading(false); });
     }, [caseName]);
 
+    // Work out who each document is from and to, for the party filter
+    useEffect(() => {
+        if (!caseName) return;
+        Promise.all([listParties(caseName), linkDocumentParties(caseName)])
+            .then(([p, links]) => {
+                setParties(p);
+                setPartyLinks(Object.fromEntries(links.map((l) => [l.document_id, l])));
+            })
+            .catch(() => { setParties([]); setPartyLinks({}); });
+    }, [caseName, documents]);
+
+    const involvesParty = (doc: DocumentEntry) => {
+        if (!partyFilter) return true;
+        const links = partyLinks[doc.id];
+        return !!links && (links.from.includes(partyFilter) || links.to.includes(partyFilter));
+    };
+    const partyName = (id: string) => parties.find((p) => p.id === id)?.name ?? id;
+
     // Set up Tauri native drag-and-drop listener for file imports
     useEffect(() => {
         if (!caseName) return;
@@ -72,6 +106,72 @@ export default function DocumentLibrary() {
         return () => { if (unlisten) unlisten(); };
     }, [caseName, selectedFolder]);
 
+    // Follow background text extraction, filling in each document when it finishes
+    useEffect(() => {
+        if (!caseName) return;
+        let unlisten: (() => void) | undefined;
+
+        import('@tauri-apps/api/event').then(({ listen }) => {
+            listen<Job>('job-progress', (event) => {
+                const job = event.payload;
+                if (job.case_name !== caseName || job.kind.type !== 'extraction') return;
+
+                if (job.status === 'queued' || job.status === 'running') {
+                    setExtracting((prev) => ({ ...prev, [job.id]: job.message || `Waiting to extract ${job.kind.relative_path}` }));
+                    return;
+                }
+                setExtracting((prev) => {
+                    const next = { ...prev };
+                    delete next[job.id];
+                    return next;
+                });
+                const docId = extractionJobs.current.get(job.id);
+                if (docId) {
+                    extractionJobs.current.delete(job.id);
+                    applyExtraction(docId, job);
+                } else {
+                    finishedJobs.current.set(job.id, job);
+                }
+            }).then((fn) => { unlisten = fn; });
+        }).catch(() => {
+            // Not in Tauri environment — gracefully ignore
+        });
+
+        return () => { if (unlisten) unlisten(); };
+    }, [caseName]);
+
+    // Fill in the text and header details from a finished extraction job,
+    // keeping anything already entered
+    const applyExtraction = async (docId: string, job: Job) => {
+        if (job.status === 'failed') {
+            setError(`Could not extract text from ${job.kind.relative_path}: ${job.error}`);
+            return;
+        }
+        if (job.status !== 'completed' || !job.result) return;
+        const ext = job.result as ExtractedContent;
+
+        try {
+            const docs = await invoke<DocumentEntry[]>('load_documents_index', { caseName });
+            const doc = docs.find((d) => d.id === docId);
+            if (!doc) return;
+            const updated = await invoke<DocumentEntry[]>('update_document_metadata', {
+                caseName,
+                document: {
+                    ...doc,
+                    date: doc.date || ext.metadata_date || null,
+                    from: doc.from || ext.from || null,
+                    to: doc.to || ext.to || null,
+                    description: doc.description || ext.subject || '',
+                    extracted_text: doc.extracted_text || ext.text || null,
+                    metadata_date: doc.metadata_date || ext.metadata_date || null,
+                },
+            });
+            setDocuments(updated);
+        } catch (e) {
+            setError(`Could not save the extracted text: ${e}`);
+        }
+    };
+
     const docCount = documents.length;
     const showWarning = docCount >= DOC_LIMIT_WARN;
     const showStrongWarning = docCount >= DOC_LIMIT_STRONG;
@@ -87,12 +187,7 @@ export default function DocumentLibrary() {
             setImportProgress({ current: i + 1, total: filePaths.length });
 
             try {
-                const copyResult = await invoke<{ relative_path: string; extracted: { text: string; metadata_date: string | null; subject: string | null; from: string | null; to: string | null } | null }>('copy_file_to_case', {
-                    caseName,
-                    sourcePath: pathStr,
-                    folder: selectedFolder,
-                    filename,
-                });
+                const copyResult = await copyFileToCase(caseName, pathStr, selectedFolder, filename);
 
                 const ext = copyResult.extracted;
 
@@ -108,6 +203,7 @@ export default function DocumentLibrary() {
                     description: ext?.subject || '',
                     tags: [],
                     extracted_text: ext?.text || null,
+                    metadata_date: ext?.metadata_date || null,
                     added_at: new Date().toISOString(),
                 };
 
@@ -116,6 +212,18 @@ export default function DocumentLibrary() {
                     document: newDoc,
                 });
                 setDocuments(updated);
+
+                // The text is extracted in the background and filled in when ready
+                const jobId = copyResult.job_id;
+                if (jobId) {
+                    const finished = finishedJobs.current.get(jobId);
+                    if (finished) {
+                        finishedJobs.current.delete(jobId);
+                        await applyExtraction(newDoc.id, finished);
+                    } else {
+                        extractionJobs.current.set(jobId, newDoc.id);
+                    }
+                }
             } catch (e) {
                 setError(`Failed to import ${filename}: ${e}`);
             }
@@ -285,6 +393,13 @@ export default function DocumentLibrary() {
                     )}
                 </div>
 
+                {Object.keys(extracting).length > 0 && (
+                    <div style={{ marginTop: 'var(--space-2)', color: 'var(--text-muted)', fontSize: '0.85rem' }}>
+                        {Object.values(extracting).map((message, i) => (
+                            <div key={i}>{message}…</div>
+                        ))}
+                    </div>
+                )}
                 {error && (
                     <div style={{ marginTop: 'var(--space-2)', color: 'var(--red)', fontSize: '0.85rem' }}>
                         {error}
@@ -343,8 +458,34 @@ export default function DocumentLibrary() {
                 </div>
             ) : (
                 <div style={{ display: 'grid', gap: 'var(--space-2)' }}>
+                    {parties.length > 0 && (
+                        <div style={{ display: 'flex', gap: 'var(--space-3)', alignItems: 'center' }}>
+                            <label style={{ fontSize: '0.8rem', fontWeight: 500, color: 'var(--text-muted)' }}>
+                                Correspondence with:
+                            </label>
+                            <select
+                                value={partyFilter}
+                                onChange={(e) => setPartyFilter(e.target.value)}
+                                style={{
+                                    padding: 'var(--space-2) var(--space-3)',
+                                    borderRadius: 'var(--radius)',
+                                    border: '1px solid var(--border)',
+                                    fontSize: '0.875rem',
+                                    fontFamily: 'var(--font-sans)',
+                                    background: 'white',
+                                }}
+                            >
+                                <option value="">Anyone</option>
+                                {parties.map((p) => (
+                                    <option key={p.id} value={p.id}>
+                                        {p.name} ({p.role.replace('_', ' ')})
+                                    </option>
+                                ))}
+                            </select>
+                        </div>
+                    )}
                     {FOLDERS.map((folder) => {
-                        const folderDocs = documents.filter((d) => d.folder === folder.value);
+                        const folderDocs = documents.filter((d) => d.folder === folder.value && involvesParty(d));
                         if (folderDocs.length === 0) return null;
                         return (
                             <div key={folder.value}>
@@ -403,6 +544,13 @@ export default function DocumentLibrary() {
                                                 {doc.from && (
                                                     <div style={{ fontSize: '0.75rem', color: 'var(--text-muted)' }}>From: {doc.from}</div>
                                                 )}
+                                                {partyLinks[doc.id] && partyLinks[doc.id].from.length + partyLinks[doc.id].to.length > 0 && (
+                                                    <div style={{ fontSize: '0.75rem', color: 'var(--text-muted)' }}>
+                                                        {partyLinks[doc.id].from.map(partyName).join(', ') || 'Unknown'}
+                                                        {' → '}
+                                                        {partyLinks[doc.id].to.map(partyName).join(', ') || 'Unknown'}
+                                                    </div>
+                                                )}
                                             </div>
                                             <div style={{ display: 'flex', gap: 'var(--space-2)', alignItems: 'center' }}>
                                                 {doc.date && <span className="badge badge-accent">{doc.date}</span>}
@@ -498,6 +646,62 @@ export default function DocumentLibrary() {
                                                         Cancel
                                                     </button>
                                                 </div>
+
+                                                {doc.filename.toLowerCase().endsWith('.pdf') && (
+                                                    <div style={{ marginTop: 'var(--space-3)', borderTop: '1px solid var(--border)', paddingTop: 'var(--space-2)' }}>
+                                                        <label style={{ display: 'block', fontSize: '0.75rem', fontWeight: 500, color: 'var(--text-muted)', marginBottom: 'var(--space-1)' }}>
+                                                            Redacted copy for disclosure — names, numbers or phrases to remove, one per line
+                                                        </label>
+                                                        <textarea
+                                                            value={redactTerms}
+                                                            onChange={(e) => setRedactTerms(e.target.value)}
+                                                            rows={3}
+                                                            style={{
+                                                                width: '100%',
+                                                                fontSize: '0.8rem',
+                                                                padding: 'var(--space-2)',
+                                                                border: '1px solid var(--border)',
+                                                                borderRadius: 'var(--radius)',
+                                                                resize: 'vertical',
+                                                            }}
+                                                        />
+                                                        <div style={{ display: 'flex', gap: 'var(--space-2)', alignItems: 'center', marginTop: 'var(--space-2)' }}>
+                                                            <button
+                                                                className="btn btn-secondary"
+                                                                style={{ fontSize: '0.8rem', padding: '0.375rem 0.75rem' }}
+                                                                disabled={redacting || !redactTerms.trim()}
+                                                                onClick={async () => {
+                                                                    setRedacting(true);
+                                                                    setRedactResult(null);
+                                                                    try {
+                                                                        const result = await redactPdf(caseName, doc.path, {
+                                                                            terms: redactTerms.split('\n').map((t) => t.trim()).filter(Boolean),
+                                                                        });
+                                                                        setDocuments(result.documents);
+                                                                        const found = result.report.term_hits
+                                                                            .map((h) => `${h.term}: ${h.occurrences}`)
+                                                                            .join(', ');
+                                                                        setRedactResult(
+                                                                            [`Saved ${result.relative_path} (${found}).`, ...result.report.warnings].join(' ')
+                                                                        );
+                                                                    } catch (e) {
+                                                                        setRedactResult(String(e));
+                                                                    } finally {
+                                                                        setRedacting(false);
+                                                                    }
+                                                                }}
+                                                            >
+                                                                {redacting ? 'Redacting…' : 'Make redacted copy'}
+                                                            </button>
+                                                            <span style={{ fontSize: '0.75rem', color: 'var(--text-muted)' }}>
+                                                                The text is removed from the copy, not just covered, and the copy is saved to Bundle.
+                                                            </span>
+                                                        </div>
+                                                        {redactResult && (
+                                                            <div style={{ fontSize: '0.8rem', marginTop: 'var(--space-2)' }}>{redactResult}</div>
+                                                        )}
+                                                    </div>
+                                                )}
                                             </div>
                                         )}
                                     </div>
@@ -124,11 +124,15 @@ export default function YourDataAndAi() {
                         <tbody>
                             <tr style={{ borderBottom: '1px solid var(--border)' }}>
                                 <td style={{ padding: 'var(--space-2)', fontWeight: 500 }}>Bank & card numbers</td>
-                                <td style={{ padding: 'var(--space-2)', color: 'var(--text-muted)' }}>Account numbers, sort codes, card numbers</td>
+                                <td style={{ padding: 'var(--space-2)', color: 'var(--text-muted)' }}>Account numbers and sort codes (near words like "account" or "sort code"), IBANs, card numbers</td>
                             </tr>
                             <tr style={{ borderBottom: '1px solid var(--border)' }}>
-                                <td style={{ padding: 'var(--space-2)', fontWeight: 500 }}>NI numbers</td>
-                                <td style={{ padding: 'var(--space-2)', color: 'var(--text-muted)' }}>National Insurance number format</td>
+                                <td style={{ padding: 'var(--space-2)', fontWeight: 500 }}>NI & NHS numbers</td>
+                                <td style={{ padding: 'var(--space-2)', color: 'var(--text-muted)' }}>National Insurance numbers; NHS numbers with a valid check digit</td>
+                            </tr>
+                            <tr style={{ borderBottom: '1px solid var(--border)' }}>
+                                <td style={{ padding: 'var(--space-2)', fontWeight: 500 }}>Dates of birth</td>
+                                <td style={{ padding: 'var(--space-2)', color: 'var(--text-muted)' }}>Dates following "DOB", "date of birth" or "born"</td>
                             </tr>
                             <tr style={{ borderBottom: '1px solid var(--border)' }}>
                                 <td style={{ padding: 'var(--space-2)', fontWeight: 500 }}>Email addresses</td>
@@ -153,6 +157,12 @@ export default function YourDataAndAi() {
                     The redaction tool runs before the preview step — so you redact first, then review
                     the redacted version, then confirm. You always see what will be sent.
                 </p>
+                <p style={{ fontSize: '0.85rem', color: 'var(--text-muted)', marginTop: 'var(--space-2)' }}>
+                    Each value is replaced with a numbered placeholder such as [REDACTED EMAIL 1], used
+                    consistently across documents. The list of what each placeholder stands for is kept only
+                    in your case folder; the AI history records how many items of each kind were removed,
+                    never the items themselves.
+                </p>
             </div>
 
             <div className="info-block" style={{ borderColor: 'var(--accent)', borderLeftWidth: '3px' }}>
@@ -0,0 +1,102 @@
+/**
+ * Display, sorting and editing helpers for chronology dates.
+ * Parsing of free-text dates happens in the backend when an entry is saved.
+ */
+
+import type { ChronoDate, DatePrecision } from '../types/ai';
+
+const MONTHS = ['Jan', 'Feb', 'Mar', 'Apr', 'May', 'Jun', 'Jul', 'Aug', 'Sep', 'Oct', 'Nov', 'Dec'];
+
+const PRECISION_RANK: Record<DatePrecision, number> = {
+    day: 0,
+    approximate: 1,
+    range: 2,
+    month: 3,
+    year: 4,
+};
+
+function lastOfMonth(year: number, month: number): number {
+    return new Date(Date.UTC(year, month, 0)).getUTCDate();
+}
+
+/** Turns an unsaved string date into a ChronoDate; only ISO forms are understood here */
+export function toChronoDate(date: ChronoDate | string): ChronoDate {
+    if (typeof date !== 'string') return date;
+    const text = date.trim();
+    const base = { time: null, timezone: null, text: null };
+    const m = /^(\d{4})(?:-(\d{2}))?(?:-(\d{2}))?$/.exec(text);
+    if (!m) {
+        return { ...base, precision: 'approximate', start: null, end: null, text: text || null };
+    }
+    const [, y, mo, d] = m;
+    if (d) return { ...base, precision: 'day', start: text, end: text };
+    if (mo) {
+        const end = `${y}-${mo}-${String(lastOfMonth(+y, +mo)).padStart(2, '0')}`;
+        return { ...base, precision: 'month', start: `${y}-${mo}-01`, end };
+    }
+    return { ...base, precision: 'year', start: `${y}-01-01`, end: `${y}-12-31` };
+}
+
+function parts(iso: string): [number, number, number] {
+    const [y, m, d] = iso.split('-').map((p) => parseInt(p, 10));
+    return [y, m, d];
+}
+
+function dayLabel(iso: string, withYear = true): string {
+    const [y, m, d] = parts(iso);
+    return withYear ? `${d} ${MONTHS[m - 1]} ${y}` : `${d} ${MONTHS[m - 1]}`;
+}
+
+/** The days a date could fall on, as briefly as they allow: "5 Mar 2024", "Mar 2024", "5–9 Mar 2024" */
+function spanLabel(start: string, end: string): string {
+    const [sy, sm, sd] = parts(start);
+    const [ey, em, ed] = parts(end);
+    if (start === end) return dayLabel(start);
+    if (sy === ey && sm === em && sd === 1 && ed === lastOfMonth(ey, em)) return `${MONTHS[sm - 1]} ${sy}`;
+    if (sm === 1 && sd === 1 && em === 12 && ed === 31) return sy === ey ? `${sy}` : `${sy}–${ey}`;
+    if (sy === ey && sm === em) return `${sd}–${dayLabel(end)}`;
+    if (sy === ey) return `${dayLabel(start, false)} – ${dayLabel(end)}`;
+    return `${dayLabel(start)} – ${dayLabel(end)}`;
+}
+
+export function formatChronoDate(value: ChronoDate | string): string {
+    const date = toChronoDate(value);
+    if (!date.start || !date.end) return date.text || 'Undated';
+    if (date.precision === 'approximate') return date.text || `c. ${spanLabel(date.start, date.end)}`;
+    const label = spanLabel(date.start, date.end);
+    if (!date.time) return label;
+    return date.timezone ? `${label}, ${date.time} ${date.timezone}` : `${label}, ${date.time}`;
+}
+
+/** Undated entries last; otherwise earliest possible day, then the more precise, then time of day */
+export function compareChronoDates(a: ChronoDate | string, b: ChronoDate | string): number {
+    const x = toChronoDate(a);
+    const y = toChronoDate(b);
+    if (!x.start || !y.start) return (x.start ? 0 : 1) - (y.start ? 0 : 1);
+    return (
+        x.start.localeCompare(y.start)
+        || PRECISION_RANK[x.precision] - PRECISION_RANK[y.precision]
+        || (x.time || '').localeCompare(y.time || '')
+        || (x.end || '').localeCompare(y.end || '')
+    );
+}
+
+/** The text shown in the date field when editing an entry */
+export function chronoDateInput(value: ChronoDate | string): string {
+    if (typeof value === 'string') return value;
+    if (value.text) return value.text;
+    if (!value.start) return '';
+    switch (value.precision) {
+        case 'year':
+            return value.start.slice(0, 4);
+        case 'month':
+            return value.start.slice(0, 7);
+        case 'range':
+            return `${value.start}/${value.end}`;
+        case 'approximate':
+            return value.start === value.end ? `c. ${value.start}` : `c. ${value.start}/${value.end}`;
+        default:
+            if (!value.time) return value.start;
+            return value.timezone ? `${value.start} ${value.time} ${value.timezone}` : `${value.start} ${value.time}`;
+    }
+}
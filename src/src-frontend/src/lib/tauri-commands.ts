@@ -1,7 +1,10 @@
 import { invoke } from '@tauri-apps/api/core';
-import type { CaseMetadata } from '../types/case';
-import type { DocumentEntry } from '../types/document';
-import type { ChronologyEntry } from '../types/ai';
+import type { CaseMetadata, CompanyCheck, CompanyRecord, Deadline, DocumentParties, LimitationReport, Party, ProtocolReport, RemediesReport } from '../types/case';
+import type { CopyResult, DocumentEntry, PdfRedactionOptions, RedactedDocument } from '../types/document';
+import type { CostsBreakdown, CostsRequest, TrackAdvice } from '../types/fees';
+import type { AiProvider, AiRequest, ChronologyEntry, ChronologyIssue, PreparedRequest, Redacted, RedactionOptions, SourceReport, StoredKey } from '../types/ai';
+import type { ChronologyExportRequest, DraftInputs, DraftKind, GeneratedDraft } from '../types/drafting';
+import type { FieldIssue, FormId, FormInputs, FormSpec, GeneratedForm, ValidationReport } from '../types/forms';
 
 // Filesystem
 export async function getBasePath(): Promise<string> {
@@ -37,13 +40,136 @@ export async function updateCase(
     return invoke('update_case', { caseName, metadata });
 }
 
+// Parties
+export async function listParties(caseName: string): Promise<Party[]> {
+    return invoke('list_parties', { caseName });
+}
+
+export async function linkDocumentParties(caseName: string): Promise<DocumentParties[]> {
+    return invoke('link_document_parties', { caseName });
+}
+
+export async function listPartyDocuments(caseName: string, partyId: string): Promise<DocumentEntry[]> {
+    return invoke('list_party_documents', { caseName, partyId });
+}
+
+// Companies House
+export async function searchCompanies(query: string, live = false): Promise<CompanyRecord[]> {
+    return invoke('search_companies', { query, live });
+}
+
+export async function checkPartyCompany(
+    caseName: string,
+    partyId: string,
+    companyNumber: string,
+    live = false
+): Promise<CompanyCheck> {
+    return invoke('check_party_company', { caseName, partyId, companyNumber, live });
+}
+
+/** Whether a Companies House API key is saved; the key itself stays in the backend */
+export async function hasCompaniesHouseKey(): Promise<boolean> {
+    return invoke('has_companies_house_key');
+}
+
+export async function setCompaniesHouseKey(key: string): Promise<void> {
+    return invoke('set_companies_house_key', { key });
+}
+
+export async function removeCompaniesHouseKey(): Promise<void> {
+    return invoke('remove_companies_house_key');
+}
+
+// Deadlines & limitation
+export async function computeDeadlines(caseName: string): Promise<Deadline[]> {
+    return invoke('compute_deadlines', { caseName });
+}
+
+export async function checkLimitation(caseName: string): Promise<LimitationReport> {
+    return invoke('check_limitation', { caseName });
+}
+
+// Court fees & interest
+export async function calculateClaimCosts(
+    caseName: string,
+    request: CostsRequest
+): Promise<CostsBreakdown> {
+    return invoke('calculate_claim_costs', { caseName, request });
+}
+
+// Consumer Rights Act remedies
+export async function assessRemedies(caseName: string): Promise<RemediesReport> {
+    return invoke('assess_remedies', { caseName });
+}
+
+// Court forms
+export async function listCourtForms(): Promise<FormSpec[]> {
+    return invoke('list_court_forms');
+}
+
+export async function checkCourtForm(
+    caseName: string,
+    form: FormId,
+    inputs: FormInputs
+): Promise<ValidationReport> {
+    return invoke('check_court_form', { caseName, form, inputs });
+}
+
+export async function generateCourtForm(
+    caseName: string,
+    form: FormId,
+    inputs: FormInputs
+): Promise<GeneratedForm> {
+    return invoke('generate_court_form', { caseName, form, inputs });
+}
+
+/** Add a blank form downloaded from GOV.UK; returns mapped fields it lacks */
+export async function installCourtFormBlank(form: FormId, sourcePath: string): Promise<FieldIssue[]> {
+    return invoke('install_court_form_blank', { form, sourcePath });
+}
+
+export async function listFormFields(pdfPath: string): Promise<string[]> {
+    return invoke('list_form_fields', { pdfPath });
+}
+
+// Drafting
+export async function generateDraft(
+    caseName: string,
+    kind: DraftKind,
+    inputs: DraftInputs
+): Promise<GeneratedDraft> {
+    return invoke('generate_draft', { caseName, kind, inputs });
+}
+
+export async function exportChronology(
+    caseName: string,
+    request: ChronologyExportRequest,
+    exportPath: string
+): Promise<string> {
+    return invoke('export_chronology', { caseName, request, exportPath });
+}
+
+// Track allocation
+export async function adviseTrack(caseName: string): Promise<TrackAdvice> {
+    return invoke('advise_track', { caseName });
+}
+
+// Pre-action protocol
+export async function checkPreAction(caseName: string): Promise<ProtocolReport> {
+    return invoke('check_pre_action', { caseName });
+}
+
+export async function addPreActionEntries(caseName: string): Promise<ChronologyEntry[]> {
+    return invoke('add_pre_action_entries', { caseName });
+}
+
 // Documents
 export async function copyFileToCase(
     caseName: string,
     sourcePath: string,
     folder: string,
     filename: string
-): Promise<string> {
+): Promise<CopyResult> {
     return invoke('copy_file_to_case', { caseName, sourcePath, folder, filename });
 }
 
@@ -100,6 +226,13 @@ export async function updateChronologyEntry(
     return invoke('update_chronology_entry', { caseName, entry });
 }
 
+export async function checkChronology(
+    caseName: string,
+    gapDays?: number
+): Promise<ChronologyIssue[]> {
+    return invoke('check_chronology', { caseName, gapDays: gapDays ?? null });
+}
+
 // Export
 export async function exportBundle(
     caseName: string,
@@ -131,6 +264,9 @@ export interface AiHistoryRecord {
     model: string;
     response: string;
     summary?: string;
+    redaction?: RedactionLog | null;
+    payloadHash?: string | null;
+    verification?: SourceReport | null;
 }
 
 export async function saveAiCall(caseName: string, record: AiHistoryRecord): Promise<void> {
@@ -141,6 +277,72 @@ export async function loadAiHistory(caseName: string): Promise<AiHistoryRecord[]
     return invoke('load_ai_history', { caseName });
 }
 
+// AI providers — requests are built and sent by the backend
+/** With a redaction map, the backend redacts the request with it before building the body */
+export async function previewAiRequest(
+    caseName: string,
+    request: AiRequest,
+    redactionMapId?: string | null
+): Promise<PreparedRequest> {
+    return invoke('preview_ai_request', { caseName, request, redactionMapId: redactionMapId ?? null });
+}
+
+/** Streams the reply as `ai-stream` events tagged with `streamId`, then saves the call to the case's AI history */
+export async function sendAiRequest(
+    caseName: string,
+    request: AiRequest,
+    callType: string,
+    streamId: string,
+    payloadHash: string,
+    redactionMapId?: string | null
+): Promise<AiHistoryRecord> {
+    return invoke('send_ai_request', {
+        caseName, request, callType, streamId, payloadHash, redactionMapId: redactionMapId ?? null,
+    });
+}
+
+/** Check a saved response against the case documents again and store the new report with it */
+export async function checkAiResponse(caseName: string, recordId: string): Promise<SourceReport> {
+    return invoke('check_ai_response', { caseName, recordId });
+}
+
+export async function listAiKeys(): Promise<StoredKey[]> {
+    return invoke('list_ai_keys');
+}
+
+export async function setAiKey(provider: AiProvider, key: string): Promise<void> {
+    return invoke('set_ai_key', { provider, key });
+}
+
+export async function removeAiKey(provider: AiProvider): Promise<void> {
+    return invoke('remove_ai_key', { provider });
+}
+
+export async function testAiConnection(provider: AiProvider, model: string): Promise<string> {
+    return invoke('test_ai_connection', { provider, model });
+}
+
+// Redaction
+export async function redactTexts(
+    caseName: string,
+    texts: string[],
+    options?: RedactionOptions
+): Promise<Redacted> {
+    return invoke('redact_texts', { caseName, texts, options: options ?? null });
+}
+
+export async function restoreRedactedText(caseName: string, mapId: string, text: string): Promise<string> {
+    return invoke('restore_redacted_text', { caseName, mapId, text });
+}
+
+export async function redactPdf(
+    caseName: string,
+    relativePath: string,
+    options: PdfRedactionOptions
+): Promise<RedactedDocument> {
+    return invoke('redact_pdf', { caseName, relativePath, options });
+}
+
 // ===== Citation Resolution & Legal Research =====
 
 import type {
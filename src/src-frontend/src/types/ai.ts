@@ -1,12 +1,75 @@
+export type DatePrecision = 'day' | 'month' | 'year' | 'range' | 'approximate';
+
+/** A chronology date; `start` and `end` are the first and last days it could fall on (null when undated) */
+export interface ChronoDate {
+    precision: DatePrecision;
+    start: string | null;
+    end: string | null;
+    time: string | null;
+    timezone: string | null;
+    /** The date as written, kept for approximate and undated entries */
+    text: string | null;
+}
+
+export type EventType =
+    | 'order_placed'
+    | 'payment_made'
+    | 'delivered'
+    | 'fault_reported'
+    | 'repair_attempted'
+    | 'refund_requested'
+    | 'refund_refused'
+    | 'offer_made'
+    | 'letter_before_claim_sent'
+    | 'response_received'
+    | 'claim_issued'
+    | 'claim_served'
+    | 'acknowledgment_filed'
+    | 'defence_filed'
+    | 'directions_questionnaire'
+    | 'mediation'
+    | 'allocated'
+    | 'hearing_listed'
+    | 'judgment';
+
 export interface ChronologyEntry {
     id: string;
-    date: string;
+    /** Always a ChronoDate from the backend; a string is parsed when the entry is saved */
+    date: ChronoDate | string;
     description: string;
     source: 'document' | 'intake' | 'manual' | 'ai_extracted';
     document_id?: string;
     source_document_path?: string;
     significance: 'key' | 'supporting' | 'background';
     confidence?: 'high' | 'medium' | 'low';
+    /** What happened, where the event classifier recognised it */
+    event_type?: EventType | null;
+    /** The phrase that identified the event */
+    trigger?: string | null;
+    /** How a relative date such as "yesterday" was resolved */
+    relative?: RelativeDate | null;
+}
+
+export interface RelativeDate {
+    /** The phrase as written */
+    phrase: string;
+    /** The date it was counted from (yyyy-mm-dd) */
+    anchor: string;
+    /** What the anchor is, e.g. "the email's sent date" */
+    anchor_label: string;
+    /** Days from the anchor, negative when earlier; null when only a month is given */
+    offset_days: number | null;
+    reading: string;
+}
+
+export type ChronologyIssueKind = 'impossible_order' | 'gap' | 'duplicate' | 'intake_contradicted';
+
+export interface ChronologyIssue {
+    kind: ChronologyIssueKind;
+    severity: 'warning' | 'info';
+    message: string;
+    /** Ids of the chronology entries involved, earliest first */
+    entry_ids: string[];
 }
 
 export interface AssembledContext {
@@ -53,6 +116,125 @@ export interface DraftingInstructions {
     interestClaimed?: boolean;
 }
 
+export type PiiKind =
+    | 'sort_code'
+    | 'account_number'
+    | 'iban'
+    | 'ni_number'
+    | 'nhs_number'
+    | 'card_number'
+    | 'phone'
+    | 'email'
+    | 'postcode'
+    | 'date_of_birth'
+    | 'custom';
+
+export interface RedactionOptions {
+    /** Omit to redact every kind */
+    kinds?: PiiKind[];
+    custom_terms?: string[];
+}
+
+/** Placeholder → original value. Stays in the case folder. */
+export interface RedactionEntry {
+    placeholder: string;
+    kind: PiiKind;
+    original: string;
+    occurrences: number;
+}
+
+export interface RedactionMap {
+    id: string;
+    created_at: string;
+    entries: RedactionEntry[];
+}
+
+/** What was redacted, without the values themselves */
+export interface RedactionLog {
+    map_id: string;
+    created_at: string;
+    counts: { kind: PiiKind; values: number; occurrences: number }[];
+    total: number;
+    custom_terms: number;
+    kinds_checked: PiiKind[];
+}
+
+export interface Redacted {
+    texts: string[];
+    map: RedactionMap;
+    log: RedactionLog;
+}
+
+export type AiProvider = 'anthropic' | 'openai' | 'gemini' | 'mock';
+
+/** What to ask, independent of provider. Sent to the backend, which builds the real request. */
+export interface AiRequest {
+    provider: AiProvider;
+    model: string;
+    prompt: string;
+    system?: string | null;
+    /** Defaults to 16000 */
+    max_tokens?: number;
+    /** Ask for a JSON object where the model supports it */
+    json?: boolean;
+}
+
+/** The exact request that will be sent, with the API key masked */
+export interface PreparedRequest {
+    provider: AiProvider;
+    model: string;
+    method: string;
+    url: string;
+    headers: [string, string][];
+    body: unknown;
+    payload_bytes: number;
+    /** SHA-256 of the body; sending is refused if the body has changed */
+    payload_hash: string;
+    estimated_input_tokens: number;
+    key_stored: boolean;
+}
+
+export interface StoredKey {
+    provider: AiProvider;
+    name: string;
+}
+
+/** Payload of the `ai-stream` event */
+export type AiStreamEvent =
+    | { type: 'delta'; stream_id: string; text: string }
+    | { type: 'done'; stream_id: string; record_id: string }
+    | { type: 'error'; stream_id: string; message: string };
+
+export type ClaimStatus = 'supported' | 'partial' | 'no_source' | 'legal' | 'to_confirm';
+
+/** The passage that best supports a claim */
+export interface SourcePassage {
+    document_id: string;
+    filename: string;
+    path: string;
+    excerpt: string;
+    /** Share of the claim's words found in the passage, 0 to 1 */
+    score: number;
+}
+
+export interface SourceIssue {
+    kind: 'no_source' | 'quote_not_found' | 'date_not_in_chronology';
+    message: string;
+    text: string;
+}
+
+/** How well an AI response is backed by the case documents and chronology */
+export interface SourceReport {
+    checked_at: string;
+    documents_checked: number;
+    claims: { text: string; status: ClaimStatus; source: SourcePassage | null }[];
+    quotes: { quote: string; found_in: string | null }[];
+    dates: { text: string; date: string; in_chronology: boolean; in_documents: boolean }[];
+    issues: SourceIssue[];
+    /** Anything that limited the check itself, e.g. redaction placeholders that could not be put back */
+    warnings?: string[];
+}
+
 export interface AiCallRecord {
     id: string;
     callType: AiCallType;
@@ -62,6 +244,7 @@ export interface AiCallRecord {
     model: string;
     response: string;
     parsedResponse?: ParsedMeritsResponse;
+    verification?: SourceReport | null;
 }
 
 export interface ParsedMeritsResponse {
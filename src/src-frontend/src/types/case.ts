@@ -1,4 +1,5 @@
 export interface CaseMetadata {
+    schema_version?: number;
     id: string;
     name: string;
     created_at: string;
@@ -11,7 +12,9 @@ export interface CaseMetadata {
     product_service_type: 'goods' | 'services' | 'digital_content';
     issues: string[];
     desired_outcome: string[];
+    /** Total claim value in pence */
     claim_value: number;
+    claim_heads?: ClaimHead[];
     date_of_purchase: string | null;
     date_problem_discovered: string | null;
     date_first_complained: string | null;
@@ -21,17 +24,140 @@ export interface CaseMetadata {
     complexity_triggers: string[];
     overall_risk: 'within_scope' | 'borderline' | 'seek_advice';
     multiple_parties: boolean;
+    /** Absent or empty on cases created before parties were recorded */
+    parties?: Party[];
     cross_border: boolean;
     personal_injury: boolean;
+    /** Damages claimed for pain, suffering and loss of amenity, in pence */
+    psla_damages?: number | null;
     existing_proceedings: boolean;
     insolvency: boolean;
     regulatory_overlap: boolean;
     counterclaim: boolean;
+    agreement_under_deed?: boolean;
     user_role: UserRole;
+    procedural_dates?: ProceduralDates;
+    /** Always redacted before text is sent to an AI provider */
+    redaction_terms?: string[];
+}
+
+export type ServiceMethod =
+    | 'first_class_post'
+    | 'document_exchange'
+    | 'delivered_to_address'
+    | 'personal'
+    | 'email'
+    | 'fax';
+
+export interface ServiceEvent {
+    date: string;
+    method: ServiceMethod;
+    after_4_30pm?: boolean;
+}
+
+export interface ProceduralDates {
+    claim_issued: string | null;
+    claim_form_served: ServiceEvent | null;
+    particulars_served: ServiceEvent | null;
+    acknowledgment_filed: string | null;
+    defence_filed: string | null;
+    allocation_notice_served: ServiceEvent | null;
+    dq_deadline: string | null;
+    dq_filed: string | null;
+    decision_date: string | null;
+    appeal_filed: string | null;
+}
+
+export interface Deadline {
+    id: string;
+    title: string;
+    due_date: string;
+    rule: string;
+    explanation: string;
+    days_remaining: number;
+    status: 'upcoming' | 'due_soon' | 'overdue' | 'met';
+    party: 'claimant' | 'defendant' | 'both';
+    estimate: boolean;
+}
+
+export interface ClaimHead {
+    id: string;
+    description: string;
+    /** Pence */
+    amount: number;
+    evidence_doc_ids: string[];
 }
 
 export type UserRole = 'claimant' | 'defendant';
 
+export type PartyRole = 'claimant' | 'defendant' | 'third_party' | 'witness';
+export type PartyType = 'individual' | 'company' | 'sole_trader' | 'partnership' | 'other';
+
+export interface Representative {
+    name: string;
+    firm?: string | null;
+    reference?: string | null;
+    email?: string | null;
+    phone?: string | null;
+    address?: string | null;
+}
+
+export interface Party {
+    id: string;
+    role: PartyRole;
+    party_type: PartyType;
+    name: string;
+    aliases: string[];
+    /** Addresses the party writes from; "@example.co.uk" covers a whole domain */
+    emails: string[];
+    company_number?: string | null;
+    address?: string | null;
+    address_for_service?: string | null;
+    representative?: Representative | null;
+    companies_house?: CompanyRecord | null;
+}
+
+export type CompanyStatus =
+    | 'active'
+    | 'proposal_to_strike_off'
+    | 'dissolved'
+    | 'liquidation'
+    | 'administration'
+    | 'receivership'
+    | 'voluntary_arrangement'
+    | 'insolvency_proceedings'
+    | 'closed'
+    | 'unknown';
+
+export interface CompanyRecord {
+    company_number: string;
+    company_name: string;
+    registered_office: string | null;
+    status: CompanyStatus;
+    status_text: string;
+    company_type: string | null;
+    incorporated_on: string | null;
+    dissolved_on: string | null;
+    /** The snapshot's file name, or the live API */
+    source: { bulk_snapshot: string } | 'live_api';
+    retrieved_at: string;
+}
+
+export interface CompanyCheck {
+    party_id: string;
+    record: CompanyRecord;
+    /** Dissolved or in an insolvency process */
+    red_flag: boolean;
+    warnings: string[];
+}
+
+/** The parties a document is from and to, by party id */
+export interface DocumentParties {
+    document_id: string;
+    from: string[];
+    to: string[];
+}
+
 export type CaseStatus =
     | 'intake'
     | 'pre_action'
@@ -46,3 +172,63 @@ export type CaseStatus =
 export type DefendantType = 'company' | 'individual' | 'sole_trader';
 export type ClaimType = 'cra_goods' | 'cra_services' | 'cra_digital';
 export type ProductServiceType = 'goods' | 'services' | 'digital_content';
+
+export interface LimitationPeriod {
+    basis: 'contract' | 'tort' | 'latent_damage' | 'personal_injury' | 'deed';
+    section: string;
+    runs_from: string;
+    expiry_date: string;
+    days_remaining: number;
+    explanation: string;
+}
+
+export interface LimitationReport {
+    status: 'ok' | 'warning' | 'red_flag' | 'expired' | 'issued_in_time' | 'unknown';
+    primary: LimitationPeriod | null;
+    alternatives: LimitationPeriod[];
+    assumptions: string[];
+    red_flag: boolean;
+}
+
+export type RemedyStatus = 'available' | 'conditional' | 'expired' | 'unknown';
+
+export interface Remedy {
+    id: string;
+    title: string;
+    section: string;
+    status: RemedyStatus;
+    deadline: string | null;
+    days_remaining: number | null;
+    explanation: string;
+    conditions: string[];
+}
+
+export interface RemediesReport {
+    claim_type: ClaimType;
+    remedies: Remedy[];
+    assumptions: string[];
+}
+
+export type ProtocolStepStatus = 'done' | 'pending' | 'overdue' | 'not_started';
+
+export interface ProtocolStep {
+    id: 'letter_before_claim' | 'response' | 'document_exchange' | 'adr' | 'issue';
+    title: string;
+    status: ProtocolStepStatus;
+    completed_date: string | null;
+    due_date: string | null;
+    reference: string;
+    guidance: string;
+    evidence: {
+        document_id: string | null;
+        chronology_id: string | null;
+        trigger: string;
+    } | null;
+}
+
+export interface ProtocolReport {
+    applicable: boolean;
+    complex: boolean;
+    steps: ProtocolStep[];
+    reminders: { step_id: string; title: string; due_date: string }[];
+}
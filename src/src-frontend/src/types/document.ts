@@ -11,6 +11,9 @@ export interface DocumentEntry {
     tags: string[];
     extracted_text: string | null;
     added_at: string;
+    pending_review?: boolean;
+    /** An email's sent date from its headers */
+    metadata_date?: string | null;
 }
 
 export type DocumentType =
@@ -43,3 +46,70 @@ export const DOCUMENT_TYPES: { value: DocumentType; label: string }[] = [
     { value: 'order', label: 'Court Order' },
     { value: 'other', label: 'Other' },
 ];
+
+/** An area to black out, in PDF points from the bottom-left corner of the page */
+export interface RedactionRegion {
+    page: number;
+    x: number;
+    y: number;
+    width: number;
+    height: number;
+}
+
+export interface PdfRedactionOptions {
+    regions?: RedactionRegion[];
+    terms?: string[];
+    label?: string | null;
+}
+
+export interface PdfRedactionReport {
+    pages_changed: number[];
+    boxes: number;
+    characters_removed: number;
+    term_hits: { term: string; occurrences: number }[];
+    images_edited: number;
+    images_removed: number;
+    annotations_removed: number;
+    warnings: string[];
+}
+
+export interface RedactedDocument {
+    relative_path: string;
+    report: PdfRedactionReport;
+    documents: DocumentEntry[];
+}
+
+/** Text and header details read from a file */
+export interface ExtractedContent {
+    text: string;
+    metadata_date: string | null;
+    subject: string | null;
+    from: string | null;
+    to: string | null;
+}
+
+export interface CopyResult {
+    relative_path: string;
+    /** Only set when extraction ran before the copy returned */
+    extracted: ExtractedContent | null;
+    /** The background job extracting the file's text */
+    job_id: string | null;
+}
+
+export type JobStatus = 'queued' | 'running' | 'completed' | 'failed' | 'cancelled';
+
+/** A background job, as sent with each `job-progress` event */
+export interface Job {
+    id: string;
+    case_name: string;
+    kind: { type: string; relative_path?: string; document_id?: string | null };
+    status: JobStatus;
+    progress: number;
+    message: string | null;
+    result: unknown;
+    error: string | null;
+    attempts: number;
+    created_at: string;
+    started_at: string | null;
+    finished_at: string | null;
+}
@@ -0,0 +1,30 @@
+import type { DocumentEntry } from './document';
+
+export type DraftKind = 'letter_before_claim' | 'witness_statement' | 'schedule_of_loss' | 'chronology';
+
+export interface DraftInputs {
+    claim_number?: string | null;
+    court?: string | null;
+    claimant_address?: string | null;
+    defendant_address?: string | null;
+    witness_name?: string | null;
+    witness_address?: string | null;
+    witness_occupation?: string | null;
+    /** Any other template placeholder by key */
+    answers?: Record<string, string>;
+}
+
+export interface GeneratedDraft {
+    relative_path: string;
+    /** Placeholders left for the user to complete */
+    missing: string[];
+    documents: DocumentEntry[];
+}
+
+export type ChronologyExportFormat = 'docx' | 'pdf' | 'csv';
+
+export interface ChronologyExportRequest {
+    format: ChronologyExportFormat;
+    /** Only include these significances; empty includes everything */
+    significance: Array<'key' | 'supporting' | 'background'>;
+}
@@ -0,0 +1,57 @@
+// All amounts are in pence
+
+export type IssueChannel = 'online' | 'paper';
+export type Track = 'small_claims' | 'fast' | 'intermediate' | 'multi';
+export type InterestBasis = 'county_courts_act' | 'late_payment';
+
+export interface CostsRequest {
+    channel: IssueChannel;
+    track: Track | null;
+    interest: { basis: InterestBasis; from_date: string } | null;
+    issue_date: string | null;
+}
+
+export interface InterestBreakdown {
+    basis: InterestBasis;
+    authority: string;
+    from_date: string;
+    to_date: string;
+    days: number;
+    rate_bp: number;
+    amount: number;
+    daily_rate: number;
+    fixed_compensation: number;
+}
+
+export interface N1Figures {
+    amount_claimed: number;
+    court_fee: number;
+    legal_representative_costs: number;
+    total_amount: number;
+}
+
+export interface CostsBreakdown {
+    fee_table_version: string;
+    principal: number;
+    interest: InterestBreakdown | null;
+    channel: IssueChannel;
+    issue_fee: number;
+    track: Track;
+    hearing_fee: number;
+    n1: N1Figures;
+    steps: string[];
+    warnings: string[];
+}
+
+export interface TrackAdvice {
+    track: Track;
+    track_by_value: Track;
+    /** Why the track can't be predicted yet; `track` is then the track by value */
+    undetermined: string | null;
+    value: number;
+    frc_band: number | null;
+    frc_explanation: string | null;
+    reasons: string[];
+    factors: string[];
+    overall_risk: 'within_scope' | 'borderline' | 'seek_advice';
+}
@@ -0,0 +1,62 @@
+import type { DocumentEntry } from './document';
+import type { CostsRequest } from './fees';
+
+export type FormId = 'n1' | 'n9a' | 'n9b' | 'n180' | 'n181' | 'n244';
+
+export interface FieldSpec {
+    key: string;
+    pdf_field: string;
+    label: string;
+    required: boolean;
+    kind: 'text' | 'checkbox';
+    max_len: number | null;
+}
+
+export interface FormSpec {
+    form: FormId;
+    version: string;
+    effective_from: string;
+    title: string;
+    pdf_file: string;
+    /** SHA-256 of the only blank accepted for this map, when pinned */
+    blank_sha256: string | null;
+    /** Whether the field names have been checked against the blank */
+    verified: boolean;
+    source: string;
+    notes: string | null;
+    fields: FieldSpec[];
+}
+
+export interface FormInputs {
+    claim_number?: string | null;
+    court?: string | null;
+    claimant_address?: string | null;
+    defendant_address?: string | null;
+    particulars?: string | null;
+    signatory?: string | null;
+    costs?: CostsRequest | null;
+    /** Any other field by key; checkboxes take "yes" or "no" */
+    answers?: Record<string, string>;
+}
+
+export interface FieldIssue {
+    key: string;
+    label: string;
+    problem: 'missing' | 'too_long' | 'not_in_pdf' | 'unverified';
+    message: string;
+}
+
+export interface ValidationReport {
+    form: FormId;
+    version: string;
+    filled: string[];
+    issues: FieldIssue[];
+    ready: boolean;
+}
+
+export interface GeneratedForm {
+    /** Null when the form wasn't ready and nothing was saved */
+    relative_path: string | null;
+    report: ValidationReport;
+    documents: DocumentEntry[];
+}
@@ -0,0 +1,144 @@
+//! Anthropic Messages API, streamed.
+
+use super::{AiRequest, SseEvent, StreamState};
+use serde_json::{json, Value};
+
+pub const API_VERSION: &str = "2023-06-01";
+
+pub fn endpoint() -> String {
+    "https://api.anthropic.com/v1/messages".to_string()
+}
+
+pub fn headers(key: &str) -> Vec<(String, String)> {
+    vec![
+        ("x-api-key".to_string(), key.to_string()),
+        ("anthropic-version".to_string(), API_VERSION.to_string()),
+    ]
+}
+
+pub fn body(request: &AiRequest) -> Value {
+    let mut body = json!({
+        "model": request.model,
+        "max_tokens": request.max_tokens,
+        "stream": true,
+        "messages": [{ "role": "user", "content": request.prompt }],
+    });
+    if let Some(system) = request.system.as_deref().filter(|s| !s.trim().is_empty()) {
+        body["system"] = json!(system);
+    }
+    body
+}
+
+/// Events arrive as message_start (input usage), content_block_delta (text
+/// or thinking), message_delta (stop reason and output usage) and
+/// message_stop. Thinking deltas are not part of the answer.
+pub fn apply_event(state: &mut StreamState, event: &SseEvent) -> Result<Option<String>, String> {
+    let data: Value = match serde_json::from_str(&event.data) {
+        Ok(v) => v,
+        Err(_) => return Ok(None),
+    };
+    match data.get("type").and_then(|t| t.as_str()).unwrap_or_default() {
+        "message_start" => {
+            if let Some(usage) = data.pointer("/message/usage") {
+                let input = ["input_tokens", "cache_creation_input_tokens", "cache_read_input_tokens"]
+                    .iter()
+                    .filter_map(|f| usage.get(f).and_then(|v| v.as_u64()))
+                    .sum();
+                state.input_tokens = Some(input);
+            }
+            Ok(None)
+        }
+        "content_block_delta" => {
+            let delta = &data["delta"];
+            if delta.get("type").and_then(|t| t.as_str()) != Some("text_delta") {
+                return Ok(None);
+            }
+            let text = delta.get("text").and_then(|t| t.as_str()).unwrap_or_default();
+            if text.is_empty() {
+                return Ok(None);
+            }
+            state.text.push_str(text);
+            Ok(Some(text.to_string()))
+        }
+        "message_delta" => {
+            if let Some(output) = data.pointer("/usage/output_tokens").and_then(|v| v.as_u64()) {
+                state.output_tokens = Some(output);
+            }
+            if let Some(reason) = data.pointer("/delta/stop_reason").and_then(|v| v.as_str()) {
+                state.stop_reason = Some(reason.to_string());
+            }
+            Ok(None)
+        }
+        "error" => Err(format!(
+            "Anthropic stopped with an error: {}",
+            data.pointer("/error/message").and_then(|m| m.as_str()).unwrap_or("unknown error")
+        )),
+        _ => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::{Provider, SseParser};
+
+    #[test]
+    fn body_includes_system_only_when_given() {
+        let mut request = AiRequest {
+            provider: Provider::Anthropic,
+            model: "claude-sonnet-4-5".to_string(),
+            prompt: "Hello".to_string(),
+            system: None,
+            max_tokens: 16000,
+            json: false,
+        };
+        let b = body(&request);
+        assert_eq!(b["stream"], true);
+        assert_eq!(b["max_tokens"], 16000);
+        assert_eq!(b["messages"][0]["content"], "Hello");
+        assert!(b.get("system").is_none());
+
+        request.system = Some("Be brief".to_string());
+        assert_eq!(body(&request)["system"], "Be brief");
+    }
+
+    #[test]
+    fn stream_collects_text_and_usage() {
+        let stream = concat!(
+            "event: message_start\n",
+            "data: {\"type\":\"message_start\",\"message\":{\"usage\":{\"input_tokens\":120,\"output_tokens\":1}}}\n\n",
+            "event: ping\ndata: {\"type\":\"ping\"}\n\n",
+            "event: content_block_delta\n",
+            "data: {\"type\":\"content_block_delta\",\"index\":0,\"delta\":{\"type\":\"thinking_delta\",\"thinking\":\"hmm\"}}\n\n",
+            "event: content_block_delta\n",
+            "data: {\"type\":\"content_block_delta\",\"index\":1,\"delta\":{\"type\":\"text_delta\",\"text\":\"The claim \"}}\n\n",
+            "event: content_block_delta\n",
+            "data: {\"type\":\"content_block_delta\",\"index\":1,\"delta\":{\"type\":\"text_delta\",\"text\":\"is strong.\"}}\n\n",
+            "event: message_delta\n",
+            "data: {\"type\":\"message_delta\",\"delta\":{\"stop_reason\":\"end_turn\"},\"usage\":{\"output_tokens\":42}}\n\n",
+            "event: message_stop\ndata: {\"type\":\"message_stop\"}\n\n",
+        );
+        let mut state = StreamState::default();
+        let mut pieces = Vec::new();
+        for event in SseParser::default().push(stream.as_bytes()) {
+            if let Some(t) = apply_event(&mut state, &event).unwrap() {
+                pieces.push(t);
+            }
+        }
+        assert_eq!(pieces, vec!["The claim ", "is strong."]);
+        assert_eq!(state.text, "The claim is strong.");
+        assert_eq!(state.input_tokens, Some(120));
+        assert_eq!(state.output_tokens, Some(42));
+        assert_eq!(state.stop_reason.as_deref(), Some("end_turn"));
+    }
+
+    #[test]
+    fn error_event_stops_the_stream() {
+        let event = SseEvent {
+            event: Some("error".to_string()),
+            data: r#"{"type":"error","error":{"type":"overloaded_error","message":"Overloaded"}}"#.to_string(),
+        };
+        let err = apply_event(&mut StreamState::default(), &event).unwrap_err();
+        assert_eq!(err, "Anthropic stopped with an error: Overloaded");
+    }
+}
@@ -0,0 +1,125 @@
+//! Google Gemini generateContent API, streamed as server-sent events.
+
+use super::{AiRequest, SseEvent, StreamState};
+use serde_json::{json, Value};
+
+pub fn endpoint(model: &str) -> String {
+    format!(
+        "https://generativelanguage.googleapis.com/v1beta/models/{}:streamGenerateContent?alt=sse",
+        urlencoding::encode(model.trim())
+    )
+}
+
+/// Sent as a header rather than in the URL, so the key stays out of logs
+pub fn headers(key: &str) -> Vec<(String, String)> {
+    vec![("x-goog-api-key".to_string(), key.to_string())]
+}
+
+pub fn body(request: &AiRequest) -> Value {
+    let mut config = json!({ "maxOutputTokens": request.max_tokens });
+    if request.json {
+        config["responseMimeType"] = json!("application/json");
+    }
+    let mut body = json!({
+        "contents": [{ "role": "user", "parts": [{ "text": request.prompt }] }],
+        "generationConfig": config,
+    });
+    if let Some(system) = request.system.as_deref().filter(|s| !s.trim().is_empty()) {
+        body["systemInstruction"] = json!({ "parts": [{ "text": system }] });
+    }
+    body
+}
+
+/// Every chunk is a full response object holding only the new parts. Parts
+/// marked `thought` are the model's reasoning, not the answer. Usage is
+/// repeated in each chunk, so the last one wins; thinking tokens are billed
+/// as output and counted with it.
+pub fn apply_event(state: &mut StreamState, event: &SseEvent) -> Result<Option<String>, String> {
+    let data: Value = match serde_json::from_str(&event.data) {
+        Ok(v) => v,
+        Err(_) => return Ok(None),
+    };
+    if let Some(error) = data.get("error") {
+        return Err(format!(
+            "Gemini stopped with an error: {}",
+            error.get("message").and_then(|m| m.as_str()).unwrap_or("unknown error")
+        ));
+    }
+    if let Some(usage) = data.get("usageMetadata") {
+        let count = |field: &str| usage.get(field).and_then(|v| v.as_u64());
+        if let Some(prompt) = count("promptTokenCount") {
+            state.input_tokens = Some(prompt);
+        }
+        if count("candidatesTokenCount").is_some() || count("thoughtsTokenCount").is_some() {
+            state.output_tokens =
+                Some(count("candidatesTokenCount").unwrap_or(0) + count("thoughtsTokenCount").unwrap_or(0));
+        }
+    }
+    let candidate = data.pointer("/candidates/0");
+    if let Some(reason) = candidate.and_then(|c| c.get("finishReason")).and_then(|r| r.as_str()) {
+        state.stop_reason = Some(reason.to_string());
+    }
+    let text: String = candidate
+        .and_then(|c| c.pointer("/content/parts"))
+        .and_then(|p| p.as_array())
+        .map(|parts| {
+            parts
+                .iter()
+                .filter(|p| !p.get("thought").and_then(|t| t.as_bool()).unwrap_or(false))
+                .filter_map(|p| p.get("text").and_then(|t| t.as_str()))
+                .collect()
+        })
+        .unwrap_or_default();
+    if text.is_empty() {
+        return Ok(None);
+    }
+    state.text.push_str(&text);
+    Ok(Some(text))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::{Provider, SseParser};
+
+    #[test]
+    fn body_and_endpoint() {
+        let request = AiRequest {
+            provider: Provider::Gemini,
+            model: "gemini-3-pro-preview".to_string(),
+            prompt: "Assess".to_string(),
+            system: Some("Be careful".to_string()),
+            max_tokens: 16000,
+            json: true,
+        };
+        let b = body(&request);
+        assert_eq!(b["contents"][0]["parts"][0]["text"], "Assess");
+        assert_eq!(b["generationConfig"]["maxOutputTokens"], 16000);
+        assert_eq!(b["generationConfig"]["responseMimeType"], "application/json");
+        assert_eq!(b["systemInstruction"]["parts"][0]["text"], "Be careful");
+        assert_eq!(
+            endpoint(&request.model),
+            "https://generativelanguage.googleapis.com/v1beta/models/gemini-3-pro-preview:streamGenerateContent?alt=sse"
+        );
+    }
+
+    #[test]
+    fn stream_skips_thoughts_and_keeps_last_usage() {
+        let stream = concat!(
+            "data: {\"candidates\":[{\"content\":{\"parts\":[{\"text\":\"Thinking...\",\"thought\":true}],\"role\":\"model\"}}],",
+            "\"usageMetadata\":{\"promptTokenCount\":50}}\r\n\r\n",
+            "data: {\"candidates\":[{\"content\":{\"parts\":[{\"text\":\"Fair \"}],\"role\":\"model\"}}],",
+            "\"usageMetadata\":{\"promptTokenCount\":50,\"candidatesTokenCount\":1}}\r\n\r\n",
+            "data: {\"candidates\":[{\"content\":{\"parts\":[{\"text\":\"prospects.\"}],\"role\":\"model\"},\"finishReason\":\"STOP\"}],",
+            "\"usageMetadata\":{\"promptTokenCount\":50,\"candidatesTokenCount\":4,\"thoughtsTokenCount\":10}}\r\n\r\n",
+        );
+        let mut state = StreamState::default();
+        for event in SseParser::default().push(stream.as_bytes()) {
+            apply_event(&mut state, &event).unwrap();
+        }
+        assert_eq!(state.text, "Fair prospects.");
+        assert_eq!(state.input_tokens, Some(50));
+        assert_eq!(state.output_tokens, Some(14));
+        assert_eq!(state.stop_reason.as_deref(), Some("STOP"));
+    }
+}
@@ -0,0 +1,226 @@
+//! API keys for the AI providers and Companies House, kept in an encrypted file.
+//!
+//! The keys are sealed with AES-256-GCM in `<config dir>/CaseKit/ai-keys.enc`.
+//! The random 256-bit secret that opens them is written to
+//! `<local data dir>/CaseKit/ai-keys.secret`.
+//!
+//! How far apart the two files are depends on the platform:
+//!
+//! - Linux: `~/.config` and `~/.local/share`, so copying the config folder
+//!   alone doesn't carry usable keys. The secret is readable only by the user.
+//! - Windows: `AppData\Roaming` and `AppData\Local`. Roaming profiles and
+//!   some backups copy only the first. No ACL is set on the secret; it is
+//!   protected only by the permissions the user's profile folder already has.
+//! - macOS: both are `~/Library/Application Support`, so the two files sit
+//!   side by side and anything that copies one folder copies both. The secret
+//!   is readable only by the user.
+//!
+//! This keeps keys out of the webview's local storage and out of plain-text
+//! files. It is not a substitute for the system keychain: other software
+//! running as the same user, or anyone with a copy of both files, can read
+//! the keys.
+
+use super::Provider;
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN};
+use ring::rand::{SecureRandom, SystemRandom};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Leads every sealed file, and is authenticated with it
+const MAGIC: &[u8] = b"CKK1";
+const SECRET_LEN: usize = 32;
+
+/// The Companies House public data API key
+pub const COMPANIES_HOUSE: &str = "companies-house";
+
+fn store_path() -> Result<PathBuf, String> {
+    let config_dir = dirs::config_dir()
+        .ok_or_else(|| "Could not determine configuration directory".to_string())?;
+    Ok(config_dir.join("CaseKit").join("ai-keys.enc"))
+}
+
+fn secret_path() -> Result<PathBuf, String> {
+    let data_dir = dirs::data_local_dir()
+        .ok_or_else(|| "Could not determine local data directory".to_string())?;
+    Ok(data_dir.join("CaseKit").join("ai-keys.secret"))
+}
+
+/// Write a file only the current user can read. On Windows the file takes
+/// the permissions of its folder.
+fn write_private(path: &Path, bytes: &[u8]) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Could not create folder for API keys: {}", e))?;
+    }
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options
+        .open(path)
+        .map_err(|e| format!("Could not write {}: {}", path.display(), e))?;
+    std::io::Write::write_all(&mut file, bytes)
+        .map_err(|e| format!("Could not write {}: {}", path.display(), e))
+}
+
+fn load_secret(create: bool) -> Result<Option<[u8; SECRET_LEN]>, String> {
+    let path = secret_path()?;
+    if path.exists() {
+        let bytes = fs::read(&path).map_err(|e| format!("Could not read the API key secret: {}", e))?;
+        let secret: [u8; SECRET_LEN] = bytes
+            .try_into()
+            .map_err(|_| "The API key secret is damaged; remove your keys and add them again".to_string())?;
+        return Ok(Some(secret));
+    }
+    if !create {
+        return Ok(None);
+    }
+    let mut secret = [0u8; SECRET_LEN];
+    SystemRandom::new()
+        .fill(&mut secret)
+        .map_err(|_| "Could not generate a secret for the API keys".to_string())?;
+    write_private(&path, &secret)?;
+    Ok(Some(secret))
+}
+
+fn cipher(secret: &[u8; SECRET_LEN]) -> Result<LessSafeKey, String> {
+    UnboundKey::new(&AES_256_GCM, secret)
+        .map(LessSafeKey::new)
+        .map_err(|_| "Could not set up encryption for the API keys".to_string())
+}
+
+/// Encrypt with a fresh random nonce: magic, nonce, then ciphertext and tag
+pub fn seal(secret: &[u8; SECRET_LEN], plaintext: &[u8]) -> Result<Vec<u8>, String> {
+    let mut nonce = [0u8; NONCE_LEN];
+    SystemRandom::new()
+        .fill(&mut nonce)
+        .map_err(|_| "Could not generate a nonce for the API keys".to_string())?;
+    let mut data = plaintext.to_vec();
+    cipher(secret)?
+        .seal_in_place_append_tag(Nonce::assume_unique_for_key(nonce), Aad::from(MAGIC), &mut data)
+        .map_err(|_| "Could not encrypt the API keys".to_string())?;
+
+    let mut sealed = MAGIC.to_vec();
+    sealed.extend_from_slice(&nonce);
+    sealed.extend_from_slice(&data);
+    Ok(sealed)
+}
+
+pub fn open(secret: &[u8; SECRET_LEN], sealed: &[u8]) -> Result<Vec<u8>, String> {
+    let damaged = || "The saved API keys could not be decrypted; remove them and add them again".to_string();
+    let rest = sealed.strip_prefix(MAGIC).ok_or_else(damaged)?;
+    if rest.len() < NONCE_LEN {
+        return Err(damaged());
+    }
+    let (nonce, data) = rest.split_at(NONCE_LEN);
+    let nonce = Nonce::try_assume_unique_for_key(nonce).map_err(|_| damaged())?;
+    let mut data = data.to_vec();
+    let plaintext = cipher(secret)?
+        .open_in_place(nonce, Aad::from(MAGIC), &mut data)
+        .map_err(|_| damaged())?;
+    Ok(plaintext.to_vec())
+}
+
+fn load_all() -> Result<BTreeMap<String, String>, String> {
+    let path = store_path()?;
+    if !path.exists() {
+        return Ok(BTreeMap::new());
+    }
+    let secret = load_secret(false)?
+        .ok_or_else(|| "The API key secret is missing; remove your keys and add them again".to_string())?;
+    let sealed = fs::read(&path).map_err(|e| format!("Could not read ai-keys.enc: {}", e))?;
+    let plaintext = open(&secret, &sealed)?;
+    serde_json::from_slice(&plaintext).map_err(|e| format!("Could not parse the saved API keys: {}", e))
+}
+
+fn save_all(keys: &BTreeMap<String, String>) -> Result<(), String> {
+    let path = store_path()?;
+    if keys.is_empty() {
+        if path.exists() {
+            fs::remove_file(&path).map_err(|e| format!("Could not remove ai-keys.enc: {}", e))?;
+        }
+        return Ok(());
+    }
+    let secret = load_secret(true)?.ok_or_else(|| "Could not create the API key secret".to_string())?;
+    let json = serde_json::to_vec(keys).map_err(|e| format!("Could not serialise the API keys: {}", e))?;
+    write_private(&path, &seal(&secret, &json)?)
+}
+
+pub fn get(provider: Provider) -> Result<Option<String>, String> {
+    get_named(provider.id())
+}
+
+pub fn set(provider: Provider, key: &str) -> Result<(), String> {
+    if !provider.needs_key() {
+        return Err(format!("{} does not use an API key", provider.name()));
+    }
+    set_named(provider.id(), key)
+}
+
+pub fn remove(provider: Provider) -> Result<(), String> {
+    remove_named(provider.id())
+}
+
+/// Keys for services other than the AI providers are stored under their own
+/// id, e.g. [`COMPANIES_HOUSE`]
+pub fn get_named(id: &str) -> Result<Option<String>, String> {
+    Ok(load_all()?.remove(id))
+}
+
+pub fn set_named(id: &str, key: &str) -> Result<(), String> {
+    let key = key.trim();
+    if key.is_empty() {
+        return Err("Enter an API key".to_string());
+    }
+    let mut keys = load_all()?;
+    keys.insert(id.to_string(), key.to_string());
+    save_all(&keys)
+}
+
+pub fn remove_named(id: &str) -> Result<(), String> {
+    // A damaged store can only be cleared, so start again if it won't open
+    let mut keys = load_all().unwrap_or_default();
+    keys.remove(id);
+    save_all(&keys)
+}
+
+/// Providers with a saved key
+pub fn stored() -> Result<Vec<Provider>, String> {
+    let keys = load_all()?;
+    Ok(Provider::REMOTE.into_iter().filter(|p| keys.contains_key(p.id())).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sealed_keys_round_trip() {
+        let secret = [7u8; SECRET_LEN];
+        let sealed = seal(&secret, b"{\"anthropic\":\"sk-ant-123\"}").unwrap();
+        assert!(sealed.starts_with(MAGIC));
+        assert!(!sealed.windows(7).any(|w| w == b"sk-ant-"));
+        assert_eq!(open(&secret, &sealed).unwrap(), b"{\"anthropic\":\"sk-ant-123\"}");
+    }
+
+    #[test]
+    fn each_seal_uses_a_new_nonce() {
+        let secret = [1u8; SECRET_LEN];
+        assert_ne!(seal(&secret, b"same").unwrap(), seal(&secret, b"same").unwrap());
+    }
+
+    #[test]
+    fn tampering_or_wrong_secret_is_detected() {
+        let secret = [3u8; SECRET_LEN];
+        let mut sealed = seal(&secret, b"key").unwrap();
+        assert!(open(&[4u8; SECRET_LEN], &sealed).is_err());
+        let last = sealed.len() - 1;
+        sealed[last] ^= 1;
+        assert!(open(&secret, &sealed).is_err());
+        assert!(open(&secret, b"CKK1short").is_err());
+        assert!(open(&secret, b"nonsense").is_err());
+    }
+}
@@ -0,0 +1,49 @@
+//! A local provider that answers without a network connection or API key.
+//!
+//! The reply depends only on the request, so tests can rely on it. It is
+//! streamed word by word like a real response.
+
+use super::AiRequest;
+use serde_json::{json, Value};
+
+pub const ENDPOINT: &str = "local://mock";
+
+pub fn body(request: &AiRequest) -> Value {
+    json!({
+        "model": request.model,
+        "max_tokens": request.max_tokens,
+        "system": request.system,
+        "prompt": request.prompt,
+        "json": request.json,
+    })
+}
+
+/// The reply, split into the pieces it is streamed in
+pub fn reply(request: &AiRequest) -> Vec<String> {
+    let words = request.prompt.split_whitespace().count();
+    let text = if request.json {
+        json!({
+            "factualSummary": format!("Mock assessment of a prompt of {} words.", words),
+            "mock": true,
+        })
+        .to_string()
+    } else {
+        format!(
+            "This is a mock response from CaseKit. The prompt had {} words and was not sent anywhere.",
+            words
+        )
+    };
+
+    let mut pieces = Vec::new();
+    let mut current = String::new();
+    for c in text.chars() {
+        current.push(c);
+        if c == ' ' {
+            pieces.push(std::mem::take(&mut current));
+        }
+    }
+    if !current.is_empty() {
+        pieces.push(current);
+    }
+    pieces
+}
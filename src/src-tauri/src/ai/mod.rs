@@ -0,0 +1,517 @@
+//! Calls to AI providers, made from the backend rather than the webview.
+//!
+//! Every call goes through two steps. `prepare` builds the exact request that
+//! would be sent — URL, headers (with the key masked) and JSON body — so the
+//! user can see it before anything leaves the machine, along with a SHA-256 of
+//! the body. `send` builds the request again, refuses to go ahead if the body
+//! no longer matches the hash the user approved, and streams the response back
+//! a piece at a time.
+//!
+//! Anthropic, OpenAI and Gemini each have a small adapter that knows the
+//! provider's URL, headers, body shape and streaming events. The `mock`
+//! provider answers locally without a network connection or key, for tests
+//! and for trying the workflow out.
+//!
+//! API keys are held in an encrypted file by `keys`, never in the webview.
+
+pub mod anthropic;
+pub mod gemini;
+pub mod keys;
+pub mod mock;
+pub mod openai;
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::time::Duration;
+
+/// Matches the limit the review panel has always used
+pub const DEFAULT_MAX_TOKENS: u32 = 16000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Provider {
+    #[serde(rename = "anthropic")]
+    Anthropic,
+    #[serde(rename = "openai")]
+    OpenAi,
+    #[serde(rename = "gemini")]
+    Gemini,
+    #[serde(rename = "mock")]
+    Mock,
+}
+
+impl Provider {
+    /// Providers that need an API key
+    pub const REMOTE: [Provider; 3] = [Provider::Anthropic, Provider::OpenAi, Provider::Gemini];
+
+    pub fn id(&self) -> &'static str {
+        match self {
+            Provider::Anthropic => "anthropic",
+            Provider::OpenAi => "openai",
+            Provider::Gemini => "gemini",
+            Provider::Mock => "mock",
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Provider::Anthropic => "Anthropic",
+            Provider::OpenAi => "OpenAI",
+            Provider::Gemini => "Google Gemini",
+            Provider::Mock => "Mock provider",
+        }
+    }
+
+    pub fn needs_key(&self) -> bool {
+        *self != Provider::Mock
+    }
+}
+
+fn default_max_tokens() -> u32 {
+    DEFAULT_MAX_TOKENS
+}
+
+/// What the user wants to ask, independent of provider
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AiRequest {
+    pub provider: Provider,
+    pub model: String,
+    pub prompt: String,
+    #[serde(default)]
+    pub system: Option<String>,
+    #[serde(default = "default_max_tokens")]
+    pub max_tokens: u32,
+    /// Ask for a JSON object rather than free text, where the model supports it
+    #[serde(default)]
+    pub json: bool,
+}
+
+/// The exact request that will be sent, for the user to inspect
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PreparedRequest {
+    pub provider: Provider,
+    pub model: String,
+    pub method: String,
+    pub url: String,
+    /// Header names and values, with the API key masked
+    pub headers: Vec<(String, String)>,
+    pub body: serde_json::Value,
+    pub payload_bytes: usize,
+    /// SHA-256 of the body bytes; `send` refuses a request whose body differs
+    pub payload_hash: String,
+    pub estimated_input_tokens: u64,
+    /// Whether a key is saved for this provider
+    pub key_stored: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AiResponse {
+    pub provider: Provider,
+    pub model: String,
+    pub text: String,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    /// True when the provider did not report usage and the counts are estimates
+    pub tokens_estimated: bool,
+    pub stop_reason: Option<String>,
+    pub payload_hash: String,
+}
+
+/// Accumulated state while a response streams in
+#[derive(Debug, Default)]
+pub struct StreamState {
+    pub text: String,
+    pub input_tokens: Option<u64>,
+    pub output_tokens: Option<u64>,
+    pub stop_reason: Option<String>,
+}
+
+/// One server-sent event
+#[derive(Debug, Clone, PartialEq)]
+pub struct SseEvent {
+    pub event: Option<String>,
+    pub data: String,
+}
+
+/// Splits a byte stream into server-sent events. Chunks can end anywhere,
+/// including part-way through a UTF-8 character, so bytes are buffered until
+/// a blank line closes the event.
+#[derive(Debug, Default)]
+pub struct SseParser {
+    buffer: Vec<u8>,
+}
+
+impl SseParser {
+    pub fn push(&mut self, chunk: &[u8]) -> Vec<SseEvent> {
+        self.buffer.extend(chunk.iter().copied().filter(|b| *b != b'\r'));
+        let mut events = Vec::new();
+        while let Some(end) = self.buffer.windows(2).position(|w| w == b"\n\n") {
+            let block: Vec<u8> = self.buffer.drain(..end + 2).collect();
+            events.extend(parse_block(&String::from_utf8_lossy(&block)));
+        }
+        events
+    }
+
+    /// Any event left without a closing blank line when the stream ends
+    pub fn finish(&mut self) -> Option<SseEvent> {
+        let block = std::mem::take(&mut self.buffer);
+        parse_block(&String::from_utf8_lossy(&block))
+    }
+}
+
+fn parse_block(block: &str) -> Option<SseEvent> {
+    let mut event = None;
+    let mut data: Vec<&str> = Vec::new();
+    for line in block.lines() {
+        if line.starts_with(':') {
+            continue;
+        }
+        let (field, value) = line.split_once(':').unwrap_or((line, ""));
+        let value = value.strip_prefix(' ').unwrap_or(value);
+        match field {
+            "event" => event = Some(value.to_string()),
+            "data" => data.push(value),
+            _ => {}
+        }
+    }
+    if data.is_empty() {
+        return None;
+    }
+    Some(SseEvent { event, data: data.join("\n") })
+}
+
+/// Rough token count for text, about four characters per token
+pub fn estimate_tokens(text: &str) -> u64 {
+    (text.chars().count() as u64).div_ceil(4)
+}
+
+/// Show enough of a key to recognise it, and no more
+pub fn mask_key(key: &str) -> String {
+    let chars: Vec<char> = key.trim().chars().collect();
+    if chars.len() <= 12 {
+        return "••••".to_string();
+    }
+    let start: String = chars[..4].iter().collect();
+    let end: String = chars[chars.len() - 4..].iter().collect();
+    format!("{}••••{}", start, end)
+}
+
+/// Request parts that vary by provider
+struct Parts {
+    url: String,
+    headers: Vec<(String, String)>,
+    body: serde_json::Value,
+}
+
+fn parts(request: &AiRequest, key: &str) -> Result<Parts, String> {
+    if request.model.trim().is_empty() {
+        return Err("Choose a model first".to_string());
+    }
+    if request.prompt.trim().is_empty() {
+        return Err("There is nothing to send".to_string());
+    }
+    Ok(match request.provider {
+        Provider::Anthropic => Parts {
+            url: anthropic::endpoint(),
+            headers: anthropic::headers(key),
+            body: anthropic::body(request),
+        },
+        Provider::OpenAi => Parts {
+            url: openai::endpoint(),
+            headers: openai::headers(key),
+            body: openai::body(request),
+        },
+        Provider::Gemini => Parts {
+            url: gemini::endpoint(&request.model),
+            headers: gemini::headers(key),
+            body: gemini::body(request),
+        },
+        Provider::Mock => Parts {
+            url: mock::ENDPOINT.to_string(),
+            headers: Vec::new(),
+            body: mock::body(request),
+        },
+    })
+}
+
+fn body_bytes(body: &serde_json::Value) -> Result<Vec<u8>, String> {
+    serde_json::to_vec(body).map_err(|e| format!("Could not serialise the request: {}", e))
+}
+
+fn hash_hex(bytes: &[u8]) -> String {
+    Sha256::digest(bytes).iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Build the request exactly as `send` would, with the key masked
+pub fn prepare(request: &AiRequest, key_stored: bool) -> Result<PreparedRequest, String> {
+    let mut parts = parts(request, "API_KEY")?;
+    for (name, value) in parts.headers.iter_mut() {
+        if is_key_header(name) {
+            *value = value.replace("API_KEY", "••••");
+        }
+    }
+    let bytes = body_bytes(&parts.body)?;
+    Ok(PreparedRequest {
+        provider: request.provider,
+        model: request.model.clone(),
+        method: "POST".to_string(),
+        url: parts.url,
+        headers: parts.headers,
+        payload_bytes: bytes.len(),
+        payload_hash: hash_hex(&bytes),
+        estimated_input_tokens: estimate_tokens(&request.prompt)
+            + request.system.as_deref().map(estimate_tokens).unwrap_or(0),
+        key_stored: key_stored || !request.provider.needs_key(),
+        body: parts.body,
+    })
+}
+
+fn is_key_header(name: &str) -> bool {
+    matches!(
+        name.to_ascii_lowercase().as_str(),
+        "x-api-key" | "authorization" | "x-goog-api-key"
+    )
+}
+
+/// Pull the provider's own explanation out of an error body
+fn error_message(body: &str) -> Option<String> {
+    let value: serde_json::Value = serde_json::from_str(body).ok()?;
+    let error = value.get("error").or_else(|| value.get(0).and_then(|v| v.get("error")))?;
+    error
+        .get("message")
+        .and_then(|m| m.as_str())
+        .or_else(|| error.as_str())
+        .map(|m| m.to_string())
+}
+
+fn status_error(provider: Provider, status: u16, body: &str) -> String {
+    let detail = error_message(body).map(|m| format!(": {}", m)).unwrap_or_default();
+    match status {
+        401 | 403 => format!("{} rejected the API key{}", provider.name(), detail),
+        429 => format!("{} rate limit reached; wait a minute and try again{}", provider.name(), detail),
+        529 | 503 => format!("{} is overloaded; try again shortly{}", provider.name(), detail),
+        s => format!("{} returned an error (HTTP {}){}", provider.name(), s, detail),
+    }
+}
+
+/// Feed one event to the provider's adapter, returning any new text
+pub fn apply_event(provider: Provider, state: &mut StreamState, event: &SseEvent) -> Result<Option<String>, String> {
+    match provider {
+        Provider::Anthropic => anthropic::apply_event(state, event),
+        Provider::OpenAi => openai::apply_event(state, event),
+        Provider::Gemini => gemini::apply_event(state, event),
+        Provider::Mock => Ok(None),
+    }
+}
+
+fn finish(request: &AiRequest, state: StreamState, payload_hash: String) -> AiResponse {
+    let tokens_estimated = state.input_tokens.is_none() || state.output_tokens.is_none();
+    let input_tokens = state.input_tokens.unwrap_or_else(|| {
+        estimate_tokens(&request.prompt) + request.system.as_deref().map(estimate_tokens).unwrap_or(0)
+    });
+    let output_tokens = state.output_tokens.unwrap_or_else(|| estimate_tokens(&state.text));
+    AiResponse {
+        provider: request.provider,
+        model: request.model.clone(),
+        text: state.text,
+        input_tokens,
+        output_tokens,
+        tokens_estimated,
+        stop_reason: state.stop_reason,
+        payload_hash,
+    }
+}
+
+fn client() -> Result<reqwest::Client, String> {
+    reqwest::Client::builder()
+        .connect_timeout(Duration::from_secs(15))
+        // Long drafts can take several minutes to stream
+        .timeout(Duration::from_secs(600))
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))
+}
+
+/// Send a request and stream the reply, calling `on_text` with each piece of
+/// text as it arrives. If `approved_hash` is given the body must match it.
+pub async fn send(
+    request: &AiRequest,
+    api_key: Option<&str>,
+    approved_hash: Option<&str>,
+    mut on_text: impl FnMut(&str),
+) -> Result<AiResponse, String> {
+    let key = match (request.provider.needs_key(), api_key) {
+        (true, Some(k)) if !k.trim().is_empty() => k.trim(),
+        (true, _) => return Err(format!("Add an API key for {} first", request.provider.name())),
+        (false, _) => "",
+    };
+
+    let parts = parts(request, key)?;
+    let bytes = body_bytes(&parts.body)?;
+    let payload_hash = hash_hex(&bytes);
+    if approved_hash.is_some_and(|h| h != payload_hash) {
+        return Err("The request has changed since it was previewed. Review it again before sending.".to_string());
+    }
+
+    if request.provider == Provider::Mock {
+        let mut state = StreamState::default();
+        for piece in mock::reply(request) {
+            on_text(&piece);
+            state.text.push_str(&piece);
+        }
+        state.stop_reason = Some("end_turn".to_string());
+        return Ok(finish(request, state, payload_hash));
+    }
+
+    let mut builder = client()?
+        .post(&parts.url)
+        .header("content-type", "application/json")
+        .header("accept", "text/event-stream");
+    for (name, value) in &parts.headers {
+        builder = builder.header(name.as_str(), value.as_str());
+    }
+    let mut response = builder
+        .body(bytes)
+        .send()
+        .await
+        .map_err(|e| format!("Could not reach {}: {}", request.provider.name(), e))?;
+
+    let status = response.status().as_u16();
+    if status >= 400 {
+        let body = response.text().await.unwrap_or_default();
+        return Err(status_error(request.provider, status, &body));
+    }
+
+    let mut parser = SseParser::default();
+    let mut state = StreamState::default();
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .map_err(|e| format!("The connection to {} dropped: {}", request.provider.name(), e))?
+    {
+        for event in parser.push(&chunk) {
+            if let Some(text) = apply_event(request.provider, &mut state, &event)? {
+                on_text(&text);
+            }
+        }
+    }
+    if let Some(event) = parser.finish() {
+        if let Some(text) = apply_event(request.provider, &mut state, &event)? {
+            on_text(&text);
+        }
+    }
+
+    Ok(finish(request, state, payload_hash))
+}
+
+/// Check a key with the smallest possible request
+pub async fn test_key(provider: Provider, model: &str, api_key: &str) -> Result<String, String> {
+    let request = AiRequest {
+        provider,
+        model: model.to_string(),
+        prompt: "Reply with exactly: \"CaseKit connection successful.\"".to_string(),
+        system: None,
+        max_tokens: 100,
+        json: false,
+    };
+    let response = send(&request, Some(api_key), None, |_| {}).await?;
+    Ok(response.text.trim().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(provider: Provider) -> AiRequest {
+        AiRequest {
+            provider,
+            model: "test-model".to_string(),
+            prompt: "Summarise the dispute".to_string(),
+            system: None,
+            max_tokens: DEFAULT_MAX_TOKENS,
+            json: false,
+        }
+    }
+
+    #[test]
+    fn sse_events_split_across_chunks() {
+        let mut parser = SseParser::default();
+        assert!(parser.push(b"event: ping\r\ndata: {\"a\"").is_empty());
+        let events = parser.push(b":1}\r\n\r\ndata: one\ndata: two\n\n: comment\n\n");
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].event.as_deref(), Some("ping"));
+        assert_eq!(events[0].data, "{\"a\":1}");
+        assert_eq!(events[1].data, "one\ntwo");
+        assert!(parser.finish().is_none());
+    }
+
+    #[test]
+    fn sse_keeps_split_utf8_characters() {
+        let mut parser = SseParser::default();
+        let bytes = "data: £5\n\n".as_bytes();
+        assert!(parser.push(&bytes[..7]).is_empty());
+        let events = parser.push(&bytes[7..]);
+        assert_eq!(events[0].data, "£5");
+    }
+
+    #[test]
+    fn preview_masks_key_and_hashes_body() {
+        let prepared = prepare(&request(Provider::Anthropic), true).unwrap();
+        assert_eq!(prepared.url, "https://api.anthropic.com/v1/messages");
+        let key = prepared.headers.iter().find(|(n, _)| n == "x-api-key").unwrap();
+        assert_eq!(key.1, "••••");
+        assert_eq!(prepared.payload_hash.len(), 64);
+        assert_eq!(prepared.payload_bytes, serde_json::to_vec(&prepared.body).unwrap().len());
+
+        let mut changed = request(Provider::Anthropic);
+        changed.prompt.push('!');
+        assert_ne!(prepare(&changed, true).unwrap().payload_hash, prepared.payload_hash);
+    }
+
+    #[test]
+    fn empty_prompt_is_refused() {
+        let mut r = request(Provider::OpenAi);
+        r.prompt = "  ".to_string();
+        assert!(prepare(&r, true).is_err());
+    }
+
+    #[test]
+    fn masking_keeps_only_the_ends() {
+        assert_eq!(mask_key("sk-ant-abcdefghijklmnop"), "sk-a••••mnop");
+        assert_eq!(mask_key("short"), "••••");
+    }
+
+    #[test]
+    fn status_errors_use_the_provider_message() {
+        let body = r#"{"type":"error","error":{"type":"authentication_error","message":"invalid x-api-key"}}"#;
+        assert_eq!(
+            status_error(Provider::Anthropic, 401, body),
+            "Anthropic rejected the API key: invalid x-api-key"
+        );
+        assert_eq!(status_error(Provider::Gemini, 500, ""), "Google Gemini returned an error (HTTP 500)");
+    }
+
+    #[tokio::test]
+    async fn mock_provider_streams_and_counts_tokens() {
+        let r = request(Provider::Mock);
+        let hash = prepare(&r, false).unwrap().payload_hash;
+        let mut pieces = Vec::new();
+        let response = send(&r, None, Some(&hash), |t| pieces.push(t.to_string())).await.unwrap();
+        assert!(pieces.len() > 1);
+        assert_eq!(pieces.concat(), response.text);
+        assert_eq!(response.input_tokens, estimate_tokens(&r.prompt));
+        assert_eq!(response.output_tokens, estimate_tokens(&response.text));
+        assert!(response.tokens_estimated);
+    }
+
+    #[tokio::test]
+    async fn changed_payload_is_not_sent() {
+        let r = request(Provider::Mock);
+        let err = send(&r, None, Some("0000"), |_| {}).await.unwrap_err();
+        assert!(err.contains("changed since it was previewed"));
+    }
+
+    #[tokio::test]
+    async fn remote_provider_needs_a_key() {
+        let err = send(&request(Provider::OpenAi), None, None, |_| {}).await.unwrap_err();
+        assert_eq!(err, "Add an API key for OpenAI first");
+    }
+}
@@ -0,0 +1,139 @@
+//! OpenAI Chat Completions API, streamed.
+
+use super::{AiRequest, SseEvent, StreamState};
+use serde_json::{json, Value};
+
+pub fn endpoint() -> String {
+    "https://api.openai.com/v1/chat/completions".to_string()
+}
+
+pub fn headers(key: &str) -> Vec<(String, String)> {
+    vec![("Authorization".to_string(), format!("Bearer {}", key))]
+}
+
+/// o3, o4-mini and the like take max_completion_tokens and can't be forced
+/// into JSON mode
+pub fn is_reasoning_model(model: &str) -> bool {
+    let mut chars = model.chars();
+    chars.next() == Some('o') && chars.next().is_some_and(|c| c.is_ascii_digit())
+}
+
+pub fn body(request: &AiRequest) -> Value {
+    let reasoning = is_reasoning_model(&request.model);
+    let mut messages = Vec::new();
+    if let Some(system) = request.system.as_deref().filter(|s| !s.trim().is_empty()) {
+        messages.push(json!({ "role": "system", "content": system }));
+    }
+    messages.push(json!({ "role": "user", "content": request.prompt }));
+
+    let mut body = json!({
+        "model": request.model,
+        "messages": messages,
+        "stream": true,
+        // Usage only arrives in the final chunk, and only if asked for
+        "stream_options": { "include_usage": true },
+    });
+    let limit = if reasoning { "max_completion_tokens" } else { "max_tokens" };
+    body[limit] = json!(request.max_tokens);
+    if request.json && !reasoning {
+        body["response_format"] = json!({ "type": "json_object" });
+    }
+    body
+}
+
+/// Each chunk carries a text delta in choices[0]; the last carries usage with
+/// no choices. The stream ends with a literal `[DONE]`.
+pub fn apply_event(state: &mut StreamState, event: &SseEvent) -> Result<Option<String>, String> {
+    if event.data.trim() == "[DONE]" {
+        return Ok(None);
+    }
+    let data: Value = match serde_json::from_str(&event.data) {
+        Ok(v) => v,
+        Err(_) => return Ok(None),
+    };
+    if let Some(error) = data.get("error") {
+        return Err(format!(
+            "OpenAI stopped with an error: {}",
+            error.get("message").and_then(|m| m.as_str()).unwrap_or("unknown error")
+        ));
+    }
+    if let Some(usage) = data.get("usage").filter(|u| !u.is_null()) {
+        state.input_tokens = usage.get("prompt_tokens").and_then(|v| v.as_u64());
+        state.output_tokens = usage.get("completion_tokens").and_then(|v| v.as_u64());
+    }
+    let choice = data.pointer("/choices/0");
+    if let Some(reason) = choice.and_then(|c| c.get("finish_reason")).and_then(|r| r.as_str()) {
+        state.stop_reason = Some(reason.to_string());
+    }
+    let text = choice
+        .and_then(|c| c.pointer("/delta/content"))
+        .and_then(|t| t.as_str())
+        .unwrap_or_default();
+    if text.is_empty() {
+        return Ok(None);
+    }
+    state.text.push_str(text);
+    Ok(Some(text.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::{Provider, SseParser};
+
+    fn request(model: &str, json: bool) -> AiRequest {
+        AiRequest {
+            provider: Provider::OpenAi,
+            model: model.to_string(),
+            prompt: "Assess the merits".to_string(),
+            system: Some("You are careful".to_string()),
+            max_tokens: 16000,
+            json,
+        }
+    }
+
+    #[test]
+    fn json_mode_only_for_chat_models() {
+        let b = body(&request("gpt-5.2", true));
+        assert_eq!(b["response_format"]["type"], "json_object");
+        assert_eq!(b["max_tokens"], 16000);
+        assert_eq!(b["messages"][0]["role"], "system");
+        assert_eq!(b["messages"][1]["content"], "Assess the merits");
+        assert_eq!(b["stream_options"]["include_usage"], true);
+
+        let b = body(&request("o4-mini", true));
+        assert!(b.get("response_format").is_none());
+        assert!(b.get("max_tokens").is_none());
+        assert_eq!(b["max_completion_tokens"], 16000);
+    }
+
+    #[test]
+    fn reasoning_models_are_recognised() {
+        assert!(is_reasoning_model("o3"));
+        assert!(is_reasoning_model("o4-mini"));
+        assert!(!is_reasoning_model("gpt-4o"));
+        assert!(!is_reasoning_model("omni"));
+    }
+
+    #[test]
+    fn stream_collects_text_and_usage() {
+        let stream = concat!(
+            "data: {\"choices\":[{\"index\":0,\"delta\":{\"role\":\"assistant\",\"content\":\"\"}}],\"usage\":null}\n\n",
+            "data: {\"choices\":[{\"index\":0,\"delta\":{\"content\":\"Strong \"}}],\"usage\":null}\n\n",
+            "data: {\"choices\":[{\"index\":0,\"delta\":{\"content\":\"case.\"},\"finish_reason\":\"stop\"}],\"usage\":null}\n\n",
+            "data: {\"choices\":[],\"usage\":{\"prompt_tokens\":88,\"completion_tokens\":3}}\n\n",
+            "data: [DONE]\n\n",
+        );
+        let mut state = StreamState::default();
+        let mut pieces = Vec::new();
+        for event in SseParser::default().push(stream.as_bytes()) {
+            if let Some(t) = apply_event(&mut state, &event).unwrap() {
+                pieces.push(t);
+            }
+        }
+        assert_eq!(pieces, vec!["Strong ", "case."]);
+        assert_eq!(state.input_tokens, Some(88));
+        assert_eq!(state.output_tokens, Some(3));
+        assert_eq!(state.stop_reason.as_deref(), Some("stop"));
+    }
+}
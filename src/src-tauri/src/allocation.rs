@@ -0,0 +1,322 @@
+//! Track allocation advice (CPR 26).
+//!
+//! Predicts which track the court is likely to allocate the claim to, using
+//! the value thresholds in force from 1 October 2023, the personal injury
+//! sub-thresholds and the CPR 26.13 factors that can move a claim up a track.
+//! Also suggests the fixed recoverable costs complexity band and derives the
+//! case's `overall_risk`. The court decides allocation — this is a prediction.
+
+use crate::models::case::{CaseMetadata, Track};
+use crate::models::money::Money;
+use serde::{Deserialize, Serialize};
+
+const SMALL_CLAIMS_LIMIT: Money = Money::from_pounds(10_000);
+const FAST_TRACK_LIMIT: Money = Money::from_pounds(25_000);
+const INTERMEDIATE_TRACK_LIMIT: Money = Money::from_pounds(100_000);
+
+/// Personal injury claims only stay on the small claims track if damages for
+/// pain, suffering and loss of amenity are no more than this (CPR 26.9)
+const PI_SMALL_CLAIMS_PSLA_LIMIT: Money = Money::from_pounds(1_500);
+
+/// Road traffic accident claims have higher limits: PSLA damages up to this,
+/// with the claim as a whole within the small claims limit (CPR 26.9)
+const RTA_SMALL_CLAIMS_PSLA_LIMIT: Money = Money::from_pounds(5_000);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrackAdvice {
+    pub track: Track,
+    /// The track by value alone, before any other factor
+    pub track_by_value: Track,
+    /// Why the track can't be predicted yet, e.g. a personal injury claim
+    /// without the PSLA figure. `track` is then the track by value.
+    pub undetermined: Option<String>,
+    pub value: Money,
+    /// Fixed recoverable costs complexity band (1–4), for fast and intermediate track claims
+    pub frc_band: Option<u8>,
+    pub frc_explanation: Option<String>,
+    /// Why this track, step by step, with CPR references
+    pub reasons: Vec<String>,
+    /// Things that could lead the court to allocate differently
+    pub factors: Vec<String>,
+    /// "within_scope", "borderline" or "seek_advice"
+    pub overall_risk: String,
+}
+
+/// The normal track for a claim of this value (CPR 26.9)
+pub fn track_for_value(value: Money) -> Track {
+    if value <= SMALL_CLAIMS_LIMIT {
+        Track::SmallClaims
+    } else if value <= FAST_TRACK_LIMIT {
+        Track::Fast
+    } else if value <= INTERMEDIATE_TRACK_LIMIT {
+        Track::Intermediate
+    } else {
+        Track::Multi
+    }
+}
+
+pub fn track_name(track: Track) -> &'static str {
+    match track {
+        Track::SmallClaims => "small claims track",
+        Track::Fast => "fast track",
+        Track::Intermediate => "intermediate track",
+        Track::Multi => "multi-track",
+    }
+}
+
+fn next_track(track: Track) -> Track {
+    match track {
+        Track::SmallClaims => Track::Fast,
+        Track::Fast => Track::Intermediate,
+        Track::Intermediate | Track::Multi => Track::Multi,
+    }
+}
+
+/// Indicative FRC band for the claim (CPR 26.15 Tables 1 and 2). Consumer
+/// claims are contract claims, so they fall in the "any other claim" rows
+/// unless complexity pushes them higher.
+fn frc_band(track: Track, case: &CaseMetadata, complex: bool) -> Option<(u8, String)> {
+    match track {
+        Track::Fast => Some(if case.personal_injury {
+            (4, "Band 4 — personal injury claims outside the road traffic and accident rows".to_string())
+        } else if complex {
+            (4, "Band 4 — complex claims".to_string())
+        } else {
+            (4, "Band 4 — any other claim not listed in Bands 1–3".to_string())
+        }),
+        Track::Intermediate => Some(if complex || case.personal_injury {
+            (4, "Band 4 — complex claims, or personal injury outside the accident rows".to_string())
+        } else {
+            (3, "Band 3 — any other claim with more than one issue in dispute".to_string())
+        }),
+        Track::SmallClaims | Track::Multi => None,
+    }
+}
+
+/// Recommend a track for the case and explain why
+pub fn advise(case: &CaseMetadata) -> TrackAdvice {
+    let value = case.total_claim_value();
+    let by_value = track_for_value(value);
+    let mut track = by_value;
+    let mut reasons = vec![format!(
+        "Claim value {} falls within the {} (CPR 26.9)",
+        value,
+        track_name(by_value)
+    )];
+    let mut factors = Vec::new();
+    let mut undetermined = None;
+
+    if case.personal_injury && track == Track::SmallClaims {
+        match case.psla_damages {
+            None => {
+                let why = format!(
+                    "Personal injury: the track can't be predicted without the damages claimed for pain, suffering and loss of amenity. The claim stays on the small claims track only if they are {} or less (CPR 26.9)",
+                    PI_SMALL_CLAIMS_PSLA_LIMIT
+                );
+                reasons.push(why.clone());
+                undetermined = Some(why);
+            }
+            Some(psla) if psla > PI_SMALL_CLAIMS_PSLA_LIMIT => {
+                track = Track::Fast;
+                reasons.push(format!(
+                    "Personal injury: damages for pain, suffering and loss of amenity of {} are over the {} small claims limit, so fast track (CPR 26.9)",
+                    psla, PI_SMALL_CLAIMS_PSLA_LIMIT
+                ));
+            }
+            Some(psla) => reasons.push(format!(
+                "Personal injury: damages for pain, suffering and loss of amenity of {} are within the {} small claims limit (CPR 26.9)",
+                psla, PI_SMALL_CLAIMS_PSLA_LIMIT
+            )),
+        }
+        factors.push(format!(
+            "Road traffic accident claims have higher limits: small claims track if damages for pain, suffering and loss of amenity are {} or less and the claim as a whole is {} or less (CPR 26.9)",
+            RTA_SMALL_CLAIMS_PSLA_LIMIT, SMALL_CLAIMS_LIMIT
+        ));
+    }
+
+    // CPR 26.13 factors that can justify a higher track
+    let complex = !case.complexity_triggers.is_empty();
+    if complex {
+        for trigger in &case.complexity_triggers {
+            factors.push(format!("Complexity: {} (CPR 26.13(1)(c))", trigger));
+        }
+        if track != Track::Multi {
+            let higher = next_track(track);
+            reasons.push(format!(
+                "Complexity factors may justify the {} instead (CPR 26.13)",
+                track_name(higher)
+            ));
+        }
+    }
+    if case.multiple_parties {
+        factors.push("More than one claimant or defendant (CPR 26.13(1)(d))".to_string());
+        if track == Track::Intermediate {
+            reasons.push("Several parties make a trial within the intermediate track's three-day limit less likely, so the multi-track is possible".to_string());
+        }
+    }
+    if case.counterclaim {
+        factors.push("Counterclaim: its value is considered too, and the track normally follows the larger claim (CPR 26.13(1)(e))".to_string());
+    }
+    if case.existing_proceedings {
+        factors.push("Related proceedings may be managed together".to_string());
+    }
+
+    let frc = frc_band(track, case, complex);
+    match track {
+        Track::SmallClaims => reasons.push(
+            "Small claims costs are very limited: usually only court fees and fixed issue costs are recoverable (CPR 27.14)".to_string(),
+        ),
+        Track::Fast | Track::Intermediate => reasons.push(
+            "Fixed recoverable costs apply, so the costs either side can recover are capped by the complexity band (CPR 45)".to_string(),
+        ),
+        Track::Multi => reasons.push(
+            "Multi-track costs are assessed, so the loser can face a substantial costs bill (CPR 44)".to_string(),
+        ),
+    }
+
+    let overall_risk = overall_risk(case, track, complex);
+
+    TrackAdvice {
+        track,
+        track_by_value: by_value,
+        undetermined,
+        value,
+        frc_band: frc.as_ref().map(|(b, _)| *b),
+        frc_explanation: frc.map(|(_, e)| e),
+        reasons,
+        factors,
+        overall_risk,
+    }
+}
+
+/// The case's risk rating: litigants in person are best placed on the small
+/// claims track; costs exposure and complexity raise the risk
+fn overall_risk(case: &CaseMetadata, track: Track, complex: bool) -> String {
+    let serious = case.insolvency
+        || case.cross_border
+        || case.regulatory_overlap
+        || case.existing_proceedings;
+
+    let risk = match track {
+        Track::Intermediate | Track::Multi => "seek_advice",
+        _ if serious => "seek_advice",
+        Track::Fast => "borderline",
+        Track::SmallClaims
+            if complex || case.personal_injury || case.multiple_parties || case.counterclaim =>
+        {
+            "borderline"
+        }
+        Track::SmallClaims => "within_scope",
+    };
+    risk.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn case_worth(pounds: i64) -> CaseMetadata {
+        CaseMetadata {
+            claim_value: Money::from_pounds(pounds),
+            ..CaseMetadata::default()
+        }
+    }
+
+    #[test]
+    fn value_thresholds() {
+        assert_eq!(track_for_value(Money::from_pounds(10_000)), Track::SmallClaims);
+        assert_eq!(track_for_value(Money::from_pence(1_000_001)), Track::Fast);
+        assert_eq!(track_for_value(Money::from_pounds(25_000)), Track::Fast);
+        assert_eq!(track_for_value(Money::from_pounds(100_000)), Track::Intermediate);
+        assert_eq!(track_for_value(Money::from_pounds(100_001)), Track::Multi);
+    }
+
+    #[test]
+    fn simple_small_claim_is_within_scope() {
+        let advice = advise(&case_worth(850));
+        assert_eq!(advice.track, Track::SmallClaims);
+        assert_eq!(advice.frc_band, None);
+        assert_eq!(advice.overall_risk, "within_scope");
+    }
+
+    #[test]
+    fn personal_injury_moves_up_to_fast_track() {
+        let mut case = case_worth(4_000);
+        case.personal_injury = true;
+        case.psla_damages = Some(Money::from_pounds(3_000));
+        let advice = advise(&case);
+        assert_eq!(advice.track_by_value, Track::SmallClaims);
+        assert_eq!(advice.track, Track::Fast);
+        assert_eq!(advice.frc_band, Some(4));
+        assert_eq!(advice.overall_risk, "borderline");
+    }
+
+    #[test]
+    fn personal_injury_small_claims_limit_is_1500() {
+        let mut case = case_worth(5_000);
+        case.personal_injury = true;
+        case.psla_damages = Some(Money::from_pounds(1_500));
+        assert_eq!(advise(&case).track, Track::SmallClaims);
+        case.psla_damages = Some(Money::from_pence(150_001));
+        assert_eq!(advise(&case).track, Track::Fast);
+    }
+
+    #[test]
+    fn road_traffic_limits_are_stated_correctly() {
+        let mut case = case_worth(1_200);
+        case.personal_injury = true;
+        let advice = advise(&case);
+        let rta = advice.factors.iter().find(|f| f.starts_with("Road traffic accident")).unwrap();
+        assert!(rta.contains("£5,000.00 or less"), "{}", rta);
+        assert!(rta.contains("£10,000.00 or less"), "{}", rta);
+    }
+
+    #[test]
+    fn only_the_psla_damages_count_towards_the_personal_injury_limit() {
+        // £200 for the injury and £1,000 of special damages
+        let mut case = case_worth(1_200);
+        case.personal_injury = true;
+        case.psla_damages = Some(Money::from_pounds(200));
+        let advice = advise(&case);
+        assert_eq!(advice.track, Track::SmallClaims);
+        assert!(advice.undetermined.is_none());
+
+        case.psla_damages = None;
+        let advice = advise(&case);
+        assert_eq!(advice.track, Track::SmallClaims);
+        assert!(advice.undetermined.unwrap().contains("pain, suffering and loss of amenity"));
+    }
+
+    #[test]
+    fn intermediate_claims_need_advice() {
+        let advice = advise(&case_worth(60_000));
+        assert_eq!(advice.track, Track::Intermediate);
+        assert_eq!(advice.frc_band, Some(3));
+        assert_eq!(advice.overall_risk, "seek_advice");
+
+        let mut case = case_worth(60_000);
+        case.multiple_parties = true;
+        let advice = advise(&case);
+        assert_eq!(advice.track, Track::Intermediate);
+        assert!(advice.factors.iter().any(|f| f.contains("More than one claimant")));
+    }
+
+    #[test]
+    fn claim_heads_drive_the_value() {
+        let mut case = case_worth(500);
+        case.claim_heads = vec![crate::models::case::ClaimHead {
+            id: "h1".to_string(),
+            description: "Rebuild".to_string(),
+            amount: Money::from_pounds(12_000),
+            evidence_doc_ids: Vec::new(),
+        }];
+        assert_eq!(advise(&case).track, Track::Fast);
+    }
+
+    #[test]
+    fn insolvency_always_needs_advice() {
+        let mut case = case_worth(500);
+        case.insolvency = true;
+        assert_eq!(advise(&case).overall_risk, "seek_advice");
+    }
+}
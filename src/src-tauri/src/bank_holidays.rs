@@ -0,0 +1,198 @@
+//! England & Wales bank holiday calendar, bundled for offline use.
+//!
+//! The bundled list (from gov.uk/bank-holidays) includes one-off holidays
+//! such as jubilees and state funerals. For years outside it, the standard
+//! statutory pattern is calculated instead, so deadlines far ahead are still
+//! computed — but may miss a one-off holiday announced later.
+
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
+use serde::Deserialize;
+use std::collections::BTreeSet;
+use std::sync::OnceLock;
+
+const BUNDLED: &str = include_str!("../data/bank-holidays-england-and-wales.json");
+
+#[derive(Deserialize)]
+struct BundledCalendar {
+    events: Vec<BundledEvent>,
+}
+
+#[derive(Deserialize)]
+struct BundledEvent {
+    date: String,
+}
+
+struct Calendar {
+    dates: BTreeSet<NaiveDate>,
+    first_year: i32,
+    last_year: i32,
+}
+
+fn calendar() -> &'static Calendar {
+    static CALENDAR: OnceLock<Calendar> = OnceLock::new();
+    CALENDAR.get_or_init(|| {
+        let parsed: BundledCalendar =
+            serde_json::from_str(BUNDLED).expect("bundled bank holiday data is valid JSON");
+        let dates: BTreeSet<NaiveDate> = parsed
+            .events
+            .iter()
+            .filter_map(|e| NaiveDate::parse_from_str(&e.date, "%Y-%m-%d").ok())
+            .collect();
+        let first_year = dates.iter().next().map(|d| d.year()).unwrap_or(i32::MAX);
+        let last_year = dates.iter().next_back().map(|d| d.year()).unwrap_or(i32::MIN);
+        Calendar {
+            dates,
+            first_year,
+            last_year,
+        }
+    })
+}
+
+/// Easter Sunday (anonymous Gregorian algorithm)
+fn easter_sunday(year: i32) -> NaiveDate {
+    let a = year % 19;
+    let b = year / 100;
+    let c = year % 100;
+    let d = b / 4;
+    let e = b % 4;
+    let f = (b + 8) / 25;
+    let g = (b - f + 1) / 3;
+    let h = (19 * a + b - d - g + 15) % 30;
+    let i = c / 4;
+    let k = c % 4;
+    let l = (32 + 2 * e + 2 * i - h - k) % 7;
+    let m = (a + 11 * h + 22 * l) / 451;
+    let month = (h + l - 7 * m + 114) / 31;
+    let day = (h + l - 7 * m + 114) % 31 + 1;
+    NaiveDate::from_ymd_opt(year, month as u32, day as u32).expect("valid Easter date")
+}
+
+fn first_monday(year: i32, month: u32) -> NaiveDate {
+    let mut d = NaiveDate::from_ymd_opt(year, month, 1).expect("valid month");
+    while d.weekday() != Weekday::Mon {
+        d += Duration::days(1);
+    }
+    d
+}
+
+fn last_monday(year: i32, month: u32) -> NaiveDate {
+    let mut d = NaiveDate::from_ymd_opt(year, month + 1, 1).expect("valid month") - Duration::days(1);
+    while d.weekday() != Weekday::Mon {
+        d -= Duration::days(1);
+    }
+    d
+}
+
+fn is_weekend(date: NaiveDate) -> bool {
+    matches!(date.weekday(), Weekday::Sat | Weekday::Sun)
+}
+
+/// Move a holiday falling at a weekend to the next free weekday
+fn substitute(date: NaiveDate, taken: &[NaiveDate]) -> NaiveDate {
+    let mut d = date;
+    while is_weekend(d) || taken.contains(&d) {
+        d += Duration::days(1);
+    }
+    d
+}
+
+/// The usual eight England & Wales bank holidays for a year
+fn standard_holidays(year: i32) -> Vec<NaiveDate> {
+    let easter = easter_sunday(year);
+    let new_year = substitute(NaiveDate::from_ymd_opt(year, 1, 1).expect("valid date"), &[]);
+    let christmas = substitute(NaiveDate::from_ymd_opt(year, 12, 25).expect("valid date"), &[]);
+    let boxing_day = substitute(NaiveDate::from_ymd_opt(year, 12, 26).expect("valid date"), &[christmas]);
+
+    vec![
+        new_year,
+        easter - Duration::days(2),
+        easter + Duration::days(1),
+        first_monday(year, 5),
+        last_monday(year, 5),
+        last_monday(year, 8),
+        christmas,
+        boxing_day,
+    ]
+}
+
+/// Whether a date is an England & Wales bank holiday
+pub fn is_bank_holiday(date: NaiveDate) -> bool {
+    let cal = calendar();
+    if (cal.first_year..=cal.last_year).contains(&date.year()) {
+        cal.dates.contains(&date)
+    } else {
+        standard_holidays(date.year()).contains(&date)
+    }
+}
+
+/// A "business day" under CPR 6.2(b): not a Saturday, Sunday, bank holiday,
+/// Good Friday or Christmas Day
+pub fn is_business_day(date: NaiveDate) -> bool {
+    let good_friday = easter_sunday(date.year()) - Duration::days(2);
+    let christmas = NaiveDate::from_ymd_opt(date.year(), 12, 25);
+    !is_weekend(date) && !is_bank_holiday(date) && date != good_friday && Some(date) != christmas
+}
+
+/// The next business day strictly after `date`
+pub fn next_business_day(date: NaiveDate) -> NaiveDate {
+    let mut d = date + Duration::days(1);
+    while !is_business_day(d) {
+        d += Duration::days(1);
+    }
+    d
+}
+
+/// Add `n` business days after `date`
+pub fn add_business_days(date: NaiveDate, n: u32) -> NaiveDate {
+    (0..n).fold(date, |d, _| next_business_day(d))
+}
+
+/// `date` itself if it is a business day, otherwise the next one
+pub fn roll_forward(date: NaiveDate) -> NaiveDate {
+    if is_business_day(date) {
+        date
+    } else {
+        next_business_day(date)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn bundled_one_off_holidays() {
+        assert!(is_bank_holiday(date("2022-09-19"))); // State funeral
+        assert!(is_bank_holiday(date("2023-05-08"))); // Coronation
+        assert!(is_bank_holiday(date("2020-05-08"))); // VE day, moved early May holiday
+        assert!(!is_bank_holiday(date("2020-05-04")));
+    }
+
+    #[test]
+    fn computed_years_match_statutory_pattern() {
+        // 2030 is outside the bundled data
+        assert!(is_bank_holiday(date("2030-04-19"))); // Good Friday
+        assert!(is_bank_holiday(date("2030-04-22"))); // Easter Monday
+        assert!(is_bank_holiday(date("2030-05-06"))); // Early May
+        assert!(is_bank_holiday(date("2030-08-26"))); // Summer
+    }
+
+    #[test]
+    fn substitute_days_over_christmas() {
+        // Christmas 2027 falls on a Saturday
+        assert!(is_bank_holiday(date("2027-12-27")));
+        assert!(is_bank_holiday(date("2027-12-28")));
+        assert_eq!(next_business_day(date("2027-12-24")), date("2027-12-29"));
+    }
+
+    #[test]
+    fn business_days_skip_weekends_and_holidays() {
+        // Thursday before Easter 2024 → next business day is the Tuesday after
+        assert_eq!(add_business_days(date("2024-03-28"), 1), date("2024-04-02"));
+        assert_eq!(add_business_days(date("2024-03-28"), 2), date("2024-04-03"));
+    }
+}
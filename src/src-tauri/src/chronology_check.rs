@@ -0,0 +1,410 @@
+//! Consistency checks over a built chronology.
+//!
+//! Flags events in an order that can't have happened (a complaint before the
+//! purchase, a defence before the claim was issued), long silences, entries
+//! that record the same thing twice, and intake-form dates that the documents
+//! contradict. Every issue lists the entries involved so the user can jump
+//! to them and decide which date is wrong — nothing is changed here.
+
+use crate::models::case::CaseMetadata;
+use crate::models::chronology::{ChronoDate, ChronologyEntry, ChronologySource, EventType};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// Silences longer than this are reported unless the user sets their own period
+pub const DEFAULT_GAP_DAYS: u32 = 90;
+
+/// Document dates this close to an intake date are taken to agree with it
+const INTAKE_TOLERANCE_DAYS: i64 = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum IssueKind {
+    #[serde(rename = "impossible_order")]
+    ImpossibleOrder,
+    #[serde(rename = "gap")]
+    Gap,
+    #[serde(rename = "duplicate")]
+    Duplicate,
+    #[serde(rename = "intake_contradicted")]
+    IntakeContradicted,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum IssueSeverity {
+    /// Probably a wrong date somewhere
+    #[serde(rename = "warning")]
+    Warning,
+    /// Worth a look, may be fine
+    #[serde(rename = "info")]
+    Info,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChronologyIssue {
+    pub kind: IssueKind,
+    pub severity: IssueSeverity,
+    pub message: String,
+    /// Ids of the chronology entries involved, earliest first
+    pub entry_ids: Vec<String>,
+}
+
+/// Events that must come first, paired with those that can only follow
+const ORDER: &[(EventType, EventType)] = &[
+    (EventType::OrderPlaced, EventType::Delivered),
+    (EventType::OrderPlaced, EventType::FaultReported),
+    (EventType::OrderPlaced, EventType::RefundRequested),
+    (EventType::OrderPlaced, EventType::RefundRefused),
+    (EventType::OrderPlaced, EventType::LetterBeforeClaimSent),
+    (EventType::OrderPlaced, EventType::ClaimIssued),
+    (EventType::RefundRequested, EventType::RefundRefused),
+    (EventType::LetterBeforeClaimSent, EventType::ClaimIssued),
+    (EventType::ClaimIssued, EventType::ClaimServed),
+    (EventType::ClaimIssued, EventType::AcknowledgmentFiled),
+    (EventType::ClaimIssued, EventType::DefenceFiled),
+    (EventType::ClaimIssued, EventType::DirectionsQuestionnaire),
+    (EventType::ClaimIssued, EventType::Allocated),
+    (EventType::ClaimIssued, EventType::HearingListed),
+    (EventType::ClaimIssued, EventType::Judgment),
+    (EventType::DefenceFiled, EventType::Allocated),
+    (EventType::Allocated, EventType::HearingListed),
+];
+
+/// The event an entry records: what the classifier found, or what the
+/// intake entry stands for
+fn event_of(entry: &ChronologyEntry) -> Option<EventType> {
+    entry.event_type.or(match entry.id.as_str() {
+        "intake-purchase" => Some(EventType::OrderPlaced),
+        "intake-problem" | "intake-complaint" => Some(EventType::FaultReported),
+        _ => None,
+    })
+}
+
+fn label(entry: &ChronologyEntry) -> String {
+    format!("\"{}\" ({})", entry.description.trim(), entry.date)
+}
+
+fn parse_date(s: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(s.trim(), "%Y-%m-%d").ok()
+}
+
+/// `later` certainly happened before `earlier` — their possible dates don't overlap
+fn definitely_before(later: &ChronoDate, earlier: &ChronoDate) -> bool {
+    matches!((later.end, earlier.start), (Some(end), Some(start)) if end < start)
+}
+
+fn impossible_orderings(entries: &[ChronologyEntry]) -> Vec<ChronologyIssue> {
+    let mut issues = Vec::new();
+    for &(first, then) in ORDER {
+        for before in entries.iter().filter(|e| event_of(e) == Some(first)) {
+            for after in entries.iter().filter(|e| event_of(e) == Some(then)) {
+                if definitely_before(&after.date, &before.date) {
+                    issues.push(ChronologyIssue {
+                        kind: IssueKind::ImpossibleOrder,
+                        severity: IssueSeverity::Warning,
+                        message: format!(
+                            "{} is dated before {}, but \"{}\" can't come before \"{}\".",
+                            label(after),
+                            label(before),
+                            then.label(),
+                            first.label()
+                        ),
+                        entry_ids: vec![after.id.clone(), before.id.clone()],
+                    });
+                }
+            }
+        }
+    }
+
+    // The intake dates themselves: purchase, then the problem, then the complaint
+    let intake = |id: &str| entries.iter().find(|e| e.id == id);
+    let pairs = [
+        ("intake-purchase", "intake-problem"),
+        ("intake-purchase", "intake-complaint"),
+        ("intake-problem", "intake-complaint"),
+    ];
+    for (first, then) in pairs {
+        if let (Some(before), Some(after)) = (intake(first), intake(then)) {
+            if definitely_before(&after.date, &before.date) {
+                issues.push(ChronologyIssue {
+                    kind: IssueKind::ImpossibleOrder,
+                    severity: IssueSeverity::Warning,
+                    message: format!("On the intake form, {} is dated before {}.", label(after), label(before)),
+                    entry_ids: vec![after.id.clone(), before.id.clone()],
+                });
+            }
+        }
+    }
+    issues
+}
+
+fn gaps(sorted: &[&ChronologyEntry], gap_days: u32) -> Vec<ChronologyIssue> {
+    sorted
+        .windows(2)
+        .filter_map(|pair| {
+            let (from, to) = (pair[0].date.end?, pair[1].date.start?);
+            let days = (to - from).num_days();
+            (days > gap_days as i64).then(|| ChronologyIssue {
+                kind: IssueKind::Gap,
+                severity: IssueSeverity::Info,
+                message: format!(
+                    "Nothing is recorded for {} days between {} and {}. Check whether anything happened in between.",
+                    days,
+                    label(pair[0]),
+                    label(pair[1])
+                ),
+                entry_ids: vec![pair[0].id.clone(), pair[1].id.clone()],
+            })
+        })
+        .collect()
+}
+
+fn words(text: &str) -> HashSet<String> {
+    text.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| w.len() > 2)
+        .map(|w| w.to_string())
+        .collect()
+}
+
+/// Same date and either the same recognised event or near-identical wording
+fn is_duplicate(a: &ChronologyEntry, b: &ChronologyEntry) -> bool {
+    if a.date.start != b.date.start || a.date.end != b.date.end || a.date.start.is_none() {
+        return false;
+    }
+    // Intake entries stand for broad events, so only the classifier's own types count
+    if let (Some(x), Some(y)) = (a.event_type, b.event_type) {
+        return x == y;
+    }
+    let (wa, wb) = (words(&a.description), words(&b.description));
+    if wa.is_empty() || wb.is_empty() {
+        return false;
+    }
+    let shared = wa.intersection(&wb).count() as f64;
+    let all = wa.union(&wb).count() as f64;
+    shared / all >= 0.8
+}
+
+fn duplicates(sorted: &[&ChronologyEntry]) -> Vec<ChronologyIssue> {
+    let mut issues = Vec::new();
+    let mut reported: HashSet<&str> = HashSet::new();
+    for (i, a) in sorted.iter().enumerate() {
+        if reported.contains(a.id.as_str()) {
+            continue;
+        }
+        let same: Vec<&ChronologyEntry> = sorted[i + 1..]
+            .iter()
+            .take_while(|b| b.date.start == a.date.start)
+            .filter(|b| !reported.contains(b.id.as_str()) && is_duplicate(a, b))
+            .copied()
+            .collect();
+        if same.is_empty() {
+            continue;
+        }
+        let mut ids = vec![a.id.clone()];
+        for b in &same {
+            reported.insert(b.id.as_str());
+            ids.push(b.id.clone());
+        }
+        issues.push(ChronologyIssue {
+            kind: IssueKind::Duplicate,
+            severity: IssueSeverity::Info,
+            message: format!(
+                "{} {} look like the same event recorded {} times.",
+                label(a),
+                if same.len() == 1 { "and 1 other entry" } else { "and other entries" },
+                same.len() + 1
+            ),
+            entry_ids: ids,
+        });
+    }
+    issues
+}
+
+/// Entries taken from documents, not typed in by the user
+fn from_documents(entry: &ChronologyEntry) -> bool {
+    matches!(entry.source, ChronologySource::Document | ChronologySource::AiExtracted)
+}
+
+fn intake_contradictions(entries: &[ChronologyEntry], case: &CaseMetadata) -> Vec<ChronologyIssue> {
+    let mut issues = Vec::new();
+    let documented = |event: EventType| -> Vec<&ChronologyEntry> {
+        let mut found: Vec<&ChronologyEntry> = entries
+            .iter()
+            .filter(|e| from_documents(e) && e.event_type == Some(event) && e.date.is_dated())
+            .collect();
+        found.sort_by(|a, b| a.date.cmp(&b.date));
+        found
+    };
+    let intake_id = |id: &str| entries.iter().find(|e| e.id == id).map(|e| e.id.clone());
+
+    // The purchase date should match an order or purchase in the documents
+    if let Some(purchase) = case.date_of_purchase.as_deref().and_then(parse_date) {
+        let orders = documented(EventType::OrderPlaced);
+        let agrees = orders.iter().any(|e| {
+            e.date.start.map(|s| s - chrono::Duration::days(INTAKE_TOLERANCE_DAYS) <= purchase).unwrap_or(false)
+                && e.date.end.map(|end| end + chrono::Duration::days(INTAKE_TOLERANCE_DAYS) >= purchase).unwrap_or(false)
+        });
+        if !orders.is_empty() && !agrees {
+            issues.push(ChronologyIssue {
+                kind: IssueKind::IntakeContradicted,
+                severity: IssueSeverity::Warning,
+                message: format!(
+                    "The intake form gives the date of purchase as {}, but the documents show an order on {}.",
+                    ChronoDate::day(purchase),
+                    orders.iter().map(|e| e.date.to_string()).collect::<Vec<_>>().join(", ")
+                ),
+                entry_ids: intake_id("intake-purchase").into_iter().chain(orders.iter().map(|e| e.id.clone())).collect(),
+            });
+        }
+    }
+
+    // Documents can't show the problem, or a complaint, before the intake form says it started
+    let faults = documented(EventType::FaultReported);
+    let checks = [
+        (&case.date_problem_discovered, "intake-problem", "the problem was discovered"),
+        (&case.date_first_complained, "intake-complaint", "the first complaint was made"),
+    ];
+    for (date, id, what) in checks {
+        let Some(stated) = date.as_deref().and_then(parse_date) else {
+            continue;
+        };
+        let earlier: Vec<&&ChronologyEntry> = faults
+            .iter()
+            .filter(|e| definitely_before(&e.date, &ChronoDate::day(stated)))
+            .collect();
+        if let Some(first) = earlier.first() {
+            issues.push(ChronologyIssue {
+                kind: IssueKind::IntakeContradicted,
+                severity: IssueSeverity::Warning,
+                message: format!(
+                    "The intake form says {} on {}, but {} is earlier.",
+                    what,
+                    ChronoDate::day(stated),
+                    label(first)
+                ),
+                entry_ids: intake_id(id).into_iter().chain(earlier.iter().map(|e| e.id.clone())).collect(),
+            });
+        }
+    }
+    issues
+}
+
+/// Run every check over a chronology as returned by `build_chronology`
+pub fn check(entries: &[ChronologyEntry], case: &CaseMetadata, gap_days: u32) -> Vec<ChronologyIssue> {
+    let mut sorted: Vec<&ChronologyEntry> = entries.iter().filter(|e| e.date.is_dated()).collect();
+    sorted.sort_by(|a, b| a.date.cmp(&b.date));
+
+    let mut issues = impossible_orderings(entries);
+    issues.extend(intake_contradictions(entries, case));
+    issues.extend(duplicates(&sorted));
+    issues.extend(gaps(&sorted, gap_days));
+    issues
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::chronology::Significance;
+
+    fn entry(id: &str, date: &str, description: &str, event: Option<EventType>) -> ChronologyEntry {
+        ChronologyEntry {
+            id: id.to_string(),
+            date: ChronoDate::from_text(date),
+            description: description.to_string(),
+            source: if id.starts_with("intake-") {
+                ChronologySource::Intake
+            } else {
+                ChronologySource::Document
+            },
+            significance: Significance::Key,
+            event_type: event,
+            ..ChronologyEntry::default()
+        }
+    }
+
+    fn case(purchase: &str, problem: Option<&str>, complained: Option<&str>) -> CaseMetadata {
+        CaseMetadata {
+            date_of_purchase: Some(purchase.to_string()),
+            date_problem_discovered: problem.map(|d| d.to_string()),
+            date_first_complained: complained.map(|d| d.to_string()),
+            ..CaseMetadata::default()
+        }
+    }
+
+    fn kinds(issues: &[ChronologyIssue]) -> Vec<IssueKind> {
+        issues.iter().map(|i| i.kind).collect()
+    }
+
+    #[test]
+    fn complaint_before_purchase_is_impossible() {
+        let entries = vec![
+            entry("intake-purchase", "2024-03-01", "Date of purchase/service", None),
+            entry("scan-a", "2024-02-20", "Fault reported: I complained about the fridge", Some(EventType::FaultReported)),
+        ];
+        let issues = check(&entries, &case("2024-03-01", None, None), DEFAULT_GAP_DAYS);
+        assert_eq!(kinds(&issues), vec![IssueKind::ImpossibleOrder]);
+        assert_eq!(issues[0].entry_ids, vec!["scan-a", "intake-purchase"]);
+    }
+
+    #[test]
+    fn partial_dates_only_conflict_when_they_cannot_overlap() {
+        let entries = vec![
+            entry("a", "2024-03", "Order placed", Some(EventType::OrderPlaced)),
+            entry("b", "2024-03-02", "It stopped working", Some(EventType::FaultReported)),
+        ];
+        assert!(check(&entries, &CaseMetadata::default(), DEFAULT_GAP_DAYS).is_empty());
+    }
+
+    #[test]
+    fn reports_gaps_longer_than_the_period() {
+        let entries = vec![
+            entry("a", "2024-01-01", "Bought", None),
+            entry("b", "2024-02-01", "Complained", None),
+            entry("c", "2024-09-01", "Sent letter", None),
+        ];
+        let issues = check(&entries, &CaseMetadata::default(), 60);
+        assert_eq!(kinds(&issues), vec![IssueKind::Gap]);
+        assert_eq!(issues[0].entry_ids, vec!["b", "c"]);
+        assert!(issues[0].message.contains("213 days"));
+        assert!(check(&entries, &CaseMetadata::default(), 365).is_empty());
+    }
+
+    #[test]
+    fn reports_duplicates_on_the_same_day() {
+        let entries = vec![
+            entry("a", "2024-04-02", "Refund refused: They refused to refund", Some(EventType::RefundRefused)),
+            entry("b", "2024-04-02", "Refund refused: email from the shop", Some(EventType::RefundRefused)),
+            entry("c", "2024-04-02", "Phoned the shop about the oven door", None),
+            entry("d", "2024-04-02", "phoned the shop about the oven door.", None),
+            entry("e", "2024-04-02", "Delivered: the oven arrived", Some(EventType::Delivered)),
+        ];
+        let issues = check(&entries, &CaseMetadata::default(), DEFAULT_GAP_DAYS);
+        let dupes: Vec<&Vec<String>> = issues.iter().filter(|i| i.kind == IssueKind::Duplicate).map(|i| &i.entry_ids).collect();
+        assert_eq!(dupes.len(), 2);
+        assert!(dupes.contains(&&vec!["a".to_string(), "b".to_string()]));
+        assert!(dupes.contains(&&vec!["c".to_string(), "d".to_string()]));
+    }
+
+    #[test]
+    fn intake_dates_contradicted_by_documents() {
+        let entries = vec![
+            entry("intake-purchase", "2024-03-01", "Date of purchase/service", None),
+            entry("intake-complaint", "2024-05-10", "First complaint to seller", None),
+            entry("order", "2024-03-20", "Order placed: order confirmation", Some(EventType::OrderPlaced)),
+            entry("email", "2024-04-28", "Fault reported: I complained", Some(EventType::FaultReported)),
+        ];
+        let issues = check(&entries, &case("2024-03-01", None, Some("2024-05-10")), DEFAULT_GAP_DAYS);
+        let contradicted: Vec<&ChronologyIssue> =
+            issues.iter().filter(|i| i.kind == IssueKind::IntakeContradicted).collect();
+        assert_eq!(contradicted.len(), 2);
+        assert_eq!(contradicted[0].entry_ids, vec!["intake-purchase", "order"]);
+        assert_eq!(contradicted[1].entry_ids, vec!["intake-complaint", "email"]);
+    }
+
+    #[test]
+    fn order_within_a_few_days_agrees_with_intake() {
+        let entries = vec![entry("order", "2024-03-03", "Order placed", Some(EventType::OrderPlaced))];
+        assert!(check(&entries, &case("2024-03-01", None, None), DEFAULT_GAP_DAYS).is_empty());
+    }
+}
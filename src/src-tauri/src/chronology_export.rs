@@ -0,0 +1,481 @@
+//! Chronology export in the court format: a table of date, event and
+//! document reference, as DOCX, PDF or CSV.
+//!
+//! Where a bundle has been paginated the document reference is the bundle
+//! page (e.g. "E/23"); otherwise it is the document's file name.
+
+use crate::drafting::{Block, RenderedDraft};
+use crate::models::bundle::BundleIndex;
+use crate::models::case::CaseMetadata;
+use crate::models::chronology::{ChronologyEntry, Significance};
+use crate::models::document::DocumentEntry;
+use lopdf::content::{Content, Operation};
+use lopdf::{dictionary, Document, Object, Stream, StringFormat};
+use serde::{Deserialize, Serialize};
+
+const PAGE_WIDTH: f32 = 595.0;
+const PAGE_HEIGHT: f32 = 842.0;
+const MARGIN: f32 = 50.0;
+const FONT_SIZE: f32 = 9.0;
+const LEADING: f32 = 11.0;
+/// Column widths in points: date, event, document
+const COLUMNS: [f32; 3] = [85.0, 300.0, 110.0];
+const COLUMN_GAP: f32 = 5.0;
+/// Helvetica averages about half an em per character; a little over keeps lines inside the column
+const CHAR_WIDTH_EM: f32 = 0.52;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ExportFormat {
+    #[serde(rename = "docx")]
+    Docx,
+    #[serde(rename = "pdf")]
+    Pdf,
+    #[serde(rename = "csv")]
+    Csv,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChronologyExportRequest {
+    pub format: ExportFormat,
+    /// Only include entries of these significances. Empty includes everything.
+    #[serde(default)]
+    pub significance: Vec<Significance>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExportRow {
+    pub date: String,
+    pub event: String,
+    /// Bundle index reference, or the document's file name
+    pub reference: String,
+}
+
+/// The rows to export, in date order
+pub fn rows(
+    entries: &[ChronologyEntry],
+    significance: &[Significance],
+    documents: &[DocumentEntry],
+    bundle: Option<&BundleIndex>,
+) -> Vec<ExportRow> {
+    let mut selected: Vec<&ChronologyEntry> = entries
+        .iter()
+        .filter(|e| significance.is_empty() || significance.contains(&e.significance))
+        .collect();
+    selected.sort_by(|a, b| a.date.cmp(&b.date));
+
+    selected
+        .into_iter()
+        .map(|entry| {
+            let path = entry.source_document_path.clone().or_else(|| {
+                entry
+                    .document_id
+                    .as_ref()
+                    .and_then(|id| documents.iter().find(|d| &d.id == id))
+                    .map(|d| d.path.clone())
+            });
+            let reference = path
+                .as_deref()
+                .map(|p| {
+                    bundle
+                        .and_then(|b| b.reference(p))
+                        .unwrap_or_else(|| p.rsplit('/').next().unwrap_or(p).to_string())
+                })
+                .unwrap_or_default();
+            ExportRow {
+                date: entry.date.to_string(),
+                event: entry.description.trim().to_string(),
+                reference,
+            }
+        })
+        .collect()
+}
+
+/// Heading lines: claim title, then the document title
+fn heading(case: &CaseMetadata) -> Vec<String> {
+    vec![
+        format!("{} v {}", case.claimant_name, case.defendant_name),
+        "CHRONOLOGY".to_string(),
+    ]
+}
+
+fn reference_header(bundle: Option<&BundleIndex>) -> &'static str {
+    if bundle.is_some() {
+        "Index ref"
+    } else {
+        "Document"
+    }
+}
+
+/// Quote a CSV cell where needed. A cell starting with a character a
+/// spreadsheet reads as a formula gets a leading `'`, so an event or file
+/// name like "=HYPERLINK(…)" is shown as text rather than run.
+pub(crate) fn csv_field(value: &str) -> String {
+    let value = if value.starts_with(['=', '+', '-', '@', '\t', '\r']) {
+        format!("'{}", value)
+    } else {
+        value.to_string()
+    };
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value
+    }
+}
+
+pub fn to_csv(rows: &[ExportRow], bundle: Option<&BundleIndex>) -> String {
+    let mut out = format!("Date,Event,{}\r\n", reference_header(bundle));
+    for row in rows {
+        out.push_str(&format!(
+            "{},{},{}\r\n",
+            csv_field(&row.date),
+            csv_field(&row.event),
+            csv_field(&row.reference)
+        ));
+    }
+    out
+}
+
+pub fn to_docx(case: &CaseMetadata, rows: &[ExportRow], bundle: Option<&BundleIndex>) -> Result<Vec<u8>, String> {
+    let title = heading(case);
+    let draft = RenderedDraft {
+        blocks: vec![
+            Block::Centred(title[0].clone()),
+            Block::Title(title[1].clone()),
+            Block::Blank,
+            Block::Table {
+                header: vec![
+                    "Date".to_string(),
+                    "Event".to_string(),
+                    reference_header(bundle).to_string(),
+                ],
+                rows: rows
+                    .iter()
+                    .map(|r| vec![r.date.clone(), r.event.clone(), r.reference.clone()])
+                    .collect(),
+            },
+        ],
+        missing: Vec::new(),
+    };
+    crate::drafting::to_docx(&draft)
+}
+
+/// Encode text for the standard fonts with WinAnsiEncoding. Characters the
+/// encoding can't represent become '?'.
+fn win_ansi(text: &str) -> Vec<u8> {
+    text.chars()
+        .map(|c| match c {
+            '\u{20}'..='\u{7E}' | '\u{A0}'..='\u{FF}' => c as u8,
+            '€' => 0x80,
+            '‘' => 0x91,
+            '’' => 0x92,
+            '“' => 0x93,
+            '”' => 0x94,
+            '•' => 0x95,
+            '–' => 0x96,
+            '—' => 0x97,
+            _ => b'?',
+        })
+        .collect()
+}
+
+/// Split text into lines that fit a column
+fn wrap(text: &str, width: f32) -> Vec<String> {
+    let max_chars = ((width / (FONT_SIZE * CHAR_WIDTH_EM)) as usize).max(1);
+    let mut lines = Vec::new();
+    for paragraph in text.lines() {
+        let mut line = String::new();
+        for word in paragraph.split_whitespace() {
+            let mut word = word.to_string();
+            // Break words longer than the column (e.g. file names)
+            while word.chars().count() > max_chars {
+                if !line.is_empty() {
+                    lines.push(std::mem::take(&mut line));
+                }
+                let split: String = word.chars().take(max_chars).collect();
+                word = word.chars().skip(max_chars).collect();
+                lines.push(split);
+            }
+            let needed = if line.is_empty() { word.chars().count() } else { line.chars().count() + 1 + word.chars().count() };
+            if needed > max_chars && !line.is_empty() {
+                lines.push(std::mem::take(&mut line));
+            }
+            if !line.is_empty() {
+                line.push(' ');
+            }
+            line.push_str(&word);
+        }
+        lines.push(line);
+    }
+    if lines.is_empty() {
+        lines.push(String::new());
+    }
+    lines
+}
+
+fn text_op(ops: &mut Vec<Operation>, font: &str, x: f32, y: f32, text: &str) {
+    ops.push(Operation::new("BT", vec![]));
+    ops.push(Operation::new("Tf", vec![font.into(), FONT_SIZE.into()]));
+    ops.push(Operation::new("Td", vec![x.into(), y.into()]));
+    ops.push(Operation::new(
+        "Tj",
+        vec![Object::String(win_ansi(text), StringFormat::Literal)],
+    ));
+    ops.push(Operation::new("ET", vec![]));
+}
+
+fn line_op(ops: &mut Vec<Operation>, y: f32) {
+    ops.push(Operation::new("m", vec![MARGIN.into(), y.into()]));
+    ops.push(Operation::new("l", vec![(PAGE_WIDTH - MARGIN).into(), y.into()]));
+    ops.push(Operation::new("S", vec![]));
+}
+
+/// Lay out the table across as many A4 pages as it needs, repeating the
+/// header row on each page
+fn layout(title: &[String], header: &[&str; 3], rows: &[ExportRow]) -> Vec<Vec<Operation>> {
+    let mut pages = Vec::new();
+    let mut ops = Vec::new();
+    let mut y = PAGE_HEIGHT - MARGIN;
+    let column_x = [
+        MARGIN,
+        MARGIN + COLUMNS[0] + COLUMN_GAP,
+        MARGIN + COLUMNS[0] + COLUMNS[1] + 2.0 * COLUMN_GAP,
+    ];
+
+    for line in title {
+        text_op(&mut ops, "F2", MARGIN, y, line);
+        y -= LEADING * 1.5;
+    }
+
+    let header_row = |ops: &mut Vec<Operation>, y: &mut f32| {
+        for (i, h) in header.iter().enumerate() {
+            text_op(ops, "F2", column_x[i], *y, h);
+        }
+        *y -= 4.0;
+        line_op(ops, *y);
+        *y -= LEADING;
+    };
+    header_row(&mut ops, &mut y);
+
+    for row in rows {
+        let cells = [
+            wrap(&row.date, COLUMNS[0]),
+            wrap(&row.event, COLUMNS[1]),
+            wrap(&row.reference, COLUMNS[2]),
+        ];
+        let height = cells.iter().map(|c| c.len()).max().unwrap_or(1) as f32 * LEADING;
+        if y - height < MARGIN + LEADING {
+            pages.push(std::mem::take(&mut ops));
+            y = PAGE_HEIGHT - MARGIN;
+            header_row(&mut ops, &mut y);
+        }
+        for (i, lines) in cells.iter().enumerate() {
+            for (n, line) in lines.iter().enumerate() {
+                text_op(&mut ops, "F1", column_x[i], y - n as f32 * LEADING, line);
+            }
+        }
+        y -= height + LEADING * 0.5;
+    }
+    pages.push(ops);
+
+    // Page numbers once the page count is known
+    let total = pages.len();
+    for (i, page) in pages.iter_mut().enumerate() {
+        text_op(
+            page,
+            "F1",
+            PAGE_WIDTH / 2.0 - 20.0,
+            MARGIN / 2.0,
+            &format!("Page {} of {}", i + 1, total),
+        );
+    }
+    pages
+}
+
+pub fn to_pdf(case: &CaseMetadata, rows: &[ExportRow], bundle: Option<&BundleIndex>) -> Result<Vec<u8>, String> {
+    let err = |e: lopdf::Error| format!("Could not build PDF: {}", e);
+    let header = ["Date", "Event", reference_header(bundle)];
+    let pages = layout(&heading(case), &header, rows);
+
+    let mut doc = Document::with_version("1.5");
+    let pages_id = doc.new_object_id();
+    let font = |name: &str| {
+        dictionary! {
+            "Type" => "Font",
+            "Subtype" => "Type1",
+            "BaseFont" => name.to_string(),
+            "Encoding" => "WinAnsiEncoding",
+        }
+    };
+    let regular = doc.add_object(font("Helvetica"));
+    let bold = doc.add_object(font("Helvetica-Bold"));
+    let resources = doc.add_object(dictionary! {
+        "Font" => dictionary! { "F1" => regular, "F2" => bold },
+    });
+
+    let mut kids = Vec::new();
+    for operations in pages {
+        let content = Content { operations }.encode().map_err(err)?;
+        let content_id = doc.add_object(Stream::new(dictionary! {}, content));
+        let page_id = doc.add_object(dictionary! {
+            "Type" => "Page",
+            "Parent" => pages_id,
+            "Contents" => content_id,
+            "Resources" => resources,
+            "MediaBox" => vec![0.into(), 0.into(), PAGE_WIDTH.into(), PAGE_HEIGHT.into()],
+        });
+        kids.push(Object::Reference(page_id));
+    }
+    let count = kids.len() as i64;
+    doc.objects.insert(
+        pages_id,
+        Object::Dictionary(dictionary! {
+            "Type" => "Pages",
+            "Kids" => kids,
+            "Count" => count,
+        }),
+    );
+    let catalog = doc.add_object(dictionary! {
+        "Type" => "Catalog",
+        "Pages" => pages_id,
+    });
+    doc.trailer.set("Root", catalog);
+    doc.compress();
+
+    let mut output = Vec::new();
+    doc.save_to(&mut output).map_err(|e| format!("Could not write PDF: {}", e))?;
+    Ok(output)
+}
+
+/// Render the export in the requested format
+pub fn export(
+    case: &CaseMetadata,
+    entries: &[ChronologyEntry],
+    documents: &[DocumentEntry],
+    bundle: Option<&BundleIndex>,
+    request: &ChronologyExportRequest,
+) -> Result<Vec<u8>, String> {
+    let rows = rows(entries, &request.significance, documents, bundle);
+    match request.format {
+        ExportFormat::Csv => Ok(to_csv(&rows, bundle).into_bytes()),
+        ExportFormat::Docx => to_docx(case, &rows, bundle),
+        ExportFormat::Pdf => to_pdf(case, &rows, bundle),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::bundle::BundlePageRange;
+    use crate::models::chronology::ChronoDate;
+
+    fn entry(date: &str, description: &str, significance: Significance, path: Option<&str>) -> ChronologyEntry {
+        ChronologyEntry {
+            date: ChronoDate::from_text(date),
+            description: description.to_string(),
+            significance,
+            source_document_path: path.map(|p| p.to_string()),
+            ..ChronologyEntry::default()
+        }
+    }
+
+    fn entries() -> Vec<ChronologyEntry> {
+        vec![
+            entry("2025-02-01", "Complaint, \"urgent\"", Significance::Key, Some("01_Correspondence/complaint.pdf")),
+            entry("2025-01-10", "Bought the machine", Significance::Key, Some("02_Evidence/receipt.pdf")),
+            entry("2025-01-12", "Delivery", Significance::Background, None),
+        ]
+    }
+
+    fn bundle() -> BundleIndex {
+        BundleIndex {
+            generated_at: "2025-06-01T00:00:00Z".to_string(),
+            entries: vec![BundlePageRange {
+                path: "02_Evidence/receipt.pdf".to_string(),
+                section: Some("E".to_string()),
+                start_page: 23,
+                end_page: 25,
+            }],
+        }
+    }
+
+    #[test]
+    fn filters_by_significance_in_date_order() {
+        let rows = rows(&entries(), &[Significance::Key], &[], None);
+        let dates: Vec<&str> = rows.iter().map(|r| r.date.as_str()).collect();
+        assert_eq!(dates, vec!["10 January 2025", "1 February 2025"]);
+        assert_eq!(rows[0].reference, "receipt.pdf");
+
+        assert_eq!(super::rows(&entries(), &[], &[], None).len(), 3);
+    }
+
+    #[test]
+    fn cross_references_bundle_pages() {
+        let bundle = bundle();
+        let rows = rows(&entries(), &[], &[], Some(&bundle));
+        assert_eq!(rows[0].reference, "E/23–25");
+        // Not in the bundle: falls back to the file name
+        assert_eq!(rows[2].reference, "complaint.pdf");
+    }
+
+    #[test]
+    fn csv_escapes_quotes_and_commas() {
+        let rows = rows(&entries(), &[Significance::Key], &[], None);
+        let csv = to_csv(&rows, None);
+        assert_eq!(
+            csv,
+            "Date,Event,Document\r\n10 January 2025,Bought the machine,receipt.pdf\r\n1 February 2025,\"Complaint, \"\"urgent\"\"\",complaint.pdf\r\n"
+        );
+    }
+
+    #[test]
+    fn csv_neutralises_formulas() {
+        assert_eq!(csv_field("=HYPERLINK(\"http://x\")"), "\"'=HYPERLINK(\"\"http://x\"\")\"");
+        assert_eq!(csv_field("+44 7700 900123"), "'+44 7700 900123");
+        assert_eq!(csv_field("-5 refund"), "'-5 refund");
+        assert_eq!(csv_field("@SUM(A1)"), "'@SUM(A1)");
+        assert_eq!(csv_field("Paid £5 - cash"), "Paid £5 - cash");
+    }
+
+    #[test]
+    fn wraps_long_text_to_the_column() {
+        let lines = wrap("one two three four five six seven eight nine ten", 60.0);
+        assert!(lines.len() > 1);
+        assert!(lines.iter().all(|l| l.chars().count() <= 12));
+        assert_eq!(wrap("averyveryverylongfilename.pdf", 30.0).concat(), "averyveryverylongfilename.pdf");
+    }
+
+    #[test]
+    fn pdf_has_extractable_text_and_paginates() {
+        let case = CaseMetadata {
+            claimant_name: "Jane Smith".to_string(),
+            defendant_name: "Acme Ltd".to_string(),
+            ..CaseMetadata::default()
+        };
+        let many: Vec<ChronologyEntry> = (1..=120)
+            .map(|i| entry("2025-01-10", &format!("Event number {}", i), Significance::Key, None))
+            .collect();
+        let rows = rows(&many, &[], &[], None);
+        let pdf = to_pdf(&case, &rows, None).unwrap();
+
+        let doc = Document::load_mem(&pdf).unwrap();
+        let pages = doc.get_pages();
+        assert!(pages.len() > 1);
+        let text = doc.extract_text(&[1]).unwrap();
+        assert!(text.contains("CHRONOLOGY"));
+        assert!(text.contains("Event number 1"));
+    }
+
+    #[test]
+    fn docx_contains_the_table() {
+        let case = CaseMetadata::default();
+        let bundle = bundle();
+        let rows = rows(&entries(), &[], &[], Some(&bundle));
+        let bytes = to_docx(&case, &rows, Some(&bundle)).unwrap();
+        let doc = docx_rs::read_docx(&bytes).unwrap();
+        assert!(doc
+            .document
+            .children
+            .iter()
+            .any(|c| matches!(c, docx_rs::DocumentChild::Table(_))));
+    }
+}
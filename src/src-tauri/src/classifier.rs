@@ -0,0 +1,195 @@
+//! Heuristic document classification for bulk imports.
+//!
+//! Proposes a `DocumentType` and case folder (01–04) from the filename,
+//! extension and extracted text. Every proposal carries a short reason so the
+//! user can see why a file landed where it did before anything is committed.
+
+use crate::models::document::DocumentType;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+/// A proposed document type and destination folder
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Classification {
+    pub document_type: DocumentType,
+    /// Case folder number: "01" Correspondence, "02" Evidence, "03" Legal, "04" Court
+    pub folder: String,
+    /// Which rule matched, e.g. "Filename mentions 'invoice'"
+    pub reason: String,
+}
+
+fn proposal(document_type: DocumentType, folder: &str, reason: String) -> Classification {
+    Classification {
+        document_type,
+        folder: folder.to_string(),
+        reason,
+    }
+}
+
+/// Court form numbers commonly seen in small claims and fast track cases
+const COURT_FORMS: &[&str] = &[
+    "N1", "N1A", "N1C", "N9", "N9A", "N9B", "N9C", "N9D", "N11", "N20", "N149A", "N149B",
+    "N149C", "N150", "N170", "N180", "N181", "N205A", "N215", "N225", "N244", "N260",
+    "N279", "N242A", "EX160",
+];
+
+/// Headings that identify a court form or order in the first part of the text
+const COURT_HEADINGS: &[&str] = &[
+    "claim form",
+    "directions questionnaire",
+    "application notice",
+    "acknowledgment of service",
+    "notice of allocation",
+    "certificate of service",
+    "request for judgment",
+    "notice of proposed allocation",
+];
+
+const ORDER_MARKERS: &[&str] = &["it is ordered", "order made by", "upon hearing", "before district judge", "before deputy district judge"];
+
+const RECEIPT_WORDS: &[&str] = &["invoice", "receipt", "order confirmation", "proof of purchase", "tax invoice"];
+
+const WARRANTY_WORDS: &[&str] = &["warranty", "guarantee"];
+
+const CONTRACT_WORDS: &[&str] = &["contract", "agreement", "terms and conditions", "terms of service", "quotation", "quote"];
+
+const LETTER_MARKERS: &[&str] = &["dear ", "yours faithfully", "yours sincerely", "letter before claim", "letter before action", "letter of claim"];
+
+const IMAGE_EXTENSIONS: &[&str] = &["jpg", "jpeg", "png", "bmp", "tiff", "tif", "gif", "heic"];
+
+/// Look for a court form number in a filename, e.g. "N180 DQ.pdf" or "form_n9a.pdf"
+fn court_form_in_filename(stem: &str) -> Option<&'static str> {
+    let re = Regex::new(r"(?i)(?:^|[^a-z0-9])((?:n|ex)\d{1,3}[a-d]?)(?:[^a-z0-9]|$)").unwrap();
+    let found = re.captures_iter(stem).find_map(|cap| {
+        let number = cap[1].to_uppercase();
+        COURT_FORMS.iter().find(|f| **f == number).copied()
+    });
+    found
+}
+
+/// Look for "Form N1" style references in text
+fn court_form_in_text(text: &str) -> Option<&'static str> {
+    let re = Regex::new(r"(?i)\bform\s+((?:n|ex)\d{1,3}[a-d]?)\b").unwrap();
+    let found = re.captures_iter(text).find_map(|cap| {
+        let number = cap[1].to_uppercase();
+        COURT_FORMS.iter().find(|f| **f == number).copied()
+    });
+    found
+}
+
+fn first_match<'a>(haystack: &str, needles: &[&'a str]) -> Option<&'a str> {
+    needles.iter().find(|n| haystack.contains(*n)).copied()
+}
+
+/// Only the opening of a document is used for text rules, where headings and
+/// letterheads live — a passing mention deep in a long email shouldn't decide its type
+fn opening(text: &str, chars: usize) -> String {
+    text.chars().take(chars).collect::<String>().to_lowercase()
+}
+
+/// Propose a document type and folder for a file
+pub fn classify(filename: &str, text: Option<&str>) -> Classification {
+    let path = std::path::Path::new(filename);
+    let ext = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("")
+        .to_lowercase();
+    let stem = path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or(filename)
+        .to_lowercase();
+    let head = text.map(|t| opening(t, 1500)).unwrap_or_default();
+
+    if ext == "eml" || ext == "msg" {
+        return proposal(DocumentType::Email, "01", "Email file".to_string());
+    }
+
+    if let Some(form) = court_form_in_filename(&stem) {
+        return proposal(DocumentType::CourtForm, "04", format!("Filename mentions court form {}", form));
+    }
+    if let Some(form) = court_form_in_text(&head) {
+        return proposal(DocumentType::CourtForm, "04", format!("Text refers to court form {}", form));
+    }
+    if let Some(marker) = first_match(&head, ORDER_MARKERS) {
+        return proposal(DocumentType::Order, "04", format!("Text contains '{}'", marker));
+    }
+    if let Some(heading) = first_match(&head, COURT_HEADINGS) {
+        return proposal(DocumentType::CourtForm, "04", format!("Text contains heading '{}'", heading));
+    }
+
+    if let Some(word) = first_match(&stem, RECEIPT_WORDS) {
+        return proposal(DocumentType::Receipt, "02", format!("Filename mentions '{}'", word));
+    }
+    if let Some(word) = first_match(&stem, WARRANTY_WORDS) {
+        return proposal(DocumentType::Warranty, "02", format!("Filename mentions '{}'", word));
+    }
+    if let Some(word) = first_match(&stem, CONTRACT_WORDS) {
+        return proposal(DocumentType::Contract, "02", format!("Filename mentions '{}'", word));
+    }
+
+    if IMAGE_EXTENSIONS.contains(&ext.as_str()) {
+        return proposal(DocumentType::Photo, "02", "Image file".to_string());
+    }
+
+    if let Some(word) = first_match(&head, RECEIPT_WORDS) {
+        return proposal(DocumentType::Receipt, "02", format!("Text mentions '{}'", word));
+    }
+    if let Some(marker) = first_match(&head, LETTER_MARKERS) {
+        return proposal(DocumentType::Letter, "01", format!("Text contains '{}'", marker.trim()));
+    }
+    if let Some(word) = first_match(&head, WARRANTY_WORDS) {
+        return proposal(DocumentType::Warranty, "02", format!("Text mentions '{}'", word));
+    }
+    if let Some(word) = first_match(&head, CONTRACT_WORDS) {
+        return proposal(DocumentType::Contract, "02", format!("Text mentions '{}'", word));
+    }
+
+    proposal(DocumentType::Other, "02", "No rule matched — defaulting to evidence".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn folder_and_type(filename: &str, text: Option<&str>) -> (String, String) {
+        let c = classify(filename, text);
+        (c.folder, format!("{:?}", c.document_type))
+    }
+
+    #[test]
+    fn eml_is_correspondence() {
+        assert_eq!(folder_and_type("Re complaint.eml", None), ("01".into(), "Email".into()));
+    }
+
+    #[test]
+    fn invoice_and_receipt_filenames() {
+        assert_eq!(folder_and_type("Invoice 4471.pdf", None), ("02".into(), "Receipt".into()));
+        assert_eq!(folder_and_type("currys_receipt.jpg", None), ("02".into(), "Receipt".into()));
+    }
+
+    #[test]
+    fn court_form_numbers_in_filenames() {
+        assert_eq!(folder_and_type("N1 claim form.pdf", None), ("04".into(), "CourtForm".into()));
+        assert_eq!(folder_and_type("defence_N9B.pdf", None), ("04".into(), "CourtForm".into()));
+        assert_eq!(folder_and_type("n180-dq.pdf", None), ("04".into(), "CourtForm".into()));
+        // Not a form number: part of a longer token
+        assert_ne!(folder_and_type("IMGN1234.pdf", None).1, "CourtForm");
+    }
+
+    #[test]
+    fn text_rules_when_filename_is_uninformative() {
+        let letter = "14 Acacia Avenue\n\nDear Sir or Madam,\n\nI write regarding the washing machine.";
+        assert_eq!(folder_and_type("scan0001.pdf", Some(letter)), ("01".into(), "Letter".into()));
+
+        let order = "IN THE COUNTY COURT AT LEEDS\nBefore District Judge Smith\nIT IS ORDERED THAT";
+        assert_eq!(folder_and_type("scan0002.pdf", Some(order)), ("04".into(), "Order".into()));
+    }
+
+    #[test]
+    fn photos_and_fallback() {
+        assert_eq!(folder_and_type("IMG_2041.jpg", None), ("02".into(), "Photo".into()));
+        assert_eq!(folder_and_type("notes.txt", Some("random notes")), ("02".into(), "Other".into()));
+    }
+}
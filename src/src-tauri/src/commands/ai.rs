@@ -0,0 +1,279 @@
+use crate::ai::{keys, AiRequest, PreparedRequest, Provider};
+use crate::commands::ai_history::{append_ai_call, load_ai_history, write_ai_history, AiCallRecord};
+use crate::commands::chronology::build_chronology;
+use crate::commands::documents::load_docs_index;
+use crate::redaction::RedactionLog;
+use crate::source_check::SourceReport;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter};
+
+/// Event carrying the text of an AI response as it streams in
+pub const STREAM_EVENT: &str = "ai-stream";
+
+const SUMMARY_CHARS: usize = 200;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum StreamEvent {
+    #[serde(rename = "delta")]
+    Delta { stream_id: String, text: String },
+    #[serde(rename = "done")]
+    Done { stream_id: String, record_id: String },
+    #[serde(rename = "error")]
+    Error { stream_id: String, message: String },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StoredKey {
+    pub provider: Provider,
+    pub name: String,
+}
+
+/// The first 200 characters, or a merits assessment's factual summary
+fn summary_of(text: &str) -> String {
+    let parsed = serde_json::from_str::<serde_json::Value>(text.trim()).ok();
+    let source = parsed
+        .as_ref()
+        .and_then(|v| v.get("factualSummary"))
+        .and_then(|s| s.as_str())
+        .unwrap_or(text);
+    source.chars().take(SUMMARY_CHARS).collect()
+}
+
+/// Check a response against the case documents and chronology. Redaction
+/// placeholders are put back first, so quotations can match the originals.
+fn verify_response(case_name: &str, response: &str, redaction: Option<&RedactionLog>) -> Result<SourceReport, String> {
+    // Without the map the placeholders stay in, and the report says so
+    let (text, warning) = match redaction {
+        Some(log) => match crate::commands::redaction::load_map(case_name, &log.map_id) {
+            Ok(map) => (crate::redaction::restore(response, &map), None),
+            Err(e) => (
+                response.to_string(),
+                Some(format!(
+                    "Redacted values could not be put back ({}), so quotations and statements containing them were checked with the placeholders in place",
+                    e
+                )),
+            ),
+        },
+        None => (response.to_string(), None),
+    };
+    let documents = load_docs_index(case_name)?;
+    let chronology = build_chronology(case_name.to_string())?;
+    let mut report = crate::source_check::check(&text, &documents, &chronology);
+    report.warnings.extend(warning);
+    Ok(report)
+}
+
+/// Redact a request with the case's saved redaction map, replacing any
+/// original value still in it, and build the log from what will be sent.
+/// Without a map the request goes as it is, with no log.
+fn redact_request(
+    case_name: &str,
+    request: &mut AiRequest,
+    redaction_map_id: Option<&str>,
+) -> Result<Option<RedactionLog>, String> {
+    let Some(map_id) = redaction_map_id else {
+        return Ok(None);
+    };
+    let map = crate::commands::redaction::load_map(case_name, map_id)?;
+    request.prompt = crate::redaction::redact_with_map(&request.prompt, &map);
+    if let Some(system) = request.system.as_mut() {
+        *system = crate::redaction::redact_with_map(system, &map);
+    }
+    let sent = format!("{}\n{}", request.system.as_deref().unwrap_or(""), request.prompt);
+    Ok(Some(crate::redaction::log_for(&sent, &map)))
+}
+
+/// Build the exact request for a call so the user can see what will be sent,
+/// redacted with the case's saved map if one is given
+#[tauri::command]
+pub fn preview_ai_request(
+    case_name: String,
+    mut request: AiRequest,
+    redaction_map_id: Option<String>,
+) -> Result<PreparedRequest, String> {
+    redact_request(&case_name, &mut request, redaction_map_id.as_deref())?;
+    let key_stored = !request.provider.needs_key() || keys::get(request.provider)?.is_some();
+    crate::ai::prepare(&request, key_stored)
+}
+
+/// Send a previewed request, streaming the reply as `ai-stream` events, and
+/// record the call with its token counts in the case's AI history. The
+/// request is redacted again with the same map, so the body must still match
+/// the hash from the preview and the recorded log describes what was sent.
+#[tauri::command]
+pub async fn send_ai_request(
+    app: AppHandle,
+    case_name: String,
+    mut request: AiRequest,
+    call_type: String,
+    stream_id: String,
+    payload_hash: String,
+    redaction_map_id: Option<String>,
+) -> Result<AiCallRecord, String> {
+    let redaction = redact_request(&case_name, &mut request, redaction_map_id.as_deref())?;
+    let key = if request.provider.needs_key() {
+        keys::get(request.provider)?
+    } else {
+        None
+    };
+
+    let outcome = crate::ai::send(&request, key.as_deref(), Some(&payload_hash), |text| {
+        let _ = app.emit(
+            STREAM_EVENT,
+            StreamEvent::Delta { stream_id: stream_id.clone(), text: text.to_string() },
+        );
+    })
+    .await;
+    let response = match outcome {
+        Ok(r) => r,
+        Err(message) => {
+            let _ = app.emit(
+                STREAM_EVENT,
+                StreamEvent::Error { stream_id: stream_id.clone(), message: message.clone() },
+            );
+            return Err(message);
+        }
+    };
+
+    // A failed check shouldn't lose the response; it can be run again later
+    let verification = {
+        let case_name = case_name.clone();
+        let text = response.text.clone();
+        let log = redaction.clone();
+        tauri::async_runtime::spawn_blocking(move || verify_response(&case_name, &text, log.as_ref()))
+            .await
+            .ok()
+            .and_then(|r| r.ok())
+    };
+
+    let now = chrono::Utc::now();
+    let record = AiCallRecord {
+        id: format!("ai-{}", now.timestamp_millis()),
+        call_type,
+        timestamp: now.to_rfc3339(),
+        input_tokens: response.input_tokens,
+        output_tokens: response.output_tokens,
+        model: response.model,
+        summary: Some(summary_of(&response.text)),
+        response: response.text,
+        redaction,
+        payload_hash: Some(response.payload_hash),
+        verification,
+    };
+    append_ai_call(&case_name, record.clone())?;
+
+    let _ = app.emit(
+        STREAM_EVENT,
+        StreamEvent::Done { stream_id, record_id: record.id.clone() },
+    );
+    Ok(record)
+}
+
+/// Check a saved response against the case's documents and chronology again,
+/// e.g. after more documents have been added, and store the new report with it
+#[tauri::command]
+pub async fn check_ai_response(case_name: String, record_id: String) -> Result<SourceReport, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let mut records = load_ai_history(case_name.clone())?;
+        let record = records
+            .iter_mut()
+            .find(|r| r.id == record_id)
+            .ok_or_else(|| format!("AI call {} not found", record_id))?;
+        let report = verify_response(&case_name, &record.response, record.redaction.as_ref())?;
+        record.verification = Some(report.clone());
+        write_ai_history(&case_name, &records)?;
+        Ok(report)
+    })
+    .await
+    .map_err(|e| format!("Source check stopped unexpectedly: {}", e))?
+}
+
+/// Providers with a saved API key. The keys themselves never leave the backend.
+#[tauri::command]
+pub fn list_ai_keys() -> Result<Vec<StoredKey>, String> {
+    Ok(keys::stored()?
+        .into_iter()
+        .map(|provider| StoredKey { provider, name: provider.name().to_string() })
+        .collect())
+}
+
+#[tauri::command]
+pub fn set_ai_key(provider: Provider, key: String) -> Result<(), String> {
+    keys::set(provider, &key)
+}
+
+#[tauri::command]
+pub fn remove_ai_key(provider: Provider) -> Result<(), String> {
+    keys::remove(provider)
+}
+
+/// Send a one-line prompt with the saved key to check it works
+#[tauri::command]
+pub async fn test_ai_connection(provider: Provider, model: String) -> Result<String, String> {
+    let key = if provider.needs_key() {
+        keys::get(provider)?.ok_or_else(|| format!("No API key saved for {}", provider.name()))?
+    } else {
+        String::new()
+    };
+    crate::ai::test_key(provider, &model, &key).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_missing_redaction_map_is_recorded_in_the_report() {
+        let case_name = format!("ai-{}", uuid::Uuid::new_v4());
+        crate::commands::case::create_case(case_name.clone(), "A".into(), "B".into(), "claimant".into()).unwrap();
+        let log = RedactionLog {
+            map_id: uuid::Uuid::new_v4().to_string(),
+            created_at: String::new(),
+            counts: Vec::new(),
+            total: 0,
+            custom_terms: 0,
+            kinds_checked: Vec::new(),
+        };
+
+        let report = verify_response(&case_name, "The seller was [REDACTED NAME 1].", Some(&log)).unwrap();
+        assert_eq!(report.warnings.len(), 1);
+        assert!(report.warnings[0].contains("no longer exists"));
+        assert!(verify_response(&case_name, "The seller was Acme.", None).unwrap().warnings.is_empty());
+    }
+
+    #[test]
+    fn requests_are_redacted_with_the_saved_map() {
+        let case_name = format!("ai-{}", uuid::Uuid::new_v4());
+        crate::commands::case::create_case(case_name.clone(), "A".into(), "B".into(), "claimant".into()).unwrap();
+        let options = crate::redaction::RedactionOptions {
+            kinds: None,
+            custom_terms: vec!["Acme Ltd".to_string()],
+        };
+        let saved = crate::commands::redaction::redact_texts(
+            case_name.clone(),
+            vec!["Invoice from Acme Ltd to jane@example.com".to_string()],
+            Some(options),
+        )
+        .unwrap();
+
+        // The original typed back in is caught, and the log counts what is sent
+        let mut request = AiRequest {
+            provider: Provider::Mock,
+            model: "mock".to_string(),
+            prompt: format!("{} Is ACME LTD liable? Ask jane@example.com", saved.texts[0]),
+            system: None,
+            max_tokens: 100,
+            json: false,
+        };
+        let log = redact_request(&case_name, &mut request, Some(&saved.log.map_id)).unwrap().unwrap();
+        assert!(!request.prompt.to_lowercase().contains("acme") && !request.prompt.contains("jane@"));
+        assert_eq!(log.total, 4);
+        assert_eq!(log.custom_terms, 1);
+
+        // A log can't be attached without a map the backend has
+        let mut request = AiRequest { prompt: "Acme Ltd".to_string(), ..request };
+        assert!(redact_request(&case_name, &mut request, Some("missing")).is_err());
+        assert!(redact_request(&case_name, &mut request, None).unwrap().is_none());
+    }
+}
@@ -1,4 +1,6 @@
 use crate::path_safety::safe_case_path;
+use crate::redaction::RedactionLog;
+use crate::source_check::SourceReport;
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::PathBuf;
@@ -20,12 +22,24 @@ pub struct AiCallRecord {
     pub model: String,
     pub response: String,
     pub summary: Option<String>,
+    /// What was redacted from the text sent with this call
+    #[serde(default)]
+    pub redaction: Option<RedactionLog>,
+    /// SHA-256 of the request body the user approved, for calls made by the backend
+    #[serde(default, rename = "payloadHash")]
+    pub payload_hash: Option<String>,
+    /// How well the response is backed by the case documents
+    #[serde(default)]
+    pub verification: Option<SourceReport>,
 }
 
 #[tauri::command]
 pub fn save_ai_call(case_name: String, record: AiCallRecord) -> Result<(), String> {
-    let case_path = get_case_path(&case_name)?;
-    let history_path = case_path.join(".casekit").join("ai-history.json");
+    append_ai_call(&case_name, record)
+}
+
+pub(crate) fn append_ai_call(case_name: &str, record: AiCallRecord) -> Result<(), String> {
+    let history_path = get_case_path(case_name)?.join(".casekit").join("ai-history.json");
 
     let mut records: Vec<AiCallRecord> = if history_path.exists() {
         let content = fs::read_to_string(&history_path)
@@ -36,8 +50,14 @@ pub fn save_ai_call(case_name: String, record: AiCallRecord) -> Result<(), Strin
     };
 
     records.push(record);
+    write_ai_history(case_name, &records)
+}
+
+pub(crate) fn write_ai_history(case_name: &str, records: &[AiCallRecord]) -> Result<(), String> {
+    let case_path = get_case_path(case_name)?;
+    let history_path = case_path.join(".casekit").join("ai-history.json");
 
-    let json = serde_json::to_string_pretty(&records)
+    let json = serde_json::to_string_pretty(records)
         .map_err(|e| format!("Could not serialise AI history: {}", e))?;
     fs::write(&history_path, &json)
         .map_err(|e| format!("Could not write ai-history.json: {}", e))?;
@@ -0,0 +1,16 @@
+use crate::allocation::TrackAdvice;
+use crate::commands::case::{load_case, update_case};
+
+/// Predict the track for a case, explain why, and bring its `overall_risk` up to date
+#[tauri::command]
+pub fn advise_track(case_name: String) -> Result<TrackAdvice, String> {
+    let metadata = load_case(case_name.clone())?;
+    let advice = crate::allocation::advise(&metadata);
+
+    if metadata.overall_risk != advice.overall_risk {
+        // update_case recalculates overall_risk before saving
+        update_case(case_name, metadata)?;
+    }
+
+    Ok(advice)
+}
@@ -1,5 +1,5 @@
 use crate::path_safety::safe_case_path;
-use crate::models::case::CaseMetadata;
+use crate::models::case::{parse_case_json, CaseMetadata, CASE_SCHEMA_VERSION};
 use std::fs;
 use std::path::PathBuf;
 
@@ -82,8 +82,8 @@ pub fn list_cases() -> Result<Vec<CaseMetadata>, String> {
             if case_file.exists() {
                 let content = fs::read_to_string(&case_file)
                     .map_err(|e| format!("Could not read {}: {}", case_file.display(), e))?;
-                let metadata: CaseMetadata = serde_json::from_str(&content)
-                    .map_err(|e| format!("Could not parse {}: {}", case_file.display(), e))?;
+                let (metadata, _) = parse_case_json(&content)
+                    .map_err(|e| format!("{}: {}", case_file.display(), e))?;
                 cases.push(metadata);
             }
         }
@@ -105,8 +105,15 @@ pub fn load_case(case_name: String) -> Result<CaseMetadata, String> {
 
     let content = fs::read_to_string(&case_file)
         .map_err(|e| format!("Could not read case.json: {}", e))?;
-    let metadata: CaseMetadata = serde_json::from_str(&content)
-        .map_err(|e| format!("Could not parse case.json: {}", e))?;
+    let (metadata, migrated) = parse_case_json(&content)?;
+
+    // Save the upgraded layout so older files are only migrated once
+    if migrated {
+        let json = serde_json::to_string_pretty(&metadata)
+            .map_err(|e| format!("Could not serialise case metadata: {}", e))?;
+        fs::write(&case_file, &json)
+            .map_err(|e| format!("Could not write case.json: {}", e))?;
+    }
 
     Ok(metadata)
 }
@@ -122,6 +129,12 @@ pub fn update_case(case_name: String, metadata: CaseMetadata) -> Result<CaseMeta
 
     let mut updated = metadata;
     updated.updated_at = chrono::Utc::now().to_rfc3339();
+    updated.schema_version = CASE_SCHEMA_VERSION;
+    updated.sync_parties();
+    if !updated.claim_heads.is_empty() {
+        updated.claim_value = updated.total_claim_value();
+    }
+    updated.overall_risk = crate::allocation::advise(&updated).overall_risk;
 
     let json = serde_json::to_string_pretty(&updated)
         .map_err(|e| format!("Could not serialise case metadata: {}", e))?;
@@ -1,7 +1,8 @@
 use crate::path_safety::safe_case_path;
-use crate::models::chronology::ChronologyEntry;
+use crate::models::chronology::{ChronoDate, ChronologyEntry};
 use crate::models::document::DocumentEntry;
-use crate::models::case::CaseMetadata;
+use crate::chronology_check::ChronologyIssue;
+use crate::event_classifier::{self, ClassifiedEvent};
 use std::collections::HashSet;
 use std::fs;
 use std::path::PathBuf;
@@ -43,13 +44,16 @@ pub fn build_chronology(case_name: String) -> Result<Vec<ChronologyEntry>, Strin
                 if !saved_ids.contains(&id) {
                     entries.push(ChronologyEntry {
                         id,
-                        date: date.clone(),
+                        date: ChronoDate::from_text(date),
                         description: format!("{}: {}", doc.filename, doc.description),
                         source: crate::models::chronology::ChronologySource::Document,
                         document_id: Some(doc.id.clone()),
                         source_document_path: Some(doc.path.clone()),
                         significance: crate::models::chronology::Significance::Supporting,
                         confidence: None,
+                        event_type: None,
+                        trigger: None,
+                        relative: None,
                     });
                 }
             }
@@ -61,21 +65,23 @@ pub fn build_chronology(case_name: String) -> Result<Vec<ChronologyEntry>, Strin
     if case_file.exists() {
         let content = fs::read_to_string(&case_file)
             .map_err(|e| format!("Could not read case.json: {}", e))?;
-        let case: CaseMetadata = serde_json::from_str(&content)
-            .map_err(|e| format!("Could not parse case.json: {}", e))?;
+        let (case, _) = crate::models::case::parse_case_json(&content)?;
 
         if let Some(date) = &case.date_of_purchase {
             let id = "intake-purchase".to_string();
             if !saved_ids.contains(&id) {
                 entries.push(ChronologyEntry {
                     id,
-                    date: date.clone(),
+                    date: ChronoDate::from_text(date),
                     description: "Date of purchase/service".to_string(),
                     source: crate::models::chronology::ChronologySource::Intake,
                     document_id: None,
                     source_document_path: None,
                     significance: crate::models::chronology::Significance::Key,
                     confidence: None,
+                    event_type: None,
+                    trigger: None,
+                    relative: None,
                 });
             }
         }
@@ -85,13 +91,16 @@ pub fn build_chronology(case_name: String) -> Result<Vec<ChronologyEntry>, Strin
             if !saved_ids.contains(&id) {
                 entries.push(ChronologyEntry {
                     id,
-                    date: date.clone(),
+                    date: ChronoDate::from_text(date),
                     description: "Problem discovered".to_string(),
                     source: crate::models::chronology::ChronologySource::Intake,
                     document_id: None,
                     source_document_path: None,
                     significance: crate::models::chronology::Significance::Key,
                     confidence: None,
+                    event_type: None,
+                    trigger: None,
+                    relative: None,
                 });
             }
         }
@@ -101,13 +110,16 @@ pub fn build_chronology(case_name: String) -> Result<Vec<ChronologyEntry>, Strin
             if !saved_ids.contains(&id) {
                 entries.push(ChronologyEntry {
                     id,
-                    date: date.clone(),
+                    date: ChronoDate::from_text(date),
                     description: "First complaint to seller".to_string(),
                     source: crate::models::chronology::ChronologySource::Intake,
                     document_id: None,
                     source_document_path: None,
                     significance: crate::models::chronology::Significance::Key,
                     confidence: None,
+                    event_type: None,
+                    trigger: None,
+                    relative: None,
                 });
             }
         }
@@ -204,6 +216,18 @@ pub fn update_chronology_entry(case_name: String, entry: ChronologyEntry) -> Res
     build_chronology(case_name)
 }
 
+/// Check the chronology for impossible orderings, long gaps, duplicates and
+/// intake dates the documents contradict. `gap_days` overrides the period in settings.
+#[tauri::command]
+pub fn check_chronology(case_name: String, gap_days: Option<u32>) -> Result<Vec<ChronologyIssue>, String> {
+    let entries = build_chronology(case_name.clone())?;
+    let case = crate::commands::case::load_case(case_name)?;
+    let gap_days = gap_days
+        .or_else(|| crate::settings::load_settings().ok().and_then(|s| s.chronology_gap_days))
+        .unwrap_or(crate::chronology_check::DEFAULT_GAP_DAYS);
+    Ok(crate::chronology_check::check(&entries, &case, gap_days))
+}
+
 /// Extract a clean, readable description from around a date match.
 /// Priority: 1) user-provided description, 2) email Subject line, 3) best sentence from context.
 fn extract_clean_description(context: &str, filename: &str, doc_description: &str) -> String {
@@ -270,6 +294,17 @@ fn extract_clean_description(context: &str, filename: &str, doc_description: &st
     }
 }
 
+/// Trim a sentence to a readable description length on a word boundary
+fn shorten(text: &str) -> String {
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if text.chars().count() <= 160 {
+        return text;
+    }
+    let cut: String = text.chars().take(160).collect();
+    let cut = cut.rfind(' ').map(|i| &cut[..i]).unwrap_or(&cut);
+    format!("{}…", cut)
+}
+
 /// Detect common boilerplate fragments that shouldn't be used as descriptions
 fn is_boilerplate(text: &str) -> bool {
     let lower = text.to_lowercase();
@@ -308,7 +343,9 @@ pub fn scan_documents_for_dates(case_name: String) -> Result<Vec<ChronologyEntry
 
     // Also track dates we've already seen globally to avoid near-duplicate entries
     let mut seen_dates: std::collections::HashSet<String> = std::collections::HashSet::new();
+    let mut classified_dates: std::collections::HashSet<String> = std::collections::HashSet::new();
     let mut candidates = Vec::new();
+    let mut undescribed: Vec<(String, ChronologyEntry)> = Vec::new();
 
     for doc in &docs {
         let text = match &doc.extracted_text {
@@ -316,10 +353,83 @@ pub fn scan_documents_for_dates(case_name: String) -> Result<Vec<ChronologyEntry
             _ => continue,
         };
 
-        let scanned = crate::date_scanner::scan_for_dates(&text);
+        let mut scanned = crate::date_scanner::scan_for_dates(&text);
+
+        // Relative dates and deadlines are counted from the email's sent date,
+        // then the document's own date, then a date near the top of the letter
+        let anchor = crate::date_scanner::anchor_date(doc.metadata_date.as_deref(), "")
+            .or_else(|| {
+                doc.date
+                    .as_deref()
+                    .and_then(|d| chrono::NaiveDate::parse_from_str(d, "%Y-%m-%d").ok())
+                    .map(|date| crate::date_scanner::DateAnchor {
+                        date,
+                        label: "the document's date".to_string(),
+                    })
+            })
+            .or_else(|| crate::date_scanner::anchor_date(doc.date.as_deref(), &text));
+        if let Some(anchor) = &anchor {
+            scanned.extend(crate::date_scanner::scan_relative_dates(&text, anchor));
+        }
 
         for sd in scanned {
-            let entry_id = format!("scan-{}-{}", doc.id, sd.date);
+            let is_deadline = sd.kind == crate::date_scanner::DateKind::Deadline;
+            let confidence = match sd.confidence {
+                crate::date_scanner::Confidence::High => Some("high".to_string()),
+                crate::date_scanner::Confidence::Medium => Some("medium".to_string()),
+                crate::date_scanner::Confidence::Low => Some("low".to_string()),
+            };
+
+            // Classify the sentence around every mention of the date, keeping
+            // one entry per kind of event
+            let mut events: Vec<(ClassifiedEvent, String)> = Vec::new();
+            if !is_deadline {
+                for (at, found) in text.match_indices(&sd.original_text) {
+                    let sentence = event_classifier::sentence_at(&text, at, at + found.len());
+                    for event in event_classifier::classify(&sentence) {
+                        if !events.iter().any(|(e, _)| e.event_type == event.event_type) {
+                            events.push((event, sentence.clone()));
+                        }
+                    }
+                }
+            }
+
+            for (event, sentence) in events {
+                let key = serde_json::to_value(event.event_type)
+                    .ok()
+                    .and_then(|v| v.as_str().map(|s| s.to_string()))
+                    .unwrap_or_default();
+                let entry_id = format!("scan-{}-{}-{}", doc.id, sd.date, key);
+                // The same event on the same day is often described in several documents
+                let date_key = format!("{}-{}", sd.date, key);
+                if existing_ids.contains(&entry_id) || !seen_dates.insert(date_key) {
+                    continue;
+                }
+                classified_dates.insert(sd.date.clone());
+
+                candidates.push(ChronologyEntry {
+                    id: entry_id,
+                    date: ChronoDate::from_text(&sd.date),
+                    description: format!("{}: {}", event.event_type.label(), shorten(&sentence)),
+                    source: crate::models::chronology::ChronologySource::Document,
+                    document_id: Some(doc.id.clone()),
+                    source_document_path: Some(doc.path.clone()),
+                    significance: event.significance,
+                    confidence: confidence.clone(),
+                    event_type: Some(event.event_type),
+                    trigger: Some(event.trigger),
+                    relative: sd.relative.clone(),
+                });
+            }
+            if classified_dates.contains(&sd.date) && !is_deadline {
+                continue;
+            }
+
+            let entry_id = if is_deadline {
+                format!("scan-{}-{}-deadline", doc.id, sd.date)
+            } else {
+                format!("scan-{}-{}", doc.id, sd.date)
+            };
 
             // Skip if already in chronology or if we've already seen this date
             // from another part of the same document
@@ -328,37 +438,90 @@ pub fn scan_documents_for_dates(case_name: String) -> Result<Vec<ChronologyEntry
             }
 
             // Deduplicate: only keep the first occurrence of each date globally
-            let date_key = sd.date.clone();
+            let date_key = if is_deadline {
+                format!("{}-deadline", sd.date)
+            } else {
+                sd.date.clone()
+            };
             if seen_dates.contains(&date_key) {
                 continue;
             }
             seen_dates.insert(date_key);
 
-            let confidence = match sd.confidence {
-                crate::date_scanner::Confidence::High => Some("high".to_string()),
-                crate::date_scanner::Confidence::Medium => Some("medium".to_string()),
-                crate::date_scanner::Confidence::Low => Some("low".to_string()),
-            };
-
-            let description = extract_clean_description(
+            let mut description = extract_clean_description(
                 &sd.context,
                 &doc.filename,
                 &doc.description,
             );
+            if is_deadline {
+                description = format!("Deadline: {}", description);
+            }
 
-            candidates.push(ChronologyEntry {
+            let entry = ChronologyEntry {
                 id: entry_id,
-                date: sd.date,
+                date: ChronoDate::from_text(&sd.date),
                 description,
                 source: crate::models::chronology::ChronologySource::Document,
                 document_id: Some(doc.id.clone()),
                 source_document_path: Some(doc.path.clone()),
-                significance: crate::models::chronology::Significance::Supporting,
+                significance: if is_deadline {
+                    crate::models::chronology::Significance::Key
+                } else {
+                    crate::models::chronology::Significance::Supporting
+                },
                 confidence,
-            });
+                event_type: None,
+                trigger: None,
+                relative: sd.relative.clone(),
+            };
+            if is_deadline {
+                candidates.push(entry);
+            } else {
+                undescribed.push((sd.date.clone(), entry));
+            }
         }
     }
 
+    // A bare date mention adds nothing once an event on that day has been recognised
+    candidates.extend(
+        undescribed
+            .into_iter()
+            .filter(|(date, _)| !classified_dates.contains(date))
+            .map(|(_, entry)| entry),
+    );
     candidates.sort_by(|a, b| a.date.cmp(&b.date));
     Ok(candidates)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn relative_dates_in_an_email_count_from_its_sent_date() {
+        let case_name = format!("chrono-{}", uuid::Uuid::new_v4());
+        crate::commands::case::create_case(case_name.clone(), "A".into(), "B".into(), "claimant".into()).unwrap();
+        crate::commands::documents::add_document_metadata(
+            case_name.clone(),
+            DocumentEntry {
+                filename: "chase.eml".to_string(),
+                // The letter heading says 1 March; the email went on 15 March
+                extracted_text: Some("1 March 2024\n\nI telephoned your office yesterday about the repair.".to_string()),
+                metadata_date: Some("2024-03-15T09:30:00+00:00".to_string()),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        let entries = scan_documents_for_dates(case_name).unwrap();
+        let entry = entries
+            .iter()
+            .find(|e| e.relative.as_ref().is_some_and(|r| r.phrase.eq_ignore_ascii_case("yesterday")))
+            .expect("an entry for 'yesterday'");
+        let relative = entry.relative.as_ref().unwrap();
+        assert_eq!(relative.anchor, "2024-03-15");
+        assert_eq!(relative.anchor_label, "the email's sent date");
+        assert_eq!(relative.offset_days, Some(-1));
+        assert!(!entry.description.contains('['));
+    }
+}
@@ -219,6 +219,32 @@ fn try_neutral_citation_urls(citation: &str) -> Option<(String, String, String)>
     None
 }
 
+/// Find every neutral citation (e.g. "[2019] EWCA Civ 123") in a block of text,
+/// in order of first appearance and without duplicates
+pub fn find_neutral_citations(text: &str) -> Vec<String> {
+    let mut found: Vec<(usize, String)> = Vec::new();
+
+    for pattern in NEUTRAL_PATTERNS {
+        let re = match regex::RegexBuilder::new(pattern.regex)
+            .case_insensitive(true)
+            .build()
+        {
+            Ok(r) => r,
+            Err(_) => continue,
+        };
+
+        for m in re.find_iter(text) {
+            let normalised = m.as_str().split_whitespace().collect::<Vec<_>>().join(" ");
+            if !found.iter().any(|(_, c)| c.eq_ignore_ascii_case(&normalised)) {
+                found.push((m.start(), normalised));
+            }
+        }
+    }
+
+    found.sort_by_key(|(pos, _)| *pos);
+    found.into_iter().map(|(_, c)| c).collect()
+}
+
 // ===== Strategy 2: BAILII Citation Finder (302 redirect) =====
 
 async fn try_bailii_citation_finder(
@@ -0,0 +1,110 @@
+use crate::commands::case::{load_case, update_case};
+use crate::companies_house::CompanyCheck;
+use crate::models::company::CompanyRecord;
+use crate::settings::AppSettings;
+use std::path::{Path, PathBuf};
+
+const SEARCH_LIMIT: usize = 20;
+
+fn api_key() -> Result<String, String> {
+    crate::ai::keys::get_named(crate::ai::keys::COMPANIES_HOUSE)?
+        .ok_or_else(|| "Add a Companies House API key in Settings to search the live register".to_string())
+}
+
+fn snapshot_path(settings: &AppSettings) -> Result<PathBuf, String> {
+    let path = settings
+        .companies_house_snapshot
+        .as_deref()
+        .filter(|p| !p.trim().is_empty())
+        .map(PathBuf::from)
+        .ok_or_else(|| {
+            "Download the Companies House \"Basic Company Data\" file and choose it in Settings first".to_string()
+        })?;
+    if !path.exists() {
+        return Err(format!("Companies House data not found at {}", path.display()));
+    }
+    Ok(path)
+}
+
+async fn lookup(query: String, live: bool) -> Result<Vec<CompanyRecord>, String> {
+    let settings = crate::settings::load_settings()?;
+    if live {
+        let key = api_key()?;
+        return if crate::companies_house::is_company_number(&query) {
+            Ok(crate::companies_house::fetch_company(&key, &query).await?.into_iter().collect())
+        } else {
+            crate::companies_house::search_live(&key, &query, SEARCH_LIMIT).await
+        };
+    }
+
+    // The snapshot has millions of rows, so read it off the main thread
+    let path = snapshot_path(&settings)?;
+    tauri::async_runtime::spawn_blocking(move || {
+        crate::companies_house::search_snapshot(&path, &query, SEARCH_LIMIT)
+    })
+    .await
+    .map_err(|e| format!("Company search stopped unexpectedly: {}", e))?
+}
+
+/// Search for a company by name or number, in the downloaded snapshot or, if
+/// `live` is set, on the live register
+#[tauri::command]
+pub async fn search_companies(query: String, live: Option<bool>) -> Result<Vec<CompanyRecord>, String> {
+    lookup(query, live.unwrap_or(false)).await
+}
+
+/// Look up a party's company number, record the result against the party and
+/// flag the case if the company is dissolved or insolvent. Only an exact
+/// number is accepted, so a similar name can never replace the party's
+/// details. A number missing from the downloaded snapshot is flagged too.
+#[tauri::command]
+pub async fn check_party_company(
+    case_name: String,
+    party_id: String,
+    company_number: String,
+    live: Option<bool>,
+) -> Result<CompanyCheck, String> {
+    let live = live.unwrap_or(false);
+    if !crate::companies_house::is_company_number(&company_number) {
+        return Err(format!(
+            "'{}' is not a company number. Search by name first, then check the company by its number.",
+            company_number.trim()
+        ));
+    }
+    let record = lookup(company_number.clone(), live).await?.into_iter().next();
+
+    let mut case = load_case(case_name.clone())?;
+    let check = match record {
+        Some(record) => crate::companies_house::apply_to_party(&mut case, &party_id, record)?,
+        None if live => return Err(format!("No company with number {} was found", company_number.trim())),
+        None => {
+            let snapshot = crate::settings::load_settings()?
+                .companies_house_snapshot
+                .as_deref()
+                .and_then(|p| Path::new(p).file_name().and_then(|n| n.to_str()).map(str::to_string))
+                .unwrap_or_else(|| "the downloaded data".to_string());
+            crate::companies_house::apply_missing_from_snapshot(&mut case, &party_id, &company_number, &snapshot)?
+        }
+    };
+    update_case(case_name, case)?;
+    Ok(check)
+}
+
+/// Whether a Companies House API key is saved. The key itself never leaves
+/// the backend.
+#[tauri::command]
+pub fn has_companies_house_key() -> Result<bool, String> {
+    // Loading the settings moves a key saved by an earlier version
+    crate::settings::load_settings()?;
+    Ok(crate::ai::keys::get_named(crate::ai::keys::COMPANIES_HOUSE)?.is_some())
+}
+
+#[tauri::command]
+pub fn set_companies_house_key(key: String) -> Result<(), String> {
+    crate::ai::keys::set_named(crate::ai::keys::COMPANIES_HOUSE, &key)
+}
+
+#[tauri::command]
+pub fn remove_companies_house_key() -> Result<(), String> {
+    crate::ai::keys::remove_named(crate::ai::keys::COMPANIES_HOUSE)
+}
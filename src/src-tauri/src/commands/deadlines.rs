@@ -0,0 +1,10 @@
+use crate::commands::case::load_case;
+use crate::deadlines::Deadline;
+
+/// Calculate the CPR deadlines that follow from the procedural dates recorded in a case
+#[tauri::command]
+pub fn compute_deadlines(case_name: String) -> Result<Vec<Deadline>, String> {
+    let metadata = load_case(case_name)?;
+    let today = chrono::Local::now().date_naive();
+    Ok(crate::deadlines::compute(&metadata.procedural_dates, metadata.total_claim_value(), today))
+}
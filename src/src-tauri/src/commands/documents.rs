@@ -1,22 +1,28 @@
 use crate::path_safety::{safe_case_path, sanitise_path_component, validate_relative_path};
 use crate::models::document::DocumentEntry;
 use crate::extraction::ExtractedContent;
+use crate::models::job::JobKind;
+use crate::ocr::OcrOptions;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
 
-/// Result from copying a file to a case — includes extracted text
+/// Result from copying a file to a case — includes extracted text, or the id of
+/// the background job extracting it
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CopyResult {
     pub relative_path: String,
     pub extracted: Option<ExtractedContent>,
+    pub job_id: Option<String>,
 }
 
 fn get_case_path(case_name: &str) -> Result<PathBuf, String> {
     safe_case_path(case_name)
 }
 
-fn load_docs_index(case_name: &str) -> Result<Vec<DocumentEntry>, String> {
+pub(crate) fn load_docs_index(case_name: &str) -> Result<Vec<DocumentEntry>, String> {
     let case_path = get_case_path(case_name)?;
     let index_path = case_path.join(".casekit").join("documents.json");
 
@@ -36,7 +42,7 @@ pub fn load_documents_index(case_name: String) -> Result<Vec<DocumentEntry>, Str
     load_docs_index(&case_name)
 }
 
-fn save_documents_index(case_name: &str, docs: &[DocumentEntry]) -> Result<(), String> {
+pub(crate) fn save_documents_index(case_name: &str, docs: &[DocumentEntry]) -> Result<(), String> {
     let case_path = get_case_path(case_name)?;
     let index_path = case_path.join(".casekit").join("documents.json");
 
@@ -47,43 +53,116 @@ fn save_documents_index(case_name: &str, docs: &[DocumentEntry]) -> Result<(), S
     Ok(())
 }
 
-#[tauri::command]
-pub fn copy_file_to_case(
-    case_name: String,
-    source_path: String,
-    folder: String,
-    filename: String,
-) -> Result<CopyResult, String> {
-    let case_path = get_case_path(&case_name)?;
+/// One lock per case, held across every read-modify-write of documents.json.
+/// Imports, the inbox watcher, background jobs and the UI can all change the
+/// index at once, and without it the last writer would drop the others' entries.
+fn docs_lock(case_name: &str) -> Arc<Mutex<()>> {
+    static LOCKS: OnceLock<Mutex<HashMap<String, Arc<Mutex<()>>>>> = OnceLock::new();
+    let mut locks = LOCKS
+        .get_or_init(|| Mutex::new(HashMap::new()))
+        .lock()
+        .unwrap_or_else(|e| e.into_inner());
+    locks.entry(case_name.trim().to_string()).or_default().clone()
+}
+
+/// Load documents.json, apply `f` to the entries, and save them back, with no
+/// other change to the case's index in between. Returns the saved entries.
+pub(crate) fn with_docs_index(
+    case_name: &str,
+    f: impl FnOnce(&mut Vec<DocumentEntry>) -> Result<(), String>,
+) -> Result<Vec<DocumentEntry>, String> {
+    let lock = docs_lock(case_name);
+    let _guard = lock.lock().unwrap_or_else(|e| e.into_inner());
+    let mut docs = load_docs_index(case_name)?;
+    f(&mut docs)?;
+    save_documents_index(case_name, &docs)?;
+    Ok(docs)
+}
+
+/// Map a folder number ("01"–"05") to its directory name within the case
+pub(crate) fn folder_dir_name(folder: &str) -> Result<&'static str, String> {
+    match folder {
+        "01" => Ok("01_Correspondence"),
+        "02" => Ok("02_Evidence"),
+        "03" => Ok("03_Legal"),
+        "04" => Ok("04_Court"),
+        "05" => Ok("05_Bundle"),
+        _ => Err(format!("Invalid folder number: {}", folder)),
+    }
+}
+
+/// Copy a source file into a case folder, returning the destination path and
+/// its path relative to the case directory
+pub(crate) fn copy_into_case(
+    case_name: &str,
+    source_path: &Path,
+    folder: &str,
+    filename: &str,
+) -> Result<(PathBuf, String), String> {
+    let case_path = get_case_path(case_name)?;
 
     // Sanitise the filename to prevent path traversal
-    let safe_filename = sanitise_path_component(&filename, "Filename")?;
+    let safe_filename = sanitise_path_component(filename, "Filename")?;
 
-    let folder_name = match folder.as_str() {
-        "01" => "01_Correspondence",
-        "02" => "02_Evidence",
-        "03" => "03_Legal",
-        "04" => "04_Court",
-        "05" => "05_Bundle",
-        _ => return Err(format!("Invalid folder number: {}", folder)),
-    };
+    let folder_name = folder_dir_name(folder)?;
 
     let dest_dir = case_path.join(folder_name);
     fs::create_dir_all(&dest_dir)
         .map_err(|e| format!("Could not create folder {}: {}", folder_name, e))?;
 
     let dest_path = dest_dir.join(&safe_filename);
-    fs::copy(&source_path, &dest_path)
+    fs::copy(source_path, &dest_path)
         .map_err(|e| format!("Could not copy file to {}: {}", dest_path.display(), e))?;
 
     let relative_path = format!("{}/{}", folder_name, safe_filename);
+    Ok((dest_path, relative_path))
+}
+
+/// Copy a file into a case folder and queue a background job to extract its
+/// text, so slow extraction and OCR don't block the UI. The job stores the text
+/// on the document whose path matches once it finishes. Pass `background: false`
+/// to extract before returning instead.
+#[tauri::command]
+pub fn copy_file_to_case(
+    app: tauri::AppHandle,
+    case_name: String,
+    source_path: String,
+    folder: String,
+    filename: String,
+    ocr_options: Option<OcrOptions>,
+    background: Option<bool>,
+) -> Result<CopyResult, String> {
+    let (dest_path, relative_path) =
+        copy_into_case(&case_name, Path::new(&source_path), &folder, &filename)?;
+
+    if background.unwrap_or(true) {
+        let job = crate::jobs::enqueue(
+            &app,
+            case_name,
+            JobKind::Extraction {
+                relative_path: relative_path.clone(),
+                document_id: None,
+                ocr_options,
+            },
+        )?;
+        return Ok(CopyResult {
+            relative_path,
+            extracted: None,
+            job_id: Some(job.id),
+        });
+    }
 
     // Auto-extract text from the copied file
-    let extracted = crate::extraction::extract_from_file(&dest_path).ok();
+    let extracted = crate::extraction::extract_from_file_with_options(
+        &dest_path,
+        &ocr_options.unwrap_or_default(),
+    )
+    .ok();
 
     Ok(CopyResult {
         relative_path,
         extracted,
+        job_id: None,
     })
 }
 
@@ -130,27 +209,91 @@ pub fn read_file_text(case_name: String, relative_path: String) -> Result<String
 
 #[tauri::command]
 pub fn add_document_metadata(case_name: String, document: DocumentEntry) -> Result<Vec<DocumentEntry>, String> {
-    let mut docs = load_docs_index(&case_name)?;
-    docs.push(document);
-    save_documents_index(&case_name, &docs)?;
+    let docs = with_docs_index(&case_name, |docs| {
+        docs.push(document);
+        Ok(())
+    })?;
+    Ok(docs)
+}
+
+/// Replace a document's metadata by id — used to confirm a pending document after review
+#[tauri::command]
+pub fn update_document_metadata(case_name: String, document: DocumentEntry) -> Result<Vec<DocumentEntry>, String> {
+    let docs = with_docs_index(&case_name, |docs| {
+        let existing = docs
+            .iter_mut()
+            .find(|d| d.id == document.id)
+            .ok_or_else(|| format!("Document '{}' not found", document.id))?;
+        *existing = document;
+        Ok(())
+    })?;
     Ok(docs)
 }
 
 #[tauri::command]
 pub fn remove_document_metadata(case_name: String, document_id: String) -> Result<Vec<DocumentEntry>, String> {
-    let docs = load_docs_index(&case_name)?;
-    let filtered: Vec<DocumentEntry> = docs.into_iter().filter(|d| d.id != document_id).collect();
-    save_documents_index(&case_name, &filtered)?;
-    Ok(filtered)
+    let docs = with_docs_index(&case_name, |docs| {
+        docs.retain(|d| d.id != document_id);
+        Ok(())
+    })?;
+    Ok(docs)
 }
 
 /// Extract text from any file path (for drag-and-drop / file picker on Citation Audit).
 /// Uses the same extraction engine as document upload (PDF, DOCX, EML, TXT, images).
 #[tauri::command]
-pub fn extract_text_from_path(path: String) -> Result<ExtractedContent, String> {
+pub fn extract_text_from_path(
+    path: String,
+    ocr_options: Option<OcrOptions>,
+) -> Result<ExtractedContent, String> {
     let file_path = std::path::PathBuf::from(&path);
     if !file_path.exists() {
         return Err(format!("File not found: {}", path));
     }
-    crate::extraction::extract_from_file(&file_path)
+    crate::extraction::extract_from_file_with_options(&file_path, &ocr_options.unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn concurrent_updates_to_the_index_are_all_kept() {
+        let case_name = format!("docs-{}", uuid::Uuid::new_v4());
+        crate::commands::case::create_case(case_name.clone(), "A".into(), "B".into(), "claimant".into()).unwrap();
+
+        let writers: Vec<_> = (0..8)
+            .map(|w| {
+                let case_name = case_name.clone();
+                std::thread::spawn(move || {
+                    for i in 0..10 {
+                        with_docs_index(&case_name, |docs| {
+                            docs.push(DocumentEntry { filename: format!("{}-{}.txt", w, i), ..Default::default() });
+                            Ok(())
+                        })
+                        .unwrap();
+                    }
+                })
+            })
+            .collect();
+        for writer in writers {
+            writer.join().unwrap();
+        }
+
+        assert_eq!(load_docs_index(&case_name).unwrap().len(), 80);
+    }
+
+    #[test]
+    fn a_failed_update_leaves_the_index_unchanged() {
+        let case_name = format!("docs-{}", uuid::Uuid::new_v4());
+        crate::commands::case::create_case(case_name.clone(), "A".into(), "B".into(), "claimant".into()).unwrap();
+        add_document_metadata(case_name.clone(), DocumentEntry::default()).unwrap();
+
+        let result = with_docs_index(&case_name, |docs| {
+            docs.clear();
+            Err("stop".to_string())
+        });
+        assert!(result.is_err());
+        assert_eq!(load_docs_index(&case_name).unwrap().len(), 1);
+    }
 }
@@ -0,0 +1,69 @@
+use crate::commands::case::load_case;
+use crate::commands::chronology::build_chronology;
+use crate::commands::documents::{load_docs_index, with_docs_index};
+use crate::drafting::{DraftContext, DraftInputs, DraftKind};
+use crate::models::document::{DocumentEntry, DocumentType};
+use crate::path_safety::safe_case_path;
+use serde::{Deserialize, Serialize};
+use std::fs;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GeneratedDraft {
+    pub relative_path: String,
+    /// Placeholders left for the user to complete
+    pub missing: Vec<String>,
+    pub documents: Vec<DocumentEntry>,
+}
+
+/// Draft a letter or statement from its template, save it to 03_Legal and add it to the documents index
+#[tauri::command]
+pub fn generate_draft(
+    case_name: String,
+    kind: DraftKind,
+    inputs: DraftInputs,
+) -> Result<GeneratedDraft, String> {
+    let metadata = load_case(case_name.clone())?;
+    let chronology = build_chronology(case_name.clone())?;
+    let documents = load_docs_index(&case_name)?;
+    let today = chrono::Local::now().date_naive();
+
+    let ctx = DraftContext {
+        case: &metadata,
+        chronology: &chronology,
+        documents: &documents,
+        values: crate::drafting::context_values(&metadata, &documents, &inputs, today),
+    };
+    let rendered = crate::drafting::render(&crate::drafting::load_template(kind), &ctx);
+    let bytes = crate::drafting::to_docx(&rendered)?;
+
+    let legal_dir = safe_case_path(&case_name)?.join("03_Legal");
+    fs::create_dir_all(&legal_dir)
+        .map_err(|e| format!("Could not create folder 03_Legal: {}", e))?;
+    let filename = crate::drafting::output_filename(kind, today, &legal_dir);
+    fs::write(legal_dir.join(&filename), &bytes)
+        .map_err(|e| format!("Could not save {}: {}", filename, e))?;
+
+    let relative_path = format!("03_Legal/{}", filename);
+    let documents = with_docs_index(&case_name, |documents| {
+        documents.push(DocumentEntry {
+            filename: filename.clone(),
+            path: relative_path.clone(),
+            folder: "03".to_string(),
+            document_type: match kind {
+                DraftKind::LetterBeforeClaim => DocumentType::Letter,
+                _ => DocumentType::Other,
+            },
+            date: Some(today.format("%Y-%m-%d").to_string()),
+            description: format!("{} (draft)", kind.title()),
+            tags: vec!["generated".to_string(), "draft".to_string()],
+            ..DocumentEntry::default()
+        });
+        Ok(())
+    })?;
+
+    Ok(GeneratedDraft {
+        relative_path,
+        missing: rendered.missing,
+        documents,
+    })
+}
@@ -1,8 +1,75 @@
+use crate::chronology_export::ChronologyExportRequest;
+use crate::commands::case::load_case;
+use crate::commands::chronology::build_chronology;
+use crate::commands::documents::load_docs_index;
+use crate::models::bundle::{BundleIndex, BundlePageRange};
 use crate::path_safety::{safe_case_path, validate_relative_path};
 use std::fs;
 use std::io::Write;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
+/// Bundle section for a case file, by the folder it sits in
+fn bundle_folder(relative_path: &str) -> &'static str {
+    if relative_path.starts_with("01_") {
+        "D_Correspondence"
+    } else if relative_path.starts_with("03_") {
+        "A_Claim"
+    } else if relative_path.starts_with("04_") {
+        "C_Orders"
+    } else {
+        "E_Evidence"
+    }
+}
+
+/// Pages a document takes up in the bundle. Files other than PDFs, and PDFs
+/// that can't be read, count as one page.
+fn page_count(path: &Path) -> u32 {
+    let is_pdf = path
+        .extension()
+        .map(|e| e.eq_ignore_ascii_case("pdf"))
+        .unwrap_or(false);
+    if !is_pdf {
+        return 1;
+    }
+    lopdf::Document::load(path)
+        .map(|doc| doc.get_pages().len() as u32)
+        .unwrap_or(1)
+        .max(1)
+}
+
+/// Number the pages of each bundle section from 1, in section order and then
+/// the order the documents were chosen in
+fn paginate(documents: &[(String, PathBuf)]) -> BundleIndex {
+    let mut ordered: Vec<&(String, PathBuf)> = documents.iter().collect();
+    ordered.sort_by_key(|(relative, _)| bundle_folder(relative));
+
+    let mut entries: Vec<BundlePageRange> = Vec::new();
+    for (relative, full_path) in ordered {
+        let section = bundle_folder(relative)[..1].to_string();
+        let start_page = entries
+            .iter()
+            .rev()
+            .find(|e| e.section.as_deref() == Some(section.as_str()))
+            .map(|e| e.end_page + 1)
+            .unwrap_or(1);
+        entries.push(BundlePageRange {
+            path: relative.clone(),
+            section: Some(section),
+            start_page,
+            end_page: start_page + page_count(full_path) - 1,
+        });
+    }
+
+    BundleIndex {
+        generated_at: chrono::Utc::now().to_rfc3339(),
+        entries,
+    }
+}
+
+/// Export the chosen documents as a zipped bundle, paginated by section with
+/// an index of page ranges. The files are copied as they are, without page
+/// numbers stamped on them, so the ranges are index references. The index is
+/// also saved to the case so chronology exports can cite them.
 #[tauri::command]
 pub fn export_bundle(
     case_name: String,
@@ -12,6 +79,16 @@ pub fn export_bundle(
     let case_path = safe_case_path(&case_name)?;
     let export_file = PathBuf::from(&export_path);
 
+    let mut documents = Vec::new();
+    for doc_rel_path in &document_paths {
+        // Validate each document path stays within the case directory
+        let full_path = validate_relative_path(&case_path, doc_rel_path)?;
+        if full_path.exists() {
+            documents.push((doc_rel_path.clone(), full_path));
+        }
+    }
+    let index = paginate(&documents);
+
     let file = fs::File::create(&export_file)
         .map_err(|e| format!("Could not create export file: {}", e))?;
     let mut zip = zip::ZipWriter::new(file);
@@ -19,44 +96,176 @@ pub fn export_bundle(
     let options: zip::write::FileOptions<'_, ()> = zip::write::FileOptions::default()
         .compression_method(zip::CompressionMethod::Deflated);
 
-    for doc_rel_path in &document_paths {
-        // Validate each document path stays within the case directory
-        let full_path = validate_relative_path(&case_path, doc_rel_path)?;
-
-        if !full_path.exists() {
-            continue;
-        }
-
-        // Determine bundle folder based on source folder
-        let bundle_folder = if doc_rel_path.starts_with("01_") {
-            "D_Correspondence"
-        } else if doc_rel_path.starts_with("02_") {
-            "E_Evidence"
-        } else if doc_rel_path.starts_with("03_") {
-            "A_Claim"
-        } else if doc_rel_path.starts_with("04_") {
-            "C_Orders"
-        } else {
-            "E_Evidence"
-        };
-
+    let mut index_csv = String::from("Index ref,Document\r\n");
+    for range in &index.entries {
+        let full_path = validate_relative_path(&case_path, &range.path)?;
         let filename = full_path.file_name()
             .map(|n| n.to_string_lossy().to_string())
             .unwrap_or_else(|| "unknown".to_string());
 
-        let zip_path = format!("Bundle/{}/{}", bundle_folder, filename);
+        let zip_path = format!("Bundle/{}/{}", bundle_folder(&range.path), filename);
 
-        zip.start_file(&zip_path, options.clone())
+        zip.start_file(&zip_path, options)
             .map_err(|e| format!("Could not add file to bundle: {}", e))?;
 
         let content = fs::read(&full_path)
-            .map_err(|e| format!("Could not read {}: {}", doc_rel_path, e))?;
+            .map_err(|e| format!("Could not read {}: {}", range.path, e))?;
         zip.write_all(&content)
             .map_err(|e| format!("Could not write to bundle: {}", e))?;
+
+        index_csv.push_str(&format!(
+            "{},{}\r\n",
+            index.reference(&range.path).unwrap_or_default(),
+            crate::chronology_export::csv_field(&filename)
+        ));
     }
 
+    zip.start_file("Bundle/Index.csv", options)
+        .map_err(|e| format!("Could not add index to bundle: {}", e))?;
+    zip.write_all(index_csv.as_bytes())
+        .map_err(|e| format!("Could not write to bundle: {}", e))?;
+
     zip.finish()
         .map_err(|e| format!("Could not finalise bundle: {}", e))?;
 
+    save_bundle_index(&case_name, &index)?;
     Ok(export_file.to_string_lossy().to_string())
 }
+
+fn save_bundle_index(case_name: &str, index: &BundleIndex) -> Result<(), String> {
+    let index_path = safe_case_path(case_name)?.join(".casekit").join("bundle-index.json");
+    let json = serde_json::to_string_pretty(index)
+        .map_err(|e| format!("Could not serialise bundle index: {}", e))?;
+    fs::write(&index_path, &json)
+        .map_err(|e| format!("Could not write bundle-index.json: {}", e))
+}
+
+/// The bundle index saved when a bundle was paginated, if there is one
+pub(crate) fn load_bundle_index(case_name: &str) -> Result<Option<BundleIndex>, String> {
+    let index_path = safe_case_path(case_name)?.join(".casekit").join("bundle-index.json");
+    if !index_path.exists() {
+        return Ok(None);
+    }
+    let content = fs::read_to_string(&index_path)
+        .map_err(|e| format!("Could not read bundle-index.json: {}", e))?;
+    let index = serde_json::from_str(&content)
+        .map_err(|e| format!("Could not parse bundle-index.json: {}", e))?;
+    Ok(Some(index))
+}
+
+/// Export the merged chronology as a court-format table (DOCX, PDF or CSV)
+#[tauri::command]
+pub fn export_chronology(
+    case_name: String,
+    request: ChronologyExportRequest,
+    export_path: String,
+) -> Result<String, String> {
+    let metadata = load_case(case_name.clone())?;
+    let entries = build_chronology(case_name.clone())?;
+    let documents = load_docs_index(&case_name)?;
+    let bundle = load_bundle_index(&case_name)?;
+
+    let bytes = crate::chronology_export::export(&metadata, &entries, &documents, bundle.as_ref(), &request)?;
+    fs::write(&export_path, &bytes)
+        .map_err(|e| format!("Could not write chronology export: {}", e))?;
+    Ok(export_path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chronology_export::ExportFormat;
+    use crate::models::chronology::{ChronoDate, ChronologyEntry};
+    use lopdf::{dictionary, Document, Object};
+    use std::io::Read;
+
+    fn pdf_with_pages(count: u32) -> Vec<u8> {
+        let mut doc = Document::with_version("1.5");
+        let pages_id = doc.new_object_id();
+        let kids: Vec<Object> = (0..count)
+            .map(|_| {
+                doc.add_object(dictionary! {
+                    "Type" => "Page",
+                    "Parent" => pages_id,
+                    "MediaBox" => vec![0.into(), 0.into(), 595.into(), 842.into()],
+                })
+                .into()
+            })
+            .collect();
+        doc.objects.insert(
+            pages_id,
+            Object::Dictionary(dictionary! { "Type" => "Pages", "Kids" => kids, "Count" => count }),
+        );
+        let catalog = doc.add_object(dictionary! { "Type" => "Catalog", "Pages" => pages_id });
+        doc.trailer.set("Root", catalog);
+        let mut bytes = Vec::new();
+        doc.save_to(&mut bytes).unwrap();
+        bytes
+    }
+
+    #[test]
+    fn chronology_cites_pages_of_the_exported_bundle() {
+        let case_name = format!("export-{}", uuid::Uuid::new_v4());
+        crate::commands::case::create_case(case_name.clone(), "A".into(), "B".into(), "claimant".into()).unwrap();
+        let case_path = safe_case_path(&case_name).unwrap();
+        fs::write(case_path.join("02_Evidence/receipt.pdf"), pdf_with_pages(3)).unwrap();
+        fs::write(case_path.join("02_Evidence/photo.jpg"), b"not really a photo").unwrap();
+        fs::write(case_path.join("01_Correspondence/complaint.txt"), "Please refund me").unwrap();
+
+        for (date, description, path) in [
+            ("2025-01-10", "Bought the machine", "02_Evidence/receipt.pdf"),
+            ("2025-01-20", "Photographed the fault", "02_Evidence/photo.jpg"),
+            ("2025-02-01", "Complained", "01_Correspondence/complaint.txt"),
+        ] {
+            crate::commands::chronology::add_chronology_entry(
+                case_name.clone(),
+                ChronologyEntry {
+                    date: ChronoDate::from_text(date),
+                    description: description.to_string(),
+                    source_document_path: Some(path.to_string()),
+                    ..ChronologyEntry::default()
+                },
+            )
+            .unwrap();
+        }
+
+        let bundle_path = std::env::temp_dir().join(format!("{}.zip", case_name));
+        export_bundle(
+            case_name.clone(),
+            vec![
+                "02_Evidence/receipt.pdf".to_string(),
+                "02_Evidence/photo.jpg".to_string(),
+                "01_Correspondence/complaint.txt".to_string(),
+            ],
+            bundle_path.to_string_lossy().to_string(),
+        )
+        .unwrap();
+
+        let index = load_bundle_index(&case_name).unwrap().unwrap();
+        assert_eq!(index.reference("01_Correspondence/complaint.txt").as_deref(), Some("D/1"));
+        assert_eq!(index.reference("02_Evidence/receipt.pdf").as_deref(), Some("E/1–3"));
+        assert_eq!(index.reference("02_Evidence/photo.jpg").as_deref(), Some("E/4"));
+
+        let mut zip = zip::ZipArchive::new(fs::File::open(&bundle_path).unwrap()).unwrap();
+        let mut index_csv = String::new();
+        zip.by_name("Bundle/Index.csv").unwrap().read_to_string(&mut index_csv).unwrap();
+        assert_eq!(
+            index_csv,
+            "Index ref,Document\r\nD/1,complaint.txt\r\nE/1–3,receipt.pdf\r\nE/4,photo.jpg\r\n"
+        );
+        assert!(zip.by_name("Bundle/E_Evidence/receipt.pdf").is_ok());
+
+        let csv_path = std::env::temp_dir().join(format!("{}.csv", case_name));
+        export_chronology(
+            case_name.clone(),
+            ChronologyExportRequest { format: ExportFormat::Csv, significance: Vec::new() },
+            csv_path.to_string_lossy().to_string(),
+        )
+        .unwrap();
+        let csv = fs::read_to_string(&csv_path).unwrap();
+        assert!(csv.starts_with("Date,Event,Index ref\r\n"));
+        assert!(csv.contains("Bought the machine,E/1–3"));
+        assert!(csv.contains("Photographed the fault,E/4"));
+        assert!(csv.contains("Complained,D/1"));
+    }
+}
@@ -0,0 +1,20 @@
+use crate::commands::case::load_case;
+use crate::fees::{CostsBreakdown, CostsRequest, FeeTable};
+
+/// Court fees, interest and N1 figures for a case's claim value
+#[tauri::command]
+pub fn calculate_claim_costs(case_name: String, mut request: CostsRequest) -> Result<CostsBreakdown, String> {
+    let metadata = load_case(case_name)?;
+    if request.track.is_none() {
+        request.track = Some(crate::allocation::advise(&metadata).track);
+    }
+    let tables = crate::fees::load_fee_tables()?;
+    let today = chrono::Local::now().date_naive();
+    crate::fees::calculate(&tables, metadata.total_claim_value(), &metadata.defendant_type, &request, today)
+}
+
+/// All fee tables available, bundled and user-supplied
+#[tauri::command]
+pub fn list_fee_tables() -> Result<Vec<FeeTable>, String> {
+    crate::fees::load_fee_tables()
+}
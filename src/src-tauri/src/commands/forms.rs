@@ -0,0 +1,173 @@
+use crate::commands::case::load_case;
+use crate::commands::documents::{load_docs_index, with_docs_index};
+use crate::fees::{CostsRequest, IssueChannel};
+use crate::forms::{FieldIssue, FormId, FormInputs, FormSpec, ValidationReport};
+use crate::models::document::{DocumentEntry, DocumentType};
+use crate::path_safety::safe_case_path;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use tauri::Manager;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GeneratedForm {
+    /// None when the form wasn't ready and nothing was saved
+    pub relative_path: Option<String>,
+    pub report: ValidationReport,
+    pub documents: Vec<DocumentEntry>,
+}
+
+/// Work out the field map and values for a form on a case
+fn prepare(
+    case_name: &str,
+    form: FormId,
+    inputs: &FormInputs,
+) -> Result<(FormSpec, BTreeMap<String, String>), String> {
+    let metadata = load_case(case_name.to_string())?;
+    let today = chrono::Local::now().date_naive();
+
+    let specs = crate::forms::load_form_specs()?;
+    let spec = crate::forms::spec_for(&specs, form, today)
+        .cloned()
+        .ok_or_else(|| format!("No field map for form {} is in force", form.code()))?;
+
+    let advice = crate::allocation::advise(&metadata);
+    let request = inputs.costs.clone().unwrap_or(CostsRequest {
+        channel: IssueChannel::Online,
+        track: Some(advice.track),
+        interest: None,
+        issue_date: None,
+    });
+    let tables = crate::fees::load_fee_tables()?;
+    let costs = crate::fees::calculate(
+        &tables,
+        metadata.total_claim_value(),
+        &metadata.defendant_type,
+        &request,
+        today,
+    )?;
+
+    let values = crate::forms::field_values(&metadata, Some(&costs), &advice, inputs, today);
+    Ok((spec, values))
+}
+
+/// Folders searched for blank forms: the user's first, then the bundled resources
+fn blank_dirs(app: &tauri::AppHandle) -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+    if let Some(dir) = crate::forms::user_forms_dir() {
+        dirs.push(dir);
+    }
+    if let Ok(resources) = app.path().resource_dir() {
+        dirs.push(resources.join("data").join("forms"));
+    }
+    dirs
+}
+
+/// Field maps for every supported court form version
+#[tauri::command]
+pub fn list_court_forms() -> Result<Vec<FormSpec>, String> {
+    crate::forms::load_form_specs()
+}
+
+/// Field names in the installed blank for a map, if there is one
+fn blank_fields(app: &tauri::AppHandle, spec: &FormSpec) -> Option<Vec<String>> {
+    let path = crate::forms::find_blank(spec, &blank_dirs(app)).ok()?;
+    let bytes = fs::read(path).ok()?;
+    crate::forms::list_fields(&bytes).ok()
+}
+
+/// Check which fields of a form can be filled from the case, without generating it
+#[tauri::command]
+pub fn check_court_form(
+    app: tauri::AppHandle,
+    case_name: String,
+    form: FormId,
+    inputs: FormInputs,
+) -> Result<ValidationReport, String> {
+    let (spec, values) = prepare(&case_name, form, &inputs)?;
+    let pdf_fields = blank_fields(&app, &spec);
+    Ok(crate::forms::report(&spec, &values, pdf_fields.as_deref()))
+}
+
+/// Add the blank PDF for the version of a form in force, downloaded by the
+/// user from GOV.UK. It is checked against the field map's checksum, if it
+/// has one, and saved to the user's forms folder. Returns the mapped fields
+/// the blank doesn't have, which stop the form being reported ready.
+#[tauri::command]
+pub fn install_court_form_blank(form: FormId, source_path: String) -> Result<Vec<FieldIssue>, String> {
+    let specs = crate::forms::load_form_specs()?;
+    let spec = crate::forms::spec_for(&specs, form, chrono::Local::now().date_naive())
+        .ok_or_else(|| format!("No field map for form {} is in force", form.code()))?;
+
+    let pdf = fs::read(&source_path).map_err(|e| format!("Could not read {}: {}", source_path, e))?;
+    let unmatched = crate::forms::check_blank(spec, &pdf)?;
+
+    let dir = crate::forms::user_forms_dir()
+        .ok_or_else(|| "Could not determine configuration directory".to_string())?;
+    fs::create_dir_all(&dir).map_err(|e| format!("Could not create {}: {}", dir.display(), e))?;
+    fs::write(dir.join(&spec.pdf_file), &pdf)
+        .map_err(|e| format!("Could not save {}: {}", spec.pdf_file, e))?;
+    Ok(unmatched)
+}
+
+/// Fill a court form from the case, save it to 04_Court and add it to the
+/// documents index. A form that isn't ready is not saved, so a partly filled
+/// form can't end up among the court papers; the report says what is missing.
+#[tauri::command]
+pub fn generate_court_form(
+    app: tauri::AppHandle,
+    case_name: String,
+    form: FormId,
+    inputs: FormInputs,
+) -> Result<GeneratedForm, String> {
+    let (spec, values) = prepare(&case_name, form, &inputs)?;
+
+    let blank_path = crate::forms::find_blank(&spec, &blank_dirs(&app))?;
+    let blank = fs::read(&blank_path)
+        .map_err(|e| format!("Could not read {}: {}", blank_path.display(), e))?;
+    let (pdf, report) = crate::forms::fill(&blank, &spec, &values)?;
+    if !report.ready || report.filled.is_empty() {
+        return Ok(GeneratedForm {
+            relative_path: None,
+            report,
+            documents: load_docs_index(&case_name)?,
+        });
+    }
+
+    let court_dir = safe_case_path(&case_name)?.join("04_Court");
+    fs::create_dir_all(&court_dir)
+        .map_err(|e| format!("Could not create folder 04_Court: {}", e))?;
+    let today = chrono::Local::now().date_naive();
+    let filename = crate::forms::output_filename(form, today, &court_dir);
+    fs::write(court_dir.join(&filename), &pdf)
+        .map_err(|e| format!("Could not save {}: {}", filename, e))?;
+
+    let relative_path = format!("04_Court/{}", filename);
+    let documents = with_docs_index(&case_name, |documents| {
+        documents.push(DocumentEntry {
+            filename: filename.clone(),
+            path: relative_path.clone(),
+            folder: "04".to_string(),
+            document_type: DocumentType::CourtForm,
+            date: Some(today.format("%Y-%m-%d").to_string()),
+            description: format!("{} {} (version {})", form.code(), spec.title, spec.version),
+            tags: vec!["generated".to_string()],
+            ..DocumentEntry::default()
+        });
+        Ok(())
+    })?;
+
+    Ok(GeneratedForm {
+        relative_path: Some(relative_path),
+        report,
+        documents,
+    })
+}
+
+/// Field names in a blank PDF form, for checking or writing a field map
+#[tauri::command]
+pub fn list_form_fields(pdf_path: String) -> Result<Vec<String>, String> {
+    let bytes = fs::read(&pdf_path).map_err(|e| format!("Could not read {}: {}", pdf_path, e))?;
+    crate::forms::list_fields(&bytes)
+}
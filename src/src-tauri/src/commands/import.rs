@@ -0,0 +1,284 @@
+//! Bulk folder import.
+//!
+//! `plan_bulk_import` walks a source directory, extracts text from each
+//! supported file and proposes a document type, folder and metadata for it.
+//! Nothing touches the case until the reviewed plan is passed back to
+//! `commit_bulk_import`, which copies the included files in and registers them.
+
+use crate::commands::documents::{copy_into_case, folder_dir_name, load_docs_index, with_docs_index};
+use crate::extraction::{is_supported_file, ExtractedContent};
+use crate::models::document::{DocumentEntry, DocumentType};
+use crate::path_safety::safe_case_path;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Directory depth limit when walking a source folder
+const MAX_DEPTH: usize = 8;
+
+/// One file in an import plan, with its proposed placement and metadata
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportItem {
+    pub id: String,
+    pub source_path: String,
+    pub filename: String,
+    /// Proposed case folder ("01"–"04")
+    pub folder: String,
+    pub document_type: DocumentType,
+    /// Why the classifier chose this type and folder
+    pub classification_reason: String,
+    pub date: Option<String>,
+    pub from: Option<String>,
+    pub to: Option<String>,
+    pub description: String,
+    pub extracted_text: Option<String>,
+    /// Sent date from the file's headers, kept as the anchor for relative dates
+    #[serde(default)]
+    pub metadata_date: Option<String>,
+    pub extraction_error: Option<String>,
+    /// Path of an existing case document with the same filename, if any
+    pub duplicate_of: Option<String>,
+    /// Whether the file will be imported on commit — duplicates start unticked
+    pub include: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SkippedFile {
+    pub source_path: String,
+    pub reason: String,
+}
+
+/// A reviewable proposal for importing a folder of files
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportPlan {
+    pub source_dir: String,
+    pub items: Vec<ImportItem>,
+    pub skipped: Vec<SkippedFile>,
+}
+
+/// Collect files under `dir`, skipping hidden files and folders
+fn walk(dir: &Path, depth: usize, files: &mut Vec<PathBuf>) -> Result<(), String> {
+    let entries = fs::read_dir(dir)
+        .map_err(|e| format!("Could not read {}: {}", dir.display(), e))?;
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if entry.file_name().to_string_lossy().starts_with('.') {
+            continue;
+        }
+        if path.is_dir() {
+            if depth < MAX_DEPTH {
+                walk(&path, depth + 1, files)?;
+            }
+        } else if path.is_file() {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// Pick a document date: the email/file metadata date if present, otherwise
+/// the first unambiguous date near the top of the text (where a letter is dated)
+fn infer_date(extracted: &ExtractedContent) -> Option<String> {
+    crate::date_scanner::anchor_date(extracted.metadata_date.as_deref(), &extracted.text)
+        .map(|anchor| anchor.date.format("%Y-%m-%d").to_string())
+}
+
+fn plan_item(path: &Path, existing: &[DocumentEntry]) -> ImportItem {
+    let filename = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    let (extracted, extraction_error) = match crate::extraction::extract_from_file(path) {
+        Ok(e) => (Some(e), None),
+        Err(e) => (None, Some(e)),
+    };
+
+    let classification = crate::classifier::classify(&filename, extracted.as_ref().map(|e| e.text.as_str()));
+    let duplicate_of = existing
+        .iter()
+        .find(|d| d.filename.eq_ignore_ascii_case(&filename))
+        .map(|d| d.path.clone());
+
+    ImportItem {
+        id: uuid::Uuid::new_v4().to_string(),
+        source_path: path.to_string_lossy().to_string(),
+        filename,
+        folder: classification.folder,
+        document_type: classification.document_type,
+        classification_reason: classification.reason,
+        date: extracted.as_ref().and_then(infer_date),
+        from: extracted.as_ref().and_then(|e| e.from.clone()),
+        to: extracted.as_ref().and_then(|e| e.to.clone()),
+        description: extracted
+            .as_ref()
+            .and_then(|e| e.subject.clone())
+            .unwrap_or_default(),
+        metadata_date: extracted.as_ref().and_then(|e| e.metadata_date.clone()),
+        extracted_text: extracted.map(|e| e.text),
+        extraction_error,
+        include: duplicate_of.is_none(),
+        duplicate_of,
+    }
+}
+
+fn build_plan(case_name: &str, source_dir: &str) -> Result<ImportPlan, String> {
+    let source = PathBuf::from(source_dir);
+    if !source.is_dir() {
+        return Err(format!("Not a folder: {}", source_dir));
+    }
+
+    // Don't let a case import itself
+    let case_path = safe_case_path(case_name)?;
+    if let (Ok(src), Ok(case)) = (source.canonicalize(), case_path.canonicalize()) {
+        if src.starts_with(&case) || case.starts_with(&src) {
+            return Err("Source folder overlaps the case folder".to_string());
+        }
+    }
+
+    let existing = load_docs_index(case_name)?;
+
+    let mut files = Vec::new();
+    walk(&source, 0, &mut files)?;
+    files.sort();
+
+    let mut items = Vec::new();
+    let mut skipped = Vec::new();
+    for path in files {
+        if is_supported_file(&path) {
+            items.push(plan_item(&path, &existing));
+        } else {
+            skipped.push(SkippedFile {
+                source_path: path.to_string_lossy().to_string(),
+                reason: "Unsupported file type".to_string(),
+            });
+        }
+    }
+
+    Ok(ImportPlan {
+        source_dir: source_dir.to_string(),
+        items,
+        skipped,
+    })
+}
+
+/// Walk a folder and propose how each file should be imported. Extraction can
+/// take a while for large folders, so this runs off the main thread.
+#[tauri::command]
+pub async fn plan_bulk_import(case_name: String, source_dir: String) -> Result<ImportPlan, String> {
+    tauri::async_runtime::spawn_blocking(move || build_plan(&case_name, &source_dir))
+        .await
+        .map_err(|e| format!("Import planning stopped unexpectedly: {}", e))?
+}
+
+/// Avoid overwriting: "letter.pdf" becomes "letter (2).pdf", "letter (3).pdf", ...
+pub(crate) fn unique_filename(dir: &Path, filename: &str) -> String {
+    if !dir.join(filename).exists() {
+        return filename.to_string();
+    }
+
+    let path = Path::new(filename);
+    let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or(filename);
+    let ext = path.extension().and_then(|e| e.to_str());
+
+    (2..)
+        .map(|n| match ext {
+            Some(ext) => format!("{} ({}).{}", stem, n, ext),
+            None => format!("{} ({})", stem, n),
+        })
+        .find(|candidate| !dir.join(candidate).exists())
+        .unwrap_or_else(|| filename.to_string())
+}
+
+/// Copy the included items of a reviewed plan into the case and register them.
+/// Copying a large folder takes a while, so this also runs off the main thread.
+#[tauri::command]
+pub async fn commit_bulk_import(case_name: String, items: Vec<ImportItem>) -> Result<Vec<DocumentEntry>, String> {
+    tauri::async_runtime::spawn_blocking(move || commit_plan(&case_name, &items))
+        .await
+        .map_err(|e| format!("Import stopped unexpectedly: {}", e))?
+}
+
+fn commit_plan(case_name: &str, items: &[ImportItem]) -> Result<Vec<DocumentEntry>, String> {
+    let case_path = safe_case_path(case_name)?;
+    let mut docs = load_docs_index(case_name)?;
+
+    for item in items.iter().filter(|i| i.include) {
+        let source = PathBuf::from(&item.source_path);
+        if !source.is_file() {
+            return Err(format!("File no longer exists: {}", item.source_path));
+        }
+
+        let dest_dir = case_path.join(folder_dir_name(&item.folder)?);
+        let filename = unique_filename(&dest_dir, &item.filename);
+        let (_, relative_path) = copy_into_case(case_name, &source, &item.folder, &filename)?;
+
+        let doc = DocumentEntry {
+            filename,
+            path: relative_path,
+            folder: item.folder.clone(),
+            document_type: item.document_type.clone(),
+            date: item.date.clone(),
+            from: item.from.clone(),
+            to: item.to.clone(),
+            description: item.description.clone(),
+            tags: vec!["imported".to_string()],
+            extracted_text: item.extracted_text.clone(),
+            metadata_date: item.metadata_date.clone(),
+            ..DocumentEntry::default()
+        };
+
+        // Save as we go so a failure part-way leaves the index matching the files copied
+        docs = with_docs_index(case_name, |docs| {
+            docs.push(doc);
+            Ok(())
+        })?;
+    }
+
+    Ok(docs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn infers_date_from_email_header() {
+        let extracted = ExtractedContent {
+            text: "Body mentioning 1 January 2020".to_string(),
+            metadata_date: Some("2024-03-15T09:30:00+00:00".to_string()),
+            subject: None,
+            from: None,
+            to: None,
+            ocr: None,
+        };
+        assert_eq!(infer_date(&extracted).as_deref(), Some("2024-03-15"));
+    }
+
+    #[test]
+    fn infers_first_letter_date_from_text() {
+        let extracted = ExtractedContent {
+            text: "12 High Street\n20 May 2024\n\nDear Sir, further to my letter of 2 April 2024".to_string(),
+            metadata_date: None,
+            subject: None,
+            from: None,
+            to: None,
+            ocr: None,
+        };
+        assert_eq!(infer_date(&extracted).as_deref(), Some("2024-05-20"));
+    }
+
+    #[test]
+    fn unique_filename_adds_suffix() {
+        let dir = std::env::temp_dir().join("casekit_test_unique_filename");
+        let _ = fs::create_dir_all(&dir);
+        fs::write(dir.join("letter.pdf"), "x").unwrap();
+        fs::write(dir.join("letter (2).pdf"), "x").unwrap();
+
+        assert_eq!(unique_filename(&dir, "letter.pdf"), "letter (3).pdf");
+        assert_eq!(unique_filename(&dir, "other.pdf"), "other.pdf");
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,73 @@
+use crate::inbox::{InboxConfig, SweepResult};
+use crate::path_safety::safe_case_path;
+use std::path::PathBuf;
+use tauri::AppHandle;
+
+#[tauri::command]
+pub fn get_case_inbox(case_name: String) -> Result<Option<InboxConfig>, String> {
+    crate::inbox::load_config(&case_name)
+}
+
+/// Register (or change) a case's inbox directory and start watching it.
+/// The record of already-ingested files is kept when the directory changes.
+#[tauri::command]
+pub fn set_case_inbox(
+    app: AppHandle,
+    case_name: String,
+    directory: String,
+    folder: Option<String>,
+) -> Result<InboxConfig, String> {
+    let dir = PathBuf::from(&directory);
+    if !dir.is_dir() {
+        return Err(format!("Not a folder: {}", directory));
+    }
+
+    // Watching a folder inside the case would re-ingest our own copies
+    let case_path = safe_case_path(&case_name)?;
+    if let (Ok(inbox), Ok(case)) = (dir.canonicalize(), case_path.canonicalize()) {
+        if inbox.starts_with(&case) || case.starts_with(&inbox) {
+            return Err("The inbox folder must be outside the case folder".to_string());
+        }
+    }
+
+    if let Some(f) = &folder {
+        crate::inbox::inbox_folder_dir(f)?;
+    }
+
+    let ingested = crate::inbox::load_config(&case_name)?
+        .map(|c| c.ingested)
+        .unwrap_or_default();
+    let config = InboxConfig {
+        directory,
+        folder,
+        enabled: true,
+        ingested,
+    };
+    crate::inbox::save_config(&case_name, &config)?;
+
+    crate::inbox::watch(&app, &case_name, &dir)?;
+    crate::inbox::schedule_sweep(&app, &case_name);
+    Ok(config)
+}
+
+/// Stop watching a case's inbox. Its settings and ingest history are kept.
+#[tauri::command]
+pub fn disable_case_inbox(app: AppHandle, case_name: String) -> Result<Option<InboxConfig>, String> {
+    crate::inbox::unwatch(&app, &case_name);
+
+    let mut config = match crate::inbox::load_config(&case_name)? {
+        Some(c) => c,
+        None => return Ok(None),
+    };
+    config.enabled = false;
+    crate::inbox::save_config(&case_name, &config)?;
+    Ok(Some(config))
+}
+
+/// Check the inbox now rather than waiting for the watcher
+#[tauri::command]
+pub async fn sweep_case_inbox(app: AppHandle, case_name: String) -> Result<SweepResult, String> {
+    tauri::async_runtime::spawn_blocking(move || crate::inbox::sweep(&app, &case_name))
+        .await
+        .map_err(|e| format!("Inbox sweep stopped unexpectedly: {}", e))?
+}
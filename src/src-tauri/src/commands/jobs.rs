@@ -0,0 +1,29 @@
+use crate::jobs::JobManager;
+use crate::models::job::{Job, JobKind};
+use tauri::{AppHandle, Manager};
+
+#[tauri::command]
+pub fn enqueue_job(app: AppHandle, case_name: String, kind: JobKind) -> Result<Job, String> {
+    crate::jobs::enqueue(&app, case_name, kind)
+}
+
+#[tauri::command]
+pub fn list_jobs(case_name: String) -> Result<Vec<Job>, String> {
+    crate::jobs::load_jobs(&case_name)
+}
+
+#[tauri::command]
+pub fn cancel_job(app: AppHandle, case_name: String, job_id: String) -> Result<Job, String> {
+    crate::jobs::cancel(&app, &case_name, &job_id)
+}
+
+#[tauri::command]
+pub fn retry_job(app: AppHandle, case_name: String, job_id: String) -> Result<Job, String> {
+    crate::jobs::retry(&app, &case_name, &job_id)
+}
+
+/// Remove completed, failed and cancelled jobs, returning what is still pending
+#[tauri::command]
+pub fn clear_finished_jobs(app: AppHandle, case_name: String) -> Result<Vec<Job>, String> {
+    app.state::<JobManager>().clear_finished(&case_name)
+}
@@ -0,0 +1,10 @@
+use crate::commands::case::load_case;
+use crate::limitation::LimitationReport;
+
+/// Check how long is left to issue the claim under the Limitation Act 1980
+#[tauri::command]
+pub fn check_limitation(case_name: String) -> Result<LimitationReport, String> {
+    let metadata = load_case(case_name)?;
+    let today = chrono::Local::now().date_naive();
+    Ok(crate::limitation::check(&metadata, today))
+}
@@ -1,8 +1,23 @@
+pub mod ai;
 pub mod ai_history;
+pub mod allocation;
 pub mod case;
 pub mod citation;
+pub mod companies_house;
 pub mod chronology;
+pub mod deadlines;
 pub mod documents;
+pub mod drafting;
 pub mod export;
+pub mod fees;
 pub mod filesystem;
+pub mod forms;
+pub mod import;
+pub mod inbox;
+pub mod jobs;
+pub mod limitation;
+pub mod parties;
+pub mod pre_action;
+pub mod redaction;
+pub mod remedies;
 pub mod system;
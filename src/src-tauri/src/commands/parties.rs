@@ -0,0 +1,40 @@
+use crate::commands::case::load_case;
+use crate::commands::documents::load_docs_index;
+use crate::models::document::DocumentEntry;
+use crate::models::party::Party;
+use crate::parties::DocumentParties;
+
+/// The case's parties. Older cases get a claimant and defendant made up from
+/// the intake names.
+#[tauri::command]
+pub fn list_parties(case_name: String) -> Result<Vec<Party>, String> {
+    Ok(load_case(case_name)?.parties())
+}
+
+/// Which parties each document is from and to
+#[tauri::command]
+pub fn link_document_parties(case_name: String) -> Result<Vec<DocumentParties>, String> {
+    let parties = load_case(case_name.clone())?.parties();
+    let docs = load_docs_index(&case_name)?;
+    Ok(docs
+        .iter()
+        .map(|doc| crate::parties::link_document(&parties, doc))
+        .collect())
+}
+
+/// Documents sent by or to one party
+#[tauri::command]
+pub fn list_party_documents(case_name: String, party_id: String) -> Result<Vec<DocumentEntry>, String> {
+    let parties = load_case(case_name.clone())?.parties();
+    if !parties.iter().any(|p| p.id == party_id) {
+        return Err(format!("No party with id '{}'", party_id));
+    }
+    let docs = load_docs_index(&case_name)?;
+    Ok(docs
+        .into_iter()
+        .filter(|doc| {
+            let links = crate::parties::link_document(&parties, doc);
+            links.from.contains(&party_id) || links.to.contains(&party_id)
+        })
+        .collect())
+}
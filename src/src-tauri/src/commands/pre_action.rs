@@ -0,0 +1,53 @@
+use crate::commands::case::load_case;
+use crate::commands::chronology::build_chronology;
+use crate::commands::documents::load_docs_index;
+use crate::models::chronology::ChronologyEntry;
+use crate::path_safety::safe_case_path;
+use crate::pre_action::ProtocolReport;
+use std::collections::HashSet;
+use std::fs;
+
+fn report_for(case_name: &str) -> Result<ProtocolReport, String> {
+    let metadata = load_case(case_name.to_string())?;
+    let docs = load_docs_index(case_name)?;
+    let chronology = build_chronology(case_name.to_string())?;
+    let today = chrono::Local::now().date_naive();
+    Ok(crate::pre_action::check(&metadata, &docs, &chronology, today))
+}
+
+/// Check which pre-action protocol steps have been taken and what is due
+#[tauri::command]
+pub fn check_pre_action(case_name: String) -> Result<ProtocolReport, String> {
+    report_for(&case_name)
+}
+
+/// Add chronology entries for the pre-action steps found so far. Entries the
+/// user has already saved (and perhaps edited) are left alone.
+#[tauri::command]
+pub fn add_pre_action_entries(case_name: String) -> Result<Vec<ChronologyEntry>, String> {
+    let report = report_for(&case_name)?;
+    let chrono_path = safe_case_path(&case_name)?.join(".casekit").join("chronology.json");
+
+    let mut entries: Vec<ChronologyEntry> = Vec::new();
+    if chrono_path.exists() {
+        let content = fs::read_to_string(&chrono_path)
+            .map_err(|e| format!("Could not read chronology.json: {}", e))?;
+        entries = serde_json::from_str(&content)
+            .map_err(|e| format!("Could not parse chronology.json: {}", e))?;
+    }
+
+    let existing: HashSet<String> = entries.iter().map(|e| e.id.clone()).collect();
+    entries.extend(
+        crate::pre_action::chronology_entries(&report)
+            .into_iter()
+            .filter(|e| !existing.contains(&e.id)),
+    );
+    entries.sort_by(|a, b| a.date.cmp(&b.date));
+
+    let json = serde_json::to_string_pretty(&entries)
+        .map_err(|e| format!("Could not serialise chronology: {}", e))?;
+    fs::write(&chrono_path, &json)
+        .map_err(|e| format!("Could not write chronology.json: {}", e))?;
+
+    build_chronology(case_name)
+}
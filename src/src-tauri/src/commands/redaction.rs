@@ -0,0 +1,159 @@
+use crate::commands::case::load_case;
+use crate::commands::documents::with_docs_index;
+use crate::commands::import::unique_filename;
+use crate::models::document::DocumentEntry;
+use crate::path_safety::{safe_case_path, validate_relative_path};
+use crate::pdf_redaction::{PdfRedactionOptions, PdfRedactionReport};
+use crate::redaction::{Redacted, RedactionMap, RedactionOptions};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RedactedDocument {
+    pub relative_path: String,
+    pub report: PdfRedactionReport,
+    pub documents: Vec<DocumentEntry>,
+}
+
+/// Redaction maps hold the original values, so they live in the case folder
+/// and nowhere else
+fn map_path(case_name: &str, map_id: &str) -> Result<PathBuf, String> {
+    uuid::Uuid::parse_str(map_id).map_err(|_| format!("Invalid redaction id '{}'", map_id))?;
+    Ok(safe_case_path(case_name)?
+        .join(".casekit")
+        .join("redactions")
+        .join(format!("{}.json", map_id)))
+}
+
+/// Redact texts before they are sent to an AI provider. The case's own
+/// redaction terms are always included. The map needed to reverse the
+/// redaction is saved in the case folder.
+#[tauri::command]
+pub fn redact_texts(
+    case_name: String,
+    texts: Vec<String>,
+    options: Option<RedactionOptions>,
+) -> Result<Redacted, String> {
+    let case = load_case(case_name.clone())?;
+    let mut options = options.unwrap_or_default();
+    for term in case.redaction_terms {
+        if !options.custom_terms.contains(&term) {
+            options.custom_terms.push(term);
+        }
+    }
+
+    let redacted = crate::redaction::redact_all(&texts, &options);
+
+    let path = map_path(&case_name, &redacted.map.id)?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Could not create redactions folder: {}", e))?;
+    }
+    let json = serde_json::to_string_pretty(&redacted.map)
+        .map_err(|e| format!("Could not serialise redaction map: {}", e))?;
+    fs::write(&path, &json)
+        .map_err(|e| format!("Could not write redaction map: {}", e))?;
+
+    Ok(redacted)
+}
+
+/// Put the original values back into text that contains placeholders, e.g.
+/// an AI response
+#[tauri::command]
+pub fn restore_redacted_text(case_name: String, map_id: String, text: String) -> Result<String, String> {
+    let map = load_map(&case_name, &map_id)?;
+    Ok(crate::redaction::restore(&text, &map))
+}
+
+pub(crate) fn load_map(case_name: &str, map_id: &str) -> Result<RedactionMap, String> {
+    let path = map_path(case_name, map_id)?;
+    if !path.exists() {
+        return Err("The redaction map for this text no longer exists".to_string());
+    }
+    let content = fs::read_to_string(&path)
+        .map_err(|e| format!("Could not read redaction map: {}", e))?;
+    serde_json::from_str(&content)
+        .map_err(|e| format!("Could not parse redaction map: {}", e))
+}
+
+fn redact_case_pdf(
+    case_name: &str,
+    relative_path: &str,
+    options: &PdfRedactionOptions,
+) -> Result<RedactedDocument, String> {
+    let case_path = safe_case_path(case_name)?;
+    let source = validate_relative_path(&case_path, relative_path)?;
+    let is_pdf = source
+        .extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| e.eq_ignore_ascii_case("pdf"));
+    if !is_pdf {
+        return Err("Only PDF documents can be redacted".to_string());
+    }
+    let original = fs::read(&source)
+        .map_err(|e| format!("Could not read {}: {}", relative_path, e))?;
+
+    let redacted = crate::pdf_redaction::redact(&original, options)?;
+    let text = crate::pdf_redaction::verify(&original, &redacted)?;
+
+    let bundle_dir = case_path.join("05_Bundle");
+    fs::create_dir_all(&bundle_dir)
+        .map_err(|e| format!("Could not create folder 05_Bundle: {}", e))?;
+    let source_name = source
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or(relative_path)
+        .to_string();
+    let stem = Path::new(&source_name)
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("document");
+    let filename = unique_filename(&bundle_dir, &format!("{} (redacted).pdf", stem));
+    fs::write(bundle_dir.join(&filename), &redacted.bytes)
+        .map_err(|e| format!("Could not save {}: {}", filename, e))?;
+
+    // The copy keeps the original's date, type and correspondents
+    let relative = format!("05_Bundle/{}", filename);
+    let documents = with_docs_index(case_name, |documents| {
+        let original_entry = documents
+            .iter()
+            .find(|d| d.path == relative_path)
+            .cloned()
+            .unwrap_or_default();
+        let defaults = DocumentEntry::default();
+        documents.push(DocumentEntry {
+            id: defaults.id,
+            filename,
+            path: relative.clone(),
+            folder: "05".to_string(),
+            description: format!("Redacted copy of {}", source_name),
+            tags: vec!["redacted".to_string()],
+            extracted_text: Some(text),
+            added_at: defaults.added_at,
+            pending_review: false,
+            ..original_entry
+        });
+        Ok(())
+    })?;
+
+    Ok(RedactedDocument {
+        relative_path: relative,
+        report: redacted.report,
+        documents,
+    })
+}
+
+/// Write a copy of a case PDF to 05_Bundle with the given areas and terms
+/// permanently removed, and add it to the documents index. Nothing is saved
+/// unless the redacted text is confirmed to be gone.
+#[tauri::command]
+pub async fn redact_pdf(
+    case_name: String,
+    relative_path: String,
+    options: PdfRedactionOptions,
+) -> Result<RedactedDocument, String> {
+    tauri::async_runtime::spawn_blocking(move || redact_case_pdf(&case_name, &relative_path, &options))
+        .await
+        .map_err(|e| format!("Redaction stopped unexpectedly: {}", e))?
+}
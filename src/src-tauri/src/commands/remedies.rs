@@ -0,0 +1,10 @@
+use crate::commands::case::load_case;
+use crate::remedies::RemediesReport;
+
+/// List the Consumer Rights Act 2015 remedies still open to the consumer
+#[tauri::command]
+pub fn assess_remedies(case_name: String) -> Result<RemediesReport, String> {
+    let metadata = load_case(case_name)?;
+    let today = chrono::Local::now().date_naive();
+    Ok(crate::remedies::assess(&metadata, today))
+}
@@ -1,5 +1,6 @@
 //! System-level commands for dependency checks and environment info.
 
+use crate::settings::AppSettings;
 use serde::Serialize;
 
 #[derive(Debug, Serialize)]
@@ -10,6 +11,10 @@ pub struct DependencyStatus {
     pub required: bool,
     pub install_url: String,
     pub description: String,
+    /// Where the dependency was found, if installed
+    pub path: Option<String>,
+    /// Installed language packs (Tesseract only)
+    pub languages: Vec<String>,
 }
 
 #[tauri::command]
@@ -24,59 +29,27 @@ pub fn check_dependencies() -> Vec<DependencyStatus> {
 }
 
 fn check_tesseract() -> DependencyStatus {
-    let candidates = [
-        r"C:\Program Files\Tesseract-OCR\tesseract.exe",
-        r"C:\Program Files (x86)\Tesseract-OCR\tesseract.exe",
-    ];
-
-    // Check known paths
-    for path in &candidates {
-        if std::path::Path::new(path).exists() {
-            let version = get_tesseract_version(path);
-            return DependencyStatus {
-                name: "Tesseract OCR".to_string(),
-                installed: true,
-                version,
-                required: false,
-                install_url: "https://github.com/UB-Mannheim/tesseract/wiki".to_string(),
-                description: "Extracts text from scanned documents and images".to_string(),
-            };
-        }
-    }
-
-    // Check PATH
-    if let Ok(output) = std::process::Command::new("tesseract").arg("--version").output() {
-        if output.status.success() {
-            let ver = String::from_utf8_lossy(&output.stdout);
-            let version = ver.lines().next().map(|l| l.trim().to_string());
-            return DependencyStatus {
-                name: "Tesseract OCR".to_string(),
-                installed: true,
-                version,
-                required: false,
-                install_url: "https://github.com/UB-Mannheim/tesseract/wiki".to_string(),
-                description: "Extracts text from scanned documents and images".to_string(),
-            };
-        }
-    }
+    let install = crate::ocr::discover_tesseract();
 
     DependencyStatus {
         name: "Tesseract OCR".to_string(),
-        installed: false,
-        version: None,
+        installed: install.is_some(),
+        version: install.as_ref().and_then(|i| i.version.clone()),
         required: false,
-        install_url: "https://github.com/UB-Mannheim/tesseract/wiki".to_string(),
+        install_url: crate::ocr::install_url().to_string(),
         description: "Extracts text from scanned documents and images".to_string(),
+        path: install.as_ref().map(|i| i.path.to_string_lossy().to_string()),
+        languages: install.map(|i| i.languages).unwrap_or_default(),
     }
 }
 
-fn get_tesseract_version(path: &str) -> Option<String> {
-    std::process::Command::new(path)
-        .arg("--version")
-        .output()
-        .ok()
-        .and_then(|o| {
-            let out = String::from_utf8_lossy(&o.stdout);
-            out.lines().next().map(|l| l.trim().to_string())
-        })
+#[tauri::command]
+pub fn load_app_settings() -> Result<AppSettings, String> {
+    crate::settings::load_settings()
+}
+
+#[tauri::command]
+pub fn save_app_settings(settings: AppSettings) -> Result<AppSettings, String> {
+    crate::settings::save_settings(&settings)?;
+    Ok(settings)
 }
@@ -0,0 +1,643 @@
+//! Company lookups against the Companies House register.
+//!
+//! The main source is the free "Basic Company Data" product, a CSV of every
+//! live company (about 2GB, or a 450MB zip) that the user downloads from
+//! download.companieshouse.gov.uk and points CaseKit at. It is streamed, never
+//! loaded whole. If the user has registered for a Companies House API key the
+//! public data API can be used instead for an up-to-the-minute answer.
+//!
+//! A defendant that has been dissolved, or is in liquidation, administration
+//! or another insolvency process, is flagged on the case.
+
+use crate::models::case::CaseMetadata;
+use crate::models::company::{CompanyRecord, CompanySource, CompanyStatus};
+use crate::models::party::PartyType;
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{BufRead, BufReader, Read};
+use std::path::Path;
+use std::time::Duration;
+
+pub const API_BASE: &str = "https://api.company-information.service.gov.uk";
+
+/// The outcome of checking a party against the register
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompanyCheck {
+    pub party_id: String,
+    pub record: CompanyRecord,
+    /// Dissolved or in an insolvency process
+    pub red_flag: bool,
+    /// Points for the user, most serious first
+    pub warnings: Vec<String>,
+}
+
+/// Upper-case, no spaces, and purely numeric numbers padded to eight digits:
+/// "1234567" becomes "01234567"
+pub fn normalise_number(number: &str) -> String {
+    let n: String = number.chars().filter(|c| !c.is_whitespace()).collect::<String>().to_uppercase();
+    if !n.is_empty() && n.len() < 8 && n.chars().all(|c| c.is_ascii_digit()) {
+        format!("{:0>8}", n)
+    } else {
+        n
+    }
+}
+
+/// Whether a search looks like a company number ("01234567", "SC123456")
+/// rather than a name
+pub fn is_company_number(query: &str) -> bool {
+    let n = normalise_number(query);
+    n.len() == 8
+        && n.chars().skip(2).all(|c| c.is_ascii_digit())
+        && n.chars().take(2).all(|c| c.is_ascii_alphanumeric())
+}
+
+// ===== Bulk snapshot =====
+
+/// Column positions in the bulk CSV. Some headers have a leading space
+/// (" CompanyNumber"), so names are compared trimmed.
+struct Columns {
+    name: usize,
+    number: usize,
+    address: Vec<usize>,
+    category: Option<usize>,
+    status: Option<usize>,
+    dissolved: Option<usize>,
+    incorporated: Option<usize>,
+}
+
+impl Columns {
+    fn from_header(header: &[String]) -> Result<Self, String> {
+        let find = |name: &str| header.iter().position(|h| h.trim().eq_ignore_ascii_case(name));
+        let missing = || "This file doesn't look like Companies House basic company data (no CompanyName/CompanyNumber columns)".to_string();
+        Ok(Self {
+            name: find("CompanyName").ok_or_else(missing)?,
+            number: find("CompanyNumber").ok_or_else(missing)?,
+            address: [
+                "RegAddress.CareOf",
+                "RegAddress.POBox",
+                "RegAddress.AddressLine1",
+                "RegAddress.AddressLine2",
+                "RegAddress.PostTown",
+                "RegAddress.County",
+                "RegAddress.Country",
+                "RegAddress.PostCode",
+            ]
+            .iter()
+            .filter_map(|c| find(c))
+            .collect(),
+            category: find("CompanyCategory"),
+            status: find("CompanyStatus"),
+            dissolved: find("DissolutionDate"),
+            incorporated: find("IncorporationDate"),
+        })
+    }
+
+    fn record(&self, fields: &[String], snapshot: &str) -> CompanyRecord {
+        let get = |i: Option<usize>| {
+            i.and_then(|i| fields.get(i))
+                .map(|f| f.trim().to_string())
+                .filter(|f| !f.is_empty())
+        };
+        let address: Vec<String> = self.address.iter().filter_map(|&i| get(Some(i))).collect();
+        let status_text = get(self.status).unwrap_or_default();
+        CompanyRecord {
+            company_number: normalise_number(&get(Some(self.number)).unwrap_or_default()),
+            company_name: get(Some(self.name)).unwrap_or_default(),
+            registered_office: (!address.is_empty()).then(|| address.join("\n")),
+            status: CompanyStatus::from_text(&status_text),
+            status_text,
+            company_type: get(self.category),
+            incorporated_on: get(self.incorporated).and_then(|d| iso_date(&d)),
+            dissolved_on: get(self.dissolved).and_then(|d| iso_date(&d)),
+            source: CompanySource::BulkSnapshot(snapshot.to_string()),
+            retrieved_at: chrono::Utc::now().to_rfc3339(),
+        }
+    }
+}
+
+/// The bulk data writes dates as DD/MM/YYYY
+fn iso_date(date: &str) -> Option<String> {
+    chrono::NaiveDate::parse_from_str(date.trim(), "%d/%m/%Y")
+        .or_else(|_| chrono::NaiveDate::parse_from_str(date.trim(), "%Y-%m-%d"))
+        .ok()
+        .map(|d| d.format("%Y-%m-%d").to_string())
+}
+
+/// Split one CSV record into fields, undoing "" escapes inside quotes
+fn split_csv(record: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = record.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields.push(std::mem::take(&mut field)),
+            _ => field.push(c),
+        }
+    }
+    fields.push(field);
+    fields
+}
+
+/// The next CSV record, which may run over several lines if a quoted field
+/// contains a line break
+fn next_record(reader: &mut impl BufRead, line: &mut String) -> Result<bool, String> {
+    line.clear();
+    loop {
+        let read = reader
+            .read_line(line)
+            .map_err(|e| format!("Could not read the Companies House data: {}", e))?;
+        if read == 0 || line.matches('"').count().is_multiple_of(2) {
+            break;
+        }
+    }
+    while line.ends_with('\n') || line.ends_with('\r') {
+        line.pop();
+    }
+    Ok(!line.is_empty() || reader.fill_buf().map(|b| !b.is_empty()).unwrap_or(false))
+}
+
+/// Search a snapshot read from `reader`. A number search stops at the first
+/// match; a name search returns exact matches (ignoring "Ltd", punctuation
+/// etc.) before names that merely contain the query.
+fn search_reader(reader: impl Read, snapshot: &str, query: &str, limit: usize) -> Result<Vec<CompanyRecord>, String> {
+    let mut reader = BufReader::with_capacity(1 << 20, reader);
+    let mut line = String::new();
+    if !next_record(&mut reader, &mut line)? {
+        return Err("The Companies House data file is empty".to_string());
+    }
+    let columns = Columns::from_header(&split_csv(line.trim_start_matches('\u{feff}')))?;
+
+    let by_number = is_company_number(query).then(|| normalise_number(query));
+    let wanted = crate::parties::normalise_name(query);
+    if by_number.is_none() && wanted.is_empty() {
+        return Err("Enter a company name or number to search for".to_string());
+    }
+
+    let mut exact = Vec::new();
+    let mut partial = Vec::new();
+    while next_record(&mut reader, &mut line)? {
+        let fields = split_csv(&line);
+        if let Some(number) = &by_number {
+            if fields.get(columns.number).map(|n| normalise_number(n)).as_ref() == Some(number) {
+                return Ok(vec![columns.record(&fields, snapshot)]);
+            }
+            continue;
+        }
+        let Some(name) = fields.get(columns.name) else { continue };
+        let name = crate::parties::normalise_name(name);
+        if name == wanted {
+            exact.push(columns.record(&fields, snapshot));
+            if exact.len() >= limit {
+                break;
+            }
+        } else if partial.len() < limit && crate::parties::contains_words(&name, &wanted) {
+            partial.push(columns.record(&fields, snapshot));
+        }
+    }
+
+    exact.extend(partial);
+    exact.truncate(limit);
+    Ok(exact)
+}
+
+/// Search a downloaded snapshot: the CSV itself or the zip it comes in
+pub fn search_snapshot(path: &Path, query: &str, limit: usize) -> Result<Vec<CompanyRecord>, String> {
+    let file = File::open(path)
+        .map_err(|e| format!("Could not open Companies House data {}: {}", path.display(), e))?;
+    let file_name = path.file_name().and_then(|n| n.to_str()).unwrap_or("snapshot").to_string();
+
+    let is_zip = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| e.eq_ignore_ascii_case("zip"));
+    if !is_zip {
+        return search_reader(file, &file_name, query, limit);
+    }
+
+    let mut archive = zip::ZipArchive::new(file)
+        .map_err(|e| format!("Could not open zip {}: {}", path.display(), e))?;
+    let index = (0..archive.len())
+        .find(|&i| {
+            archive
+                .by_index(i)
+                .map(|f| f.name().to_lowercase().ends_with(".csv"))
+                .unwrap_or(false)
+        })
+        .ok_or_else(|| format!("{} does not contain a CSV file", file_name))?;
+    let entry = archive
+        .by_index(index)
+        .map_err(|e| format!("Could not read zip {}: {}", path.display(), e))?;
+    search_reader(entry, &file_name, query, limit)
+}
+
+// ===== Live API =====
+
+fn text(value: &serde_json::Value, key: &str) -> Option<String> {
+    value
+        .get(key)
+        .and_then(|v| v.as_str())
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+}
+
+/// Read a company profile, or an item from a company search, returned by the API
+pub fn record_from_api(value: &serde_json::Value) -> Option<CompanyRecord> {
+    let number = text(value, "company_number")?;
+    let name = text(value, "company_name").or_else(|| text(value, "title"))?;
+    let address = value
+        .get("registered_office_address")
+        .or_else(|| value.get("address"))
+        .map(|a| {
+            ["care_of", "po_box", "premises", "address_line_1", "address_line_2", "locality", "region", "country", "postal_code"]
+                .iter()
+                .filter_map(|k| text(a, k))
+                .collect::<Vec<_>>()
+        })
+        .filter(|lines| !lines.is_empty())
+        .map(|lines| lines.join("\n"))
+        .or_else(|| text(value, "address_snippet"));
+    let status_text = text(value, "company_status").unwrap_or_default();
+
+    Some(CompanyRecord {
+        company_number: normalise_number(&number),
+        company_name: name,
+        registered_office: address,
+        status: CompanyStatus::from_text(&status_text),
+        status_text,
+        company_type: text(value, "type").or_else(|| text(value, "company_type")),
+        incorporated_on: text(value, "date_of_creation"),
+        dissolved_on: text(value, "date_of_cessation"),
+        source: CompanySource::LiveApi,
+        retrieved_at: chrono::Utc::now().to_rfc3339(),
+    })
+}
+
+fn api_client() -> Result<reqwest::Client, String> {
+    reqwest::Client::builder()
+        .timeout(Duration::from_secs(15))
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))
+}
+
+async fn api_get(api_key: &str, path: &str) -> Result<Option<serde_json::Value>, String> {
+    let response = api_client()?
+        .get(format!("{}{}", API_BASE, path))
+        // The key is sent as the username with a blank password
+        .basic_auth(api_key, Some(""))
+        .send()
+        .await
+        .map_err(|e| format!("Could not reach Companies House: {}", e))?;
+
+    match response.status().as_u16() {
+        404 => Ok(None),
+        401 => Err("Companies House rejected the API key".to_string()),
+        429 => Err("Too many requests to Companies House; try again in five minutes".to_string()),
+        s if s >= 400 => Err(format!("Companies House returned an error (HTTP {})", s)),
+        _ => response
+            .json()
+            .await
+            .map(Some)
+            .map_err(|e| format!("Could not read the Companies House response: {}", e)),
+    }
+}
+
+/// Look up one company by number on the live register
+pub async fn fetch_company(api_key: &str, number: &str) -> Result<Option<CompanyRecord>, String> {
+    let number = normalise_number(number);
+    let path = format!("/company/{}", urlencoding::encode(&number));
+    Ok(api_get(api_key, &path).await?.as_ref().and_then(record_from_api))
+}
+
+/// Search the live register by name
+pub async fn search_live(api_key: &str, query: &str, limit: usize) -> Result<Vec<CompanyRecord>, String> {
+    let path = format!(
+        "/search/companies?q={}&items_per_page={}",
+        urlencoding::encode(query.trim()),
+        limit
+    );
+    let body = api_get(api_key, &path).await?;
+    Ok(body
+        .as_ref()
+        .and_then(|b| b.get("items"))
+        .and_then(|items| items.as_array())
+        .map(|items| items.iter().filter_map(record_from_api).collect())
+        .unwrap_or_default())
+}
+
+// ===== Applying a result to the case =====
+
+/// What the register says about suing the company, most serious first
+pub fn warnings(record: &CompanyRecord) -> Vec<String> {
+    let who = format!("{} ({})", record.company_name, record.company_number);
+    let mut warnings = Vec::new();
+    match record.status {
+        s if s.is_dissolved() => warnings.push(format!(
+            "{} was dissolved{}. A dissolved company no longer exists and cannot be sued unless it is first restored to the register (Companies Act 2006 Part 31).",
+            who,
+            record.dissolved_on.as_ref().map(|d| format!(" on {}", d)).unwrap_or_default()
+        )),
+        CompanyStatus::Liquidation => warnings.push(format!(
+            "{} is in liquidation. Once a winding-up order is made no claim can be started or continued without the court's permission (Insolvency Act 1986 s130(2)), and unsecured creditors are often paid little or nothing.",
+            who
+        )),
+        CompanyStatus::Administration => warnings.push(format!(
+            "{} is in administration. Legal proceedings need the administrator's consent or the court's permission (Insolvency Act 1986 Sch B1 para 43).",
+            who
+        )),
+        s if s.is_insolvent() => warnings.push(format!(
+            "{} is subject to insolvency proceedings ({}). Get advice before issuing: the claim may be restricted and any judgment may go unpaid.",
+            who, record.status_text
+        )),
+        CompanyStatus::ProposalToStrikeOff => warnings.push(format!(
+            "{} has a proposal to strike it off the register. If it is dissolved before judgment the claim cannot continue; you can object to the strike-off with Companies House.",
+            who
+        )),
+        _ => {}
+    }
+    if let Some(office) = &record.registered_office {
+        warnings.push(format!(
+            "Registered office: {}. A company may be served at its registered office (Companies Act 2006 s1139) or at its principal office or a place of business with a real connection to the claim (CPR 6.9).",
+            office.replace('\n', ", ")
+        ));
+    }
+    warnings
+}
+
+fn trigger_prefix(number: &str) -> String {
+    format!("Companies House ({}): ", number)
+}
+
+/// Dissolved or in an insolvency process
+fn is_red_flag(record: &CompanyRecord) -> bool {
+    record.status.is_dissolved() || record.status.is_insolvent()
+}
+
+fn any_red_flag(case: &CaseMetadata) -> bool {
+    case.parties
+        .iter()
+        .any(|p| p.companies_house.as_ref().is_some_and(is_red_flag))
+}
+
+/// The case's insolvency flag is set while any party's register entry calls
+/// for it. A flag set by an earlier check is cleared once no party needs it; one
+/// set by hand is left alone.
+fn recompute_insolvency(case: &mut CaseMetadata, was_flagged: bool) {
+    if any_red_flag(case) {
+        case.insolvency = true;
+    } else if was_flagged {
+        case.insolvency = false;
+    }
+}
+
+fn set_trigger(case: &mut CaseMetadata, number: &str, warning: Option<&String>) {
+    let prefix = trigger_prefix(number);
+    case.complexity_triggers.retain(|t| !t.starts_with(&prefix));
+    if let Some(warning) = warning {
+        case.complexity_triggers.push(format!("{}{}", prefix, warning));
+    }
+}
+
+/// Record a register entry against a party: take the registered name and
+/// number, use the registered office if no address is known, and flag the
+/// case if the company is dissolved or insolvent. An earlier flag for the
+/// same company is replaced.
+pub fn apply_to_party(case: &mut CaseMetadata, party_id: &str, record: CompanyRecord) -> Result<CompanyCheck, String> {
+    if case.parties.is_empty() {
+        case.parties = case.parties();
+    }
+    let was_flagged = any_red_flag(case);
+    let party = case
+        .parties
+        .iter_mut()
+        .find(|p| p.id == party_id)
+        .ok_or_else(|| format!("No party with id '{}'", party_id))?;
+
+    if crate::parties::normalise_name(&party.name) != crate::parties::normalise_name(&record.company_name)
+        && !party.name.trim().is_empty()
+        && !party.aliases.contains(&party.name)
+    {
+        party.aliases.push(party.name.clone());
+    }
+    party.name = record.company_name.clone();
+    party.party_type = PartyType::Company;
+    party.company_number = Some(record.company_number.clone());
+    if party.address.as_deref().is_none_or(|a| a.trim().is_empty()) {
+        party.address = record.registered_office.clone();
+    }
+    party.companies_house = Some(record.clone());
+
+    let warnings = warnings(&record);
+    let red_flag = is_red_flag(&record);
+    let flagged = red_flag || record.status == CompanyStatus::ProposalToStrikeOff;
+    set_trigger(case, &record.company_number, flagged.then(|| &warnings[0]));
+    recompute_insolvency(case, was_flagged);
+    case.sync_parties();
+
+    Ok(CompanyCheck {
+        party_id: party_id.to_string(),
+        record,
+        red_flag,
+        warnings,
+    })
+}
+
+/// Record that a company number is missing from a downloaded snapshot. The
+/// bulk data lists only companies still on the register, so the company may
+/// have been dissolved or struck off; the case is flagged until it is checked
+/// on the live register. The party's name and address are left as they are.
+pub fn apply_missing_from_snapshot(
+    case: &mut CaseMetadata,
+    party_id: &str,
+    number: &str,
+    snapshot: &str,
+) -> Result<CompanyCheck, String> {
+    if case.parties.is_empty() {
+        case.parties = case.parties();
+    }
+    let was_flagged = any_red_flag(case);
+    let number = normalise_number(number);
+    let party = case
+        .parties
+        .iter_mut()
+        .find(|p| p.id == party_id)
+        .ok_or_else(|| format!("No party with id '{}'", party_id))?;
+
+    let record = CompanyRecord {
+        company_number: number.clone(),
+        company_name: party.name.clone(),
+        registered_office: None,
+        status: CompanyStatus::Unknown,
+        status_text: "Not on the live register".to_string(),
+        company_type: None,
+        incorporated_on: None,
+        dissolved_on: None,
+        source: CompanySource::BulkSnapshot(snapshot.to_string()),
+        retrieved_at: chrono::Utc::now().to_rfc3339(),
+    };
+    party.party_type = PartyType::Company;
+    party.company_number = Some(number.clone());
+    party.companies_house = Some(record.clone());
+
+    let warnings = vec![format!(
+        "Company {} is not on the live register in {}. It may have been dissolved or struck off, or the number may be wrong. Check it on the Companies House service before issuing a claim.",
+        number, snapshot
+    )];
+    set_trigger(case, &number, warnings.first());
+    recompute_insolvency(case, was_flagged);
+    case.sync_parties();
+
+    Ok(CompanyCheck {
+        party_id: party_id.to_string(),
+        record,
+        red_flag: true,
+        warnings,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::party::PartyRole;
+
+    const SNAPSHOT: &str = "\u{feff}CompanyName, CompanyNumber,RegAddress.CareOf,RegAddress.POBox,RegAddress.AddressLine1, RegAddress.AddressLine2,RegAddress.PostTown,RegAddress.County,RegAddress.Country,RegAddress.PostCode,CompanyCategory,CompanyStatus,CountryOfOrigin,DissolutionDate,IncorporationDate\r\n\
+\"ACME APPLIANCES LIMITED\",01234567,,,\"1 HIGH STREET\",\"\",LEEDS,,ENGLAND,LS1 1AA,Private Limited Company,Active,United Kingdom,,01/02/2010\r\n\
+\"ACME APPLIANCE REPAIRS LTD\",SC123456,,,\"UNIT 2, \"\"THE YARD\"\"\",,GLASGOW,,SCOTLAND,G1 1AA,Private Limited Company,Liquidation,United Kingdom,,05/06/2015\r\n\
+\"BROWN'S KITCHENS LTD\",07654321,,,\"3 MILL LANE\",,YORK,,ENGLAND,YO1 1AA,Private Limited Company,Active - Proposal to Strike off,United Kingdom,,09/10/2018\r\n";
+
+    fn search(query: &str) -> Vec<CompanyRecord> {
+        search_reader(SNAPSHOT.as_bytes(), "BasicCompanyData.csv", query, 10).unwrap()
+    }
+
+    #[test]
+    fn normalises_numbers() {
+        assert_eq!(normalise_number("1234567"), "01234567");
+        assert_eq!(normalise_number("sc 123456"), "SC123456");
+        assert!(is_company_number("1234567"));
+        assert!(is_company_number("SC123456"));
+        assert!(!is_company_number("Acme"));
+    }
+
+    #[test]
+    fn reads_statuses_from_both_sources() {
+        assert_eq!(CompanyStatus::from_text("Active - Proposal to Strike off"), CompanyStatus::ProposalToStrikeOff);
+        assert_eq!(CompanyStatus::from_text("In Administration"), CompanyStatus::Administration);
+        assert_eq!(CompanyStatus::from_text("voluntary-arrangement"), CompanyStatus::VoluntaryArrangement);
+        assert_eq!(CompanyStatus::from_text("converted-closed"), CompanyStatus::Closed);
+        assert_eq!(CompanyStatus::from_text("RECEIVER MANAGER / ADMINISTRATIVE RECEIVER"), CompanyStatus::Receivership);
+        assert!(CompanyStatus::from_text("dissolved").is_dissolved());
+    }
+
+    #[test]
+    fn searches_a_snapshot_by_number_and_name() {
+        let found = search("1234567");
+        assert_eq!(found.len(), 1);
+        let acme = &found[0];
+        assert_eq!(acme.company_name, "ACME APPLIANCES LIMITED");
+        assert_eq!(acme.registered_office.as_deref(), Some("1 HIGH STREET\nLEEDS\nENGLAND\nLS1 1AA"));
+        assert_eq!(acme.incorporated_on.as_deref(), Some("2010-02-01"));
+        assert_eq!(acme.status, CompanyStatus::Active);
+
+        let names: Vec<String> = search("Acme Appliances Ltd").into_iter().map(|r| r.company_name).collect();
+        assert_eq!(names, vec!["ACME APPLIANCES LIMITED"]);
+        let repairs = search("acme appliance repairs");
+        assert_eq!(repairs[0].registered_office.as_deref(), Some("UNIT 2, \"THE YARD\"\nGLASGOW\nSCOTLAND\nG1 1AA"));
+        assert_eq!(search("Acme").len(), 2);
+        assert!(search("Zenith").is_empty());
+    }
+
+    #[test]
+    fn rejects_other_csv_files() {
+        let err = search_reader("Date,Amount\n1,2\n".as_bytes(), "bank.csv", "Acme", 10).unwrap_err();
+        assert!(err.contains("doesn't look like Companies House"));
+    }
+
+    #[test]
+    fn reads_api_profiles() {
+        let profile = serde_json::json!({
+            "company_name": "ACME APPLIANCES LIMITED",
+            "company_number": "01234567",
+            "company_status": "dissolved",
+            "date_of_cessation": "2024-01-09",
+            "type": "ltd",
+            "registered_office_address": { "address_line_1": "1 High Street", "locality": "Leeds", "postal_code": "LS1 1AA" }
+        });
+        let record = record_from_api(&profile).unwrap();
+        assert_eq!(record.status, CompanyStatus::Dissolved);
+        assert_eq!(record.registered_office.as_deref(), Some("1 High Street\nLeeds\nLS1 1AA"));
+        assert_eq!(record.source, CompanySource::LiveApi);
+
+        let item = serde_json::json!({ "title": "ACME LTD", "company_number": "SC1", "company_status": "active", "address_snippet": "Glasgow" });
+        assert_eq!(record_from_api(&item).unwrap().registered_office.as_deref(), Some("Glasgow"));
+    }
+
+    #[test]
+    fn flags_insolvent_defendants_on_the_case() {
+        let mut case = CaseMetadata {
+            claimant_name: "Jane Smith".to_string(),
+            defendant_name: "Acme Repairs".to_string(),
+            ..CaseMetadata::default()
+        };
+        let record = search("SC123456").remove(0);
+        let check = apply_to_party(&mut case, "defendant", record.clone()).unwrap();
+
+        assert!(check.red_flag);
+        assert!(check.warnings[0].contains("in liquidation"));
+        assert!(case.insolvency);
+        assert_eq!(case.defendant_name, "ACME APPLIANCE REPAIRS LTD");
+        assert_eq!(case.complexity_triggers.len(), 1);
+        let defendant = &case.parties_in_role(PartyRole::Defendant)[0];
+        assert_eq!(defendant.company_number.as_deref(), Some("SC123456"));
+        assert_eq!(defendant.aliases, vec!["Acme Repairs"]);
+        assert!(defendant.address.as_deref().unwrap().starts_with("UNIT 2"));
+
+        // Checking again replaces the flag rather than adding another
+        apply_to_party(&mut case, "defendant", record).unwrap();
+        assert_eq!(case.complexity_triggers.len(), 1);
+
+        // Correcting the number clears the flag the liquidation set
+        let active = search("01234567").remove(0);
+        assert!(!apply_to_party(&mut case, "defendant", active).unwrap().red_flag);
+        assert!(!case.insolvency);
+        assert!(apply_to_party(&mut case, "nobody", search("07654321").remove(0)).is_err());
+    }
+
+    #[test]
+    fn a_hand_set_insolvency_flag_is_kept() {
+        let mut case = CaseMetadata {
+            defendant_name: "Acme".to_string(),
+            insolvency: true,
+            ..CaseMetadata::default()
+        };
+        apply_to_party(&mut case, "defendant", search("01234567").remove(0)).unwrap();
+        assert!(case.insolvency);
+    }
+
+    #[test]
+    fn flags_a_company_missing_from_the_snapshot() {
+        let mut defendant = crate::models::party::Party::new(PartyRole::Defendant, PartyType::Company, "Zenith Kitchens");
+        defendant.address = Some("4 Station Road".to_string());
+        let mut case = CaseMetadata { parties: vec![defendant.clone()], ..CaseMetadata::default() };
+        assert!(search("09999999").is_empty());
+        let check = apply_missing_from_snapshot(&mut case, &defendant.id, "9999999", "BasicCompanyData.zip").unwrap();
+
+        assert!(check.red_flag);
+        assert!(check.warnings[0].contains("not on the live register"));
+        assert_eq!(case.complexity_triggers.len(), 1);
+        assert!(!case.insolvency);
+        let defendant = &case.parties_in_role(PartyRole::Defendant)[0];
+        assert_eq!(defendant.name, "Zenith Kitchens");
+        assert_eq!(defendant.address.as_deref(), Some("4 Station Road"));
+        assert_eq!(defendant.company_number.as_deref(), Some("09999999"));
+
+        // Finding it later replaces the flag
+        let found = CompanyRecord { company_number: "09999999".to_string(), ..search("01234567").remove(0) };
+        apply_to_party(&mut case, &defendant.id, found).unwrap();
+        assert!(case.complexity_triggers.is_empty());
+    }
+}
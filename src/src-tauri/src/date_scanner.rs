@@ -2,7 +2,15 @@
 //!
 //! Scans extracted text for dates and returns candidates with
 //! normalised dates, original text, surrounding context, and confidence.
-
+//!
+//! Relative dates ("yesterday", "last Tuesday", "the 3rd of this month") and
+//! deadline phrases ("within 14 days of the date of this letter") are resolved
+//! against an anchor — the email's sent date or the letter's own date — and
+//! carry a note of how they were worked out.
+
+use crate::bank_holidays::add_business_days;
+use crate::models::chronology::{ChronoDate, RelativeDate};
+use chrono::{Datelike, Duration, Months, NaiveDate, Weekday};
 use regex::Regex;
 use serde::{Deserialize, Serialize};
 
@@ -26,10 +34,35 @@ pub struct ScannedDate {
     pub context: String,
     /// Confidence level
     pub confidence: Confidence,
+    /// How a relative date was resolved; None for dates written out in full
+    #[serde(default)]
+    pub derivation: Option<String>,
+    #[serde(default)]
+    pub kind: DateKind,
+    /// The anchor and offset, for dates counted from the anchor
+    #[serde(default)]
+    pub relative: Option<RelativeDate>,
+}
+
+/// Whether a date records something that happened or a time limit
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum DateKind {
+    #[default]
+    Event,
+    Deadline,
+}
+
+/// The date relative phrases in a document are counted from
+#[derive(Debug, Clone, PartialEq)]
+pub struct DateAnchor {
+    pub date: NaiveDate,
+    /// What the date is, e.g. "the email's sent date"
+    pub label: String,
 }
 
 /// Month name lookup (case-insensitive matching done via regex flag)
-fn month_to_num(month: &str) -> Option<u32> {
+pub(crate) fn month_to_num(month: &str) -> Option<u32> {
     match month.to_lowercase().as_str() {
         "january" | "jan" => Some(1),
         "february" | "feb" => Some(2),
@@ -118,6 +151,9 @@ pub fn scan_for_dates(text: &str) -> Vec<ScannedDate> {
                     original_text: original,
                     context: get_context(text, m.start(), m.end()),
                     confidence: Confidence::Medium, // could be US format
+                    derivation: None,
+                    kind: DateKind::Event,
+                    relative: None,
                 });
             }
         }
@@ -144,6 +180,9 @@ pub fn scan_for_dates(text: &str) -> Vec<ScannedDate> {
                     original_text: original,
                     context: get_context(text, m.start(), m.end()),
                     confidence: Confidence::High, // unambiguous
+                    derivation: None,
+                    kind: DateKind::Event,
+                    relative: None,
                 });
             }
         }
@@ -170,6 +209,9 @@ pub fn scan_for_dates(text: &str) -> Vec<ScannedDate> {
                     original_text: original,
                     context: get_context(text, m.start(), m.end()),
                     confidence: Confidence::High,
+                    derivation: None,
+                    kind: DateKind::Event,
+                    relative: None,
                 });
             }
         }
@@ -196,6 +238,9 @@ pub fn scan_for_dates(text: &str) -> Vec<ScannedDate> {
                     original_text: original,
                     context: get_context(text, m.start(), m.end()),
                     confidence: Confidence::High,
+                    derivation: None,
+                    kind: DateKind::Event,
+                    relative: None,
                 });
             }
         }
@@ -225,6 +270,9 @@ pub fn scan_for_dates(text: &str) -> Vec<ScannedDate> {
                         original_text: original,
                         context: get_context(text, m.start(), m.end()),
                         confidence: Confidence::Low,
+                        derivation: None,
+                        kind: DateKind::Event,
+                        relative: None,
                     });
                 }
             }
@@ -236,6 +284,303 @@ pub fn scan_for_dates(text: &str) -> Vec<ScannedDate> {
     results
 }
 
+/// The date relative phrases are counted from: the email's sent date if the
+/// extractor found one, otherwise the first full date near the top of the
+/// text, where a letter is dated
+pub fn anchor_date(metadata_date: Option<&str>, text: &str) -> Option<DateAnchor> {
+    if let Some(sent) = metadata_date.and_then(|m| chrono::DateTime::parse_from_rfc3339(m).ok()) {
+        return Some(DateAnchor {
+            date: sent.date_naive(),
+            label: "the email's sent date".to_string(),
+        });
+    }
+
+    let head: String = text.chars().take(600).collect();
+    let mut dates = scan_for_dates(&head);
+    dates.retain(|d| d.confidence == Confidence::High && d.date.len() == 10);
+    dates.sort_by_key(|d| head.find(&d.original_text).unwrap_or(usize::MAX));
+    let first = dates.into_iter().next()?;
+    Some(DateAnchor {
+        date: NaiveDate::parse_from_str(&first.date, "%Y-%m-%d").ok()?,
+        label: "the letter's date".to_string(),
+    })
+}
+
+const NUMBER_WORDS: [&str; 21] = [
+    "zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine", "ten",
+    "eleven", "twelve", "thirteen", "fourteen", "fifteen", "sixteen", "seventeen", "eighteen",
+    "nineteen", "twenty",
+];
+
+/// A count written as digits or words, e.g. "14", "fourteen", "twenty-one", "a"
+fn number(text: &str) -> Option<u32> {
+    let text = text.trim().to_lowercase();
+    if let Ok(n) = text.parse() {
+        return Some(n);
+    }
+    if text == "a" || text == "an" {
+        return Some(1);
+    }
+    if let Some(n) = NUMBER_WORDS.iter().position(|w| *w == text) {
+        return Some(n as u32);
+    }
+    let (tens, units) = text.split_once(['-', ' '])?;
+    let tens = match tens {
+        "twenty" => 20,
+        "thirty" => 30,
+        "forty" => 40,
+        "fifty" => 50,
+        "sixty" => 60,
+        _ => return None,
+    };
+    let units = NUMBER_WORDS.iter().take(10).position(|w| *w == units)? as u32;
+    Some(tens + units)
+}
+
+const NUMBER: &str = r"\d{1,3}|a|an|(?:twenty|thirty|forty|fifty|sixty)(?:[- ](?:one|two|three|four|five|six|seven|eight|nine))?|zero|one|two|three|four|five|six|seven|eight|nine|ten|eleven|twelve|thirteen|fourteen|fifteen|sixteen|seventeen|eighteen|nineteen|twenty";
+
+fn weekday(name: &str) -> Option<Weekday> {
+    match name.to_lowercase().as_str() {
+        "monday" => Some(Weekday::Mon),
+        "tuesday" => Some(Weekday::Tue),
+        "wednesday" => Some(Weekday::Wed),
+        "thursday" => Some(Weekday::Thu),
+        "friday" => Some(Weekday::Fri),
+        "saturday" => Some(Weekday::Sat),
+        "sunday" => Some(Weekday::Sun),
+        _ => None,
+    }
+}
+
+fn long(date: NaiveDate) -> String {
+    date.format("%-d %B %Y").to_string()
+}
+
+fn ymd(date: NaiveDate) -> String {
+    date.format("%Y-%m-%d").to_string()
+}
+
+/// Scan text for dates written relative to `anchor`, and for deadlines.
+///
+/// Results are candidates: they carry lower confidence than dates written
+/// out in full and a `derivation` explaining the arithmetic, so the user can
+/// check it. Absolute dates introduced by "by" or "no later than" are
+/// returned as deadlines too.
+pub fn scan_relative_dates(text: &str, anchor: &DateAnchor) -> Vec<ScannedDate> {
+    let mut results: Vec<ScannedDate> = Vec::new();
+    let a = anchor.date;
+    let from_anchor = |what: String| format!("{} ({}, {})", what, anchor.label, long(a));
+
+    // `reading` is set for dates counted from the anchor
+    let push = |results: &mut Vec<ScannedDate>,
+                m: regex::Match,
+                date: String,
+                confidence,
+                derivation,
+                reading: Option<String>,
+                kind| {
+        if results.iter().any(|r| r.date == date && r.kind == kind) {
+            return;
+        }
+        let relative = reading.map(|reading| RelativeDate {
+            phrase: m.as_str().trim().to_string(),
+            anchor: ymd(a),
+            anchor_label: anchor.label.clone(),
+            offset_days: NaiveDate::parse_from_str(&date, "%Y-%m-%d")
+                .ok()
+                .map(|d| (d - a).num_days()),
+            reading,
+        });
+        results.push(ScannedDate {
+            date,
+            original_text: m.as_str().to_string(),
+            context: get_context(text, m.start(), m.end()),
+            confidence,
+            derivation: Some(derivation),
+            kind,
+            relative,
+        });
+    };
+
+    // Pattern 1: today, yesterday, tomorrow
+    let re_day = Regex::new(
+        r"(?i)\b(the day before yesterday|the day after tomorrow|yesterday|today|tomorrow|earlier today|this (?:morning|afternoon|evening))\b"
+    ).unwrap();
+
+    for cap in re_day.captures_iter(text) {
+        let phrase = cap[1].to_lowercase();
+        let (offset, relation) = match phrase.as_str() {
+            "the day before yesterday" => (-2, "two days before"),
+            "yesterday" => (-1, "the day before"),
+            "tomorrow" => (1, "the day after"),
+            "the day after tomorrow" => (2, "two days after"),
+            _ => (0, "the same day as"),
+        };
+        let date = a + Duration::days(offset);
+        let reading = format!("\"{}\" taken as {}", cap[1].trim(), relation);
+        let derivation = from_anchor(reading.clone());
+        push(&mut results, cap.get(0).unwrap(), ymd(date), Confidence::Medium, derivation, Some(reading), DateKind::Event);
+    }
+
+    // Pattern 2: last Tuesday, on Friday, next Monday
+    let re_weekday = Regex::new(
+        r"(?i)\b(last|this past|on|next|this coming|this)\s+(monday|tuesday|wednesday|thursday|friday|saturday|sunday)\b"
+    ).unwrap();
+
+    for cap in re_weekday.captures_iter(text) {
+        let target = match weekday(&cap[2]) {
+            Some(w) => w,
+            None => continue,
+        };
+        // "on Friday 15 March 2024" is already dated in full
+        let rest = text[cap.get(0).unwrap().end()..].trim_start_matches([' ', ',']);
+        let rest = rest.strip_prefix("the ").unwrap_or(rest);
+        if rest.starts_with(|c: char| c.is_ascii_digit()) {
+            continue;
+        }
+        let qualifier = cap[1].to_lowercase();
+        let forwards = matches!(qualifier.as_str(), "next" | "this coming" | "this");
+        let step = if forwards { 1 } else { -1 };
+        let mut date = a + Duration::days(step);
+        // "this Friday" written on a Friday means that day
+        if qualifier == "this" && a.weekday() == target {
+            date = a;
+        }
+        while date.weekday() != target {
+            date += Duration::days(step);
+        }
+        let relation = if date == a {
+            "the same day as".to_string()
+        } else if forwards {
+            format!("the first {} after", cap[2].to_lowercase())
+        } else {
+            format!("the last {} before", cap[2].to_lowercase())
+        };
+        let reading = format!("\"{}\" taken as {}", &cap[0], relation);
+        let derivation = from_anchor(reading.clone());
+        push(&mut results, cap.get(0).unwrap(), ymd(date), Confidence::Low, derivation, Some(reading), DateKind::Event);
+    }
+
+    // Pattern 3: three days ago, a fortnight ago, two months ago
+    let re_ago = Regex::new(&format!(
+        r"(?i)\b({})\s+(day|week|fortnight|month|year)s?\s+ago\b",
+        NUMBER
+    )).unwrap();
+
+    for cap in re_ago.captures_iter(text) {
+        let n = match number(&cap[1]) {
+            Some(n) => n,
+            None => continue,
+        };
+        let date = match cap[2].to_lowercase().as_str() {
+            "day" => Some(a - Duration::days(n as i64)),
+            "week" => Some(a - Duration::weeks(n as i64)),
+            "fortnight" => Some(a - Duration::weeks(2 * n as i64)),
+            "month" => a.checked_sub_months(Months::new(n)),
+            _ => a.checked_sub_months(Months::new(12 * n)),
+        };
+        if let Some(date) = date {
+            let reading = format!("\"{}\" counted back", &cap[0]);
+            let derivation = from_anchor(reading.clone());
+            push(&mut results, cap.get(0).unwrap(), ymd(date), Confidence::Low, derivation, Some(reading), DateKind::Event);
+        }
+    }
+
+    // Pattern 4: the 3rd of this month, the 28th of last month, 3rd inst., 28th ult.
+    let re_of_month = Regex::new(
+        r"(?i)\b(?:the\s+)?(\d{1,2})(?:st|nd|rd|th)?\s+(?:(?:day\s+)?of\s+(this|last|next)\s+month|(inst|ult|prox)\b\.?)"
+    ).unwrap();
+
+    for cap in re_of_month.captures_iter(text) {
+        let day: u32 = cap[1].parse().unwrap_or(0);
+        let which = cap.get(2).or(cap.get(3)).map(|m| m.as_str().to_lowercase()).unwrap_or_default();
+        let month_start = a.with_day(1).unwrap();
+        let month = match which.as_str() {
+            "last" | "ult" => month_start.checked_sub_months(Months::new(1)),
+            "next" | "prox" => month_start.checked_add_months(Months::new(1)),
+            _ => Some(month_start),
+        };
+        if let Some(date) = month.and_then(|m| m.with_day(day)) {
+            let reading = format!("\"{}\" read against the month of", cap[0].trim());
+            let derivation = from_anchor(reading.clone());
+            push(&mut results, cap.get(0).unwrap(), ymd(date), Confidence::Low, derivation, Some(reading), DateKind::Event);
+        }
+    }
+
+    // Pattern 5: last month, this month (no day)
+    let re_month = Regex::new(r"(?i)\b(last|this|earlier this|next)\s+month\b").unwrap();
+
+    for cap in re_month.captures_iter(text) {
+        let month_start = a.with_day(1).unwrap();
+        let month = match cap[1].to_lowercase().as_str() {
+            "last" => month_start.checked_sub_months(Months::new(1)),
+            "next" => month_start.checked_add_months(Months::new(1)),
+            _ => Some(month_start),
+        };
+        if let Some(month) = month {
+            let date = month.format("%Y-%m").to_string();
+            // A specific day in that month is more useful
+            if results.iter().any(|r| r.date.starts_with(&date)) {
+                continue;
+            }
+            let reading = format!("\"{}\" read against the month of", &cap[0]);
+            let derivation = from_anchor(reading.clone());
+            push(&mut results, cap.get(0).unwrap(), date, Confidence::Low, derivation, Some(reading), DateKind::Event);
+        }
+    }
+
+    // Pattern 6: within 14 days of the date of this letter
+    let re_within = Regex::new(&format!(
+        r"(?i)\b(?:within|no later than|not later than)\s+({n})(?:\s*\((?:{n})\))?\s+(working\s+|business\s+|clear\s+)?(day|week|month)s?['’]?(?:\s+(?:of|from|after)\s+(?:the\s+date\s+(?:of\s+this\s+(?:letter|e-?mail|notice)|hereof|above)|today|(receipt)\s+of\s+this\s+(?:letter|e-?mail|notice)|the\s+date\s+of\s+receipt))?",
+        n = NUMBER
+    )).unwrap();
+
+    for cap in re_within.captures_iter(text) {
+        let n = match number(&cap[1]) {
+            Some(n) if n > 0 => n,
+            _ => continue,
+        };
+        let working = cap.get(2).map(|m| !m.as_str().trim().eq_ignore_ascii_case("clear")).unwrap_or(false);
+        let date = match cap[3].to_lowercase().as_str() {
+            "day" if working => Some(add_business_days(a, n)),
+            "day" => Some(a + Duration::days(n as i64)),
+            "week" => Some(a + Duration::weeks(n as i64)),
+            _ => a.checked_add_months(Months::new(n)),
+        };
+        if let Some(date) = date {
+            let reading = format!(
+                "\"{}\" ends {} {}{}{} after",
+                cap[0].trim(),
+                n,
+                if working { "working " } else { "" },
+                cap[3].to_lowercase(),
+                if n == 1 { "" } else { "s" },
+            );
+            let mut derivation = from_anchor(reading.clone());
+            if cap.get(4).is_some() || cap[0].to_lowercase().contains("receipt") {
+                derivation.push_str("; time may run from receipt, which could be later");
+            }
+            push(&mut results, cap.get(0).unwrap(), ymd(date), Confidence::Low, derivation, Some(reading), DateKind::Deadline);
+        }
+    }
+
+    // Pattern 7: by 4pm on 20 March 2024, no later than 3rd April 2024
+    let re_by = Regex::new(
+        r"(?i)\b(?:by|no later than|not later than|on or before|deadline(?: of|:)?)\s+(?:(?:\d{1,2}(?:[.:]\d{2})?\s*(?:am|pm)|noon|midday|close of business)\s+(?:on\s+)?)?(?:(?:mon|tues|wednes|thurs|fri|satur|sun)day,?\s+)?(?:the\s+)?(\d{1,2}(?:st|nd|rd|th)?(?:\s+of)?\s+[a-z]{3,9}\.?,?\s+\d{4}|\d{1,2}/\d{1,2}/\d{4})"
+    ).unwrap();
+
+    for cap in re_by.captures_iter(text) {
+        let written = cap[1].replace(" of ", " ");
+        if let Some(date) = ChronoDate::parse(&written).and_then(|d| d.as_day()) {
+            let derivation = format!("\"{}\" sets a time limit", cap[0].trim());
+            push(&mut results, cap.get(0).unwrap(), ymd(date), Confidence::Medium, derivation, None, DateKind::Deadline);
+        }
+    }
+
+    results.sort_by(|a, b| a.date.cmp(&b.date));
+    results
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -328,4 +673,96 @@ mod tests {
         assert_eq!(dates[0].date, "2024-01-05");
         assert_eq!(dates[1].date, "2024-02-12");
     }
+
+    fn anchor() -> DateAnchor {
+        // A Friday
+        DateAnchor {
+            date: NaiveDate::from_ymd_opt(2024, 3, 15).unwrap(),
+            label: "the letter's date".to_string(),
+        }
+    }
+
+    fn resolved<'a>(dates: &'a [ScannedDate], original: &str) -> &'a ScannedDate {
+        dates
+            .iter()
+            .find(|d| d.original_text.eq_ignore_ascii_case(original))
+            .unwrap_or_else(|| panic!("{:?} not found in {:?}", original, dates))
+    }
+
+    #[test]
+    fn test_relative_days_and_weekdays() {
+        let text = "I called yesterday, having written last Tuesday. I will chase next Monday.";
+        let dates = scan_relative_dates(text, &anchor());
+
+        let yesterday = resolved(&dates, "yesterday");
+        assert_eq!(yesterday.date, "2024-03-14");
+        assert_eq!(yesterday.confidence, Confidence::Medium);
+        assert!(yesterday.derivation.as_deref().unwrap().contains("15 March 2024"));
+
+        assert_eq!(resolved(&dates, "last Tuesday").date, "2024-03-12");
+        assert_eq!(resolved(&dates, "next Monday").date, "2024-03-18");
+        assert!(dates.iter().all(|d| d.kind == DateKind::Event));
+
+        let dated = scan_relative_dates("We met on Friday, 8 March 2024.", &anchor());
+        assert!(dated.is_empty());
+    }
+
+    #[test]
+    fn test_relative_months_and_periods_ago() {
+        let text = "Paid on the 3rd of this month. Delivered 28th ult. Ordered two weeks ago, \
+                    or was it last month?";
+        let dates = scan_relative_dates(text, &anchor());
+        assert_eq!(resolved(&dates, "the 3rd of this month").date, "2024-03-03");
+        assert_eq!(resolved(&dates, "28th ult.").date, "2024-02-28");
+        assert_eq!(resolved(&dates, "two weeks ago").date, "2024-03-01");
+        // February already has a specific day, so the month on its own adds nothing
+        assert!(dates.iter().all(|d| d.original_text != "last month"));
+    }
+
+    #[test]
+    fn test_deadline_phrases() {
+        let text = "Please pay within 14 days of the date of this letter, or reply within five (5) \
+                    working days. In any event we need the goods back by 4pm on 20 March 2024.";
+        let dates = scan_relative_dates(text, &anchor());
+
+        let pay = resolved(&dates, "within 14 days of the date of this letter");
+        assert_eq!(pay.date, "2024-03-29");
+        assert_eq!(pay.kind, DateKind::Deadline);
+        assert_eq!(pay.confidence, Confidence::Low);
+
+        assert_eq!(resolved(&dates, "within five (5) working days").date, "2024-03-22");
+
+        let by = resolved(&dates, "by 4pm on 20 March 2024");
+        assert_eq!(by.date, "2024-03-20");
+        assert_eq!(by.kind, DateKind::Deadline);
+    }
+
+    #[test]
+    fn test_deadline_from_receipt_is_flagged() {
+        let dates = scan_relative_dates("Respond within 21 days of receipt of this letter.", &anchor());
+        assert_eq!(dates.len(), 1);
+        assert_eq!(dates[0].date, "2024-04-05");
+        assert!(dates[0].derivation.as_deref().unwrap().contains("receipt"));
+    }
+
+    #[test]
+    fn test_anchor_date() {
+        let sent = anchor_date(Some("2024-03-15T23:30:00+00:00"), "").unwrap();
+        assert_eq!(sent.date, NaiveDate::from_ymd_opt(2024, 3, 15).unwrap());
+        assert_eq!(sent.label, "the email's sent date");
+
+        let letter = anchor_date(None, "12 High Street\n\n4 March 2024\n\nDear Sir, on 1 March 2024 ...").unwrap();
+        assert_eq!(letter.date, NaiveDate::from_ymd_opt(2024, 3, 4).unwrap());
+
+        assert!(anchor_date(None, "No dates here").is_none());
+    }
+
+    #[test]
+    fn test_number_words() {
+        assert_eq!(number("14"), Some(14));
+        assert_eq!(number("fourteen"), Some(14));
+        assert_eq!(number("Twenty-one"), Some(21));
+        assert_eq!(number("a"), Some(1));
+        assert_eq!(number("several"), None);
+    }
 }
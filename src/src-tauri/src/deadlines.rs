@@ -0,0 +1,505 @@
+//! CPR deadline calculation.
+//!
+//! Works out the procedural deadlines that follow from the dates recorded in
+//! a case's `ProceduralDates`, applying the CPR 2.8 rules for computing time
+//! and the deemed service rules in CPR 6.14 and 6.26. Every deadline carries
+//! the rule it comes from so the user can check it against the CPR.
+//!
+//! These are calculations, not advice — a court order or agreed extension
+//! overrides them, and the user should always check the order or notice itself.
+
+use crate::bank_holidays::{add_business_days, is_business_day, next_business_day, roll_forward};
+use crate::models::case::{ProceduralDates, ServiceEvent, ServiceMethod};
+use crate::models::money::Money;
+use chrono::{Duration, Months, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+/// Deadlines this close are flagged as due soon
+const DUE_SOON_DAYS: i64 = 7;
+
+/// Small claims track limit, used to estimate the DQ period until the court's notice arrives
+const SMALL_CLAIMS_LIMIT: Money = Money::from_pounds(10_000);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ResponsibleParty {
+    #[serde(rename = "claimant")]
+    Claimant,
+    #[serde(rename = "defendant")]
+    Defendant,
+    #[serde(rename = "both")]
+    Both,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DeadlineStatus {
+    #[serde(rename = "upcoming")]
+    Upcoming,
+    #[serde(rename = "due_soon")]
+    DueSoon,
+    #[serde(rename = "overdue")]
+    Overdue,
+    /// The step has been recorded as done
+    #[serde(rename = "met")]
+    Met,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Deadline {
+    /// Stable key, e.g. "defence"
+    pub id: String,
+    pub title: String,
+    /// Last day for the step (YYYY-MM-DD)
+    pub due_date: String,
+    /// CPR citation, e.g. "CPR 15.4(1)(a)"
+    pub rule: String,
+    /// How the date was worked out
+    pub explanation: String,
+    /// Negative once the date has passed
+    pub days_remaining: i64,
+    pub status: DeadlineStatus,
+    pub party: ResponsibleParty,
+    /// True where the date depends on an assumption (e.g. which track applies)
+    pub estimate: bool,
+}
+
+fn parse_date(s: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(s.trim(), "%Y-%m-%d").ok()
+}
+
+fn fmt(date: NaiveDate) -> String {
+    date.format("%-d %B %Y").to_string()
+}
+
+/// The last day for an act that must be done "within `days` days after"
+/// `trigger` (CPR 2.8(2)–(4)). The trigger day is not counted; for periods of
+/// five days or less, weekends and bank holidays are not counted either.
+pub fn days_after(trigger: NaiveDate, days: u32) -> NaiveDate {
+    if days <= 5 {
+        add_business_days(trigger, days)
+    } else {
+        trigger + Duration::days(days as i64)
+    }
+}
+
+/// The last day for an act that must be done "at least `days` clear days
+/// before" `event` — neither the event day nor the day of the act counts
+pub fn clear_days_before(event: NaiveDate, days: u32) -> NaiveDate {
+    if days <= 5 {
+        let mut d = event;
+        for _ in 0..=days {
+            d -= Duration::days(1);
+            while !is_business_day(d) {
+                d -= Duration::days(1);
+            }
+        }
+        d
+    } else {
+        event - Duration::days(days as i64 + 1)
+    }
+}
+
+/// Where a period for doing something at the court office ends on a day it is
+/// closed, the act is in time if done on the next day it is open (CPR 2.8(5))
+fn court_office_day(date: NaiveDate) -> NaiveDate {
+    roll_forward(date)
+}
+
+/// Deemed service of a claim form: the second business day after the step
+/// required by CPR 7.5(1) was taken (CPR 6.14)
+pub fn claim_form_deemed_served(step: NaiveDate) -> NaiveDate {
+    add_business_days(step, 2)
+}
+
+/// Deemed service of any document other than a claim form (CPR 6.26)
+pub fn document_deemed_served(event: &ServiceEvent) -> Option<NaiveDate> {
+    let date = parse_date(&event.date)?;
+    Some(match event.method {
+        // The second day after posting, if a business day; otherwise the next business day
+        ServiceMethod::FirstClassPost | ServiceMethod::DocumentExchange => {
+            roll_forward(date + Duration::days(2))
+        }
+        // That day if a business day before 4.30pm; otherwise the next business day
+        ServiceMethod::DeliveredToAddress
+        | ServiceMethod::Personal
+        | ServiceMethod::Email
+        | ServiceMethod::Fax => {
+            if is_business_day(date) && !event.after_4_30pm {
+                date
+            } else {
+                next_business_day(date)
+            }
+        }
+    })
+}
+
+fn method_name(method: ServiceMethod) -> &'static str {
+    match method {
+        ServiceMethod::FirstClassPost => "first class post",
+        ServiceMethod::DocumentExchange => "document exchange",
+        ServiceMethod::DeliveredToAddress => "delivery to the address",
+        ServiceMethod::Personal => "personal service",
+        ServiceMethod::Email => "email",
+        ServiceMethod::Fax => "fax",
+    }
+}
+
+struct Builder {
+    today: NaiveDate,
+    deadlines: Vec<Deadline>,
+}
+
+impl Builder {
+    #[allow(clippy::too_many_arguments)]
+    fn push(
+        &mut self,
+        id: &str,
+        title: &str,
+        due: NaiveDate,
+        rule: &str,
+        explanation: String,
+        party: ResponsibleParty,
+        done: bool,
+        estimate: bool,
+    ) {
+        let days_remaining = (due - self.today).num_days();
+        let status = if done {
+            DeadlineStatus::Met
+        } else if days_remaining < 0 {
+            DeadlineStatus::Overdue
+        } else if days_remaining <= DUE_SOON_DAYS {
+            DeadlineStatus::DueSoon
+        } else {
+            DeadlineStatus::Upcoming
+        };
+
+        self.deadlines.push(Deadline {
+            id: id.to_string(),
+            title: title.to_string(),
+            due_date: due.format("%Y-%m-%d").to_string(),
+            rule: rule.to_string(),
+            explanation,
+            days_remaining,
+            status,
+            party,
+            estimate,
+        });
+    }
+}
+
+/// When the particulars of claim were (deemed) served, and how that was worked out
+fn particulars_service(dates: &ProceduralDates) -> Option<(NaiveDate, String)> {
+    if let Some(event) = &dates.particulars_served {
+        let served = document_deemed_served(event)?;
+        return Some((
+            served,
+            format!(
+                "Particulars of claim sent by {} on {}, deemed served {} (CPR 6.26)",
+                method_name(event.method),
+                fmt(parse_date(&event.date)?),
+                fmt(served)
+            ),
+        ));
+    }
+
+    // Served with the claim form, so served when it is
+    let step = parse_date(&dates.claim_form_served.as_ref()?.date)?;
+    let served = claim_form_deemed_served(step);
+    Some((
+        served,
+        format!(
+            "Claim form and particulars sent on {}, deemed served on the second business day after, {} (CPR 6.14)",
+            fmt(step),
+            fmt(served)
+        ),
+    ))
+}
+
+/// Calculate every deadline that follows from the recorded procedural dates,
+/// sorted by due date. `claim_value` is used only to estimate the DQ period.
+pub fn compute(dates: &ProceduralDates, claim_value: Money, today: NaiveDate) -> Vec<Deadline> {
+    let mut b = Builder {
+        today,
+        deadlines: Vec::new(),
+    };
+
+    // Claim form: the CPR 7.5 step must be taken within four months of issue
+    if let Some(issued) = dates.claim_issued.as_deref().and_then(parse_date) {
+        if let Some(last) = issued.checked_add_months(Months::new(4)) {
+            b.push(
+                "serve_claim_form",
+                "Serve the claim form",
+                last,
+                "CPR 7.5(1)",
+                format!(
+                    "Claim issued {}; the step required for service must be taken before midnight on the calendar day four months later",
+                    fmt(issued)
+                ),
+                ResponsibleParty::Claimant,
+                dates.claim_form_served.is_some(),
+                false,
+            );
+        }
+    }
+
+    // Particulars of claim served separately: within 14 days of service of the
+    // claim form, and no later than the last day for serving it
+    if let Some(step) = dates.claim_form_served.as_ref().and_then(|e| parse_date(&e.date)) {
+        let served = claim_form_deemed_served(step);
+        let mut due = days_after(served, 14);
+        let mut explanation = format!(
+            "Claim form deemed served {} (CPR 6.14); particulars due within 14 days after that, if not served with it",
+            fmt(served)
+        );
+        if let Some(last) = dates
+            .claim_issued
+            .as_deref()
+            .and_then(parse_date)
+            .and_then(|d| d.checked_add_months(Months::new(4)))
+        {
+            if last < due {
+                due = last;
+                explanation.push_str(", but no later than the last day for serving the claim form (CPR 7.4(2))");
+            }
+        }
+        b.push(
+            "serve_particulars",
+            "Serve particulars of claim (if served separately)",
+            due,
+            "CPR 7.4(1)(b)",
+            explanation,
+            ResponsibleParty::Claimant,
+            dates.particulars_served.is_some() || dates.defence_filed.is_some(),
+            dates.particulars_served.is_none(),
+        );
+    }
+
+    // Acknowledgment of service, defence, and default judgment
+    if let Some((served, how)) = particulars_service(dates) {
+        let aos_due = court_office_day(days_after(served, 14));
+        b.push(
+            "acknowledgment",
+            "File acknowledgment of service",
+            aos_due,
+            "CPR 10.3(1)",
+            format!("{}; acknowledgment due 14 days after service of the particulars of claim", how),
+            ResponsibleParty::Defendant,
+            dates.acknowledgment_filed.is_some() || dates.defence_filed.is_some(),
+            false,
+        );
+
+        let (defence_days, rule, reason) = if dates.acknowledgment_filed.is_some() {
+            (28, "CPR 15.4(1)(b)", "28 days because an acknowledgment of service was filed")
+        } else {
+            (14, "CPR 15.4(1)(a)", "14 days, or 28 if an acknowledgment of service is filed in time")
+        };
+        let defence_due = court_office_day(days_after(served, defence_days));
+        b.push(
+            "defence",
+            "File defence",
+            defence_due,
+            rule,
+            format!("{}; defence due {} after service of the particulars of claim", how, reason),
+            ResponsibleParty::Defendant,
+            dates.defence_filed.is_some(),
+            false,
+        );
+
+        if dates.defence_filed.is_none() {
+            // No acknowledgment: the time for filing one has expired (12.3(1)).
+            // Acknowledged but no defence: 12.3(2).
+            let (rule, missing) = if dates.acknowledgment_filed.is_some() {
+                ("CPR 12.3(2)", "defence")
+            } else {
+                ("CPR 12.3(1)", "acknowledgment of service or defence")
+            };
+            b.push(
+                "default_judgment",
+                "Request default judgment (if no defence)",
+                defence_due + Duration::days(1),
+                rule,
+                format!(
+                    "If no {} is filed by {}, the claimant may request judgment in default from the next day",
+                    missing,
+                    fmt(defence_due)
+                ),
+                ResponsibleParty::Claimant,
+                false,
+                dates.acknowledgment_filed.is_none(),
+            );
+        }
+    }
+
+    // Directions questionnaire: the notice of proposed allocation states the date
+    if let Some(stated) = dates.dq_deadline.as_deref().and_then(parse_date) {
+        b.push(
+            "directions_questionnaire",
+            "File directions questionnaire",
+            stated,
+            "CPR 26.5",
+            "Date stated in the court's notice of proposed allocation".to_string(),
+            ResponsibleParty::Both,
+            dates.dq_filed.is_some(),
+            false,
+        );
+    } else if let Some(event) = &dates.allocation_notice_served {
+        if let Some(served) = document_deemed_served(event) {
+            let (days, track) = if claim_value <= SMALL_CLAIMS_LIMIT {
+                (14, "small claims track")
+            } else {
+                (28, "other tracks")
+            };
+            b.push(
+                "directions_questionnaire",
+                "File directions questionnaire",
+                court_office_day(days_after(served, days)),
+                "CPR 26.5",
+                format!(
+                    "Notice of proposed allocation deemed served {} (CPR 6.26); estimated at the minimum of {} days for {} — check the date stated in the notice",
+                    fmt(served),
+                    days,
+                    track
+                ),
+                ResponsibleParty::Both,
+                dates.dq_filed.is_some(),
+                true,
+            );
+        }
+    }
+
+    // Appeal: appellant's notice within 21 days of the decision, unless the court directs otherwise
+    if let Some(decision) = dates.decision_date.as_deref().and_then(parse_date) {
+        b.push(
+            "appeal",
+            "File appellant's notice",
+            court_office_day(days_after(decision, 21)),
+            "CPR 52.12(2)(b)",
+            format!(
+                "Decision given {}; appellant's notice due 21 days after, unless the lower court directed a different period",
+                fmt(decision)
+            ),
+            ResponsibleParty::Both,
+            dates.appeal_filed.is_some(),
+            false,
+        );
+    }
+
+    b.deadlines.sort_by(|a, b| a.due_date.cmp(&b.due_date));
+    b.deadlines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    fn event(d: &str, method: ServiceMethod) -> ServiceEvent {
+        ServiceEvent {
+            date: d.to_string(),
+            method,
+            after_4_30pm: false,
+        }
+    }
+
+    #[test]
+    fn short_periods_skip_non_business_days() {
+        // Friday + 5 days, skipping the weekend
+        assert_eq!(days_after(date("2025-03-07"), 5), date("2025-03-14"));
+        // Longer periods count calendar days
+        assert_eq!(days_after(date("2025-03-07"), 14), date("2025-03-21"));
+    }
+
+    #[test]
+    fn clear_days_before_an_event() {
+        // 3 clear days before a Friday hearing: Tue, Wed, Thu are clear, so Monday
+        assert_eq!(clear_days_before(date("2025-03-14"), 3), date("2025-03-10"));
+        // 7 clear days before 15th: 7th–14th must be clear, so the 7th
+        assert_eq!(clear_days_before(date("2025-03-15"), 7), date("2025-03-07"));
+    }
+
+    #[test]
+    fn claim_form_deemed_service_skips_easter() {
+        // Posted Thursday 17 April 2025: Good Friday and Easter Monday don't count
+        assert_eq!(claim_form_deemed_served(date("2025-04-17")), date("2025-04-23"));
+    }
+
+    #[test]
+    fn document_deemed_service_by_method() {
+        // Posted Thursday: second day is Saturday, so the next business day
+        let post = event("2025-03-06", ServiceMethod::FirstClassPost);
+        assert_eq!(document_deemed_served(&post), Some(date("2025-03-10")));
+
+        let email = event("2025-03-06", ServiceMethod::Email);
+        assert_eq!(document_deemed_served(&email), Some(date("2025-03-06")));
+
+        let late = ServiceEvent {
+            after_4_30pm: true,
+            ..email
+        };
+        assert_eq!(document_deemed_served(&late), Some(date("2025-03-07")));
+
+        // Emailed on a Saturday
+        let weekend = event("2025-03-08", ServiceMethod::Email);
+        assert_eq!(document_deemed_served(&weekend), Some(date("2025-03-10")));
+    }
+
+    #[test]
+    fn defence_period_extends_after_acknowledgment() {
+        let mut dates = ProceduralDates {
+            claim_issued: Some("2025-03-03".to_string()),
+            claim_form_served: Some(event("2025-03-04", ServiceMethod::FirstClassPost)),
+            ..ProceduralDates::default()
+        };
+        let today = date("2025-03-05");
+
+        // Deemed served Thursday 6 March; 14 days later is Thursday 20 March
+        let find = |list: &[Deadline], id: &str| list.iter().find(|d| d.id == id).cloned().unwrap();
+        let list = compute(&dates, Money::from_pounds(500), today);
+        assert_eq!(find(&list, "acknowledgment").due_date, "2025-03-20");
+        assert_eq!(find(&list, "defence").due_date, "2025-03-20");
+        assert_eq!(find(&list, "defence").rule, "CPR 15.4(1)(a)");
+        assert_eq!(find(&list, "default_judgment").rule, "CPR 12.3(1)");
+        assert_eq!(find(&list, "serve_claim_form").status, DeadlineStatus::Met);
+
+        dates.acknowledgment_filed = Some("2025-03-12".to_string());
+        let list = compute(&dates, Money::from_pounds(500), today);
+        // 28 days after 6 March is Thursday 3 April
+        assert_eq!(find(&list, "defence").due_date, "2025-04-03");
+        assert_eq!(find(&list, "default_judgment").rule, "CPR 12.3(2)");
+        assert_eq!(find(&list, "acknowledgment").status, DeadlineStatus::Met);
+    }
+
+    #[test]
+    fn court_office_deadlines_roll_past_bank_holidays() {
+        // Decision 4 December 2026: 21 days later is Christmas Day, so the
+        // appellant's notice can be filed on the next business day
+        let dates = ProceduralDates {
+            decision_date: Some("2026-12-04".to_string()),
+            ..ProceduralDates::default()
+        };
+        let list = compute(&dates, Money::from_pounds(500), date("2026-12-05"));
+        assert_eq!(list[0].due_date, "2026-12-29");
+        assert_eq!(list[0].rule, "CPR 52.12(2)(b)");
+    }
+
+    #[test]
+    fn dq_uses_stated_date_or_estimates_by_value() {
+        let mut dates = ProceduralDates {
+            allocation_notice_served: Some(event("2025-06-02", ServiceMethod::Email)),
+            ..ProceduralDates::default()
+        };
+        let today = date("2025-06-01");
+        let small = compute(&dates, Money::from_pounds(2_000), today);
+        assert_eq!(small[0].due_date, "2025-06-16");
+        assert!(small[0].estimate);
+        let fast = compute(&dates, Money::from_pounds(20_000), today);
+        assert_eq!(fast[0].due_date, "2025-06-30");
+
+        dates.dq_deadline = Some("2025-06-20".to_string());
+        let stated = compute(&dates, Money::from_pounds(2_000), today);
+        assert_eq!(stated[0].due_date, "2025-06-20");
+        assert!(!stated[0].estimate);
+    }
+}
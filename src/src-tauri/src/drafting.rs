@@ -0,0 +1,721 @@
+//! Draft documents rendered to DOCX from plain-text templates.
+//!
+//! Templates live in `data/templates`, and a file of the same name in
+//! `<config dir>/CaseKit/templates` replaces the bundled one. Each line is a
+//! paragraph, marked up as:
+//!
+//! - `# text` — title, `## text` — section heading
+//! - `^ text` — centred, `> text` — right-aligned
+//! - `n. text` — numbered paragraph (numbers run on through the document)
+//! - `[[directive]]` or `[[directive: argument]]` — a block built from the
+//!   case: `events`, `loss_table`, `documents_list`, `exhibits_list`,
+//!   `chronology_table`, `statement_of_truth` and `signature`
+//!
+//! `{{key}}` placeholders are filled from the case. Anything without a value
+//! is left as `[key]` for the user to complete, and listed in `missing`.
+
+use crate::models::case::{CaseMetadata, ClaimType, UserRole};
+use crate::models::chronology::{ChronoDate, ChronologyEntry, DatePrecision, Significance};
+use crate::models::document::DocumentEntry;
+use crate::models::party::PartyRole;
+use chrono::{Days, NaiveDate};
+use docx_rs::{
+    AlignmentType, BreakType, Docx, Paragraph, Run, Table, TableCell, TableRow, WidthType,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::Cursor;
+
+/// Days allowed for a reply to a letter before claim (PD-PAC para 6(b))
+const LBC_RESPONSE_DAYS: u64 = 14;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DraftKind {
+    #[serde(rename = "letter_before_claim")]
+    LetterBeforeClaim,
+    /// CPR Part 32 and PD 32 format, with statement of truth
+    #[serde(rename = "witness_statement")]
+    WitnessStatement,
+    #[serde(rename = "schedule_of_loss")]
+    ScheduleOfLoss,
+    #[serde(rename = "chronology")]
+    Chronology,
+}
+
+impl DraftKind {
+    pub fn title(self) -> &'static str {
+        match self {
+            DraftKind::LetterBeforeClaim => "Letter before claim",
+            DraftKind::WitnessStatement => "Witness statement",
+            DraftKind::ScheduleOfLoss => "Schedule of loss",
+            DraftKind::Chronology => "Chronology",
+        }
+    }
+
+    pub fn template_file(self) -> &'static str {
+        match self {
+            DraftKind::LetterBeforeClaim => "letter-before-claim.txt",
+            DraftKind::WitnessStatement => "witness-statement.txt",
+            DraftKind::ScheduleOfLoss => "schedule-of-loss.txt",
+            DraftKind::Chronology => "chronology.txt",
+        }
+    }
+
+    fn bundled_template(self) -> &'static str {
+        match self {
+            DraftKind::LetterBeforeClaim => include_str!("../data/templates/letter-before-claim.txt"),
+            DraftKind::WitnessStatement => include_str!("../data/templates/witness-statement.txt"),
+            DraftKind::ScheduleOfLoss => include_str!("../data/templates/schedule-of-loss.txt"),
+            DraftKind::Chronology => include_str!("../data/templates/chronology.txt"),
+        }
+    }
+}
+
+/// Details the case doesn't hold, supplied when drafting
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DraftInputs {
+    #[serde(default)]
+    pub claim_number: Option<String>,
+    #[serde(default)]
+    pub court: Option<String>,
+    #[serde(default)]
+    pub claimant_address: Option<String>,
+    #[serde(default)]
+    pub defendant_address: Option<String>,
+    /// Defaults to the user's party
+    #[serde(default)]
+    pub witness_name: Option<String>,
+    #[serde(default)]
+    pub witness_address: Option<String>,
+    #[serde(default)]
+    pub witness_occupation: Option<String>,
+    /// Any other placeholder by key. Overrides values worked out from the case.
+    #[serde(default)]
+    pub answers: BTreeMap<String, String>,
+}
+
+/// One paragraph or table of a rendered draft
+#[derive(Debug, Clone, PartialEq)]
+pub enum Block {
+    Title(String),
+    Heading(String),
+    Centred(String),
+    Right(String),
+    Numbered(usize, String),
+    Text(String),
+    Blank,
+    Table {
+        header: Vec<String>,
+        rows: Vec<Vec<String>>,
+    },
+}
+
+#[derive(Debug, Clone)]
+pub struct RenderedDraft {
+    pub blocks: Vec<Block>,
+    /// Placeholder keys left unfilled
+    pub missing: Vec<String>,
+}
+
+/// Everything a template can draw on
+pub struct DraftContext<'a> {
+    pub case: &'a CaseMetadata,
+    /// Merged chronology, in date order
+    pub chronology: &'a [ChronologyEntry],
+    pub documents: &'a [DocumentEntry],
+    pub values: BTreeMap<String, String>,
+}
+
+/// The template for a kind of draft: the user's copy if they have one
+pub fn load_template(kind: DraftKind) -> String {
+    dirs::config_dir()
+        .map(|d| d.join("CaseKit").join("templates").join(kind.template_file()))
+        .and_then(|path| fs::read_to_string(path).ok())
+        .unwrap_or_else(|| kind.bundled_template().to_string())
+}
+
+fn long_date(date: &str) -> String {
+    NaiveDate::parse_from_str(date.trim(), "%Y-%m-%d")
+        .map(|d| d.format("%-d %B %Y").to_string())
+        .unwrap_or_else(|_| date.to_string())
+}
+
+/// How an event's date opens a sentence, e.g. "On 5 March 2024", "In March 2024"
+fn when(date: &ChronoDate) -> String {
+    match (date.precision, date.start, date.end) {
+        (_, None, _) | (_, _, None) => "On a date not yet known".to_string(),
+        (DatePrecision::Day, ..) => format!("On {}", date),
+        (DatePrecision::Month, ..) | (DatePrecision::Year, ..) => format!("In {}", date),
+        (DatePrecision::Range, Some(start), Some(end)) => format!(
+            "Between {} and {}",
+            start.format("%-d %B %Y"),
+            end.format("%-d %B %Y")
+        ),
+        (DatePrecision::Approximate, Some(start), Some(end)) if start == end => {
+            format!("On or about {}", start.format("%-d %B %Y"))
+        }
+        (DatePrecision::Approximate, ..) => {
+            let span = ChronoDate { precision: DatePrecision::Range, text: None, ..date.clone() };
+            format!("In or around {}", span)
+        }
+    }
+}
+
+fn non_empty(value: Option<&String>) -> Option<String> {
+    value.map(|v| v.trim()).filter(|v| !v.is_empty()).map(|v| v.to_string())
+}
+
+/// Exhibit references use the witness's initials, e.g. "JS1"
+fn initials(name: &str) -> String {
+    name.split_whitespace()
+        .filter_map(|w| w.chars().next())
+        .filter(|c| c.is_alphabetic())
+        .map(|c| c.to_ascii_uppercase())
+        .collect()
+}
+
+fn legal_basis(claim_type: &ClaimType) -> &'static str {
+    match claim_type {
+        ClaimType::CraGoods => "Under sections 9 to 11 of the Consumer Rights Act 2015 the goods had to be of satisfactory quality, fit for purpose and as described. They were not, so you are in breach of contract and I am entitled to the remedies in sections 19 to 24 of the Act.",
+        ClaimType::CraServices => "Under section 49 of the Consumer Rights Act 2015 the service had to be performed with reasonable care and skill. It was not, so you are in breach of contract and I am entitled to the remedies in sections 54 to 56 of the Act.",
+        ClaimType::CraDigital => "Under sections 34 to 36 of the Consumer Rights Act 2015 the digital content had to be of satisfactory quality, fit for purpose and as described. It was not, so you are in breach of contract and I am entitled to the remedies in sections 42 to 46 of the Act.",
+    }
+}
+
+/// Documents the user relies on: correspondence and evidence, oldest first
+fn relied_on(documents: &[DocumentEntry]) -> Vec<&DocumentEntry> {
+    let mut docs: Vec<&DocumentEntry> = documents
+        .iter()
+        .filter(|d| !d.pending_review && (d.folder == "01" || d.folder == "02"))
+        .collect();
+    docs.sort_by(|a, b| a.date.cmp(&b.date).then(a.filename.cmp(&b.filename)));
+    docs
+}
+
+/// Placeholder values worked out from the case and the user's inputs
+pub fn context_values(
+    case: &CaseMetadata,
+    documents: &[DocumentEntry],
+    inputs: &DraftInputs,
+    today: NaiveDate,
+) -> BTreeMap<String, String> {
+    let mut values = BTreeMap::new();
+    let mut put = |key: &str, value: Option<String>| {
+        if let Some(v) = value.filter(|v| !v.trim().is_empty()) {
+            values.insert(key.to_string(), v);
+        }
+    };
+
+    let (own_role, role) = match case.user_role {
+        UserRole::Claimant => (PartyRole::Claimant, "Claimant"),
+        UserRole::Defendant => (PartyRole::Defendant, "Defendant"),
+    };
+    // A witness statement is one person's evidence, so default to the first
+    // party on the user's side rather than all of them
+    let own_party = case.parties_in_role(own_role).into_iter().next();
+    let named_witness = non_empty(inputs.witness_name.as_ref());
+    let witness_address = non_empty(inputs.witness_address.as_ref()).or_else(|| {
+        own_party.as_ref().filter(|_| named_witness.is_none()).and_then(|p| p.address.clone())
+    });
+    let witness = named_witness.unwrap_or_else(|| own_party.map(|p| p.name).unwrap_or_default());
+    let prefix = initials(&witness);
+    let exhibit_count = relied_on(documents).len();
+
+    put("claimant_name", Some(case.party_names(PartyRole::Claimant)));
+    put("defendant_name", Some(case.party_names(PartyRole::Defendant)));
+    put(
+        "claimant_address",
+        non_empty(inputs.claimant_address.as_ref()).or_else(|| case.service_addresses(PartyRole::Claimant)),
+    );
+    put(
+        "defendant_address",
+        non_empty(inputs.defendant_address.as_ref()).or_else(|| case.service_addresses(PartyRole::Defendant)),
+    );
+    put("claim_number", non_empty(inputs.claim_number.as_ref()));
+    put("court", non_empty(inputs.court.as_ref()).map(|c| c.to_uppercase()));
+    put("today", Some(today.format("%-d %B %Y").to_string()));
+    put(
+        "response_deadline",
+        Some((today + Days::new(LBC_RESPONSE_DAYS)).format("%-d %B %Y").to_string()),
+    );
+    put("claim_value", Some(case.total_claim_value().to_string()));
+    put("description", Some(case.description.trim().to_string()));
+    put("legal_basis", Some(legal_basis(&case.claim_type).to_string()));
+    put(
+        "desired_outcome",
+        Some(case.desired_outcome.join("; ")).filter(|s| !s.is_empty()),
+    );
+    put("date_of_purchase", case.date_of_purchase.as_deref().map(long_date));
+    put("date_problem_discovered", case.date_problem_discovered.as_deref().map(long_date));
+    put("date_first_complained", case.date_first_complained.as_deref().map(long_date));
+    put("party_role", Some(role.to_string()));
+    put("party_role_lower", Some(role.to_lowercase()));
+    put("witness_name_upper", Some(witness.to_uppercase()));
+    put("witness_name", Some(witness));
+    put("witness_address", witness_address);
+    put("witness_occupation", non_empty(inputs.witness_occupation.as_ref()));
+    put("statement_number", Some("1".to_string()));
+    put(
+        "exhibits",
+        Some(match exhibit_count {
+            0 => "None".to_string(),
+            1 => format!("{}1", prefix),
+            n => format!("{}1–{}{}", prefix, prefix, n),
+        }),
+    );
+    put("exhibit_prefix", Some(prefix));
+
+    for (key, value) in &inputs.answers {
+        values.insert(key.clone(), value.clone());
+    }
+    values
+}
+
+/// Replace `{{key}}` placeholders, recording any without a value
+fn fill_placeholders(text: &str, values: &BTreeMap<String, String>, missing: &mut Vec<String>) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find("{{") {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let Some(end) = after.find("}}") else {
+            out.push_str(&rest[start..]);
+            return out;
+        };
+        let key = after[..end].trim();
+        match values.get(key) {
+            Some(value) => out.push_str(value),
+            None => {
+                out.push_str(&format!("[{}]", key.replace('_', " ")));
+                if !missing.iter().any(|m| m == key) {
+                    missing.push(key.to_string());
+                }
+            }
+        }
+        rest = &after[end + 2..];
+    }
+    out.push_str(rest);
+    out
+}
+
+fn significance_filter(argument: &str) -> Vec<Significance> {
+    let wanted: Vec<Significance> = argument
+        .split(',')
+        .filter_map(|s| match s.trim() {
+            "key" => Some(Significance::Key),
+            "supporting" => Some(Significance::Supporting),
+            "background" => Some(Significance::Background),
+            _ => None,
+        })
+        .collect();
+    if wanted.is_empty() {
+        vec![Significance::Key, Significance::Supporting, Significance::Background]
+    } else {
+        wanted
+    }
+}
+
+fn statement_of_truth(document: &str) -> String {
+    format!(
+        "I believe that the facts stated in this {} are true. I understand that proceedings for contempt of court may be brought against anyone who makes, or causes to be made, a false statement in a document verified by a statement of truth without an honest belief in its truth.",
+        document
+    )
+}
+
+/// Expand a `[[directive]]` into blocks
+fn directive(name: &str, argument: &str, ctx: &DraftContext, number: &mut usize, blocks: &mut Vec<Block>) {
+    match name {
+        "events" => {
+            let wanted = significance_filter(argument);
+            for entry in ctx.chronology.iter().filter(|e| wanted.contains(&e.significance)) {
+                *number += 1;
+                let description = entry.description.trim().trim_end_matches('.');
+                blocks.push(Block::Numbered(
+                    *number,
+                    format!("{}: {}.", when(&entry.date), description),
+                ));
+            }
+        }
+        "loss_table" => {
+            let mut rows: Vec<Vec<String>> = if ctx.case.claim_heads.is_empty() {
+                vec![vec!["Claim".to_string(), ctx.case.claim_value.to_string(), String::new()]]
+            } else {
+                ctx.case
+                    .claim_heads
+                    .iter()
+                    .map(|head| {
+                        let evidence: Vec<&str> = head
+                            .evidence_doc_ids
+                            .iter()
+                            .filter_map(|id| ctx.documents.iter().find(|d| &d.id == id))
+                            .map(|d| d.filename.as_str())
+                            .collect();
+                        vec![head.description.clone(), head.amount.to_string(), evidence.join(", ")]
+                    })
+                    .collect()
+            };
+            rows.push(vec![
+                "Total".to_string(),
+                ctx.case.total_claim_value().to_string(),
+                String::new(),
+            ]);
+            blocks.push(Block::Table {
+                header: vec!["Item".to_string(), "Amount".to_string(), "Evidence".to_string()],
+                rows,
+            });
+        }
+        "documents_list" => {
+            let docs = relied_on(ctx.documents);
+            if docs.is_empty() {
+                blocks.push(Block::Text("[list the documents you rely on]".to_string()));
+            }
+            for doc in docs {
+                let date = doc.date.as_deref().map(long_date).unwrap_or_else(|| "undated".to_string());
+                blocks.push(Block::Text(format!("• {} ({})", doc.filename, date)));
+            }
+        }
+        "exhibits_list" => {
+            let prefix = ctx.values.get("exhibit_prefix").cloned().unwrap_or_default();
+            let rows: Vec<Vec<String>> = relied_on(ctx.documents)
+                .iter()
+                .enumerate()
+                .map(|(i, doc)| {
+                    vec![
+                        format!("{}{}", prefix, i + 1),
+                        doc.filename.clone(),
+                        doc.date.as_deref().map(long_date).unwrap_or_default(),
+                    ]
+                })
+                .collect();
+            if !rows.is_empty() {
+                blocks.push(Block::Table {
+                    header: vec!["Exhibit".to_string(), "Document".to_string(), "Date".to_string()],
+                    rows,
+                });
+            }
+        }
+        "chronology_table" => {
+            let wanted = significance_filter(argument);
+            let rows = ctx
+                .chronology
+                .iter()
+                .filter(|e| wanted.contains(&e.significance))
+                .map(|e| {
+                    vec![
+                        e.date.to_string(),
+                        e.description.clone(),
+                        e.source_document_path.clone().unwrap_or_default(),
+                    ]
+                })
+                .collect();
+            blocks.push(Block::Table {
+                header: vec!["Date".to_string(), "Event".to_string(), "Document".to_string()],
+                rows,
+            });
+        }
+        "statement_of_truth" => {
+            let document = if argument.is_empty() { "document" } else { argument };
+            blocks.push(Block::Heading("Statement of truth".to_string()));
+            blocks.push(Block::Text(statement_of_truth(document)));
+        }
+        "signature" => {
+            let name = ctx
+                .values
+                .get("witness_name")
+                .cloned()
+                .unwrap_or_else(|| "[name]".to_string());
+            blocks.push(Block::Blank);
+            blocks.push(Block::Text("Signed: ..............................".to_string()));
+            blocks.push(Block::Text(format!("Name: {}", name)));
+            blocks.push(Block::Text("Date: ..............................".to_string()));
+        }
+        _ => blocks.push(Block::Text(format!("[[{}]]", name))),
+    }
+}
+
+/// Render a template against the case
+pub fn render(template: &str, ctx: &DraftContext) -> RenderedDraft {
+    let mut blocks = Vec::new();
+    let mut missing = Vec::new();
+    let mut number = 0;
+
+    for line in template.lines() {
+        let line = line.trim_end();
+        if let Some(inner) = line.trim().strip_prefix("[[").and_then(|l| l.strip_suffix("]]")) {
+            let (name, argument) = inner.split_once(':').unwrap_or((inner, ""));
+            directive(name.trim(), argument.trim(), ctx, &mut number, &mut blocks);
+            continue;
+        }
+
+        let text = fill_placeholders(line, &ctx.values, &mut missing);
+        let block = if text.trim().is_empty() {
+            Block::Blank
+        } else if let Some(t) = text.strip_prefix("## ") {
+            Block::Heading(t.to_string())
+        } else if let Some(t) = text.strip_prefix("# ") {
+            Block::Title(t.to_string())
+        } else if let Some(t) = text.strip_prefix("^ ") {
+            Block::Centred(t.to_string())
+        } else if let Some(t) = text.strip_prefix("> ") {
+            Block::Right(t.to_string())
+        } else if let Some(t) = text.strip_prefix("n. ") {
+            number += 1;
+            Block::Numbered(number, t.to_string())
+        } else {
+            Block::Text(text)
+        };
+        blocks.push(block);
+    }
+
+    RenderedDraft { blocks, missing }
+}
+
+/// A run of text, with line breaks for any embedded newlines (e.g. addresses)
+fn text_run(text: &str) -> Run {
+    let mut run = Run::new();
+    for (i, line) in text.split('\n').enumerate() {
+        if i > 0 {
+            run = run.add_break(BreakType::TextWrapping);
+        }
+        run = run.add_text(line);
+    }
+    run
+}
+
+fn cell(text: &str, bold: bool) -> TableCell {
+    let run = if bold { text_run(text).bold() } else { text_run(text) };
+    TableCell::new().add_paragraph(Paragraph::new().add_run(run))
+}
+
+/// Build the DOCX file for a rendered draft
+pub fn to_docx(draft: &RenderedDraft) -> Result<Vec<u8>, String> {
+    let mut docx = Docx::new();
+    for block in &draft.blocks {
+        docx = match block {
+            Block::Title(t) => docx.add_paragraph(
+                Paragraph::new()
+                    .add_run(text_run(t).bold().size(28))
+                    .align(AlignmentType::Center),
+            ),
+            Block::Heading(t) => docx.add_paragraph(Paragraph::new().add_run(text_run(t).bold())),
+            Block::Centred(t) => {
+                docx.add_paragraph(Paragraph::new().add_run(text_run(t)).align(AlignmentType::Center))
+            }
+            Block::Right(t) => {
+                docx.add_paragraph(Paragraph::new().add_run(text_run(t)).align(AlignmentType::Right))
+            }
+            Block::Numbered(n, t) => docx.add_paragraph(
+                Paragraph::new()
+                    .add_run(text_run(&format!("{}.", n)))
+                    .add_run(Run::new().add_tab())
+                    .add_run(text_run(t))
+                    .indent(Some(720), Some(docx_rs::SpecialIndentType::Hanging(720)), None, None),
+            ),
+            Block::Text(t) => docx.add_paragraph(Paragraph::new().add_run(text_run(t))),
+            Block::Blank => docx.add_paragraph(Paragraph::new()),
+            Block::Table { header, rows } => {
+                let mut table_rows = vec![TableRow::new(header.iter().map(|h| cell(h, true)).collect())];
+                for row in rows {
+                    table_rows.push(TableRow::new(row.iter().map(|c| cell(c, false)).collect()));
+                }
+                docx.add_table(Table::new(table_rows).width(5000, WidthType::Pct))
+            }
+        };
+    }
+
+    let mut buffer = Cursor::new(Vec::new());
+    docx.build()
+        .pack(&mut buffer)
+        .map_err(|e| format!("Could not build document: {}", e))?;
+    Ok(buffer.into_inner())
+}
+
+/// The default file name for a draft, e.g. "Letter before claim 2025-03-01.docx"
+pub fn output_filename(kind: DraftKind, today: NaiveDate, existing: &std::path::Path) -> String {
+    let base = format!("{} {}", kind.title(), today.format("%Y-%m-%d"));
+    let mut name = format!("{}.docx", base);
+    let mut n = 2;
+    while existing.join(&name).exists() {
+        name = format!("{} ({}).docx", base, n);
+        n += 1;
+    }
+    name
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::case::ClaimHead;
+    use crate::models::money::Money;
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    fn case() -> CaseMetadata {
+        CaseMetadata {
+            claimant_name: "Jane Smith".to_string(),
+            defendant_name: "Acme Ltd".to_string(),
+            description: "The washing machine I bought leaked after two weeks.".to_string(),
+            desired_outcome: vec!["refund the price".to_string()],
+            claim_value: Money::from_pounds(450),
+            ..CaseMetadata::default()
+        }
+    }
+
+    fn entry(date: &str, description: &str, significance: Significance) -> ChronologyEntry {
+        ChronologyEntry {
+            date: ChronoDate::from_text(date),
+            description: description.to_string(),
+            significance,
+            ..ChronologyEntry::default()
+        }
+    }
+
+    fn doc(id: &str, filename: &str, date: &str) -> DocumentEntry {
+        DocumentEntry {
+            id: id.to_string(),
+            filename: filename.to_string(),
+            date: Some(date.to_string()),
+            folder: "02".to_string(),
+            ..DocumentEntry::default()
+        }
+    }
+
+    fn docx_text(bytes: &[u8]) -> String {
+        let doc = docx_rs::read_docx(bytes).unwrap();
+        let mut text = Vec::new();
+        for child in doc.document.children {
+            if let docx_rs::DocumentChild::Paragraph(p) = child {
+                text.push(p.raw_text());
+            }
+        }
+        text.join("\n")
+    }
+
+    #[test]
+    fn placeholders_are_filled_or_flagged() {
+        let values = BTreeMap::from([("claimant_name".to_string(), "Jane Smith".to_string())]);
+        let mut missing = Vec::new();
+        let text = fill_placeholders("{{claimant_name}} of {{claimant_address}}", &values, &mut missing);
+        assert_eq!(text, "Jane Smith of [claimant address]");
+        assert_eq!(missing, vec!["claimant_address"]);
+    }
+
+    #[test]
+    fn markup_becomes_blocks_with_running_numbers() {
+        let case = case();
+        let chronology = vec![
+            entry("2025-01-10", "Bought the machine", Significance::Key),
+            entry("2025-01-12", "Delivery note signed", Significance::Background),
+        ];
+        let ctx = DraftContext {
+            case: &case,
+            chronology: &chronology,
+            documents: &[],
+            values: BTreeMap::new(),
+        };
+        let rendered = render("# TITLE\n## Facts\nn. First\n[[events: key]]\nn. Last", &ctx);
+        assert_eq!(
+            rendered.blocks,
+            vec![
+                Block::Title("TITLE".to_string()),
+                Block::Heading("Facts".to_string()),
+                Block::Numbered(1, "First".to_string()),
+                Block::Numbered(2, "On 10 January 2025: Bought the machine.".to_string()),
+                Block::Numbered(3, "Last".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn events_are_phrased_to_the_precision_of_their_date() {
+        let phrase = |text: &str| when(&ChronoDate::from_text(text));
+        assert_eq!(phrase("2025-01"), "In January 2025");
+        assert_eq!(phrase("2024-03-05/2024-03-09"), "Between 5 March 2024 and 9 March 2024");
+        assert_eq!(phrase("c. 5 March 2024"), "On or about 5 March 2024");
+        assert_eq!(phrase("late 2022"), "In or around 1 September – 31 December 2022");
+        assert_eq!(phrase(""), "On a date not yet known");
+    }
+
+    #[test]
+    fn letter_before_claim_renders_to_docx() {
+        let case = case();
+        let documents = vec![doc("d1", "receipt.pdf", "2025-01-10")];
+        let values = context_values(&case, &documents, &DraftInputs::default(), date("2025-03-01"));
+        assert_eq!(values["response_deadline"], "15 March 2025");
+
+        let ctx = DraftContext {
+            case: &case,
+            chronology: &[],
+            documents: &documents,
+            values,
+        };
+        let rendered = render(DraftKind::LetterBeforeClaim.bundled_template(), &ctx);
+        assert!(rendered.missing.contains(&"claimant_address".to_string()));
+
+        let text = docx_text(&to_docx(&rendered).unwrap());
+        assert!(text.contains("LETTER BEFORE CLAIM"));
+        assert!(text.contains("Dear Sir or Madam"));
+        assert!(text.contains("by 15 March 2025"));
+        assert!(text.contains("• receipt.pdf (10 January 2025)"));
+    }
+
+    #[test]
+    fn witness_statement_has_pd32_heading_and_statement_of_truth() {
+        let case = case();
+        let documents = vec![doc("d1", "receipt.pdf", "2025-01-10"), doc("d2", "photo.jpg", "2025-01-25")];
+        let inputs = DraftInputs {
+            claim_number: Some("K00AB123".to_string()),
+            court: Some("Central London".to_string()),
+            ..DraftInputs::default()
+        };
+        let values = context_values(&case, &documents, &inputs, date("2025-03-01"));
+        assert_eq!(values["exhibits"], "JS1–JS2");
+
+        let ctx = DraftContext {
+            case: &case,
+            chronology: &[],
+            documents: &documents,
+            values,
+        };
+        let rendered = render(DraftKind::WitnessStatement.bundled_template(), &ctx);
+        let text = docx_text(&to_docx(&rendered).unwrap());
+        assert!(text.contains("IN THE COUNTY COURT AT CENTRAL LONDON"));
+        assert!(text.contains("WITNESS STATEMENT OF JANE SMITH"));
+        assert!(text.contains("I believe that the facts stated in this witness statement are true."));
+        assert!(text.contains("proceedings for contempt of court"));
+        assert!(rendered.missing.contains(&"witness_address".to_string()));
+    }
+
+    #[test]
+    fn schedule_of_loss_lists_heads_with_evidence() {
+        let mut case = case();
+        case.claim_heads = vec![
+            ClaimHead {
+                id: "h1".to_string(),
+                description: "Price of machine".to_string(),
+                amount: Money::from_pounds(400),
+                evidence_doc_ids: vec!["d1".to_string()],
+            },
+            ClaimHead {
+                id: "h2".to_string(),
+                description: "Flood clean-up".to_string(),
+                amount: Money::from_pence(7_550),
+                evidence_doc_ids: Vec::new(),
+            },
+        ];
+        let documents = vec![doc("d1", "receipt.pdf", "2025-01-10")];
+        let ctx = DraftContext {
+            case: &case,
+            chronology: &[],
+            documents: &documents,
+            values: BTreeMap::new(),
+        };
+        let rendered = render("[[loss_table]]", &ctx);
+        let Block::Table { rows, .. } = &rendered.blocks[0] else { panic!() };
+        assert_eq!(rows[0], vec!["Price of machine", "£400.00", "receipt.pdf"]);
+        assert_eq!(rows[2], vec!["Total", "£475.50", ""]);
+    }
+}
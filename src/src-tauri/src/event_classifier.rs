@@ -0,0 +1,253 @@
+//! Rule-based classification of what happened on a scanned date.
+//!
+//! The sentence around a date is matched against a lexicon of the phrases
+//! that turn up in consumer disputes and small claims correspondence ("was
+//! delivered", "refused to refund", "letter before claim", "listed for
+//! hearing"). Each match names the event, proposes a `Significance` and keeps
+//! the phrase that triggered it so the user can see why.
+
+use crate::models::chronology::{EventType, Significance};
+use serde::{Deserialize, Serialize};
+
+/// An event recognised in a sentence
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ClassifiedEvent {
+    pub event_type: EventType,
+    pub significance: Significance,
+    /// The lexicon phrase that matched
+    pub trigger: String,
+}
+
+struct Rule {
+    event_type: EventType,
+    significance: Significance,
+    phrases: &'static [&'static str],
+}
+
+/// Checked in order; one event per type, several types per sentence
+const LEXICON: &[Rule] = &[
+    Rule {
+        event_type: EventType::LetterBeforeClaimSent,
+        significance: Significance::Key,
+        phrases: &["letter before claim", "letter before action", "letter of claim", "pre-action letter", "pre action letter", "formal demand"],
+    },
+    Rule {
+        event_type: EventType::ClaimIssued,
+        significance: Significance::Key,
+        phrases: &["claim was issued", "claim has been issued", "claim form was issued", "issued the claim", "issued a claim", "issue the claim", "claim was started", "started a claim", "date of issue"],
+    },
+    Rule {
+        event_type: EventType::ClaimServed,
+        significance: Significance::Key,
+        phrases: &["deemed served", "deemed service", "served the claim", "claim was served", "claim form was served", "service of the claim", "certificate of service"],
+    },
+    Rule {
+        event_type: EventType::AcknowledgmentFiled,
+        significance: Significance::Background,
+        phrases: &["acknowledgment of service", "acknowledgement of service"],
+    },
+    Rule {
+        event_type: EventType::DefenceFiled,
+        significance: Significance::Key,
+        phrases: &["filed a defence", "filed its defence", "filed their defence", "defence was filed", "defence and counterclaim", "defence to the claim", "served a defence", "counterclaim"],
+    },
+    Rule {
+        event_type: EventType::DirectionsQuestionnaire,
+        significance: Significance::Background,
+        phrases: &["directions questionnaire", "n180", "n181"],
+    },
+    Rule {
+        event_type: EventType::Mediation,
+        significance: Significance::Supporting,
+        phrases: &["small claims mediation", "mediation appointment", "mediation service", "mediation"],
+    },
+    Rule {
+        event_type: EventType::Allocated,
+        significance: Significance::Key,
+        phrases: &["allocated to the small claims track", "allocated to the fast track", "allocated to the intermediate track", "allocated to the multi-track", "notice of allocation", "allocation to the"],
+    },
+    Rule {
+        event_type: EventType::HearingListed,
+        significance: Significance::Key,
+        phrases: &["listed for hearing", "listed for a hearing", "notice of hearing", "hearing will take place", "hearing has been listed", "hearing is listed", "listed for trial", "trial window", "final hearing"],
+    },
+    Rule {
+        event_type: EventType::Judgment,
+        significance: Significance::Key,
+        phrases: &["default judgment", "judgment was entered", "judgment for the claimant", "judgment for the defendant", "judgment in default", "it is ordered", "the court ordered", "the court has ordered", "the judge ordered", "order was made"],
+    },
+    Rule {
+        event_type: EventType::RefundRefused,
+        significance: Significance::Key,
+        phrases: &["refused to refund", "refused a refund", "refused my refund", "declined to refund", "declined the refund", "refund was refused", "refund has been refused", "refund was declined", "refund has been declined", "will not refund", "won't refund", "will not be refunding", "unable to offer a refund", "unable to refund", "not entitled to a refund", "rejected my claim", "rejected your claim", "rejected the claim", "denied liability", "deny liability", "not liable"],
+    },
+    Rule {
+        event_type: EventType::RefundRequested,
+        significance: Significance::Key,
+        phrases: &["asked for a refund", "requested a refund", "request a refund", "request for a refund", "demand a refund", "demanded a refund", "full refund", "right to reject", "reject the goods", "rejected the goods", "rejecting the goods", "final right to reject", "price reduction"],
+    },
+    Rule {
+        event_type: EventType::OfferMade,
+        significance: Significance::Supporting,
+        phrases: &["offer to settle", "offered to settle", "settlement offer", "part 36", "goodwill gesture", "goodwill payment", "offered a partial refund", "partial refund", "offered a refund", "without prejudice"],
+    },
+    Rule {
+        event_type: EventType::FaultReported,
+        significance: Significance::Key,
+        phrases: &["stopped working", "not working", "does not work", "doesn't work", "did not work", "didn't work", "developed a fault", "is faulty", "was faulty", "were faulty", "reported the fault", "reported a fault", "reported the problem", "reported the issue", "defective", "defect", "broke down", "has broken", "was broken", "arrived damaged", "made a complaint", "complained", "first complaint"],
+    },
+    Rule {
+        event_type: EventType::RepairAttempted,
+        significance: Significance::Supporting,
+        phrases: &["engineer visited", "engineer attended", "engineer came", "sent for repair", "was repaired", "were repaired", "attempted repair", "repair attempt", "attempt to repair", "replacement was sent", "replacement was delivered", "sent a replacement"],
+    },
+    Rule {
+        event_type: EventType::Delivered,
+        significance: Significance::Key,
+        phrases: &["was delivered", "were delivered", "been delivered", "delivered to", "took delivery", "delivery date", "date of delivery", "was installed", "were installed", "installation", "arrived on", "goods arrived", "collected the", "work was completed", "work completed", "completed the work"],
+    },
+    Rule {
+        event_type: EventType::PaymentMade,
+        significance: Significance::Supporting,
+        phrases: &["paid a deposit", "paid the deposit", "paid in full", "payment of £", "payment was taken", "payment received", "amount paid", "i paid", "we paid", "was paid", "card was charged", "total paid"],
+    },
+    Rule {
+        event_type: EventType::OrderPlaced,
+        significance: Significance::Key,
+        phrases: &["placed an order", "placed the order", "placed my order", "order was placed", "order was confirmed", "thank you for your order", "order date", "date of purchase", "purchased", "i bought", "we bought", "i ordered", "we ordered", "contract was made", "agreed to carry out", "signed the contract"],
+    },
+];
+
+/// Phrases that, just before a match, mean the event didn't happen
+const NEGATIONS: &[&str] = &["not ", "never ", "n't ", "no ", "yet to ", "failed to ", "without "];
+
+/// Finds `phrase` in `haystack` (both lowercase) as whole words, returning
+/// the byte offset of the first occurrence that isn't negated
+fn find_phrase(haystack: &str, phrase: &str) -> Option<usize> {
+    let mut from = 0;
+    while let Some(i) = haystack[from..].find(phrase) {
+        let start = from + i;
+        let end = start + phrase.len();
+        let starts_word = phrase.starts_with(|c: char| c.is_alphanumeric());
+        let ends_word = phrase.ends_with(|c: char| c.is_alphanumeric());
+        let before_ok = !starts_word || haystack[..start].chars().next_back().map(|c| !c.is_alphanumeric()).unwrap_or(true);
+        let after_ok = !ends_word || haystack[end..].chars().next().map(|c| !c.is_alphanumeric()).unwrap_or(true);
+        if before_ok && after_ok {
+            let lead: String = haystack[..start].chars().rev().take(12).collect::<Vec<_>>().into_iter().rev().collect();
+            if !NEGATIONS.iter().any(|n| lead.ends_with(n) || lead.contains(&format!(" {}", n))) {
+                return Some(start);
+            }
+        }
+        from = start + phrase.len().max(1);
+        while !haystack.is_char_boundary(from) {
+            from += 1;
+        }
+    }
+    None
+}
+
+/// Every event the sentence describes, in lexicon order
+pub fn classify(sentence: &str) -> Vec<ClassifiedEvent> {
+    let lower = sentence.to_lowercase().replace('’', "'");
+    LEXICON
+        .iter()
+        .filter_map(|rule| {
+            rule.phrases
+                .iter()
+                .find(|p| find_phrase(&lower, p).is_some())
+                .map(|p| ClassifiedEvent {
+                    event_type: rule.event_type,
+                    significance: rule.significance,
+                    trigger: p.to_string(),
+                })
+        })
+        .collect()
+}
+
+/// Where a sentence ends: a full stop, ! or ? followed by a space, or a line
+/// break. Dates like 15.03.2024 and sums like £1.50 don't end sentences.
+fn is_sentence_end(text: &str, i: usize, c: char) -> bool {
+    match c {
+        '\n' => true,
+        '.' | '!' | '?' => text[i + 1..].chars().next().map(|n| n.is_whitespace()).unwrap_or(true),
+        _ => false,
+    }
+}
+
+/// The sentence in `text` that contains the bytes `start..end`
+pub fn sentence_at(text: &str, start: usize, end: usize) -> String {
+    let from = text[..start]
+        .char_indices()
+        .rev()
+        .find(|&(i, c)| is_sentence_end(text, i, c))
+        .map(|(i, c)| i + c.len_utf8())
+        .unwrap_or(0);
+    let to = text[end..]
+        .char_indices()
+        .find(|&(i, c)| is_sentence_end(text, end + i, c))
+        .map(|(i, c)| end + i + c.len_utf8())
+        .unwrap_or(text.len());
+
+    text[from..to].trim().trim_matches('…').trim().to_string()
+}
+
+/// The sentence in `text` that contains `needle`, or the whole text if the
+/// needle isn't found
+pub fn sentence_around(text: &str, needle: &str) -> String {
+    match text.find(needle) {
+        Some(at) => sentence_at(text, at, at + needle.len()),
+        None => text.trim().to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn types(sentence: &str) -> Vec<EventType> {
+        classify(sentence).into_iter().map(|e| e.event_type).collect()
+    }
+
+    #[test]
+    fn classifies_common_events() {
+        assert_eq!(types("The washing machine was delivered on 3 March 2024"), vec![EventType::Delivered]);
+        assert_eq!(types("On 10 April 2024 it stopped working"), vec![EventType::FaultReported]);
+        assert_eq!(types("They refused to refund me on 2 May 2024"), vec![EventType::RefundRefused]);
+        assert_eq!(types("I sent a letter before claim on 1 June 2024"), vec![EventType::LetterBeforeClaimSent]);
+        assert_eq!(types("The claim was issued on 1 July 2024"), vec![EventType::ClaimIssued]);
+        assert_eq!(types("The claim is listed for hearing on 3 December 2024"), vec![EventType::HearingListed]);
+    }
+
+    #[test]
+    fn keeps_every_event_in_a_sentence_with_its_trigger() {
+        let events = classify("On 5 March I ordered the sofa and paid a deposit of £200");
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].event_type, EventType::PaymentMade);
+        assert_eq!(events[0].trigger, "paid a deposit");
+        assert_eq!(events[1].event_type, EventType::OrderPlaced);
+        assert_eq!(events[1].significance, Significance::Key);
+    }
+
+    #[test]
+    fn ignores_negated_and_partial_matches() {
+        assert!(types("The sofa has not been delivered").is_empty());
+        assert!(types("Remediation works began").is_empty());
+        assert_eq!(types("A payment of £250 was taken"), vec![EventType::PaymentMade]);
+        assert_eq!(types("The sofa still hasn't been delivered but it doesn't work"), vec![EventType::FaultReported]);
+    }
+
+    #[test]
+    fn reference_numbers_are_not_events() {
+        assert!(types("Claim number: K1QZ4567, dated 2 August 2024").is_empty());
+        assert!(types("Your order number is 100234 (12 February 2024)").is_empty());
+        assert!(types("Use Money Claim Online to check progress after 9 September 2024").is_empty());
+        assert_eq!(types("On 4 October 2024 the court ordered the defendant to pay"), vec![EventType::Judgment]);
+    }
+
+    #[test]
+    fn finds_the_sentence_around_a_date() {
+        let context = "…my order. The engineer visited on 15.03.2024. He said it cost £1.50 to fix. Then…";
+        assert_eq!(sentence_around(context, "15.03.2024"), "The engineer visited on 15.03.2024.");
+        assert_eq!(sentence_around(context, "missing"), context.trim());
+    }
+}
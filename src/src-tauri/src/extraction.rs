@@ -3,6 +3,7 @@
 //! Each extractor returns an ExtractedContent struct with the full text body
 //! and any metadata (date, subject, from, to) that can be pulled from headers.
 
+use crate::ocr::{OcrOptions, OcrRegion, OcrResult};
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::Path;
@@ -20,10 +21,65 @@ pub struct ExtractedContent {
     pub from: Option<String>,
     /// Email recipient
     pub to: Option<String>,
+    /// OCR quality report — only present when the text came from Tesseract
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ocr: Option<OcrQuality>,
+}
+
+/// How confident Tesseract was in OCR'd text, and which parts the user should check
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OcrQuality {
+    pub mean_confidence: Option<f32>,
+    pub low_confidence_regions: Vec<OcrRegion>,
+}
+
+impl ExtractedContent {
+    /// Content with only a text body and no header metadata
+    fn text_only(text: String) -> Self {
+        Self {
+            text,
+            metadata_date: None,
+            subject: None,
+            from: None,
+            to: None,
+            ocr: None,
+        }
+    }
+
+    pub(crate) fn from_ocr(result: OcrResult) -> Self {
+        Self {
+            ocr: Some(OcrQuality {
+                mean_confidence: result.mean_confidence,
+                low_confidence_regions: result.low_confidence_regions,
+            }),
+            ..Self::text_only(result.text)
+        }
+    }
+}
+
+/// File extensions `extract_from_file` can handle
+pub const SUPPORTED_EXTENSIONS: &[&str] = &[
+    "txt", "eml", "pdf", "docx", "jpg", "jpeg", "png", "bmp", "tiff", "tif", "gif",
+];
+
+/// Whether a file's extension is one we can extract text from
+pub fn is_supported_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .map(|e| SUPPORTED_EXTENSIONS.contains(&e.to_lowercase().as_str()))
+        .unwrap_or(false)
 }
 
 /// Detect file type by extension and extract content
 pub fn extract_from_file(path: &Path) -> Result<ExtractedContent, String> {
+    extract_from_file_with_options(path, &OcrOptions::default())
+}
+
+/// As `extract_from_file`, with explicit OCR settings for scanned PDFs and images
+pub fn extract_from_file_with_options(
+    path: &Path,
+    ocr_options: &OcrOptions,
+) -> Result<ExtractedContent, String> {
     let ext = path
         .extension()
         .and_then(|e| e.to_str())
@@ -33,9 +89,9 @@ pub fn extract_from_file(path: &Path) -> Result<ExtractedContent, String> {
     match ext.as_str() {
         "txt" => extract_txt(path),
         "eml" => extract_eml(path),
-        "pdf" => extract_pdf(path),
+        "pdf" => extract_pdf(path, ocr_options),
         "docx" => extract_docx(path),
-        "jpg" | "jpeg" | "png" | "bmp" | "tiff" | "tif" | "gif" => extract_image(path),
+        "jpg" | "jpeg" | "png" | "bmp" | "tiff" | "tif" | "gif" => extract_image(path, ocr_options),
         _ => Err(format!("Unsupported file type: .{}", ext)),
     }
 }
@@ -45,13 +101,7 @@ pub fn extract_from_file(path: &Path) -> Result<ExtractedContent, String> {
 fn extract_txt(path: &Path) -> Result<ExtractedContent, String> {
     let text = fs::read_to_string(path)
         .map_err(|e| format!("Could not read text file: {}", e))?;
-    Ok(ExtractedContent {
-        text,
-        metadata_date: None,
-        subject: None,
-        from: None,
-        to: None,
-    })
+    Ok(ExtractedContent::text_only(text))
 }
 
 // ── Email (.eml) ────────────────────────────────────────────────────────────
@@ -113,12 +163,13 @@ fn extract_eml(path: &Path) -> Result<ExtractedContent, String> {
         subject,
         from,
         to,
+        ocr: None,
     })
 }
 
 // ── PDF ─────────────────────────────────────────────────────────────────────
 
-fn extract_pdf(path: &Path) -> Result<ExtractedContent, String> {
+fn extract_pdf(path: &Path, ocr_options: &OcrOptions) -> Result<ExtractedContent, String> {
     // Try text extraction first
     let text = pdf_extract::extract_text(path)
         .map_err(|e| format!("PDF text extraction failed: {}", e))?;
@@ -127,46 +178,24 @@ fn extract_pdf(path: &Path) -> Result<ExtractedContent, String> {
     let trimmed = text.trim();
     if trimmed.len() < 50 {
         // This is likely a scanned/image PDF — try OCR
-        match crate::ocr::ocr_scanned_pdf(path) {
-            Ok(ocr_text) if !ocr_text.trim().is_empty() => {
-                return Ok(ExtractedContent {
-                    text: ocr_text,
-                    metadata_date: None,
-                    subject: None,
-                    from: None,
-                    to: None,
-                });
+        match crate::ocr::ocr_scanned_pdf(path, ocr_options) {
+            Ok(ocr) if !ocr.text.trim().is_empty() => {
+                return Ok(ExtractedContent::from_ocr(ocr));
             }
             Ok(_) => {
                 // OCR ran but produced nothing
-                return Ok(ExtractedContent {
-                    text: "[Scanned PDF — OCR produced no readable text. You can type content manually using the edit button.]".to_string(),
-                    metadata_date: None,
-                    subject: None,
-                    from: None,
-                    to: None,
-                });
+                return Ok(ExtractedContent::text_only(
+                    "[Scanned PDF — OCR produced no readable text. You can type content manually using the edit button.]".to_string(),
+                ));
             }
             Err(e) => {
                 // OCR not available or failed
-                return Ok(ExtractedContent {
-                    text: format!("[Scanned PDF — {}]", e),
-                    metadata_date: None,
-                    subject: None,
-                    from: None,
-                    to: None,
-                });
+                return Ok(ExtractedContent::text_only(format!("[Scanned PDF — {}]", e)));
             }
         }
     }
 
-    Ok(ExtractedContent {
-        text: text.trim().to_string(),
-        metadata_date: None,
-        subject: None,
-        from: None,
-        to: None,
-    })
+    Ok(ExtractedContent::text_only(text.trim().to_string()))
 }
 
 // ── DOCX ────────────────────────────────────────────────────────────────────
@@ -198,13 +227,7 @@ fn extract_docx(path: &Path) -> Result<ExtractedContent, String> {
         }
     }
 
-    Ok(ExtractedContent {
-        text: text_parts.join("\n"),
-        metadata_date: None,
-        subject: None,
-        from: None,
-        to: None,
-    })
+    Ok(ExtractedContent::text_only(text_parts.join("\n")))
 }
 
 /// Extract text from a docx paragraph (all runs)
@@ -248,29 +271,13 @@ fn extract_table_text(table: &docx_rs::Table) -> String {
 
 // ── Image files (OCR) ───────────────────────────────────────────────────────
 
-fn extract_image(path: &Path) -> Result<ExtractedContent, String> {
-    match crate::ocr::ocr_image_file(path) {
-        Ok(text) if !text.trim().is_empty() => Ok(ExtractedContent {
-            text,
-            metadata_date: None,
-            subject: None,
-            from: None,
-            to: None,
-        }),
-        Ok(_) => Ok(ExtractedContent {
-            text: "[Image — OCR produced no readable text. You can type content manually using the edit button.]".to_string(),
-            metadata_date: None,
-            subject: None,
-            from: None,
-            to: None,
-        }),
-        Err(e) => Ok(ExtractedContent {
-            text: format!("[Image — {}]", e),
-            metadata_date: None,
-            subject: None,
-            from: None,
-            to: None,
-        }),
+fn extract_image(path: &Path, ocr_options: &OcrOptions) -> Result<ExtractedContent, String> {
+    match crate::ocr::ocr_image_file(path, ocr_options) {
+        Ok(ocr) if !ocr.text.trim().is_empty() => Ok(ExtractedContent::from_ocr(ocr)),
+        Ok(_) => Ok(ExtractedContent::text_only(
+            "[Image — OCR produced no readable text. You can type content manually using the edit button.]".to_string(),
+        )),
+        Err(e) => Ok(ExtractedContent::text_only(format!("[Image — {}]", e))),
     }
 }
 #[cfg(test)]
@@ -0,0 +1,526 @@
+//! Court fees and statutory interest.
+//!
+//! Fee tables are versioned JSON files: the bundled ones in `data/fees`, plus
+//! any placed in `<config dir>/CaseKit/fees` so new fees can be picked up
+//! without a new release. The table used is the latest whose `effective_from`
+//! is on or before the date of issue.
+//!
+//! Amounts in the fee tables are integer pence, read directly as `Money`.
+
+use crate::models::case::{DefendantType, Track};
+use crate::models::money::Money;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::fs;
+
+const BUNDLED_TABLES: &[&str] = &[include_str!("../data/fees/civil-fees-2021-09-30.json")];
+
+const BASE_RATES: &str = include_str!("../data/bank-of-england-base-rate.json");
+
+/// s.69 County Courts Act 1984: the rate on judgment debts, also the usual rate claimed
+const COUNTY_COURT_RATE_BP: u64 = 800;
+
+/// Late Payment Act interest is 8% over the reference rate
+const LATE_PAYMENT_UPLIFT_BP: u64 = 800;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum FeeRule {
+    #[serde(rename = "fixed")]
+    Fixed { amount: Money },
+    #[serde(rename = "percent")]
+    Percent { percent: f64 },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IssueBand {
+    /// Upper limit of the band, inclusive. None for the top band.
+    pub up_to: Option<Money>,
+    pub paper: FeeRule,
+    /// None where the claim can't be issued online
+    pub online: Option<FeeRule>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HearingBand {
+    pub up_to: Option<Money>,
+    pub amount: Money,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeeTable {
+    pub version: String,
+    pub effective_from: String,
+    pub source: String,
+    #[serde(default)]
+    pub notes: Option<String>,
+    /// Largest claim that can be issued online
+    pub online_limit: Money,
+    pub issue: Vec<IssueBand>,
+    pub small_claims_hearing: Vec<HearingBand>,
+    pub fast_track_hearing: Money,
+    pub intermediate_track_hearing: Money,
+    pub multi_track_hearing: Money,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum IssueChannel {
+    #[serde(rename = "online")]
+    Online,
+    #[serde(rename = "paper")]
+    Paper,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum InterestBasis {
+    /// s.69 County Courts Act 1984, 8% simple
+    #[serde(rename = "county_courts_act")]
+    CountyCourtsAct,
+    /// Late Payment of Commercial Debts (Interest) Act 1998
+    #[serde(rename = "late_payment")]
+    LatePayment,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InterestRequest {
+    pub basis: InterestBasis,
+    /// Date interest runs from (YYYY-MM-DD), e.g. when payment was due
+    pub from_date: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CostsRequest {
+    pub channel: IssueChannel,
+    /// Track for the hearing fee. None uses the normal track for the value.
+    pub track: Option<Track>,
+    pub interest: Option<InterestRequest>,
+    /// Date of issue (YYYY-MM-DD). None means today.
+    pub issue_date: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InterestBreakdown {
+    pub basis: InterestBasis,
+    /// e.g. "s.69 County Courts Act 1984"
+    pub authority: String,
+    pub from_date: String,
+    pub to_date: String,
+    pub days: i64,
+    /// Annual rate in basis points (800 = 8%)
+    pub rate_bp: u64,
+    pub amount: Money,
+    /// Pence per day from issue until judgment or payment
+    pub daily_rate: f64,
+    /// Late Payment Act fixed compensation (£40/£70/£100)
+    pub fixed_compensation: Money,
+}
+
+/// The figures for the boxes on form N1
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct N1Figures {
+    pub amount_claimed: Money,
+    pub court_fee: Money,
+    pub legal_representative_costs: Money,
+    pub total_amount: Money,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CostsBreakdown {
+    pub fee_table_version: String,
+    pub principal: Money,
+    pub interest: Option<InterestBreakdown>,
+    pub channel: IssueChannel,
+    pub issue_fee: Money,
+    pub track: Track,
+    pub hearing_fee: Money,
+    pub n1: N1Figures,
+    /// Each step of the calculation, in order
+    pub steps: Vec<String>,
+    pub warnings: Vec<String>,
+}
+
+fn parse_date(s: &str) -> Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(s.trim(), "%Y-%m-%d")
+        .map_err(|e| format!("Could not parse date '{}': {}", s, e))
+}
+
+fn parse_table(json: &str) -> Result<FeeTable, String> {
+    serde_json::from_str(json).map_err(|e| format!("Could not parse fee table: {}", e))
+}
+
+/// Every available fee table: bundled ones, then any in the user's config folder
+pub fn load_fee_tables() -> Result<Vec<FeeTable>, String> {
+    let mut tables = BUNDLED_TABLES
+        .iter()
+        .map(|json| parse_table(json))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if let Some(dir) = dirs::config_dir().map(|d| d.join("CaseKit").join("fees")) {
+        if let Ok(entries) = fs::read_dir(&dir) {
+            for entry in entries.flatten() {
+                let path = entry.path();
+                if path.extension().and_then(|e| e.to_str()) != Some("json") {
+                    continue;
+                }
+                let content = fs::read_to_string(&path)
+                    .map_err(|e| format!("Could not read {}: {}", path.display(), e))?;
+                tables.push(parse_table(&content).map_err(|e| format!("{}: {}", path.display(), e))?);
+            }
+        }
+    }
+
+    Ok(tables)
+}
+
+/// The fee table in force on `date`
+pub fn table_for(tables: &[FeeTable], date: NaiveDate) -> Option<&FeeTable> {
+    let day = date.format("%Y-%m-%d").to_string();
+    tables
+        .iter()
+        .filter(|t| t.effective_from <= day)
+        .max_by(|a, b| a.effective_from.cmp(&b.effective_from))
+}
+
+fn in_band(value: Money, up_to: Option<Money>) -> bool {
+    !matches!(up_to, Some(limit) if value > limit)
+}
+
+fn apply(rule: &FeeRule, value: Money) -> Money {
+    match rule {
+        FeeRule::Fixed { amount } => *amount,
+        // Percentage fees are rounded down to the nearest penny
+        FeeRule::Percent { percent } => value.percent_floor(*percent),
+    }
+}
+
+/// Issue fee for a claim of `value`, and the channel actually used (claims over
+/// the online limit must be issued on paper)
+pub fn issue_fee(table: &FeeTable, value: Money, channel: IssueChannel) -> Result<(Money, IssueChannel), String> {
+    let band = table
+        .issue
+        .iter()
+        .find(|b| in_band(value, b.up_to))
+        .ok_or_else(|| format!("No issue fee band for {}", value))?;
+
+    match (channel, &band.online) {
+        (IssueChannel::Online, Some(rule)) if value <= table.online_limit => {
+            Ok((apply(rule, value), IssueChannel::Online))
+        }
+        _ => Ok((apply(&band.paper, value), IssueChannel::Paper)),
+    }
+}
+
+pub fn hearing_fee(table: &FeeTable, value: Money, track: Track) -> Money {
+    match track {
+        Track::SmallClaims => table
+            .small_claims_hearing
+            .iter()
+            .find(|b| in_band(value, b.up_to))
+            .map(|b| b.amount)
+            .unwrap_or(Money::ZERO),
+        Track::Fast => table.fast_track_hearing,
+        Track::Intermediate => table.intermediate_track_hearing,
+        Track::Multi => table.multi_track_hearing,
+    }
+}
+
+#[derive(Deserialize)]
+struct BaseRates {
+    changes: Vec<BaseRateChange>,
+}
+
+#[derive(Deserialize)]
+struct BaseRateChange {
+    date: String,
+    rate: f64,
+}
+
+/// Bank of England base rate (in basis points) in force on a date
+fn base_rate_bp(date: NaiveDate) -> Result<u64, String> {
+    let rates: BaseRates = serde_json::from_str(BASE_RATES)
+        .map_err(|e| format!("Could not parse base rate data: {}", e))?;
+    let day = date.format("%Y-%m-%d").to_string();
+    rates
+        .changes
+        .iter()
+        .filter(|c| c.date <= day)
+        .max_by(|a, b| a.date.cmp(&b.date))
+        .map(|c| (c.rate * 100.0).round() as u64)
+        .ok_or_else(|| format!("No base rate data for {}", day))
+}
+
+/// The Late Payment Act reference date: 31 December for debts falling due in
+/// January–June, 30 June for July–December
+fn reference_date(due: NaiveDate) -> NaiveDate {
+    use chrono::Datelike;
+    if due.month() <= 6 {
+        NaiveDate::from_ymd_opt(due.year() - 1, 12, 31).expect("valid date")
+    } else {
+        NaiveDate::from_ymd_opt(due.year(), 6, 30).expect("valid date")
+    }
+}
+
+/// Fixed sum for the cost of recovering a late payment (s.5A)
+fn late_payment_compensation(principal: Money) -> Money {
+    if principal < Money::from_pounds(1_000) {
+        Money::from_pounds(40)
+    } else if principal < Money::from_pounds(10_000) {
+        Money::from_pounds(70)
+    } else {
+        Money::from_pounds(100)
+    }
+}
+
+/// Simple interest on `principal` at `rate_bp` for `days`, rounded to the nearest penny
+fn simple_interest(principal: Money, rate_bp: u64, days: i64) -> Money {
+    if days <= 0 || principal <= Money::ZERO {
+        return Money::ZERO;
+    }
+    let numerator = principal.pence() as i128 * rate_bp as i128 * days as i128;
+    let denominator = 365i128 * 10_000;
+    Money::from_pence(((numerator + denominator / 2) / denominator) as i64)
+}
+
+pub fn interest(principal: Money, request: &InterestRequest, to: NaiveDate) -> Result<InterestBreakdown, String> {
+    let from = parse_date(&request.from_date)?;
+    let days = (to - from).num_days().max(0);
+
+    let (authority, rate_bp, fixed_compensation) = match request.basis {
+        InterestBasis::CountyCourtsAct => (
+            "s.69 County Courts Act 1984".to_string(),
+            COUNTY_COURT_RATE_BP,
+            Money::ZERO,
+        ),
+        InterestBasis::LatePayment => {
+            let reference = reference_date(from);
+            let base = base_rate_bp(reference)?;
+            (
+                format!(
+                    "Late Payment of Commercial Debts (Interest) Act 1998, s.6 (base rate {:.2}% on {} plus 8%)",
+                    base as f64 / 100.0,
+                    reference.format("%-d %B %Y")
+                ),
+                base + LATE_PAYMENT_UPLIFT_BP,
+                late_payment_compensation(principal),
+            )
+        }
+    };
+
+    Ok(InterestBreakdown {
+        basis: request.basis,
+        authority,
+        from_date: from.format("%Y-%m-%d").to_string(),
+        to_date: to.format("%Y-%m-%d").to_string(),
+        days,
+        rate_bp,
+        amount: simple_interest(principal, rate_bp, days),
+        daily_rate: principal.pence() as f64 * rate_bp as f64 / 10_000.0 / 365.0,
+        fixed_compensation,
+    })
+}
+
+/// Work out the court fees, interest and N1 figures for a claim of `principal`
+pub fn calculate(
+    tables: &[FeeTable],
+    principal: Money,
+    defendant_type: &DefendantType,
+    request: &CostsRequest,
+    today: NaiveDate,
+) -> Result<CostsBreakdown, String> {
+    let issue_date = match &request.issue_date {
+        Some(d) => parse_date(d)?,
+        None => today,
+    };
+    let table = table_for(tables, issue_date)
+        .ok_or_else(|| format!("No fee table in force on {}", issue_date))?;
+
+    let mut steps = vec![format!("Principal claimed: {}", principal)];
+    let mut warnings = Vec::new();
+
+    let interest = match &request.interest {
+        Some(req) => {
+            if req.basis == InterestBasis::LatePayment && matches!(defendant_type, DefendantType::Individual) {
+                warnings.push(
+                    "The Late Payment Act only applies where both parties were acting in the course of a business".to_string(),
+                );
+            }
+            let i = interest(principal, req, issue_date)?;
+            steps.push(format!(
+                "Interest under {}: {} × {:.2}% × {} days ÷ 365 = {}",
+                i.authority,
+                principal,
+                i.rate_bp as f64 / 100.0,
+                i.days,
+                i.amount
+            ));
+            if i.fixed_compensation > Money::ZERO {
+                steps.push(format!(
+                    "Fixed compensation for late payment: {}",
+                    i.fixed_compensation
+                ));
+            }
+            steps.push(format!(
+                "Interest continues at {} per day",
+                Money::from_pence(i.daily_rate.round() as i64)
+            ));
+            Some(i)
+        }
+        None => None,
+    };
+
+    // The fee band is based on the amount claimed, including interest to date
+    let amount_claimed = principal
+        + interest
+            .as_ref()
+            .map(|i| i.amount + i.fixed_compensation)
+            .unwrap_or(Money::ZERO);
+    steps.push(format!("Amount claimed (N1): {}", amount_claimed));
+
+    let (issue, channel) = issue_fee(table, amount_claimed, request.channel)?;
+    if channel != request.channel {
+        warnings.push(format!(
+            "Claims over {} can't be issued online, so the paper fee applies",
+            table.online_limit
+        ));
+    }
+    steps.push(format!(
+        "Issue fee ({}, fee table {}): {}",
+        if channel == IssueChannel::Online { "online" } else { "paper" },
+        table.version,
+        issue
+    ));
+
+    let track = request
+        .track
+        .unwrap_or_else(|| crate::allocation::track_for_value(amount_claimed));
+    let hearing = hearing_fee(table, amount_claimed, track);
+    steps.push(format!(
+        "Hearing fee, payable later if the claim goes to a hearing: {}",
+        hearing
+    ));
+
+    let n1 = N1Figures {
+        amount_claimed,
+        court_fee: issue,
+        legal_representative_costs: Money::ZERO,
+        total_amount: amount_claimed + issue,
+    };
+    steps.push(format!(
+        "Total amount (N1): {} + {} = {}",
+        amount_claimed,
+        issue,
+        n1.total_amount
+    ));
+
+    Ok(CostsBreakdown {
+        fee_table_version: table.version.clone(),
+        principal,
+        interest,
+        channel,
+        issue_fee: issue,
+        track,
+        hearing_fee: hearing,
+        n1,
+        steps,
+        warnings,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    fn tables() -> Vec<FeeTable> {
+        BUNDLED_TABLES.iter().map(|j| parse_table(j).unwrap()).collect()
+    }
+
+    fn request(channel: IssueChannel, interest: Option<InterestRequest>) -> CostsRequest {
+        CostsRequest {
+            channel,
+            track: None,
+            interest,
+            issue_date: Some("2025-01-31".to_string()),
+        }
+    }
+
+    #[test]
+    fn issue_fee_bands() {
+        let t = tables();
+        let table = table_for(&t, date("2025-01-01")).unwrap();
+        assert_eq!(issue_fee(table, Money::from_pence(30_000), IssueChannel::Paper).unwrap().0, Money::from_pence(3_500));
+        assert_eq!(issue_fee(table, Money::from_pence(30_001), IssueChannel::Paper).unwrap().0, Money::from_pence(5_000));
+        assert_eq!(issue_fee(table, Money::from_pence(80_000), IssueChannel::Online).unwrap().0, Money::from_pence(6_000));
+        // 5% of £12,345.67, rounded down
+        assert_eq!(issue_fee(table, Money::from_pence(1_234_567), IssueChannel::Paper).unwrap().0, Money::from_pence(61_728));
+        // Over the online limit falls back to paper
+        let (fee, channel) = issue_fee(table, Money::from_pence(15_000_000), IssueChannel::Online).unwrap();
+        assert_eq!((fee, channel), (Money::from_pence(750_000), IssueChannel::Paper));
+        assert_eq!(issue_fee(table, Money::from_pence(50_000_000), IssueChannel::Paper).unwrap().0, Money::from_pence(1_000_000));
+    }
+
+    #[test]
+    fn county_court_interest_is_daily_simple() {
+        // £1,000 at 8% for 365 days = £80
+        let i = interest(
+            Money::from_pence(100_000),
+            &InterestRequest {
+                basis: InterestBasis::CountyCourtsAct,
+                from_date: "2024-01-31".to_string(),
+            },
+            date("2025-01-30"),
+        )
+        .unwrap();
+        assert_eq!(i.days, 365);
+        assert_eq!(i.amount, Money::from_pounds(80));
+        assert!((i.daily_rate - 21.917_8).abs() < 0.001);
+    }
+
+    #[test]
+    fn late_payment_uses_reference_rate_and_compensation() {
+        // Due 15 March 2024: reference date 31 December 2023, base rate 5.25%
+        let i = interest(
+            Money::from_pence(250_000),
+            &InterestRequest {
+                basis: InterestBasis::LatePayment,
+                from_date: "2024-03-15".to_string(),
+            },
+            date("2024-04-14"),
+        )
+        .unwrap();
+        assert_eq!(i.rate_bp, 1_325);
+        assert_eq!(i.fixed_compensation, Money::from_pounds(70));
+        // £2,500 × 13.25% × 30 / 365 = £27.23
+        assert_eq!(i.amount, Money::from_pence(2_723));
+    }
+
+    #[test]
+    fn n1_figures_include_interest_in_fee_band() {
+        let req = request(
+            IssueChannel::Online,
+            Some(InterestRequest {
+                basis: InterestBasis::CountyCourtsAct,
+                from_date: "2024-02-01".to_string(),
+            }),
+        );
+        // £950 + £76 interest = £1,026 → online band £1,000.01–£1,500
+        let result = calculate(&tables(), Money::from_pence(95_000), &DefendantType::Company, &req, date("2025-01-31")).unwrap();
+        assert_eq!(result.n1.amount_claimed, Money::from_pence(95_000 + 7_600));
+        assert_eq!(result.n1.court_fee, Money::from_pence(7_000));
+        assert_eq!(result.n1.total_amount, Money::from_pence(102_600 + 7_000));
+        assert_eq!(result.track, Track::SmallClaims);
+        assert_eq!(result.hearing_fee, Money::from_pence(12_300));
+    }
+
+    #[test]
+    fn hearing_fees_by_track() {
+        let t = tables();
+        let table = table_for(&t, date("2025-01-01")).unwrap();
+        assert_eq!(hearing_fee(table, Money::from_pence(20_000), Track::SmallClaims), Money::from_pence(2_700));
+        assert_eq!(hearing_fee(table, Money::from_pence(900_000), Track::SmallClaims), Money::from_pence(34_600));
+        assert_eq!(hearing_fee(table, Money::from_pence(2_000_000), Track::Fast), Money::from_pence(54_500));
+    }
+}
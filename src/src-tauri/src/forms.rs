@@ -0,0 +1,913 @@
+//! Court form filling (N1, N9A, N9B, N180, N181, N244).
+//!
+//! Each supported version of a form has a JSON field map linking CaseKit's
+//! field keys to the AcroForm field names in the blank PDF.
+//! Values come from the case, the fee and interest calculation, the track
+//! advice and anything the user types in; a validation report lists the
+//! required fields still missing before the form goes anywhere near a court.
+//!
+//! Maps are read from `<config dir>/CaseKit/forms`, alongside any bundled
+//! with CaseKit, and the latest in force is used. No map is bundled yet: a
+//! map is only shipped once it has been generated from the real HMCTS blank
+//! and that blank's checksum pinned.
+//!
+//! A form is only reported ready when every mapped field exists in the blank.
+//! Maps not yet checked against the real blank say so with `verified: false`,
+//! and stay not-ready until a blank with matching fields is installed.
+
+use crate::allocation::TrackAdvice;
+use crate::fees::{CostsBreakdown, CostsRequest};
+use crate::models::case::{CaseMetadata, ClaimType, UserRole};
+use crate::models::money::Money;
+use crate::models::party::PartyRole;
+use chrono::NaiveDate;
+use lopdf::{Document, Object, ObjectId, StringFormat};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Field maps shipped with CaseKit. Only maps generated from the real HMCTS
+/// blank, with its checksum pinned and `verified: true`, belong here; none
+/// has been checked yet, so maps are added by the user (see
+/// `data/forms/README.md`).
+const BUNDLED_SPECS: &[&str] = &[];
+
+/// Field hierarchies deeper than this are treated as malformed
+const MAX_FIELD_DEPTH: usize = 32;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FormId {
+    /// Claim form (CPR Part 7)
+    #[serde(rename = "n1")]
+    N1,
+    /// Admission (specified amount)
+    #[serde(rename = "n9a")]
+    N9A,
+    /// Defence and counterclaim (specified amount)
+    #[serde(rename = "n9b")]
+    N9B,
+    /// Directions questionnaire, small claims track
+    #[serde(rename = "n180")]
+    N180,
+    /// Directions questionnaire, fast, intermediate and multi-track
+    #[serde(rename = "n181")]
+    N181,
+    /// Application notice
+    #[serde(rename = "n244")]
+    N244,
+}
+
+impl FormId {
+    pub fn code(self) -> &'static str {
+        match self {
+            FormId::N1 => "N1",
+            FormId::N9A => "N9A",
+            FormId::N9B => "N9B",
+            FormId::N180 => "N180",
+            FormId::N181 => "N181",
+            FormId::N244 => "N244",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FieldKind {
+    #[default]
+    #[serde(rename = "text")]
+    Text,
+    #[serde(rename = "checkbox")]
+    Checkbox,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FieldSpec {
+    /// CaseKit's name for the value, e.g. "claimant_name"
+    pub key: String,
+    /// Fully qualified AcroForm field name in the blank PDF
+    pub pdf_field: String,
+    pub label: String,
+    #[serde(default)]
+    pub required: bool,
+    #[serde(default)]
+    pub kind: FieldKind,
+    /// Roughly how much text fits in the box
+    #[serde(default)]
+    pub max_len: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FormSpec {
+    pub form: FormId,
+    /// The version printed in the form's footer, e.g. "04.24"
+    pub version: String,
+    pub effective_from: String,
+    pub title: String,
+    /// File name of the blank form
+    pub pdf_file: String,
+    /// SHA-256 of the blank the map was written against. When set, only that
+    /// exact file is accepted as the blank.
+    #[serde(default)]
+    pub blank_sha256: Option<String>,
+    /// Whether the `pdf_field` names have been checked against the blank
+    #[serde(default)]
+    pub verified: bool,
+    pub source: String,
+    #[serde(default)]
+    pub notes: Option<String>,
+    pub fields: Vec<FieldSpec>,
+}
+
+/// Details the case doesn't hold, supplied when generating a form
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FormInputs {
+    #[serde(default)]
+    pub claim_number: Option<String>,
+    #[serde(default)]
+    pub court: Option<String>,
+    #[serde(default)]
+    pub claimant_address: Option<String>,
+    #[serde(default)]
+    pub defendant_address: Option<String>,
+    #[serde(default)]
+    pub particulars: Option<String>,
+    /// Person signing the statement of truth. Defaults to the user's party.
+    #[serde(default)]
+    pub signatory: Option<String>,
+    /// How to work out the fee and interest figures. None issues online without interest.
+    #[serde(default)]
+    pub costs: Option<CostsRequest>,
+    /// Any other field by key, e.g. "witness_count" or "order_sought".
+    /// Overrides values worked out from the case.
+    #[serde(default)]
+    pub answers: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FieldProblem {
+    /// A required field has no value
+    #[serde(rename = "missing")]
+    Missing,
+    /// The value is probably too long for the box
+    #[serde(rename = "too_long")]
+    TooLong,
+    /// The field map names a field the blank PDF doesn't have
+    #[serde(rename = "not_in_pdf")]
+    NotInPdf,
+    /// The field map hasn't been checked against the blank, and no blank is
+    /// installed to check it now
+    #[serde(rename = "unverified")]
+    Unverified,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FieldIssue {
+    pub key: String,
+    pub label: String,
+    pub problem: FieldProblem,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ValidationReport {
+    pub form: FormId,
+    pub version: String,
+    /// Keys of the fields that were given a value
+    pub filled: Vec<String>,
+    pub issues: Vec<FieldIssue>,
+    /// True when no required field is missing and every mapped field is in the blank
+    pub ready: bool,
+}
+
+fn parse_spec(json: &str) -> Result<FormSpec, String> {
+    serde_json::from_str(json).map_err(|e| format!("Could not parse form field map: {}", e))
+}
+
+/// Every available field map: bundled ones, then any in the user's config folder
+pub fn load_form_specs() -> Result<Vec<FormSpec>, String> {
+    let mut specs = BUNDLED_SPECS
+        .iter()
+        .map(|json| parse_spec(json))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if let Some(dir) = user_forms_dir() {
+        if let Ok(entries) = fs::read_dir(&dir) {
+            for entry in entries.flatten() {
+                let path = entry.path();
+                if path.extension().and_then(|e| e.to_str()) != Some("json") {
+                    continue;
+                }
+                let content = fs::read_to_string(&path)
+                    .map_err(|e| format!("Could not read {}: {}", path.display(), e))?;
+                specs.push(parse_spec(&content).map_err(|e| format!("{}: {}", path.display(), e))?);
+            }
+        }
+    }
+
+    Ok(specs)
+}
+
+/// Where users can add newer field maps and blank forms
+pub fn user_forms_dir() -> Option<PathBuf> {
+    dirs::config_dir().map(|d| d.join("CaseKit").join("forms"))
+}
+
+/// The version of `form` in force on `date`
+pub fn spec_for(specs: &[FormSpec], form: FormId, date: NaiveDate) -> Option<&FormSpec> {
+    let day = date.format("%Y-%m-%d").to_string();
+    specs
+        .iter()
+        .filter(|s| s.form == form && s.effective_from <= day)
+        .max_by(|a, b| a.effective_from.cmp(&b.effective_from))
+}
+
+/// Find the blank PDF for a field map in the first folder that has it
+pub fn find_blank(spec: &FormSpec, dirs: &[PathBuf]) -> Result<PathBuf, String> {
+    dirs.iter()
+        .map(|dir| dir.join(&spec.pdf_file))
+        .find(|path| path.is_file())
+        .ok_or_else(|| {
+            format!(
+                "The blank {} ({}) form is not installed. Download it from GOV.UK and add it to CaseKit, or save it as '{}' in {}",
+                spec.form.code(),
+                spec.version,
+                spec.pdf_file,
+                dirs.first()
+                    .map(|d| d.display().to_string())
+                    .unwrap_or_else(|| "the CaseKit forms folder".to_string())
+            )
+        })
+}
+
+/// SHA-256 of a file's bytes as lowercase hex
+pub fn sha256_hex(bytes: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(bytes))
+}
+
+/// Check a blank PDF before it is installed: it must be the exact file the
+/// map was written against, if the map pins one, and have fillable fields.
+/// Returns the mapped fields the blank doesn't have.
+pub fn check_blank(spec: &FormSpec, pdf: &[u8]) -> Result<Vec<FieldIssue>, String> {
+    if let Some(expected) = &spec.blank_sha256 {
+        if !sha256_hex(pdf).eq_ignore_ascii_case(expected) {
+            return Err(format!(
+                "This is not the {} ({}) blank form the field map was written for. Download version {} from {}",
+                spec.form.code(),
+                spec.version,
+                spec.version,
+                spec.source
+            ));
+        }
+    }
+    Ok(unmatched_fields(spec, &list_fields(pdf)?))
+}
+
+/// Mapped fields whose `pdf_field` isn't among the blank's field names
+pub fn unmatched_fields(spec: &FormSpec, pdf_fields: &[String]) -> Vec<FieldIssue> {
+    let names: BTreeSet<&str> = pdf_fields.iter().map(String::as_str).collect();
+    spec.fields
+        .iter()
+        .filter(|field| !names.contains(field.pdf_field.as_str()))
+        .map(|field| FieldIssue {
+            key: field.key.clone(),
+            label: field.label.clone(),
+            problem: FieldProblem::NotInPdf,
+            message: format!(
+                "The blank form has no field named '{}' — check the field map matches version {}",
+                field.pdf_field, spec.version
+            ),
+        })
+        .collect()
+}
+
+fn is_ready(issues: &[FieldIssue]) -> bool {
+    !issues.iter().any(|i| {
+        matches!(
+            i.problem,
+            FieldProblem::Missing | FieldProblem::NotInPdf | FieldProblem::Unverified
+        )
+    })
+}
+
+/// Amount for a form box, which already has the £ sign printed
+fn amount(money: Money) -> String {
+    money.to_string().replacen('£', "", 1)
+}
+
+fn non_empty(value: Option<&String>) -> Option<String> {
+    value.map(|v| v.trim()).filter(|v| !v.is_empty()).map(|v| v.to_string())
+}
+
+fn brief_details(case: &CaseMetadata, costs: Option<&CostsBreakdown>) -> String {
+    let subject = match case.claim_type {
+        ClaimType::CraGoods => "goods",
+        ClaimType::CraServices => "services",
+        ClaimType::CraDigital => "digital content",
+    };
+    let mut details = format!(
+        "The claimant claims {} for breach of contract in respect of {} supplied by the defendant that did not conform to the contract under the Consumer Rights Act 2015",
+        case.total_claim_value(),
+        subject
+    );
+    if let Some(interest) = costs.and_then(|c| c.interest.as_ref()) {
+        details.push_str(&format!(", together with interest under {}", interest.authority));
+    }
+    details.push('.');
+    details
+}
+
+/// Work out every value the case can supply, keyed by field key. Keys a
+/// form doesn't use are simply ignored when filling.
+pub fn field_values(
+    case: &CaseMetadata,
+    costs: Option<&CostsBreakdown>,
+    advice: &TrackAdvice,
+    inputs: &FormInputs,
+    today: NaiveDate,
+) -> BTreeMap<String, String> {
+    let mut values = BTreeMap::new();
+    let mut put = |key: &str, value: Option<String>| {
+        if let Some(v) = value.filter(|v| !v.trim().is_empty()) {
+            values.insert(key.to_string(), v);
+        }
+    };
+
+    let own_name = case.party_names(match case.user_role {
+        UserRole::Claimant => PartyRole::Claimant,
+        UserRole::Defendant => PartyRole::Defendant,
+    });
+
+    put("claimant_name", Some(case.party_names(PartyRole::Claimant)));
+    put("defendant_name", Some(case.party_names(PartyRole::Defendant)));
+    put("party_name", Some(own_name.clone()));
+    put("claim_number", non_empty(inputs.claim_number.as_ref()));
+    put("court", non_empty(inputs.court.as_ref()));
+    put(
+        "claimant_address",
+        non_empty(inputs.claimant_address.as_ref()).or_else(|| case.service_addresses(PartyRole::Claimant)),
+    );
+    put(
+        "defendant_address",
+        non_empty(inputs.defendant_address.as_ref()).or_else(|| case.service_addresses(PartyRole::Defendant)),
+    );
+    put("particulars", non_empty(inputs.particulars.as_ref()));
+    put(
+        "signatory",
+        non_empty(inputs.signatory.as_ref()).or_else(|| Some(own_name.clone())),
+    );
+    put("statement_date", Some(today.format("%d/%m/%Y").to_string()));
+    put("brief_details", Some(brief_details(case, costs)));
+    put("human_rights", Some("no".to_string()));
+
+    if let Some(costs) = costs {
+        put("amount_claimed", Some(amount(costs.n1.amount_claimed)));
+        put("court_fee", Some(amount(costs.n1.court_fee)));
+        put("legal_rep_costs", Some(amount(costs.n1.legal_representative_costs)));
+        put("total_amount", Some(amount(costs.n1.total_amount)));
+    }
+
+    let track = crate::allocation::track_name(advice.track);
+    let mut track = track.to_string();
+    if let Some(first) = track.get_mut(0..1) {
+        first.make_ascii_uppercase();
+    }
+    put("track_preference", Some(track));
+    put("frc_band", advice.frc_band.map(|b| format!("Band {}", b)));
+
+    for (key, value) in &inputs.answers {
+        values.insert(key.clone(), value.clone());
+    }
+    values
+}
+
+fn is_checked(value: &str) -> bool {
+    matches!(
+        value.trim().to_ascii_lowercase().as_str(),
+        "yes" | "y" | "true" | "on" | "x" | "1"
+    )
+}
+
+/// Check the values against a form's field map
+pub fn validate(spec: &FormSpec, values: &BTreeMap<String, String>) -> Vec<FieldIssue> {
+    let mut issues = Vec::new();
+    for field in &spec.fields {
+        match values.get(&field.key) {
+            None if field.required => issues.push(FieldIssue {
+                key: field.key.clone(),
+                label: field.label.clone(),
+                problem: FieldProblem::Missing,
+                message: format!("{} is required", field.label),
+            }),
+            Some(value) => {
+                if let Some(max) = field.max_len {
+                    let len = value.chars().count();
+                    if len > max {
+                        issues.push(FieldIssue {
+                            key: field.key.clone(),
+                            label: field.label.clone(),
+                            problem: FieldProblem::TooLong,
+                            message: format!(
+                                "{} is {} characters and may not fit the box (about {}). Put it on a separate sheet and write 'see attached'.",
+                                field.label, len, max
+                            ),
+                        });
+                    }
+                }
+            }
+            None => {}
+        }
+    }
+    issues
+}
+
+/// Encode text for a PDF string: PDFDocEncoding matches Latin-1 for the
+/// characters we need (including £); anything else goes as UTF-16BE
+fn pdf_text(value: &str) -> Object {
+    if value.chars().all(|c| (c as u32) < 0x80 || (0xA0..=0xFF).contains(&(c as u32))) {
+        Object::String(value.chars().map(|c| c as u8).collect(), StringFormat::Literal)
+    } else {
+        let mut bytes = vec![0xFE, 0xFF];
+        for unit in value.encode_utf16() {
+            bytes.extend_from_slice(&unit.to_be_bytes());
+        }
+        Object::String(bytes, StringFormat::Hexadecimal)
+    }
+}
+
+fn decode_text(bytes: &[u8]) -> String {
+    if let Some(rest) = bytes.strip_prefix(&[0xFE, 0xFF]) {
+        let units: Vec<u16> = rest
+            .chunks_exact(2)
+            .map(|pair| u16::from_be_bytes([pair[0], pair[1]]))
+            .collect();
+        String::from_utf16_lossy(&units)
+    } else {
+        bytes.iter().map(|&b| b as char).collect()
+    }
+}
+
+fn acroform_fields(doc: &Document) -> Result<Vec<ObjectId>, String> {
+    let catalog = doc
+        .catalog()
+        .map_err(|e| format!("Could not read PDF catalog: {}", e))?;
+    let acroform = catalog
+        .get(b"AcroForm")
+        .map_err(|_| "This PDF has no fillable form fields".to_string())?;
+    let (_, acroform) = doc
+        .dereference(acroform)
+        .map_err(|e| format!("Could not read PDF form: {}", e))?;
+    let fields = acroform
+        .as_dict()
+        .and_then(|d| d.get(b"Fields"))
+        .and_then(Object::as_array)
+        .map_err(|e| format!("Could not read PDF form fields: {}", e))?;
+    Ok(fields.iter().filter_map(|f| f.as_reference().ok()).collect())
+}
+
+fn kids(doc: &Document, id: ObjectId) -> Vec<ObjectId> {
+    doc.get_dictionary(id)
+        .ok()
+        .and_then(|d| d.get(b"Kids").ok())
+        .and_then(|k| k.as_array().ok())
+        .map(|a| a.iter().filter_map(|k| k.as_reference().ok()).collect())
+        .unwrap_or_default()
+}
+
+fn collect_fields(doc: &Document, id: ObjectId, parent: &str, depth: usize, out: &mut Vec<(String, ObjectId)>) {
+    if depth > MAX_FIELD_DEPTH {
+        return;
+    }
+    let Ok(dict) = doc.get_dictionary(id) else {
+        return;
+    };
+    let name = dict.get(b"T").and_then(Object::as_str).ok().map(decode_text);
+    let full = match (&name, parent.is_empty()) {
+        (Some(n), true) => n.clone(),
+        (Some(n), false) => format!("{}.{}", parent, n),
+        (None, _) => parent.to_string(),
+    };
+
+    // Kids without a name are the field's widgets, not fields of their own
+    let children = kids(doc, id);
+    let named_children: Vec<ObjectId> = children
+        .into_iter()
+        .filter(|k| doc.get_dictionary(*k).map(|d| d.has(b"T")).unwrap_or(false))
+        .collect();
+
+    if named_children.is_empty() {
+        if name.is_some() {
+            out.push((full, id));
+        }
+    } else {
+        for child in named_children {
+            collect_fields(doc, child, &full, depth + 1, out);
+        }
+    }
+}
+
+fn all_fields(doc: &Document) -> Result<Vec<(String, ObjectId)>, String> {
+    let mut out = Vec::new();
+    for id in acroform_fields(doc)? {
+        collect_fields(doc, id, "", 0, &mut out);
+    }
+    Ok(out)
+}
+
+/// Names of every fillable field in a PDF, for writing or checking a field map
+pub fn list_fields(pdf: &[u8]) -> Result<Vec<String>, String> {
+    let doc = Document::load_mem(pdf).map_err(|e| format!("Could not read PDF: {}", e))?;
+    Ok(all_fields(&doc)?.into_iter().map(|(name, _)| name).collect())
+}
+
+/// A checkbox's widgets and the appearance name each uses for "on"
+fn checkbox_widgets(doc: &Document, field: ObjectId) -> Vec<(ObjectId, Vec<u8>)> {
+    let mut widgets = kids(doc, field);
+    if widgets.is_empty() {
+        widgets.push(field);
+    }
+    widgets
+        .into_iter()
+        .filter_map(|widget| {
+            let dict = doc.get_dictionary(widget).ok()?;
+            let (_, ap) = doc.dereference(dict.get(b"AP").ok()?).ok()?;
+            let (_, normal) = doc.dereference(ap.as_dict().ok()?.get(b"N").ok()?).ok()?;
+            let on = normal
+                .as_dict()
+                .ok()?
+                .iter()
+                .map(|(k, _)| k.clone())
+                .find(|k| k.as_slice() != b"Off")?;
+            Some((widget, on))
+        })
+        .collect()
+}
+
+fn set_field(doc: &mut Document, id: ObjectId, kind: FieldKind, value: &str) -> Result<(), String> {
+    let err = |e: lopdf::Error| format!("Could not set form field: {}", e);
+    match kind {
+        FieldKind::Text => {
+            doc.get_dictionary_mut(id).map_err(err)?.set("V", pdf_text(value));
+        }
+        FieldKind::Checkbox => {
+            let widgets = checkbox_widgets(doc, id);
+            let checked = is_checked(value);
+            let on = widgets
+                .first()
+                .map(|(_, on)| on.clone())
+                .unwrap_or_else(|| b"Yes".to_vec());
+            let state = if checked { on } else { b"Off".to_vec() };
+            doc.get_dictionary_mut(id)
+                .map_err(err)?
+                .set("V", Object::Name(state.clone()));
+            for (widget, widget_on) in widgets {
+                let widget_state = if checked { widget_on } else { b"Off".to_vec() };
+                doc.get_dictionary_mut(widget)
+                    .map_err(err)?
+                    .set("AS", Object::Name(widget_state));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Ask viewers to redraw the fields, since we set values without appearance streams
+fn set_need_appearances(doc: &mut Document) -> Result<(), String> {
+    let err = |e: lopdf::Error| format!("Could not update PDF form: {}", e);
+    let acroform = doc.catalog().map_err(err)?.get(b"AcroForm").map_err(err)?.clone();
+    let dict = match acroform {
+        Object::Reference(id) => doc.get_dictionary_mut(id).map_err(err)?,
+        _ => doc
+            .catalog_mut()
+            .map_err(err)?
+            .get_mut(b"AcroForm")
+            .and_then(Object::as_dict_mut)
+            .map_err(err)?,
+    };
+    dict.set("NeedAppearances", true);
+    Ok(())
+}
+
+/// Fill a blank form. Returns the completed PDF and the validation report,
+/// which also flags any mapped field the blank doesn't have.
+pub fn fill(
+    blank: &[u8],
+    spec: &FormSpec,
+    values: &BTreeMap<String, String>,
+) -> Result<(Vec<u8>, ValidationReport), String> {
+    let mut doc = Document::load_mem(blank).map_err(|e| format!("Could not read blank form: {}", e))?;
+    let fields: BTreeMap<String, ObjectId> = all_fields(&doc)?.into_iter().collect();
+
+    let mut issues = validate(spec, values);
+    issues.extend(unmatched_fields(spec, &fields.keys().cloned().collect::<Vec<_>>()));
+    let mut filled = Vec::new();
+    for field in &spec.fields {
+        let (Some(&id), Some(value)) = (fields.get(&field.pdf_field), values.get(&field.key)) else {
+            continue;
+        };
+        set_field(&mut doc, id, field.kind, value)?;
+        filled.push(field.key.clone());
+    }
+    set_need_appearances(&mut doc)?;
+
+    let mut output = Vec::new();
+    doc.save_to(&mut output)
+        .map_err(|e| format!("Could not write completed form: {}", e))?;
+
+    let ready = is_ready(&issues);
+    Ok((
+        output,
+        ValidationReport {
+            form: spec.form,
+            version: spec.version.clone(),
+            filled,
+            issues,
+            ready,
+        },
+    ))
+}
+
+/// Validate without filling, so missing details can be asked for up front.
+/// With the blank's field names the map is checked against them too; without
+/// them an unverified map can't be reported ready.
+pub fn report(
+    spec: &FormSpec,
+    values: &BTreeMap<String, String>,
+    pdf_fields: Option<&[String]>,
+) -> ValidationReport {
+    let mut issues = validate(spec, values);
+    match pdf_fields {
+        Some(names) => issues.extend(unmatched_fields(spec, names)),
+        None if !spec.verified => issues.push(FieldIssue {
+            key: String::new(),
+            label: spec.title.clone(),
+            problem: FieldProblem::Unverified,
+            message: format!(
+                "The field map for {} ({}) hasn't been checked against the blank form. Add the blank so CaseKit can check it.",
+                spec.form.code(),
+                spec.version
+            ),
+        }),
+        None => {}
+    }
+    ValidationReport {
+        form: spec.form,
+        version: spec.version.clone(),
+        filled: spec
+            .fields
+            .iter()
+            .filter(|f| values.contains_key(&f.key))
+            .map(|f| f.key.clone())
+            .collect(),
+        ready: is_ready(&issues),
+        issues,
+    }
+}
+
+/// The default file name for a completed form, e.g. "N1 2025-03-01.pdf"
+pub fn output_filename(form: FormId, today: NaiveDate, existing: &Path) -> String {
+    let base = format!("{} {}", form.code(), today.format("%Y-%m-%d"));
+    let mut name = format!("{}.pdf", base);
+    let mut n = 2;
+    while existing.join(&name).exists() {
+        name = format!("{} ({}).pdf", base, n);
+        n += 1;
+    }
+    name
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lopdf::{dictionary, Stream};
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    /// A one-page PDF with a text field, a nested text field and a checkbox
+    fn blank_form() -> Vec<u8> {
+        let mut doc = Document::with_version("1.5");
+        let pages_id = doc.new_object_id();
+        let page_id = doc.add_object(dictionary! {
+            "Type" => "Page",
+            "Parent" => pages_id,
+            "MediaBox" => vec![0.into(), 0.into(), 595.into(), 842.into()],
+        });
+        doc.objects.insert(
+            pages_id,
+            Object::Dictionary(dictionary! {
+                "Type" => "Pages",
+                "Kids" => vec![page_id.into()],
+                "Count" => 1,
+            }),
+        );
+
+        let name = doc.add_object(dictionary! {
+            "FT" => "Tx",
+            "T" => Object::string_literal("Claimant name"),
+        });
+        let amounts = doc.new_object_id();
+        let total = doc.add_object(dictionary! {
+            "FT" => "Tx",
+            "T" => Object::string_literal("Total"),
+            "Parent" => amounts,
+        });
+        doc.objects.insert(
+            amounts,
+            Object::Dictionary(dictionary! {
+                "T" => Object::string_literal("Amounts"),
+                "Kids" => vec![total.into()],
+            }),
+        );
+        let appearance = doc.add_object(Stream::new(dictionary! {}, Vec::new()));
+        let checkbox = doc.add_object(dictionary! {
+            "FT" => "Btn",
+            "T" => Object::string_literal("Stay"),
+            "AS" => "Off",
+            "AP" => dictionary! { "N" => dictionary! { "On" => appearance, "Off" => appearance } },
+        });
+
+        let acroform = doc.add_object(dictionary! {
+            "Fields" => vec![name.into(), amounts.into(), checkbox.into()],
+        });
+        let catalog = doc.add_object(dictionary! {
+            "Type" => "Catalog",
+            "Pages" => pages_id,
+            "AcroForm" => acroform,
+        });
+        doc.trailer.set("Root", catalog);
+
+        let mut bytes = Vec::new();
+        doc.save_to(&mut bytes).unwrap();
+        bytes
+    }
+
+    fn spec() -> FormSpec {
+        serde_json::from_str(
+            r#"{
+                "form": "n180", "version": "test", "effective_from": "2023-10-01",
+                "title": "Test", "pdf_file": "test.pdf", "source": "test",
+                "fields": [
+                    { "key": "claimant_name", "pdf_field": "Claimant name", "label": "Claimant", "required": true },
+                    { "key": "total_amount", "pdf_field": "Amounts.Total", "label": "Total", "required": true },
+                    { "key": "settlement_stay", "pdf_field": "Stay", "label": "Stay", "kind": "checkbox" },
+                    { "key": "claim_number", "pdf_field": "Claim number", "label": "Claim number", "required": true }
+                ]
+            }"#,
+        )
+        .unwrap()
+    }
+
+    fn value_of(doc: &Document, field: &str) -> Object {
+        let fields = all_fields(doc).unwrap();
+        let (_, id) = fields.iter().find(|(n, _)| n == field).unwrap();
+        doc.get_dictionary(*id).unwrap().get(b"V").unwrap().clone()
+    }
+
+    #[test]
+    fn bundled_maps_are_verified_against_their_blank() {
+        for json in BUNDLED_SPECS {
+            let spec = parse_spec(json).unwrap();
+            assert!(spec.verified && spec.blank_sha256.is_some(), "{} {}", spec.form.code(), spec.version);
+        }
+    }
+
+    #[test]
+    fn the_latest_map_in_force_is_used() {
+        let older = spec();
+        let newer = FormSpec { version: "newer".to_string(), effective_from: "2024-10-01".to_string(), ..spec() };
+        let specs = vec![older, newer];
+        assert_eq!(spec_for(&specs, FormId::N180, date("2025-01-01")).unwrap().version, "newer");
+        assert_eq!(spec_for(&specs, FormId::N180, date("2024-01-01")).unwrap().version, "test");
+        assert!(spec_for(&specs, FormId::N180, date("2020-01-01")).is_none());
+        assert!(spec_for(&specs, FormId::N1, date("2025-01-01")).is_none());
+    }
+
+    #[test]
+    fn lists_nested_field_names() {
+        let names = list_fields(&blank_form()).unwrap();
+        assert_eq!(names, vec!["Claimant name", "Amounts.Total", "Stay"]);
+    }
+
+    #[test]
+    fn fills_text_and_checkbox_fields() {
+        let mut values = BTreeMap::new();
+        values.insert("claimant_name".to_string(), "Jane Smith".to_string());
+        values.insert("total_amount".to_string(), "1,026.00".to_string());
+        values.insert("settlement_stay".to_string(), "yes".to_string());
+
+        let (pdf, report) = fill(&blank_form(), &spec(), &values).unwrap();
+        assert_eq!(report.filled, vec!["claimant_name", "total_amount", "settlement_stay"]);
+
+        let doc = Document::load_mem(&pdf).unwrap();
+        assert_eq!(value_of(&doc, "Claimant name").as_str().unwrap(), b"Jane Smith");
+        assert_eq!(value_of(&doc, "Amounts.Total").as_str().unwrap(), b"1,026.00");
+        assert_eq!(value_of(&doc, "Stay").as_name().unwrap(), b"On");
+    }
+
+    #[test]
+    fn reports_missing_and_unmapped_fields() {
+        let mut values = BTreeMap::new();
+        values.insert("claimant_name".to_string(), "Jane Smith".to_string());
+        let (_, report) = fill(&blank_form(), &spec(), &values).unwrap();
+
+        assert!(!report.ready);
+        let missing: Vec<&str> = report
+            .issues
+            .iter()
+            .filter(|i| i.problem == FieldProblem::Missing)
+            .map(|i| i.key.as_str())
+            .collect();
+        assert_eq!(missing, vec!["total_amount", "claim_number"]);
+        assert!(report
+            .issues
+            .iter()
+            .any(|i| i.key == "claim_number" && i.problem == FieldProblem::NotInPdf));
+    }
+
+    #[test]
+    fn a_field_missing_from_the_blank_stops_the_form_being_ready() {
+        let values = BTreeMap::from([
+            ("claimant_name".to_string(), "Jane Smith".to_string()),
+            ("total_amount".to_string(), "1,026.00".to_string()),
+            ("claim_number".to_string(), "K00AB123".to_string()),
+        ]);
+        let (_, report) = fill(&blank_form(), &spec(), &values).unwrap();
+        assert!(!report.issues.iter().any(|i| i.problem == FieldProblem::Missing));
+        assert!(!report.ready);
+    }
+
+    #[test]
+    fn unverified_maps_need_the_blank_to_be_ready() {
+        let values = BTreeMap::from([
+            ("claimant_name".to_string(), "Jane Smith".to_string()),
+            ("total_amount".to_string(), "1,026.00".to_string()),
+            ("claim_number".to_string(), "K00AB123".to_string()),
+        ]);
+        let mut spec = spec();
+
+        let without_blank = report(&spec, &values, None);
+        assert!(!without_blank.ready);
+        assert!(without_blank.issues.iter().any(|i| i.problem == FieldProblem::Unverified));
+
+        let names: Vec<String> = spec.fields.iter().map(|f| f.pdf_field.clone()).collect();
+        assert!(report(&spec, &values, Some(&names)).ready);
+        assert!(!report(&spec, &values, Some(&names[..2])).ready);
+
+        spec.verified = true;
+        assert!(report(&spec, &values, None).ready);
+    }
+
+    #[test]
+    fn blanks_are_checked_against_the_pinned_checksum() {
+        let blank = blank_form();
+        let mut spec = spec();
+
+        let unmatched = check_blank(&spec, &blank).unwrap();
+        assert_eq!(unmatched.len(), 1);
+        assert_eq!(unmatched[0].key, "claim_number");
+
+        spec.blank_sha256 = Some(sha256_hex(&blank).to_uppercase());
+        assert!(check_blank(&spec, &blank).is_ok());
+        spec.blank_sha256 = Some(sha256_hex(b"another form"));
+        assert!(check_blank(&spec, &blank).unwrap_err().contains("not the N180 (test) blank"));
+        assert!(check_blank(&spec, b"not a pdf").is_err());
+    }
+
+    #[test]
+    fn pound_signs_survive_encoding() {
+        assert_eq!(
+            pdf_text("£5"),
+            Object::String(vec![0xA3, b'5'], StringFormat::Literal)
+        );
+        let Object::String(bytes, _) = pdf_text("€5") else { panic!() };
+        assert_eq!(decode_text(&bytes), "€5");
+    }
+
+    #[test]
+    fn values_come_from_the_case_and_inputs() {
+        let case = CaseMetadata {
+            claimant_name: "Jane Smith".to_string(),
+            defendant_name: "Acme Ltd".to_string(),
+            claim_value: Money::from_pounds(850),
+            ..CaseMetadata::default()
+        };
+        let advice = crate::allocation::advise(&case);
+        let inputs = FormInputs {
+            claim_number: Some("K00AB123".to_string()),
+            answers: BTreeMap::from([("witness_count".to_string(), "2".to_string())]),
+            ..FormInputs::default()
+        };
+        let values = field_values(&case, None, &advice, &inputs, date("2025-03-01"));
+
+        assert_eq!(values["party_name"], "Jane Smith");
+        assert_eq!(values["signatory"], "Jane Smith");
+        assert_eq!(values["claim_number"], "K00AB123");
+        assert_eq!(values["track_preference"], "Small claims track");
+        assert_eq!(values["statement_date"], "01/03/2025");
+        assert_eq!(values["witness_count"], "2");
+        assert!(values["brief_details"].contains("£850.00"));
+        assert!(!values.contains_key("claimant_address"));
+    }
+}
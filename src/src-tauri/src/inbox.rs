@@ -0,0 +1,409 @@
+//! Watch-folder inboxes.
+//!
+//! A case can register an external directory — a scanner's output folder, say —
+//! as its inbox. A filesystem watcher sweeps the inbox whenever something
+//! changes in it: each new supported file is copied into the case through the
+//! same path as `copy_file_to_case`, registered as a pending `DocumentEntry` for
+//! the user to review, and queued for text extraction as a background job.
+//!
+//! Files are tracked by SHA-256 in `.casekit/inbox.json`, so the same content
+//! is never ingested twice, even if it is renamed or the app is restarted.
+
+use crate::commands::documents::{copy_into_case, folder_dir_name, with_docs_index};
+use crate::commands::import::unique_filename;
+use crate::models::document::DocumentEntry;
+use crate::models::job::JobKind;
+use crate::path_safety::safe_case_path;
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+
+/// Event emitted with the new `DocumentEntry` each time a file is ingested
+pub const INBOX_EVENT: &str = "inbox-ingested";
+
+/// Event emitted with an `InboxError` when an inbox file runs into a problem
+pub const INBOX_ERROR_EVENT: &str = "inbox-error";
+
+/// How long a file must go unmodified before it is ingested, so half-written
+/// scans and downloads are left alone until they're complete
+const SETTLE_TIME: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InboxConfig {
+    /// External directory to watch
+    pub directory: String,
+    /// Case folder ("01"–"04") for ingested files. None classifies each file by its name.
+    pub folder: Option<String>,
+    pub enabled: bool,
+    #[serde(default)]
+    pub ingested: Vec<IngestedFile>,
+}
+
+/// Record of a file already brought in from the inbox
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IngestedFile {
+    /// SHA-256 of the file contents (hex)
+    pub hash: String,
+    pub source_path: String,
+    pub relative_path: String,
+    pub document_id: String,
+    pub ingested_at: String,
+}
+
+/// A problem with one inbox file. A file that couldn't be copied in is tried
+/// again on the next sweep.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InboxError {
+    pub source_path: String,
+    pub error: String,
+}
+
+/// What a sweep brought in, and the files it had problems with
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SweepResult {
+    pub ingested: Vec<DocumentEntry>,
+    pub errors: Vec<InboxError>,
+}
+
+/// Active watchers, registered with Tauri via `manage`
+#[derive(Default)]
+pub struct InboxWatchers {
+    watchers: Mutex<HashMap<String, RecommendedWatcher>>,
+    /// Cases with a sweep already scheduled, to collapse bursts of events
+    scheduled: Mutex<HashSet<String>>,
+    /// Serialises sweeps so two can't ingest the same file
+    sweep_lock: Mutex<()>,
+}
+
+fn config_path(case_name: &str) -> Result<PathBuf, String> {
+    Ok(safe_case_path(case_name)?.join(".casekit").join("inbox.json"))
+}
+
+pub fn load_config(case_name: &str) -> Result<Option<InboxConfig>, String> {
+    let path = config_path(case_name)?;
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    let content = fs::read_to_string(&path)
+        .map_err(|e| format!("Could not read inbox.json: {}", e))?;
+    let config = serde_json::from_str(&content)
+        .map_err(|e| format!("Could not parse inbox.json: {}", e))?;
+    Ok(Some(config))
+}
+
+pub fn save_config(case_name: &str, config: &InboxConfig) -> Result<(), String> {
+    let path = config_path(case_name)?;
+    let json = serde_json::to_string_pretty(config)
+        .map_err(|e| format!("Could not serialise inbox settings: {}", e))?;
+    fs::write(&path, &json)
+        .map_err(|e| format!("Could not write inbox.json: {}", e))?;
+    Ok(())
+}
+
+/// SHA-256 of a file's contents as lowercase hex
+pub fn file_hash(path: &Path) -> Result<String, String> {
+    let bytes = fs::read(path)
+        .map_err(|e| format!("Could not read {}: {}", path.display(), e))?;
+    Ok(format!("{:x}", Sha256::digest(&bytes)))
+}
+
+/// Whether a file has stopped changing long enough to be safe to copy
+fn is_settled(path: &Path) -> bool {
+    fs::metadata(path)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|modified| modified.elapsed().ok())
+        .map(|age| age >= SETTLE_TIME)
+        .unwrap_or(true)
+}
+
+/// Ingest every new, settled file in a case's inbox. A file that can't be read
+/// or copied is reported and left for the next sweep; the rest still come in.
+pub fn sweep(app: &AppHandle, case_name: &str) -> Result<SweepResult, String> {
+    let watchers = app.state::<InboxWatchers>();
+    let _guard = watchers
+        .sweep_lock
+        .lock()
+        .map_err(|_| "Inbox lock poisoned".to_string())?;
+
+    let mut config = match load_config(case_name)? {
+        Some(c) if c.enabled => c,
+        _ => return Ok(SweepResult::default()),
+    };
+
+    let inbox_dir = PathBuf::from(&config.directory);
+    let entries = fs::read_dir(&inbox_dir)
+        .map_err(|e| format!("Could not read inbox {}: {}", config.directory, e))?;
+
+    let mut known: HashSet<String> = config.ingested.iter().map(|f| f.hash.clone()).collect();
+    let case_path = safe_case_path(case_name)?;
+    let mut result = SweepResult::default();
+    let mut unsettled = false;
+
+    let mut files: Vec<PathBuf> = entries
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.is_file())
+        .filter(|p| {
+            !p.file_name()
+                .map(|n| n.to_string_lossy().starts_with('.'))
+                .unwrap_or(true)
+        })
+        .filter(|p| crate::extraction::is_supported_file(p))
+        .collect();
+    files.sort();
+
+    for source in files {
+        if !is_settled(&source) {
+            unsettled = true;
+            continue;
+        }
+
+        let outcome = file_hash(&source).and_then(|hash| {
+            if known.contains(&hash) {
+                return Ok(None);
+            }
+            let doc = ingest_file(case_name, &case_path, &mut config, &source, hash.clone())?;
+            known.insert(hash);
+            Ok(Some(doc))
+        });
+
+        let error = match outcome {
+            Ok(Some(doc)) => {
+                // Extraction (and any OCR) runs in the background and fills in extracted_text
+                let queued = crate::jobs::enqueue(
+                    app,
+                    case_name.to_string(),
+                    JobKind::Extraction {
+                        relative_path: doc.path.clone(),
+                        document_id: Some(doc.id.clone()),
+                        ocr_options: None,
+                    },
+                );
+                let _ = app.emit(INBOX_EVENT, &doc);
+                result.ingested.push(doc);
+                match queued {
+                    Ok(_) => continue,
+                    Err(e) => format!("Added to the case, but its text extraction could not be queued: {}", e),
+                }
+            }
+            Ok(None) => continue,
+            Err(e) => e,
+        };
+
+        let error = InboxError {
+            source_path: source.to_string_lossy().to_string(),
+            error,
+        };
+        let _ = app.emit(INBOX_ERROR_EVENT, &error);
+        result.errors.push(error);
+    }
+
+    if unsettled {
+        schedule_sweep(app, case_name);
+    }
+
+    Ok(result)
+}
+
+/// Case folder for ingested files. Only "01"–"04": 05_Bundle holds
+/// generated bundles, not incoming documents.
+pub(crate) fn inbox_folder_dir(folder: &str) -> Result<&'static str, String> {
+    if folder == "05" {
+        return Err("Inbox files can't go in 05_Bundle; choose folder 01 to 04".to_string());
+    }
+    folder_dir_name(folder)
+}
+
+/// Copy one inbox file into the case and register it for review
+fn ingest_file(
+    case_name: &str,
+    case_path: &Path,
+    config: &mut InboxConfig,
+    source: &Path,
+    hash: String,
+) -> Result<DocumentEntry, String> {
+    let original_name = source
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let classification = crate::classifier::classify(&original_name, None);
+    let folder = config.folder.clone().unwrap_or(classification.folder);
+
+    let dest_dir = case_path.join(inbox_folder_dir(&folder)?);
+    let filename = unique_filename(&dest_dir, &original_name);
+    let (_, relative_path) = copy_into_case(case_name, source, &folder, &filename)?;
+
+    let doc = DocumentEntry {
+        filename,
+        path: relative_path.clone(),
+        folder,
+        document_type: classification.document_type,
+        tags: vec!["inbox".to_string()],
+        pending_review: true,
+        ..DocumentEntry::default()
+    };
+
+    with_docs_index(case_name, |docs| {
+        docs.push(doc.clone());
+        Ok(())
+    })?;
+
+    config.ingested.push(IngestedFile {
+        hash,
+        source_path: source.to_string_lossy().to_string(),
+        relative_path,
+        document_id: doc.id.clone(),
+        ingested_at: chrono::Utc::now().to_rfc3339(),
+    });
+    save_config(case_name, config)?;
+    Ok(doc)
+}
+
+/// Sweep a case's inbox shortly, unless a sweep is already pending
+pub fn schedule_sweep(app: &AppHandle, case_name: &str) {
+    {
+        let watchers = app.state::<InboxWatchers>();
+        let mut scheduled = watchers.scheduled.lock().unwrap_or_else(|e| e.into_inner());
+        if !scheduled.insert(case_name.to_string()) {
+            return;
+        }
+    }
+
+    let app = app.clone();
+    let case_name = case_name.to_string();
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(SETTLE_TIME).await;
+        app.state::<InboxWatchers>()
+            .scheduled
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(&case_name);
+
+        let _ = tauri::async_runtime::spawn_blocking(move || sweep(&app, &case_name)).await;
+    });
+}
+
+/// Start (or restart) the watcher for a case's inbox
+pub fn watch(app: &AppHandle, case_name: &str, directory: &Path) -> Result<(), String> {
+    let handler_app = app.clone();
+    let handler_case = case_name.to_string();
+    let mut watcher = notify::recommended_watcher(move |res: notify::Result<notify::Event>| {
+        if let Ok(event) = res {
+            if matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_)) {
+                schedule_sweep(&handler_app, &handler_case);
+            }
+        }
+    })
+    .map_err(|e| format!("Could not create folder watcher: {}", e))?;
+
+    watcher
+        .watch(directory, RecursiveMode::NonRecursive)
+        .map_err(|e| format!("Could not watch {}: {}", directory.display(), e))?;
+
+    let watchers = app.state::<InboxWatchers>();
+    let mut map = watchers.watchers.lock().unwrap_or_else(|e| e.into_inner());
+    map.insert(case_name.to_string(), watcher);
+    Ok(())
+}
+
+/// Stop watching a case's inbox (dropping the watcher ends it)
+pub fn unwatch(app: &AppHandle, case_name: &str) {
+    let watchers = app.state::<InboxWatchers>();
+    let mut map = watchers.watchers.lock().unwrap_or_else(|e| e.into_inner());
+    map.remove(case_name);
+}
+
+/// Start watchers for every case with an enabled inbox, and pick up anything
+/// that arrived while the app was closed
+pub fn start_all(app: &AppHandle) {
+    let base = match crate::path_safety::casekit_base() {
+        Ok(b) if b.exists() => b,
+        _ => return,
+    };
+    let entries = match fs::read_dir(&base) {
+        Ok(e) => e,
+        Err(_) => return,
+    };
+
+    for entry in entries.flatten() {
+        if !entry.path().join(".casekit").join("inbox.json").exists() {
+            continue;
+        }
+        let case_name = entry.file_name().to_string_lossy().to_string();
+        if let Ok(Some(config)) = load_config(&case_name) {
+            if config.enabled && watch(app, &case_name, Path::new(&config.directory)).is_ok() {
+                schedule_sweep(app, &case_name);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn case_with_inbox() -> (String, PathBuf, InboxConfig) {
+        let case_name = format!("inbox-{}", uuid::Uuid::new_v4());
+        crate::commands::case::create_case(case_name.clone(), "A".into(), "B".into(), "claimant".into()).unwrap();
+        let inbox = std::env::temp_dir().join(format!("{}-scans", case_name));
+        fs::create_dir_all(&inbox).unwrap();
+        let config = InboxConfig {
+            directory: inbox.to_string_lossy().to_string(),
+            folder: Some("02".to_string()),
+            enabled: true,
+            ingested: Vec::new(),
+        };
+        (case_name, inbox, config)
+    }
+
+    #[test]
+    fn ingests_a_file_as_a_pending_document() {
+        let (case_name, inbox, mut config) = case_with_inbox();
+        let source = inbox.join("receipt.txt");
+        fs::write(&source, "Paid £40").unwrap();
+        let case_path = safe_case_path(&case_name).unwrap();
+
+        let doc = ingest_file(&case_name, &case_path, &mut config, &source, file_hash(&source).unwrap()).unwrap();
+        assert_eq!(doc.path, "02_Evidence/receipt.txt");
+        assert!(doc.pending_review);
+        assert!(case_path.join("02_Evidence/receipt.txt").exists());
+        assert_eq!(config.ingested.len(), 1);
+        assert_eq!(crate::commands::documents::load_docs_index(&case_name).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn a_file_that_cannot_be_copied_is_not_recorded() {
+        let (case_name, inbox, mut config) = case_with_inbox();
+        let case_path = safe_case_path(&case_name).unwrap();
+
+        // Removed from the inbox between the listing and the copy
+        let gone = inbox.join("gone.pdf");
+        let result = ingest_file(&case_name, &case_path, &mut config, &gone, "abc".to_string());
+        assert!(result.unwrap_err().contains("Could not copy file"));
+        assert!(config.ingested.is_empty());
+        assert!(crate::commands::documents::load_docs_index(&case_name).unwrap().is_empty());
+    }
+
+    #[test]
+    fn files_are_never_ingested_into_the_bundle_folder() {
+        assert_eq!(inbox_folder_dir("04"), Ok("04_Court"));
+        assert!(inbox_folder_dir("05").unwrap_err().contains("05_Bundle"));
+        assert!(inbox_folder_dir("06").is_err());
+
+        let (case_name, inbox, mut config) = case_with_inbox();
+        config.folder = Some("05".to_string());
+        let source = inbox.join("receipt.txt");
+        fs::write(&source, "Paid £40").unwrap();
+        let case_path = safe_case_path(&case_name).unwrap();
+        assert!(ingest_file(&case_name, &case_path, &mut config, &source, file_hash(&source).unwrap()).is_err());
+        assert!(!case_path.join("05_Bundle/receipt.txt").exists());
+    }
+}
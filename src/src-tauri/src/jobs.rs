@@ -0,0 +1,583 @@
+//! Background job queue for slow per-case work: text extraction, OCR,
+//! date scanning and citation audits.
+//!
+//! Jobs are written to `.casekit/jobs.json` as soon as they are queued, so the
+//! queue survives a restart — on startup anything still queued, or left running
+//! when the app closed, is picked up again. Work runs on the async runtime with
+//! a small worker limit, and every state change is emitted to the UI as a
+//! `job-progress` event carrying the updated `Job`.
+//!
+//! Cancelling a queued job stops it before it starts. Extraction and OCR can't
+//! be interrupted part-way through a file, so cancelling a running job marks it
+//! cancelled immediately and its output is discarded when the work finishes.
+
+use crate::commands::documents::with_docs_index;
+use crate::extraction::ExtractedContent;
+use crate::models::job::{Job, JobKind, JobStatus};
+use crate::path_safety::{safe_case_path, validate_relative_path};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::sync::Semaphore;
+
+/// Event emitted whenever a job changes state or reports progress
+pub const JOB_EVENT: &str = "job-progress";
+
+/// Shared job state, registered with Tauri via `manage`
+#[derive(Clone)]
+pub struct JobManager {
+    workers: Arc<Semaphore>,
+    cancel_flags: Arc<Mutex<HashMap<String, Arc<AtomicBool>>>>,
+    /// Serialises read-modify-write cycles on jobs.json
+    store_lock: Arc<Mutex<()>>,
+}
+
+impl Default for JobManager {
+    fn default() -> Self {
+        // Extraction and OCR are CPU-heavy; leave headroom for the UI
+        let workers = std::thread::available_parallelism()
+            .map(|n| n.get())
+            .unwrap_or(2)
+            .clamp(1, 4);
+        Self {
+            workers: Arc::new(Semaphore::new(workers)),
+            cancel_flags: Arc::new(Mutex::new(HashMap::new())),
+            store_lock: Arc::new(Mutex::new(())),
+        }
+    }
+}
+
+fn jobs_path(case_name: &str) -> Result<PathBuf, String> {
+    Ok(safe_case_path(case_name)?.join(".casekit").join("jobs.json"))
+}
+
+pub fn load_jobs(case_name: &str) -> Result<Vec<Job>, String> {
+    let path = jobs_path(case_name)?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let content = fs::read_to_string(&path)
+        .map_err(|e| format!("Could not read jobs.json: {}", e))?;
+    serde_json::from_str(&content)
+        .map_err(|e| format!("Could not parse jobs.json: {}", e))
+}
+
+fn save_jobs(case_name: &str, jobs: &[Job]) -> Result<(), String> {
+    let path = jobs_path(case_name)?;
+    let json = serde_json::to_string_pretty(jobs)
+        .map_err(|e| format!("Could not serialise job queue: {}", e))?;
+    // Written beside the queue and moved into place, so list_jobs never
+    // reads a half-written file
+    let temp = path.with_extension("json.tmp");
+    fs::write(&temp, &json)
+        .map_err(|e| format!("Could not write jobs.json: {}", e))?;
+    fs::rename(&temp, &path)
+        .map_err(|e| format!("Could not write jobs.json: {}", e))?;
+    Ok(())
+}
+
+impl JobManager {
+    /// Load jobs.json, apply `f` to every job in it, and save it back
+    fn modify<T>(
+        &self,
+        case_name: &str,
+        f: impl FnOnce(&mut Vec<Job>) -> Result<T, String>,
+    ) -> Result<T, String> {
+        let _guard = self.store_lock.lock().map_err(|_| "Job store lock poisoned".to_string())?;
+        let mut jobs = load_jobs(case_name)?;
+        let out = f(&mut jobs)?;
+        save_jobs(case_name, &jobs)?;
+        Ok(out)
+    }
+
+    /// Apply `f` to a single job and return the updated copy
+    fn update(&self, case_name: &str, job_id: &str, f: impl FnOnce(&mut Job)) -> Result<Job, String> {
+        self.modify(case_name, |jobs| {
+            let job = jobs
+                .iter_mut()
+                .find(|j| j.id == job_id)
+                .ok_or_else(|| format!("Job '{}' not found", job_id))?;
+            f(job);
+            Ok(job.clone())
+        })
+    }
+
+    fn cancel_flag(&self, job_id: &str) -> Arc<AtomicBool> {
+        let mut flags = self.cancel_flags.lock().unwrap_or_else(|e| e.into_inner());
+        flags
+            .entry(job_id.to_string())
+            .or_insert_with(|| Arc::new(AtomicBool::new(false)))
+            .clone()
+    }
+
+    /// Drop a job's cancel flag, unless a retry has already replaced it
+    fn forget_flag(&self, job_id: &str, flag: &Arc<AtomicBool>) {
+        let mut flags = self.cancel_flags.lock().unwrap_or_else(|e| e.into_inner());
+        if flags.get(job_id).map(|f| Arc::ptr_eq(f, flag)).unwrap_or(false) {
+            flags.remove(job_id);
+        }
+    }
+
+    /// Give a retried job a fresh flag; a cancelled earlier run keeps its own
+    fn reset_flag(&self, job_id: &str) {
+        let mut flags = self.cancel_flags.lock().unwrap_or_else(|e| e.into_inner());
+        flags.insert(job_id.to_string(), Arc::new(AtomicBool::new(false)));
+    }
+
+    /// Remove completed, failed and cancelled jobs from the case's queue
+    pub fn clear_finished(&self, case_name: &str) -> Result<Vec<Job>, String> {
+        self.modify(case_name, |jobs| {
+            jobs.retain(|j| !j.status.is_finished());
+            Ok(jobs.clone())
+        })
+    }
+}
+
+/// The job manager together with how job updates reach the UI. Cloned into
+/// each spawned job.
+#[derive(Clone)]
+struct Runner {
+    manager: JobManager,
+    notify: Arc<dyn Fn(&Job) + Send + Sync>,
+}
+
+impl Runner {
+    fn for_app(app: &AppHandle) -> Self {
+        let app = app.clone();
+        Self {
+            manager: app.state::<JobManager>().inner().clone(),
+            notify: Arc::new(move |job: &Job| {
+                let _ = app.emit(JOB_EVENT, job);
+            }),
+        }
+    }
+
+    fn emit(&self, job: &Job) {
+        (self.notify)(job);
+    }
+
+    fn enqueue(&self, case_name: String, kind: JobKind) -> Result<Job, String> {
+        let case_path = safe_case_path(&case_name)?;
+        if !case_path.join(".casekit").exists() {
+            return Err(format!("Case '{}' not found", case_name));
+        }
+
+        let job = Job::new(case_name.clone(), kind);
+        self.manager.modify(&case_name, |jobs| {
+            jobs.push(job.clone());
+            Ok(())
+        })?;
+
+        self.emit(&job);
+        self.spawn(&job);
+        Ok(job)
+    }
+
+    fn cancel(&self, case_name: &str, job_id: &str) -> Result<Job, String> {
+        let flag = self.manager.cancel_flag(job_id);
+        flag.store(true, Ordering::SeqCst);
+
+        let mut already_finished = false;
+        let job = self.manager.update(case_name, job_id, |job| {
+            if job.status.is_finished() {
+                already_finished = true;
+                return;
+            }
+            job.status = JobStatus::Cancelled;
+            job.message = Some("Cancelled".to_string());
+            job.finished_at = Some(chrono::Utc::now().to_rfc3339());
+        })?;
+
+        if already_finished {
+            self.manager.forget_flag(job_id, &flag);
+            return Err("Job has already finished".to_string());
+        }
+
+        self.emit(&job);
+        Ok(job)
+    }
+
+    fn retry(&self, case_name: &str, job_id: &str) -> Result<Job, String> {
+        let mut retryable = true;
+        let job = self.manager.update(case_name, job_id, |job| {
+            if !matches!(job.status, JobStatus::Failed | JobStatus::Cancelled) {
+                retryable = false;
+                return;
+            }
+            job.status = JobStatus::Queued;
+            job.progress = 0.0;
+            job.message = None;
+            job.result = None;
+            job.error = None;
+            job.started_at = None;
+            job.finished_at = None;
+        })?;
+
+        if !retryable {
+            return Err("Only failed or cancelled jobs can be retried".to_string());
+        }
+
+        self.manager.reset_flag(job_id);
+        self.emit(&job);
+        self.spawn(&job);
+        Ok(job)
+    }
+
+    fn spawn(&self, job: &Job) {
+        let runner = self.clone();
+        let case_name = job.case_name.clone();
+        let job_id = job.id.clone();
+        let cancelled = self.manager.cancel_flag(&job_id);
+
+        tauri::async_runtime::spawn(async move {
+            runner.run(&case_name, &job_id, cancelled.clone()).await;
+            runner.manager.forget_flag(&job_id, &cancelled);
+        });
+    }
+
+    async fn run(&self, case_name: &str, job_id: &str, cancelled: Arc<AtomicBool>) {
+        let _permit = match self.manager.workers.clone().acquire_owned().await {
+            Ok(p) => p,
+            Err(_) => return,
+        };
+
+        if cancelled.load(Ordering::SeqCst) {
+            return;
+        }
+
+        let job = match self.manager.update(case_name, job_id, |job| {
+            job.status = JobStatus::Running;
+            job.attempts += 1;
+            job.progress = 0.0;
+            job.started_at = Some(chrono::Utc::now().to_rfc3339());
+        }) {
+            Ok(j) => j,
+            Err(_) => return,
+        };
+        self.emit(&job);
+
+        let outcome = self.execute(&job, &cancelled).await;
+
+        let finished = self.manager.update(case_name, job_id, |job| {
+            // A cancelled job keeps its status; its output is dropped
+            if cancelled.load(Ordering::SeqCst) {
+                return;
+            }
+            match outcome {
+                Ok(value) => {
+                    job.status = JobStatus::Completed;
+                    job.progress = 1.0;
+                    job.message = None;
+                    job.result = Some(value);
+                }
+                Err(e) => {
+                    job.status = JobStatus::Failed;
+                    job.error = Some(e);
+                }
+            }
+            job.finished_at = Some(chrono::Utc::now().to_rfc3339());
+        });
+
+        if let Ok(job) = finished {
+            self.emit(&job);
+        }
+    }
+
+    /// Record progress on a running job and notify the UI
+    fn report(&self, job: &Job, progress: f32, message: String) {
+        if let Ok(updated) = self.manager.update(&job.case_name, &job.id, |j| {
+            if j.status == JobStatus::Running {
+                j.progress = progress;
+                j.message = Some(message);
+            }
+        }) {
+            self.emit(&updated);
+        }
+    }
+
+    async fn execute(&self, job: &Job, cancelled: &Arc<AtomicBool>) -> Result<serde_json::Value, String> {
+        let case_name = job.case_name.clone();
+
+        match job.kind.clone() {
+            JobKind::Extraction { relative_path, document_id, ocr_options }
+            | JobKind::Ocr { relative_path, document_id, ocr_options } => {
+                let force_ocr = matches!(job.kind, JobKind::Ocr { .. });
+                self.report(job, 0.0, format!("Extracting text from {}", relative_path));
+
+                let file_path = resolve_case_file(&case_name, &relative_path)?;
+                let options = ocr_options.unwrap_or_default();
+                let extracted = blocking(move || {
+                    if force_ocr {
+                        force_ocr_file(&file_path, &options)
+                    } else {
+                        crate::extraction::extract_from_file_with_options(&file_path, &options)
+                    }
+                })
+                .await?;
+
+                if !cancelled.load(Ordering::SeqCst) {
+                    store_extracted_text(&case_name, document_id.as_deref(), &relative_path, &extracted)?;
+                }
+                to_json(&extracted)
+            }
+            JobKind::DateScan => {
+                self.report(job, 0.0, "Scanning documents for dates".to_string());
+                let candidates =
+                    blocking(move || crate::commands::chronology::scan_documents_for_dates(case_name)).await?;
+                to_json(&candidates)
+            }
+            JobKind::CitationAudit { relative_path } => {
+                self.report(job, 0.0, format!("Extracting text from {}", relative_path));
+                let file_path = resolve_case_file(&case_name, &relative_path)?;
+                let text = blocking(move || crate::extraction::extract_from_file(&file_path))
+                    .await?
+                    .text;
+
+                let citations = crate::commands::citation::find_neutral_citations(&text);
+                let mut resolutions = Vec::new();
+                for (i, citation) in citations.iter().enumerate() {
+                    if cancelled.load(Ordering::SeqCst) {
+                        break;
+                    }
+                    self.report(
+                        job,
+                        i as f32 / citations.len() as f32,
+                        format!("Resolving {} of {} citations", i + 1, citations.len()),
+                    );
+                    resolutions.push(
+                        crate::commands::citation::resolve_citation(citation.clone(), None).await?,
+                    );
+                }
+                to_json(&resolutions)
+            }
+        }
+    }
+}
+
+/// Queue a new job for a case and start it as soon as a worker is free
+pub fn enqueue(app: &AppHandle, case_name: String, kind: JobKind) -> Result<Job, String> {
+    Runner::for_app(app).enqueue(case_name, kind)
+}
+
+/// Cancel a queued or running job
+pub fn cancel(app: &AppHandle, case_name: &str, job_id: &str) -> Result<Job, String> {
+    Runner::for_app(app).cancel(case_name, job_id)
+}
+
+/// Re-queue a failed or cancelled job
+pub fn retry(app: &AppHandle, case_name: &str, job_id: &str) -> Result<Job, String> {
+    Runner::for_app(app).retry(case_name, job_id)
+}
+
+/// Re-queue work left over from the previous session. Jobs that were running
+/// when the app closed are restarted from the beginning.
+pub fn resume_pending(app: &AppHandle) {
+    let base = match crate::path_safety::casekit_base() {
+        Ok(b) if b.exists() => b,
+        _ => return,
+    };
+    let entries = match fs::read_dir(&base) {
+        Ok(e) => e,
+        Err(_) => return,
+    };
+
+    let runner = Runner::for_app(app);
+    for entry in entries.flatten() {
+        if !entry.path().join(".casekit").join("jobs.json").exists() {
+            continue;
+        }
+        let case_name = entry.file_name().to_string_lossy().to_string();
+
+        let pending = runner.manager.modify(&case_name, |jobs| {
+            for job in jobs.iter_mut().filter(|j| j.status == JobStatus::Running) {
+                job.status = JobStatus::Queued;
+                job.message = Some("Restarted after CaseKit was closed".to_string());
+            }
+            Ok(jobs
+                .iter()
+                .filter(|j| j.status == JobStatus::Queued)
+                .cloned()
+                .collect::<Vec<_>>())
+        });
+
+        for job in pending.unwrap_or_default() {
+            runner.spawn(&job);
+        }
+    }
+}
+
+/// Run blocking work off the async runtime
+async fn blocking<T: Send + 'static>(
+    f: impl FnOnce() -> Result<T, String> + Send + 'static,
+) -> Result<T, String> {
+    tauri::async_runtime::spawn_blocking(f)
+        .await
+        .map_err(|e| format!("Job worker stopped unexpectedly: {}", e))?
+}
+
+fn resolve_case_file(case_name: &str, relative_path: &str) -> Result<PathBuf, String> {
+    let case_path = safe_case_path(case_name)?;
+    let file_path = validate_relative_path(&case_path, relative_path)?;
+    if !file_path.exists() {
+        return Err(format!("File not found: {}", relative_path));
+    }
+    Ok(file_path)
+}
+
+/// Save extracted text, and any sent date from the file's headers, onto the
+/// matching document entry — by id if given, otherwise the entry whose path
+/// matches the extracted file
+fn store_extracted_text(
+    case_name: &str,
+    document_id: Option<&str>,
+    relative_path: &str,
+    extracted: &ExtractedContent,
+) -> Result<(), String> {
+    with_docs_index(case_name, |docs| {
+        let target = docs.iter_mut().find(|d| match document_id {
+            Some(id) => d.id == id,
+            None => d.path == relative_path,
+        });
+        if let Some(doc) = target {
+            doc.extracted_text = Some(extracted.text.clone());
+            if extracted.metadata_date.is_some() {
+                doc.metadata_date = extracted.metadata_date.clone();
+            }
+        }
+        Ok(())
+    })?;
+    Ok(())
+}
+
+fn to_json<T: serde::Serialize>(value: &T) -> Result<serde_json::Value, String> {
+    serde_json::to_value(value).map_err(|e| format!("Could not serialise job result: {}", e))
+}
+
+/// OCR a PDF or image regardless of whether it already has a text layer
+fn force_ocr_file(
+    path: &std::path::Path,
+    options: &crate::ocr::OcrOptions,
+) -> Result<ExtractedContent, String> {
+    let ext = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("")
+        .to_lowercase();
+
+    let result = match ext.as_str() {
+        "pdf" => crate::ocr::ocr_scanned_pdf(path, options)?,
+        "jpg" | "jpeg" | "png" | "bmp" | "tiff" | "tif" | "gif" => {
+            crate::ocr::ocr_image_file(path, options)?
+        }
+        _ => return Err(format!("OCR is not supported for .{} files", ext)),
+    };
+    Ok(ExtractedContent::from_ocr(result))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::documents::{load_docs_index, save_documents_index};
+    use crate::models::document::DocumentEntry;
+    use std::time::Duration;
+
+    /// A runner that records every update it would send to the UI
+    fn recording_runner() -> (Runner, Arc<Mutex<Vec<Job>>>) {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let sink = events.clone();
+        let runner = Runner {
+            manager: JobManager::default(),
+            notify: Arc::new(move |job: &Job| sink.lock().unwrap().push(job.clone())),
+        };
+        (runner, events)
+    }
+
+    fn new_case(files: &[(&str, &str)]) -> String {
+        let name = format!("jobs-{}", uuid::Uuid::new_v4());
+        crate::commands::case::create_case(name.clone(), "A".into(), "B".into(), "claimant".into()).unwrap();
+        let case_path = safe_case_path(&name).unwrap();
+        let mut docs = Vec::new();
+        for (path, text) in files {
+            fs::write(case_path.join(path), text).unwrap();
+            docs.push(DocumentEntry { path: path.to_string(), ..Default::default() });
+        }
+        save_documents_index(&name, &docs).unwrap();
+        name
+    }
+
+    async fn wait_until_finished(case_name: &str, job_id: &str) -> Job {
+        for _ in 0..500 {
+            let job = load_jobs(case_name).unwrap().into_iter().find(|j| j.id == job_id).unwrap();
+            if job.status.is_finished() {
+                return job;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        panic!("job {} did not finish", job_id);
+    }
+
+    fn extraction(relative_path: &str) -> JobKind {
+        JobKind::Extraction { relative_path: relative_path.to_string(), document_id: None, ocr_options: None }
+    }
+
+    #[test]
+    fn extraction_job_reports_progress_and_stores_text() {
+        let case_name = new_case(&[("02_Evidence/letter.txt", "Payment is due by 1 March 2024.")]);
+        let (runner, events) = recording_runner();
+
+        tauri::async_runtime::block_on(async {
+            let job = runner.enqueue(case_name.clone(), extraction("02_Evidence/letter.txt")).unwrap();
+            assert_eq!(job.status, JobStatus::Queued);
+            assert!(load_jobs(&case_name).unwrap().iter().any(|j| j.id == job.id));
+
+            let finished = wait_until_finished(&case_name, &job.id).await;
+            assert_eq!(finished.status, JobStatus::Completed);
+            assert_eq!(finished.progress, 1.0);
+            assert_eq!(finished.attempts, 1);
+            let result = finished.result.unwrap();
+            assert!(result["text"].as_str().unwrap().contains("1 March 2024"));
+        });
+
+        let docs = load_docs_index(&case_name).unwrap();
+        assert!(docs[0].extracted_text.as_deref().unwrap().contains("Payment is due"));
+
+        let events = events.lock().unwrap();
+        assert_eq!(events.first().unwrap().status, JobStatus::Queued);
+        assert!(events.iter().any(|j| j.status == JobStatus::Running
+            && j.message.as_deref() == Some("Extracting text from 02_Evidence/letter.txt")));
+        assert_eq!(events.last().unwrap().status, JobStatus::Completed);
+    }
+
+    #[test]
+    fn failed_job_keeps_its_error_and_can_be_retried() {
+        let case_name = new_case(&[]);
+        let (runner, _) = recording_runner();
+
+        tauri::async_runtime::block_on(async {
+            let job = runner.enqueue(case_name.clone(), extraction("02_Evidence/late.txt")).unwrap();
+            let failed = wait_until_finished(&case_name, &job.id).await;
+            assert_eq!(failed.status, JobStatus::Failed);
+            assert!(failed.error.unwrap().contains("File not found"));
+
+            let case_path = safe_case_path(&case_name).unwrap();
+            fs::write(case_path.join("02_Evidence/late.txt"), "Arrived late").unwrap();
+            runner.retry(&case_name, &job.id).unwrap();
+            let retried = wait_until_finished(&case_name, &job.id).await;
+            assert_eq!(retried.status, JobStatus::Completed);
+            assert_eq!(retried.attempts, 2);
+            assert!(retried.error.is_none());
+        });
+    }
+
+    #[test]
+    fn jobs_are_only_queued_for_existing_cases() {
+        let (runner, events) = recording_runner();
+        let missing = format!("jobs-missing-{}", uuid::Uuid::new_v4());
+        assert!(runner.enqueue(missing, JobKind::DateScan).is_err());
+        assert!(events.lock().unwrap().is_empty());
+    }
+}
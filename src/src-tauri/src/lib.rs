@@ -3,15 +3,52 @@ mod models;
 pub mod path_safety;
 pub mod extraction;
 pub mod date_scanner;
+pub mod event_classifier;
 pub mod ocr;
+pub mod settings;
+pub mod jobs;
+pub mod classifier;
+pub mod inbox;
+pub mod bank_holidays;
+pub mod deadlines;
+pub mod limitation;
+pub mod pre_action;
+pub mod fees;
+pub mod allocation;
+pub mod remedies;
+pub mod forms;
+pub mod drafting;
+pub mod chronology_export;
+pub mod chronology_check;
+pub mod parties;
+pub mod companies_house;
+pub mod redaction;
+pub mod pdf_redaction;
+pub mod ai;
+pub mod source_check;
 
 use commands::case::{create_case, list_cases, load_case, update_case, delete_case};
-use commands::documents::{add_document_metadata, copy_file_to_case, list_case_files, load_documents_index, remove_document_metadata, read_file_text, extract_text_from_path};
-use commands::chronology::{build_chronology, add_chronology_entry, remove_chronology_entry, update_chronology_entry, scan_documents_for_dates};
-use commands::export::export_bundle;
+use commands::documents::{add_document_metadata, copy_file_to_case, list_case_files, load_documents_index, remove_document_metadata, update_document_metadata, read_file_text, extract_text_from_path};
+use commands::chronology::{build_chronology, add_chronology_entry, remove_chronology_entry, update_chronology_entry, scan_documents_for_dates, check_chronology};
+use commands::deadlines::compute_deadlines;
+use commands::limitation::check_limitation;
+use commands::companies_house::{search_companies, check_party_company, has_companies_house_key, set_companies_house_key, remove_companies_house_key};
+use commands::parties::{list_parties, link_document_parties, list_party_documents};
+use commands::allocation::advise_track;
+use commands::remedies::assess_remedies;
+use commands::drafting::generate_draft;
+use commands::forms::{list_court_forms, check_court_form, generate_court_form, install_court_form_blank, list_form_fields};
+use commands::fees::{calculate_claim_costs, list_fee_tables};
+use commands::pre_action::{check_pre_action, add_pre_action_entries};
+use commands::export::{export_bundle, export_chronology};
+use commands::import::{plan_bulk_import, commit_bulk_import};
+use commands::inbox::{get_case_inbox, set_case_inbox, disable_case_inbox, sweep_case_inbox};
 use commands::filesystem::{get_base_path, ensure_base_directory};
-use commands::system::check_dependencies;
+use commands::system::{check_dependencies, load_app_settings, save_app_settings};
 use commands::ai_history::{save_ai_call, load_ai_history};
+use commands::ai::{preview_ai_request, send_ai_request, list_ai_keys, set_ai_key, remove_ai_key, test_ai_connection, check_ai_response};
+use commands::redaction::{redact_texts, restore_redacted_text, redact_pdf};
+use commands::jobs::{enqueue_job, list_jobs, cancel_job, retry_job, clear_finished_jobs};
 use commands::citation::{
     check_urls_exist, resolve_citation, search_bailii_cases, search_fcl_cases,
     fetch_judgment, save_authority, load_authorities, remove_authority,
@@ -23,6 +60,13 @@ pub fn run() {
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_fs::init())
+        .manage(jobs::JobManager::default())
+        .manage(inbox::InboxWatchers::default())
+        .setup(|app| {
+            jobs::resume_pending(app.handle());
+            inbox::start_all(app.handle());
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
             get_base_path,
             ensure_base_directory,
@@ -31,22 +75,71 @@ pub fn run() {
             load_case,
             update_case,
             delete_case,
+            list_parties,
+            link_document_parties,
+            list_party_documents,
+            search_companies,
+            check_party_company,
+            has_companies_house_key,
+            set_companies_house_key,
+            remove_companies_house_key,
             copy_file_to_case,
             list_case_files,
             read_file_text,
             load_documents_index,
             add_document_metadata,
             remove_document_metadata,
+            update_document_metadata,
             extract_text_from_path,
+            plan_bulk_import,
+            commit_bulk_import,
+            get_case_inbox,
+            set_case_inbox,
+            disable_case_inbox,
+            sweep_case_inbox,
             build_chronology,
             add_chronology_entry,
             remove_chronology_entry,
             update_chronology_entry,
             scan_documents_for_dates,
+            check_chronology,
+            compute_deadlines,
+            check_limitation,
+            check_pre_action,
+            add_pre_action_entries,
+            calculate_claim_costs,
+            list_fee_tables,
+            advise_track,
+            assess_remedies,
+            list_court_forms,
+            check_court_form,
+            generate_court_form,
+            install_court_form_blank,
+            list_form_fields,
+            generate_draft,
+            export_chronology,
             export_bundle,
             check_dependencies,
+            load_app_settings,
+            save_app_settings,
             save_ai_call,
             load_ai_history,
+            preview_ai_request,
+            send_ai_request,
+            list_ai_keys,
+            set_ai_key,
+            remove_ai_key,
+            test_ai_connection,
+            check_ai_response,
+            redact_texts,
+            restore_redacted_text,
+            redact_pdf,
+            // Background jobs
+            enqueue_job,
+            list_jobs,
+            cancel_job,
+            retry_job,
+            clear_finished_jobs,
             // Citation resolution & legal research
             check_urls_exist,
             resolve_citation,
@@ -0,0 +1,329 @@
+//! Limitation Act 1980 checks.
+//!
+//! Works out when the limitation period for the claim is likely to expire from
+//! the dates on the case, so the user is warned well before the claim becomes
+//! time-barred. The Act is full of exceptions (fraud, concealment, disability,
+//! acknowledgments), so every report lists the assumptions it relied on.
+
+use crate::models::case::{CaseMetadata, ClaimType};
+use chrono::{Months, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+/// Within this many days of expiry the report is amber
+const WARNING_DAYS: i64 = 180;
+
+/// Within this many days of expiry (or past it) the report is a red flag
+const RED_FLAG_DAYS: i64 = 60;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LimitationBasis {
+    /// s.5 — simple contract, 6 years from breach
+    #[serde(rename = "contract")]
+    Contract,
+    /// s.2 — tort, 6 years from damage
+    #[serde(rename = "tort")]
+    Tort,
+    /// s.14A — negligence causing latent damage, 3 years from knowledge
+    #[serde(rename = "latent_damage")]
+    LatentDamage,
+    /// s.11 — personal injury, 3 years from injury or knowledge
+    #[serde(rename = "personal_injury")]
+    PersonalInjury,
+    /// s.8 — action on a specialty (deed), 12 years
+    #[serde(rename = "deed")]
+    Deed,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LimitationStatus {
+    #[serde(rename = "ok")]
+    Ok,
+    #[serde(rename = "warning")]
+    Warning,
+    #[serde(rename = "red_flag")]
+    RedFlag,
+    #[serde(rename = "expired")]
+    Expired,
+    /// The claim form was issued before the period expired
+    #[serde(rename = "issued_in_time")]
+    IssuedInTime,
+    /// Not enough dates on the case to calculate
+    #[serde(rename = "unknown")]
+    Unknown,
+}
+
+/// One limitation period that may apply to the claim
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LimitationPeriod {
+    pub basis: LimitationBasis,
+    /// e.g. "Limitation Act 1980, s.5"
+    pub section: String,
+    /// Date the cause of action accrued (or knowledge date for s.14A)
+    pub runs_from: String,
+    /// Last day to issue the claim form (YYYY-MM-DD)
+    pub expiry_date: String,
+    pub days_remaining: i64,
+    pub explanation: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LimitationReport {
+    pub status: LimitationStatus,
+    /// The period the status is based on — the earliest to expire of those that apply
+    pub primary: Option<LimitationPeriod>,
+    /// Other periods that may apply, e.g. a concurrent claim in tort
+    pub alternatives: Vec<LimitationPeriod>,
+    pub assumptions: Vec<String>,
+    /// True when the case summary should show a red flag
+    pub red_flag: bool,
+}
+
+fn parse_date(s: Option<&str>) -> Option<NaiveDate> {
+    s.and_then(|s| NaiveDate::parse_from_str(s.trim(), "%Y-%m-%d").ok())
+}
+
+fn fmt(date: NaiveDate) -> String {
+    date.format("%-d %B %Y").to_string()
+}
+
+/// The last day to issue: the day of accrual is excluded, so a period of N
+/// years from 1 March ends on 1 March N years later. If the court office is
+/// closed that day, a claim issued on the next open day is in time.
+fn expiry(from: NaiveDate, years: u32) -> NaiveDate {
+    let end = from
+        .checked_add_months(Months::new(years * 12))
+        .unwrap_or(NaiveDate::MAX);
+    crate::bank_holidays::roll_forward(end)
+}
+
+fn period(
+    basis: LimitationBasis,
+    section: &str,
+    from: NaiveDate,
+    expires: NaiveDate,
+    today: NaiveDate,
+    explanation: String,
+) -> LimitationPeriod {
+    LimitationPeriod {
+        basis,
+        section: section.to_string(),
+        runs_from: from.format("%Y-%m-%d").to_string(),
+        expiry_date: expires.format("%Y-%m-%d").to_string(),
+        days_remaining: (expires - today).num_days(),
+        explanation,
+    }
+}
+
+/// Check limitation for a case as at `today`
+pub fn check(case: &CaseMetadata, today: NaiveDate) -> LimitationReport {
+    let purchase = parse_date(case.date_of_purchase.as_deref());
+    let discovered = parse_date(case.date_problem_discovered.as_deref());
+    let issued = parse_date(case.procedural_dates.claim_issued.as_deref());
+
+    let mut assumptions = vec![
+        "No fraud, deliberate concealment or mistake postpones the period (s.32)".to_string(),
+        "The claimant was not under a disability when the cause of action accrued (s.28)".to_string(),
+        "No written acknowledgment or part payment has restarted the period (s.29)".to_string(),
+    ];
+    let mut periods = Vec::new();
+
+    if case.personal_injury {
+        // Injury date isn't recorded separately; the problem date is the closest proxy
+        if let Some(injury) = discovered.or(purchase) {
+            assumptions.push(format!(
+                "The injury occurred on {} and the claimant knew of it then (s.14 date of knowledge)",
+                fmt(injury)
+            ));
+            periods.push(period(
+                LimitationBasis::PersonalInjury,
+                "Limitation Act 1980, s.11",
+                injury,
+                expiry(injury, 3),
+                today,
+                format!("3 years from the injury on {}", fmt(injury)),
+            ));
+        }
+    }
+
+    if let Some(breach) = purchase {
+        let (basis, section, years, what) = if case.agreement_under_deed {
+            (LimitationBasis::Deed, "Limitation Act 1980, s.8", 12, "the agreement was made by deed")
+        } else {
+            (LimitationBasis::Contract, "Limitation Act 1980, s.5", 6, "a simple contract")
+        };
+        let assumption = match case.claim_type {
+            ClaimType::CraServices => format!(
+                "The contract was breached when the service was performed, taken as {}",
+                fmt(breach)
+            ),
+            _ => format!(
+                "The contract was breached on delivery, taken as the purchase date {}",
+                fmt(breach)
+            ),
+        };
+        assumptions.push(assumption);
+        periods.push(period(
+            basis,
+            section,
+            breach,
+            expiry(breach, years),
+            today,
+            format!("{} years from breach, because {}", years, what),
+        ));
+    }
+
+    // A concurrent claim in negligence is most realistic for services
+    if !case.personal_injury && matches!(case.claim_type, ClaimType::CraServices) {
+        if let Some(damage) = purchase.or(discovered) {
+            assumptions.push(format!(
+                "Any damage in negligence occurred when the work was done, taken as {}",
+                fmt(damage)
+            ));
+            periods.push(period(
+                LimitationBasis::Tort,
+                "Limitation Act 1980, s.2",
+                damage,
+                expiry(damage, 6),
+                today,
+                format!("6 years from damage on {}", fmt(damage)),
+            ));
+        }
+
+        // s.14A only helps where the damage was latent: 3 years from knowledge,
+        // never more than 15 years from the negligent act (s.14B)
+        if let (Some(act), Some(knowledge)) = (purchase, discovered) {
+            let primary = expiry(act, 6);
+            let extended = expiry(knowledge, 3).min(expiry(act, 15));
+            if extended > primary {
+                periods.push(period(
+                    LimitationBasis::LatentDamage,
+                    "Limitation Act 1980, s.14A",
+                    knowledge,
+                    extended,
+                    today,
+                    format!(
+                        "3 years from when the damage was discovered on {}, subject to the 15-year longstop in s.14B",
+                        fmt(knowledge)
+                    ),
+                ));
+            }
+        }
+    }
+
+    // Rely on the earliest period that applies, so the warning errs towards issuing early.
+    // The s.14A extension is an alternative argument, not something to plan around.
+    periods.sort_by(|a, b| a.expiry_date.cmp(&b.expiry_date));
+    let primary_index = periods
+        .iter()
+        .position(|p| p.basis != LimitationBasis::LatentDamage);
+    let primary = primary_index.map(|i| periods.remove(i));
+
+    let status = match (&primary, issued) {
+        (None, _) => LimitationStatus::Unknown,
+        (Some(p), Some(issued)) if issued.format("%Y-%m-%d").to_string() <= p.expiry_date => {
+            LimitationStatus::IssuedInTime
+        }
+        (Some(p), _) if p.days_remaining < 0 => LimitationStatus::Expired,
+        (Some(p), _) if p.days_remaining <= RED_FLAG_DAYS => LimitationStatus::RedFlag,
+        (Some(p), _) if p.days_remaining <= WARNING_DAYS => LimitationStatus::Warning,
+        _ => LimitationStatus::Ok,
+    };
+
+    if primary.is_none() {
+        assumptions.push("Add the date of purchase (or of the problem) to calculate limitation".to_string());
+    }
+
+    LimitationReport {
+        red_flag: matches!(status, LimitationStatus::RedFlag | LimitationStatus::Expired),
+        status,
+        primary,
+        alternatives: periods,
+        assumptions,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    fn case(purchase: &str, discovered: Option<&str>) -> CaseMetadata {
+        CaseMetadata {
+            date_of_purchase: Some(purchase.to_string()),
+            date_problem_discovered: discovered.map(|d| d.to_string()),
+            ..CaseMetadata::default()
+        }
+    }
+
+    #[test]
+    fn contract_runs_six_years_from_purchase() {
+        let report = check(&case("2020-03-10", None), date("2024-01-01"));
+        let primary = report.primary.unwrap();
+        assert_eq!(primary.basis, LimitationBasis::Contract);
+        assert_eq!(primary.expiry_date, "2026-03-10");
+        assert_eq!(report.status, LimitationStatus::Ok);
+        assert!(!report.red_flag);
+    }
+
+    #[test]
+    fn expiry_on_a_weekend_moves_to_next_business_day() {
+        // 6 years from 6 March 2021 is Saturday 6 March 2027
+        let report = check(&case("2021-03-06", None), date("2027-01-01"));
+        assert_eq!(report.primary.unwrap().expiry_date, "2027-03-08");
+    }
+
+    #[test]
+    fn red_flag_near_and_after_expiry() {
+        let near = check(&case("2020-03-10", None), date("2026-02-01"));
+        assert_eq!(near.status, LimitationStatus::RedFlag);
+        assert!(near.red_flag);
+
+        let expired = check(&case("2020-03-10", None), date("2026-04-01"));
+        assert_eq!(expired.status, LimitationStatus::Expired);
+        assert!(expired.red_flag);
+    }
+
+    #[test]
+    fn personal_injury_uses_three_years() {
+        let mut c = case("2023-05-01", Some("2023-06-15"));
+        c.personal_injury = true;
+        let report = check(&c, date("2025-01-01"));
+        let primary = report.primary.unwrap();
+        assert_eq!(primary.basis, LimitationBasis::PersonalInjury);
+        assert_eq!(primary.expiry_date, "2026-06-15");
+    }
+
+    #[test]
+    fn deed_uses_twelve_years() {
+        let mut c = case("2015-07-01", None);
+        c.agreement_under_deed = true;
+        let report = check(&c, date("2025-01-01"));
+        assert_eq!(report.primary.unwrap().expiry_date, "2027-07-01");
+    }
+
+    #[test]
+    fn latent_damage_offered_as_alternative_for_services() {
+        let mut c = case("2018-04-03", Some("2024-02-01"));
+        c.claim_type = ClaimType::CraServices;
+        let report = check(&c, date("2024-03-01"));
+        assert_eq!(report.primary.as_ref().unwrap().basis, LimitationBasis::Contract);
+        let latent = report
+            .alternatives
+            .iter()
+            .find(|p| p.basis == LimitationBasis::LatentDamage)
+            .unwrap();
+        assert_eq!(latent.expiry_date, "2027-02-01");
+    }
+
+    #[test]
+    fn issued_claims_are_protected() {
+        let mut c = case("2020-03-10", None);
+        c.procedural_dates.claim_issued = Some("2026-03-01".to_string());
+        let report = check(&c, date("2026-04-01"));
+        assert_eq!(report.status, LimitationStatus::IssuedInTime);
+        assert!(!report.red_flag);
+    }
+}
@@ -0,0 +1,38 @@
+use serde::{Deserialize, Serialize};
+
+/// Where each document sits in a paginated hearing bundle, saved as
+/// `.casekit/bundle-index.json` when a bundle is paginated
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BundleIndex {
+    pub generated_at: String,
+    pub entries: Vec<BundlePageRange>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundlePageRange {
+    /// Document path relative to the case folder, as in `DocumentEntry::path`
+    pub path: String,
+    /// Bundle section or tab, e.g. "A" or "E"
+    #[serde(default)]
+    pub section: Option<String>,
+    pub start_page: u32,
+    pub end_page: u32,
+}
+
+impl BundleIndex {
+    /// Index reference for a document, e.g. "E/23" or "E/23–25". The numbers
+    /// aren't stamped on the bundled files, so this cites the index, not a
+    /// printed page.
+    pub fn reference(&self, path: &str) -> Option<String> {
+        let range = self.entries.iter().find(|e| e.path == path)?;
+        let pages = if range.end_page > range.start_page {
+            format!("{}–{}", range.start_page, range.end_page)
+        } else {
+            range.start_page.to_string()
+        };
+        Some(match &range.section {
+            Some(section) => format!("{}/{}", section, pages),
+            None => pages,
+        })
+    }
+}
@@ -1,7 +1,15 @@
+use crate::models::money::Money;
+use crate::models::party::{Party, PartyRole, PartyType};
 use serde::{Deserialize, Serialize};
 
+/// Current case.json layout. Version 2 stores money as integer pence.
+pub const CASE_SCHEMA_VERSION: u32 = 2;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CaseMetadata {
+    /// Layout version of case.json; files without one are version 1
+    #[serde(default = "legacy_schema_version")]
+    pub schema_version: u32,
     pub id: String,
     pub name: String,
     pub created_at: String,
@@ -14,7 +22,11 @@ pub struct CaseMetadata {
     pub product_service_type: ProductServiceType,
     pub issues: Vec<String>,
     pub desired_outcome: Vec<String>,
-    pub claim_value: f64,
+    /// Total value of the claim. Kept equal to the sum of `claim_heads` when any are recorded.
+    pub claim_value: Money,
+    /// Itemised heads of loss making up the claim
+    #[serde(default)]
+    pub claim_heads: Vec<ClaimHead>,
     pub date_of_purchase: Option<String>,
     pub date_problem_discovered: Option<String>,
     pub date_first_complained: Option<String>,
@@ -24,20 +36,193 @@ pub struct CaseMetadata {
     pub complexity_triggers: Vec<String>,
     pub overall_risk: String,
     pub multiple_parties: bool,
+    /// Everyone involved in the case. Cases created before parties were
+    /// recorded have none; see `parties()`.
+    #[serde(default)]
+    pub parties: Vec<Party>,
     pub cross_border: bool,
     pub personal_injury: bool,
+    /// Damages claimed for pain, suffering and loss of amenity, which decide
+    /// whether a personal injury claim can stay on the small claims track
+    #[serde(default)]
+    pub psla_damages: Option<Money>,
     pub existing_proceedings: bool,
     pub insolvency: bool,
     pub regulatory_overlap: bool,
     pub counterclaim: bool,
+    /// The contract was executed as a deed (12-year limitation period)
+    #[serde(default)]
+    pub agreement_under_deed: bool,
     #[serde(default = "default_user_role")]
     pub user_role: UserRole,
+    /// Dates of procedural steps, used to calculate CPR deadlines
+    #[serde(default)]
+    pub procedural_dates: ProceduralDates,
+    /// Names, addresses, references etc. always redacted before text is sent
+    /// to an AI provider
+    #[serde(default)]
+    pub redaction_terms: Vec<String>,
 }
 
 fn default_user_role() -> UserRole {
     UserRole::Claimant
 }
 
+fn legacy_schema_version() -> u32 {
+    1
+}
+
+/// One item of loss, e.g. "Replacement washing machine — £449.00"
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClaimHead {
+    #[serde(default = "new_id")]
+    pub id: String,
+    pub description: String,
+    pub amount: Money,
+    /// Documents proving the loss (receipts, quotes, invoices)
+    #[serde(default)]
+    pub evidence_doc_ids: Vec<String>,
+}
+
+fn new_id() -> String {
+    uuid::Uuid::new_v4().to_string()
+}
+
+impl CaseMetadata {
+    /// The value of the claim: the sum of the heads of loss if any are
+    /// recorded, otherwise the figure entered at intake
+    pub fn total_claim_value(&self) -> Money {
+        if self.claim_heads.is_empty() {
+            self.claim_value
+        } else {
+            self.claim_heads.iter().map(|h| h.amount).sum()
+        }
+    }
+
+    /// The part of the claim backed by at least one evidence document
+    pub fn evidenced_claim_value(&self) -> Money {
+        self.claim_heads
+            .iter()
+            .filter(|h| !h.evidence_doc_ids.is_empty())
+            .map(|h| h.amount)
+            .sum()
+    }
+
+    /// The recorded parties, or a claimant and defendant made up from the
+    /// names entered at intake if none have been recorded
+    pub fn parties(&self) -> Vec<Party> {
+        if !self.parties.is_empty() {
+            return self.parties.clone();
+        }
+        let mut parties = Vec::new();
+        if !self.claimant_name.trim().is_empty() {
+            let mut claimant = Party::new(PartyRole::Claimant, PartyType::Individual, &self.claimant_name);
+            claimant.id = "claimant".to_string();
+            parties.push(claimant);
+        }
+        if !self.defendant_name.trim().is_empty() {
+            let mut defendant = Party::new(PartyRole::Defendant, (&self.defendant_type).into(), &self.defendant_name);
+            defendant.id = "defendant".to_string();
+            parties.push(defendant);
+        }
+        parties
+    }
+
+    pub fn parties_in_role(&self, role: PartyRole) -> Vec<Party> {
+        self.parties().into_iter().filter(|p| p.role == role).collect()
+    }
+
+    /// Names of everyone in a role, as they go in a title: "A", "A and B",
+    /// "A, B and C"
+    pub fn party_names(&self, role: PartyRole) -> String {
+        let names: Vec<String> = self.parties_in_role(role).into_iter().map(|p| p.name).collect();
+        match names.split_last() {
+            None => String::new(),
+            Some((last, [])) => last.clone(),
+            Some((last, rest)) => format!("{} and {}", rest.join(", "), last),
+        }
+    }
+
+    /// Addresses for service of everyone in a role. With more than one party
+    /// each address is labelled with the party's name.
+    pub fn service_addresses(&self, role: PartyRole) -> Option<String> {
+        let parties = self.parties_in_role(role);
+        let addressed: Vec<(&Party, &str)> = parties
+            .iter()
+            .filter_map(|p| p.service_address().map(|a| (p, a.trim())))
+            .collect();
+        match addressed.as_slice() {
+            [] => None,
+            [(_, address)] if parties.len() == 1 => Some(address.to_string()),
+            _ => Some(
+                addressed
+                    .iter()
+                    .map(|(p, a)| format!("{}: {}", p.name, a))
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+            ),
+        }
+    }
+
+    /// Keep the intake fields in step with the recorded parties: the names,
+    /// the first defendant's type, and `multiple_parties` once there is more
+    /// than one claimant or defendant
+    pub fn sync_parties(&mut self) {
+        if self.parties.is_empty() {
+            return;
+        }
+        let claimants = self.party_names(PartyRole::Claimant);
+        if !claimants.is_empty() {
+            self.claimant_name = claimants;
+        }
+        let defendants = self.parties_in_role(PartyRole::Defendant);
+        if !defendants.is_empty() {
+            self.defendant_name = self.party_names(PartyRole::Defendant);
+            match defendants[0].party_type {
+                PartyType::Company => self.defendant_type = DefendantType::Company,
+                PartyType::Individual => self.defendant_type = DefendantType::Individual,
+                PartyType::SoleTrader => self.defendant_type = DefendantType::SoleTrader,
+                PartyType::Partnership | PartyType::Other => {}
+            }
+        }
+        let count = |role| self.parties.iter().filter(|p| p.role == role).count();
+        if count(PartyRole::Claimant) > 1 || count(PartyRole::Defendant) > 1 {
+            self.multiple_parties = true;
+        }
+    }
+}
+
+/// Parse case.json, upgrading older layouts. Returns the metadata and whether
+/// it was migrated (so the caller can write the upgraded file back).
+pub fn parse_case_json(content: &str) -> Result<(CaseMetadata, bool), String> {
+    let mut value: serde_json::Value = serde_json::from_str(content)
+        .map_err(|e| format!("Could not parse case.json: {}", e))?;
+
+    let version = value
+        .get("schema_version")
+        .and_then(|v| v.as_u64())
+        .unwrap_or(1) as u32;
+
+    if version < 2 {
+        // Version 1 stored claim_value as floating-point pounds
+        if let Some(obj) = value.as_object_mut() {
+            let pounds = obj.get("claim_value").and_then(|v| v.as_f64()).unwrap_or(0.0);
+            obj.insert(
+                "claim_value".to_string(),
+                serde_json::json!(Money::from_pounds_f64(pounds).pence()),
+            );
+        }
+    }
+
+    if let Some(obj) = value.as_object_mut() {
+        obj.insert("schema_version".to_string(), serde_json::json!(CASE_SCHEMA_VERSION));
+    }
+
+    let metadata = serde_json::from_value(value)
+        .map_err(|e| format!("Could not parse case.json: {}", e))?;
+    Ok((metadata, version < CASE_SCHEMA_VERSION))
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum DefendantType {
     #[serde(rename = "company")]
@@ -76,6 +261,65 @@ pub enum UserRole {
     Defendant,
 }
 
+/// Dates (YYYY-MM-DD) of the procedural steps taken so far. Each is None
+/// until it has happened.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ProceduralDates {
+    pub claim_issued: Option<String>,
+    /// The step required by CPR 7.5 (posting, delivering, emailing etc.)
+    pub claim_form_served: Option<ServiceEvent>,
+    /// None if the particulars of claim were served with the claim form
+    pub particulars_served: Option<ServiceEvent>,
+    pub acknowledgment_filed: Option<String>,
+    pub defence_filed: Option<String>,
+    pub allocation_notice_served: Option<ServiceEvent>,
+    /// Date for filing the directions questionnaire stated in the court's notice
+    pub dq_deadline: Option<String>,
+    pub dq_filed: Option<String>,
+    /// Date of a decision the user may want to appeal
+    pub decision_date: Option<String>,
+    pub appeal_filed: Option<String>,
+}
+
+/// When and how a document was sent, for working out deemed service
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServiceEvent {
+    pub date: String,
+    pub method: ServiceMethod,
+    /// Delivered or transmitted after 4.30pm (CPR 6.26)
+    #[serde(default)]
+    pub after_4_30pm: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ServiceMethod {
+    #[serde(rename = "first_class_post")]
+    FirstClassPost,
+    #[serde(rename = "document_exchange")]
+    DocumentExchange,
+    #[serde(rename = "delivered_to_address")]
+    DeliveredToAddress,
+    #[serde(rename = "personal")]
+    Personal,
+    #[serde(rename = "email")]
+    Email,
+    #[serde(rename = "fax")]
+    Fax,
+}
+
+/// CPR 26 allocation tracks
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Track {
+    #[serde(rename = "small_claims")]
+    SmallClaims,
+    #[serde(rename = "fast")]
+    Fast,
+    #[serde(rename = "intermediate")]
+    Intermediate,
+    #[serde(rename = "multi")]
+    Multi,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum CaseStatus {
     #[serde(rename = "intake")]
@@ -102,6 +346,7 @@ impl Default for CaseMetadata {
     fn default() -> Self {
         let now = chrono::Utc::now().to_rfc3339();
         Self {
+            schema_version: CASE_SCHEMA_VERSION,
             id: uuid::Uuid::new_v4().to_string(),
             name: String::new(),
             created_at: now.clone(),
@@ -114,7 +359,8 @@ impl Default for CaseMetadata {
             product_service_type: ProductServiceType::Goods,
             issues: Vec::new(),
             desired_outcome: Vec::new(),
-            claim_value: 0.0,
+            claim_value: Money::ZERO,
+            claim_heads: Vec::new(),
             date_of_purchase: None,
             date_problem_discovered: None,
             date_first_complained: None,
@@ -124,13 +370,99 @@ impl Default for CaseMetadata {
             complexity_triggers: Vec::new(),
             overall_risk: "within_scope".to_string(),
             multiple_parties: false,
+            parties: Vec::new(),
             cross_border: false,
             personal_injury: false,
+            psla_damages: None,
             existing_proceedings: false,
             insolvency: false,
             regulatory_overlap: false,
             counterclaim: false,
+            agreement_under_deed: false,
             user_role: UserRole::Claimant,
+            procedural_dates: ProceduralDates::default(),
+            redaction_terms: Vec::new(),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn migrates_float_claim_value_to_pence() {
+        let mut legacy = serde_json::to_value(CaseMetadata::default()).unwrap();
+        let obj = legacy.as_object_mut().unwrap();
+        obj.remove("schema_version");
+        obj.remove("claim_heads");
+        obj.insert("claim_value".to_string(), serde_json::json!(1234.56));
+
+        let (case, migrated) = parse_case_json(&legacy.to_string()).unwrap();
+        assert!(migrated);
+        assert_eq!(case.claim_value, Money::from_pence(123_456));
+        assert_eq!(case.schema_version, CASE_SCHEMA_VERSION);
+
+        // Already-migrated files are left alone
+        let current = serde_json::to_string(&case).unwrap();
+        let (again, migrated) = parse_case_json(&current).unwrap();
+        assert!(!migrated);
+        assert_eq!(again.claim_value, Money::from_pence(123_456));
+    }
+
+    #[test]
+    fn total_is_sum_of_heads() {
+        let mut case = CaseMetadata {
+            claim_value: Money::from_pounds(500),
+            ..CaseMetadata::default()
+        };
+        assert_eq!(case.total_claim_value(), Money::from_pounds(500));
+
+        case.claim_heads = vec![
+            ClaimHead {
+                id: new_id(),
+                description: "Replacement".to_string(),
+                amount: Money::from_pence(44_900),
+                evidence_doc_ids: vec!["doc-1".to_string()],
+            },
+            ClaimHead {
+                id: new_id(),
+                description: "Delivery".to_string(),
+                amount: Money::from_pence(2_500),
+                evidence_doc_ids: Vec::new(),
+            },
+        ];
+        assert_eq!(case.total_claim_value(), Money::from_pence(47_400));
+        assert_eq!(case.evidenced_claim_value(), Money::from_pence(44_900));
+    }
+
+    #[test]
+    fn parties_fall_back_to_intake_names_and_sync_back() {
+        let mut case = CaseMetadata {
+            claimant_name: "Jane Smith".to_string(),
+            defendant_name: "Acme Ltd".to_string(),
+            ..CaseMetadata::default()
+        };
+        let parties = case.parties();
+        assert_eq!(parties.len(), 2);
+        assert_eq!(parties[1].role, PartyRole::Defendant);
+        assert_eq!(parties[1].party_type, PartyType::Company);
+        assert_eq!(case.service_addresses(PartyRole::Defendant), None);
+
+        let mut acme = Party::new(PartyRole::Defendant, PartyType::Company, "Acme Appliances Ltd");
+        acme.address = Some("1 High Street, Leeds".to_string());
+        let mut fitter = Party::new(PartyRole::Defendant, PartyType::SoleTrader, "Bob Brown");
+        fitter.address_for_service = Some("2 Mill Lane, York".to_string());
+        let jane = Party::new(PartyRole::Claimant, PartyType::Individual, "Jane Smith");
+        case.parties = vec![jane, acme, fitter];
+        case.sync_parties();
+
+        assert_eq!(case.defendant_name, "Acme Appliances Ltd and Bob Brown");
+        assert!(case.multiple_parties);
+        assert_eq!(
+            case.service_addresses(PartyRole::Defendant).as_deref(),
+            Some("Acme Appliances Ltd: 1 High Street, Leeds\nBob Brown: 2 Mill Lane, York")
+        );
+        assert_eq!(case.service_addresses(PartyRole::Claimant), None);
+    }
+}
@@ -1,9 +1,17 @@
-use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::fmt;
+use std::sync::OnceLock;
+
+use chrono::{Datelike, NaiveDate};
+use regex::Regex;
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::date_scanner::month_to_num;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ChronologyEntry {
     pub id: String,
-    pub date: String,
+    pub date: ChronoDate,
     pub description: String,
     pub source: ChronologySource,
     pub document_id: Option<String>,
@@ -12,6 +20,31 @@ pub struct ChronologyEntry {
     pub significance: Significance,
     /// AI confidence level: "high", "medium", "low", or None for manual entries
     pub confidence: Option<String>,
+    /// What happened, where the event classifier recognised it
+    #[serde(default)]
+    pub event_type: Option<EventType>,
+    /// The phrase that identified the event, e.g. "refused to refund"
+    #[serde(default)]
+    pub trigger: Option<String>,
+    /// How the date was worked out, where the document gave it relative to another date
+    #[serde(default)]
+    pub relative: Option<RelativeDate>,
+}
+
+/// A date written relative to another, e.g. "three days ago" in an email
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RelativeDate {
+    /// The phrase as written
+    pub phrase: String,
+    /// The date it was counted from (yyyy-mm-dd)
+    pub anchor: String,
+    /// What the anchor is, e.g. "the email's sent date"
+    pub anchor_label: String,
+    /// Days from the anchor to the resolved date, negative when earlier.
+    /// None when the phrase only gives a month.
+    pub offset_days: Option<i64>,
+    /// How the phrase was read, e.g. "yesterday" taken as the day before
+    pub reading: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -26,7 +59,7 @@ pub enum ChronologySource {
     AiExtracted,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Significance {
     #[serde(rename = "key")]
     Key,
@@ -36,17 +69,593 @@ pub enum Significance {
     Background,
 }
 
+/// Events in a consumer claim that the scanner can recognise from wording
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum EventType {
+    #[serde(rename = "order_placed")]
+    OrderPlaced,
+    #[serde(rename = "payment_made")]
+    PaymentMade,
+    #[serde(rename = "delivered")]
+    Delivered,
+    #[serde(rename = "fault_reported")]
+    FaultReported,
+    #[serde(rename = "repair_attempted")]
+    RepairAttempted,
+    #[serde(rename = "refund_requested")]
+    RefundRequested,
+    #[serde(rename = "refund_refused")]
+    RefundRefused,
+    #[serde(rename = "offer_made")]
+    OfferMade,
+    #[serde(rename = "letter_before_claim_sent")]
+    LetterBeforeClaimSent,
+    #[serde(rename = "response_received")]
+    ResponseReceived,
+    #[serde(rename = "claim_issued")]
+    ClaimIssued,
+    #[serde(rename = "claim_served")]
+    ClaimServed,
+    #[serde(rename = "acknowledgment_filed")]
+    AcknowledgmentFiled,
+    #[serde(rename = "defence_filed")]
+    DefenceFiled,
+    #[serde(rename = "directions_questionnaire")]
+    DirectionsQuestionnaire,
+    #[serde(rename = "mediation")]
+    Mediation,
+    #[serde(rename = "allocated")]
+    Allocated,
+    #[serde(rename = "hearing_listed")]
+    HearingListed,
+    #[serde(rename = "judgment")]
+    Judgment,
+}
+
+impl EventType {
+    pub fn label(self) -> &'static str {
+        match self {
+            EventType::OrderPlaced => "Order placed",
+            EventType::PaymentMade => "Payment made",
+            EventType::Delivered => "Delivered",
+            EventType::FaultReported => "Fault reported",
+            EventType::RepairAttempted => "Repair attempted",
+            EventType::RefundRequested => "Refund requested",
+            EventType::RefundRefused => "Refund refused",
+            EventType::OfferMade => "Offer made",
+            EventType::LetterBeforeClaimSent => "Letter before claim sent",
+            EventType::ResponseReceived => "Response received",
+            EventType::ClaimIssued => "Claim issued",
+            EventType::ClaimServed => "Claim served",
+            EventType::AcknowledgmentFiled => "Acknowledgment of service filed",
+            EventType::DefenceFiled => "Defence filed",
+            EventType::DirectionsQuestionnaire => "Directions questionnaire",
+            EventType::Mediation => "Mediation",
+            EventType::Allocated => "Allocated to track",
+            EventType::HearingListed => "Hearing listed",
+            EventType::Judgment => "Judgment or order",
+        }
+    }
+}
+
 impl Default for ChronologyEntry {
     fn default() -> Self {
         Self {
             id: uuid::Uuid::new_v4().to_string(),
-            date: String::new(),
+            date: ChronoDate::undated(""),
             description: String::new(),
             source: ChronologySource::Manual,
             document_id: None,
             source_document_path: None,
             significance: Significance::Supporting,
             confidence: None,
+            event_type: None,
+            trigger: None,
+            relative: None,
+        }
+    }
+}
+
+/// How precisely a chronology date is known
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DatePrecision {
+    #[serde(rename = "day")]
+    Day,
+    #[serde(rename = "month")]
+    Month,
+    #[serde(rename = "year")]
+    Year,
+    #[serde(rename = "range")]
+    Range,
+    #[serde(rename = "approximate")]
+    Approximate,
+}
+
+impl DatePrecision {
+    /// Among dates starting on the same day, the more precise sorts first
+    fn rank(self) -> u8 {
+        match self {
+            DatePrecision::Day => 0,
+            DatePrecision::Approximate => 1,
+            DatePrecision::Range => 2,
+            DatePrecision::Month => 3,
+            DatePrecision::Year => 4,
         }
     }
 }
+
+/// A chronology date, which may only be known to the month or year, as a
+/// range, or approximately. `start` and `end` are the first and last days it
+/// could fall on; both are `None` for an entry that hasn't been dated yet.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ChronoDate {
+    pub precision: DatePrecision,
+    pub start: Option<NaiveDate>,
+    pub end: Option<NaiveDate>,
+    /// Time of day, "HH:MM" or "HH:MM:SS"
+    pub time: Option<String>,
+    /// "Z", a UTC offset such as "+01:00", or a zone name such as "Europe/London"
+    pub timezone: Option<String>,
+    /// The date as written, kept for approximate and undated entries
+    pub text: Option<String>,
+}
+
+/// Chronologies saved before dates were structured hold plain strings
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ChronoDateRepr {
+    Text(String),
+    Structured {
+        precision: DatePrecision,
+        #[serde(default)]
+        start: Option<NaiveDate>,
+        #[serde(default)]
+        end: Option<NaiveDate>,
+        #[serde(default)]
+        time: Option<String>,
+        #[serde(default)]
+        timezone: Option<String>,
+        #[serde(default)]
+        text: Option<String>,
+    },
+}
+
+impl<'de> Deserialize<'de> for ChronoDate {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(match ChronoDateRepr::deserialize(deserializer)? {
+            ChronoDateRepr::Text(text) => ChronoDate::from_text(&text),
+            ChronoDateRepr::Structured { precision, start, end, time, timezone, text } => ChronoDate {
+                precision,
+                start,
+                end: end.or(start),
+                time,
+                timezone,
+                text,
+            },
+        })
+    }
+}
+
+fn last_of_month(year: i32, month: u32) -> Option<NaiveDate> {
+    let (y, m) = if month == 12 { (year + 1, 1) } else { (year, month + 1) };
+    NaiveDate::from_ymd_opt(y, m, 1).and_then(|d| d.pred_opt())
+}
+
+fn month_span(year: i32, month: u32) -> Option<(NaiveDate, NaiveDate)> {
+    Some((NaiveDate::from_ymd_opt(year, month, 1)?, last_of_month(year, month)?))
+}
+
+fn year_span(year: i32) -> Option<(NaiveDate, NaiveDate)> {
+    Some((NaiveDate::from_ymd_opt(year, 1, 1)?, NaiveDate::from_ymd_opt(year, 12, 31)?))
+}
+
+fn cached(cell: &'static OnceLock<Regex>, pattern: &str) -> &'static Regex {
+    cell.get_or_init(|| Regex::new(pattern).unwrap())
+}
+
+/// Renders the days a date could fall on as briefly as they allow:
+/// "5 March 2024", "March 2024", "2024", "5–9 March 2024"
+fn span_label(start: NaiveDate, end: NaiveDate) -> String {
+    let whole_month = start.day() == 1
+        && start.year() == end.year()
+        && start.month() == end.month()
+        && last_of_month(end.year(), end.month()) == Some(end);
+    let whole_year = start.ordinal() == 1 && end.month() == 12 && end.day() == 31;
+
+    if start == end {
+        start.format("%-d %B %Y").to_string()
+    } else if whole_month {
+        start.format("%B %Y").to_string()
+    } else if whole_year && start.year() == end.year() {
+        start.year().to_string()
+    } else if whole_year {
+        format!("{}–{}", start.year(), end.year())
+    } else if start.year() == end.year() && start.month() == end.month() {
+        format!("{}–{}", start.day(), end.format("%-d %B %Y"))
+    } else if start.year() == end.year() {
+        format!("{} – {}", start.format("%-d %B"), end.format("%-d %B %Y"))
+    } else {
+        format!("{} – {}", start.format("%-d %B %Y"), end.format("%-d %B %Y"))
+    }
+}
+
+impl ChronoDate {
+    pub fn day(date: NaiveDate) -> Self {
+        Self::spanning(DatePrecision::Day, date, date)
+    }
+
+    fn spanning(precision: DatePrecision, start: NaiveDate, end: NaiveDate) -> Self {
+        Self {
+            precision,
+            start: Some(start),
+            end: Some(end),
+            time: None,
+            timezone: None,
+            text: None,
+        }
+    }
+
+    /// An entry whose date couldn't be worked out, keeping what was written
+    pub fn undated(text: &str) -> Self {
+        let text = text.trim();
+        Self {
+            precision: DatePrecision::Approximate,
+            start: None,
+            end: None,
+            time: None,
+            timezone: None,
+            text: (!text.is_empty()).then(|| text.to_string()),
+        }
+    }
+
+    /// Parses a date as written, or keeps the text as an undated entry
+    pub fn from_text(text: &str) -> Self {
+        Self::parse(text).unwrap_or_else(|| Self::undated(text))
+    }
+
+    /// Parses ISO dates and date-times ("2024-03-05", "2024-03", "2024",
+    /// "2024-03-05T14:30+01:00"), UK dates ("5 March 2024", "05/03/2024",
+    /// "March 2024"), ranges ("2024-03-05/2024-03-09", "5 to 9 March 2024")
+    /// and approximate dates ("c. 5 March 2024", "about May 2023", "late 2022").
+    pub fn parse(text: &str) -> Option<Self> {
+        let text = text.trim();
+        if text.is_empty() {
+            return None;
+        }
+        Self::parse_approximate(text)
+            .or_else(|| Self::parse_single(text))
+            .or_else(|| Self::parse_range(text))
+    }
+
+    fn parse_approximate(text: &str) -> Option<Self> {
+        static APPROX: OnceLock<Regex> = OnceLock::new();
+        static PART: OnceLock<Regex> = OnceLock::new();
+        let approx = cached(
+            &APPROX,
+            r"(?i)^(?:(?:~|c\.|ca\.)\s*|(?:circa|about|around|approx\.?|approximately|on or about|on or around|c)\s+)(.+)$",
+        );
+        let part = cached(&PART, r"(?i)^(early|mid-|mid|late)\s*(.+)$");
+
+        let inner = if let Some(cap) = approx.captures(text) {
+            let inner = Self::parse_single(&cap[1]).or_else(|| Self::parse_range(&cap[1]))?;
+            Self { text: None, ..inner }
+        } else if let Some(cap) = part.captures(text) {
+            let whole = Self::parse_single(&cap[2])?;
+            let (start, end) = (whole.start?, whole.end?);
+            let (first, last) = match whole.precision {
+                DatePrecision::Month => (1, last_of_month(start.year(), start.month())?.day()),
+                DatePrecision::Year => (1, 12),
+                _ => return None,
+            };
+            let third = (last - first + 1) / 3;
+            let (from, to) = match cap[1].to_lowercase().trim_end_matches('-') {
+                "early" => (first, first + third - 1),
+                "mid" => (first + third, first + 2 * third - 1),
+                _ => (first + 2 * third, last),
+            };
+            let span = match whole.precision {
+                DatePrecision::Month => (start.with_day(from)?, end.with_day(to)?),
+                _ => (month_span(start.year(), from)?.0, month_span(start.year(), to)?.1),
+            };
+            Self::spanning(DatePrecision::Approximate, span.0, span.1)
+        } else {
+            return None;
+        };
+
+        Some(Self {
+            precision: DatePrecision::Approximate,
+            text: Some(text.to_string()),
+            ..inner
+        })
+    }
+
+    /// A single date, with an optional time of day
+    fn parse_single(text: &str) -> Option<Self> {
+        static ISO: OnceLock<Regex> = OnceLock::new();
+        static ISO_PARTIAL: OnceLock<Regex> = OnceLock::new();
+        static NUMERIC: OnceLock<Regex> = OnceLock::new();
+        static NAMED: OnceLock<Regex> = OnceLock::new();
+        static WITH_TIME: OnceLock<Regex> = OnceLock::new();
+        let iso = cached(
+            &ISO,
+            r"^(\d{4})-(\d{2})-(\d{2})(?:[T ](\d{2}:\d{2}(?::\d{2})?)(?:\.\d+)?\s*(Z|[+-]\d{2}:?\d{2}|[A-Za-z_]+(?:/[A-Za-z_]+)*)?)?$",
+        );
+        let iso_partial = cached(&ISO_PARTIAL, r"^(\d{4})(?:-(\d{2}))?$");
+        let numeric = cached(&NUMERIC, r"^(\d{1,2})[/.-](\d{1,2})[/.-](\d{4})$");
+        let named = cached(
+            &NAMED,
+            r"(?i)^(?:(\d{1,2})(?:st|nd|rd|th)?\s+)?([a-z]{3,9})\.?,?\s+(\d{4})$",
+        );
+        let with_time = cached(
+            &WITH_TIME,
+            r"(?i)^(.+?),?\s+(?:at\s+)?(\d{1,2}[:.]\d{2}(?::\d{2})?)\s*(Z|UTC|GMT|BST|[+-]\d{2}:?\d{2}|[A-Za-z_]+/[A-Za-z_]+)?$",
+        );
+
+        let text = text.trim();
+        if let Some(cap) = iso.captures(text) {
+            let date = NaiveDate::from_ymd_opt(cap[1].parse().ok()?, cap[2].parse().ok()?, cap[3].parse().ok()?)?;
+            return Some(Self {
+                time: cap.get(4).map(|m| m.as_str().to_string()),
+                timezone: cap.get(5).map(|m| m.as_str().to_string()),
+                ..Self::day(date)
+            });
+        }
+        if let Some(cap) = iso_partial.captures(text) {
+            let year: i32 = cap[1].parse().ok()?;
+            return match cap.get(2) {
+                Some(m) => {
+                    let (start, end) = month_span(year, m.as_str().parse().ok()?)?;
+                    Some(Self::spanning(DatePrecision::Month, start, end))
+                }
+                None => {
+                    let (start, end) = year_span(year)?;
+                    Some(Self::spanning(DatePrecision::Year, start, end))
+                }
+            };
+        }
+        if let Some(cap) = numeric.captures(text) {
+            // UK order: day first
+            let date = NaiveDate::from_ymd_opt(cap[3].parse().ok()?, cap[2].parse().ok()?, cap[1].parse().ok()?)?;
+            return Some(Self::day(date));
+        }
+        if let Some(cap) = named.captures(text) {
+            let year: i32 = cap[3].parse().ok()?;
+            let month = month_to_num(&cap[2])?;
+            return match cap.get(1) {
+                Some(d) => Some(Self::day(NaiveDate::from_ymd_opt(year, month, d.as_str().parse().ok()?)?)),
+                None => {
+                    let (start, end) = month_span(year, month)?;
+                    Some(Self::spanning(DatePrecision::Month, start, end))
+                }
+            };
+        }
+        if let Some(cap) = with_time.captures(text) {
+            let date = Self::parse_single(&cap[1]).filter(|d| d.precision == DatePrecision::Day && d.time.is_none())?;
+            let time = cap[2].replace('.', ":");
+            let (hour, rest) = time.split_once(':')?;
+            if hour.parse::<u32>().ok()? > 23 || rest[..2].parse::<u32>().ok()? > 59 {
+                return None;
+            }
+            return Some(Self {
+                time: Some(format!("{:0>2}:{}", hour, rest)),
+                timezone: cap.get(3).map(|m| m.as_str().to_string()),
+                ..date
+            });
+        }
+        None
+    }
+
+    /// "2024-03-05/2024-03-09", "5 to 9 March 2024", "March–May 2024",
+    /// "between 2022 and 2023". The first half may borrow the month and
+    /// year from the second.
+    fn parse_range(text: &str) -> Option<Self> {
+        static RANGE: OnceLock<Regex> = OnceLock::new();
+        let range = cached(
+            &RANGE,
+            r"(?i)^(?:between\s+)?(.+?)\s*(?:/|\.\.|–|—|\s-\s|\s+to\s+|\s+and\s+|\s+until\s+)\s*(.+)$",
+        );
+        let cap = range.captures(text.trim())?;
+        let (left, right) = (cap[1].trim(), cap[2].trim());
+
+        let last = Self::parse_single(right)?;
+        let words: Vec<&str> = right.split_whitespace().collect();
+        let first = Self::parse_single(left).or_else(|| {
+            (1..words.len()).rev().find_map(|n| {
+                Self::parse_single(&format!("{} {}", left, words[words.len() - n..].join(" ")))
+            })
+        })?;
+
+        let (start, end) = (first.start?, last.end?);
+        if end < start {
+            return None;
+        }
+        Some(Self::spanning(DatePrecision::Range, start, end))
+    }
+
+    pub fn is_dated(&self) -> bool {
+        self.start.is_some()
+    }
+
+    /// The day this refers to, when it's known to the day
+    pub fn as_day(&self) -> Option<NaiveDate> {
+        match self.precision {
+            DatePrecision::Day => self.start,
+            _ => None,
+        }
+    }
+
+    /// Undated entries sort last; otherwise by the earliest day the date
+    /// could be, more precise dates first, then time of day.
+    fn sort_key(&self) -> (bool, Option<NaiveDate>, u8, Option<&str>, Option<NaiveDate>) {
+        (
+            self.start.is_none(),
+            self.start,
+            self.precision.rank(),
+            self.time.as_deref(),
+            self.end,
+        )
+    }
+}
+
+impl Ord for ChronoDate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.sort_key()
+            .cmp(&other.sort_key())
+            .then_with(|| self.timezone.cmp(&other.timezone))
+            .then_with(|| self.text.cmp(&other.text))
+    }
+}
+
+impl PartialOrd for ChronoDate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for ChronoDate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (start, end) = match (self.start, self.end) {
+            (Some(start), Some(end)) => (start, end),
+            _ => return write!(f, "{}", self.text.as_deref().unwrap_or("Undated")),
+        };
+        match self.precision {
+            DatePrecision::Approximate => match &self.text {
+                Some(text) => write!(f, "{}", text),
+                None => write!(f, "c. {}", span_label(start, end)),
+            },
+            _ => {
+                write!(f, "{}", span_label(start, end))?;
+                if let Some(time) = &self.time {
+                    write!(f, ", {}", time)?;
+                    if let Some(zone) = &self.timezone {
+                        write!(f, " {}", zone)?;
+                    }
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ymd(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    fn parsed(text: &str) -> ChronoDate {
+        ChronoDate::parse(text).unwrap_or_else(|| panic!("could not parse {:?}", text))
+    }
+
+    #[test]
+    fn parses_iso_dates_at_each_precision() {
+        let day = parsed("2024-03-05");
+        assert_eq!(day.precision, DatePrecision::Day);
+        assert_eq!((day.start, day.end), (Some(ymd(2024, 3, 5)), Some(ymd(2024, 3, 5))));
+
+        let month = parsed("2024-02");
+        assert_eq!(month.precision, DatePrecision::Month);
+        assert_eq!((month.start, month.end), (Some(ymd(2024, 2, 1)), Some(ymd(2024, 2, 29))));
+
+        let year = parsed("2023");
+        assert_eq!(year.precision, DatePrecision::Year);
+        assert_eq!((year.start, year.end), (Some(ymd(2023, 1, 1)), Some(ymd(2023, 12, 31))));
+    }
+
+    #[test]
+    fn parses_uk_dates_and_times() {
+        assert_eq!(parsed("05/03/2024").start, Some(ymd(2024, 3, 5)));
+        assert_eq!(parsed("1st January 2023").start, Some(ymd(2023, 1, 1)));
+        assert_eq!(parsed("Sept 2022").precision, DatePrecision::Month);
+
+        let timed = parsed("2024-03-05T14:30+01:00");
+        assert_eq!(timed.time.as_deref(), Some("14:30"));
+        assert_eq!(timed.timezone.as_deref(), Some("+01:00"));
+        assert_eq!(parsed("2024-03-05 14:30 Europe/London").timezone.as_deref(), Some("Europe/London"));
+        assert_eq!(parsed("c. 2024-03-01/2024-04-30").precision, DatePrecision::Approximate);
+
+        let spoken = parsed("5 March 2024 at 9.15 Europe/London");
+        assert_eq!(spoken.start, Some(ymd(2024, 3, 5)));
+        assert_eq!(spoken.time.as_deref(), Some("09:15"));
+        assert_eq!(spoken.timezone.as_deref(), Some("Europe/London"));
+        assert_eq!(spoken.to_string(), "5 March 2024, 09:15 Europe/London");
+        assert_eq!(parsed("5 March 2024 9:30:00").time.as_deref(), Some("09:30:00"));
+        assert_eq!(parsed("5 March 2024 at 14:05:30").time.as_deref(), Some("14:05:30"));
+    }
+
+    #[test]
+    fn parses_ranges_borrowing_month_and_year() {
+        let range = parsed("5 to 9 March 2024");
+        assert_eq!(range.precision, DatePrecision::Range);
+        assert_eq!((range.start, range.end), (Some(ymd(2024, 3, 5)), Some(ymd(2024, 3, 9))));
+        assert_eq!(range.to_string(), "5–9 March 2024");
+
+        let iso = parsed("2024-03-28/2024-04-02");
+        assert_eq!(iso.to_string(), "28 March – 2 April 2024");
+
+        let years = parsed("between 2022 and 2023");
+        assert_eq!(years.to_string(), "2022–2023");
+
+        assert!(ChronoDate::parse("2024-04-02/2024-03-28").is_none());
+    }
+
+    #[test]
+    fn parses_approximate_dates() {
+        let circa = parsed("c. 5 March 2024");
+        assert_eq!(circa.precision, DatePrecision::Approximate);
+        assert_eq!(circa.start, Some(ymd(2024, 3, 5)));
+        assert_eq!(circa.to_string(), "c. 5 March 2024");
+
+        let late = parsed("late 2022");
+        assert_eq!((late.start, late.end), (Some(ymd(2022, 9, 1)), Some(ymd(2022, 12, 31))));
+
+        let early = parsed("early June 2023");
+        assert_eq!((early.start, early.end), (Some(ymd(2023, 6, 1)), Some(ymd(2023, 6, 10))));
+        assert_eq!(early.to_string(), "early June 2023");
+    }
+
+    #[test]
+    fn displays_by_precision() {
+        assert_eq!(parsed("2024-03-05").to_string(), "5 March 2024");
+        assert_eq!(parsed("2024-03").to_string(), "March 2024");
+        assert_eq!(parsed("2024").to_string(), "2024");
+        assert_eq!(ChronoDate::undated("").to_string(), "Undated");
+    }
+
+    #[test]
+    fn sorts_by_earliest_day_then_precision() {
+        let mut dates = [
+            ChronoDate::undated("sometime"),
+            parsed("2024"),
+            parsed("2024-03"),
+            parsed("2024-03-01T09:00"),
+            parsed("2024-03-01"),
+            parsed("2023-12-31"),
+        ];
+        dates.sort();
+        let labels: Vec<String> = dates.iter().map(|d| d.to_string()).collect();
+        assert_eq!(
+            labels,
+            vec!["31 December 2023", "2024", "1 March 2024", "1 March 2024, 09:00", "March 2024", "sometime"]
+        );
+    }
+
+    #[test]
+    fn migrates_legacy_string_dates() {
+        let entry: ChronologyEntry = serde_json::from_str(
+            r#"{"id":"a","date":"2024-09","description":"x","source":"manual","document_id":null,
+                "source_document_path":null,"significance":"key","confidence":null}"#,
+        )
+        .unwrap();
+        assert_eq!(entry.date.precision, DatePrecision::Month);
+
+        let legacy: ChronoDate = serde_json::from_str("\"the week after\"").unwrap();
+        assert!(!legacy.is_dated());
+        assert_eq!(legacy.to_string(), "the week after");
+
+        let round_trip: ChronoDate = serde_json::from_str(&serde_json::to_string(&entry.date).unwrap()).unwrap();
+        assert_eq!(round_trip, entry.date);
+    }
+}
@@ -0,0 +1,107 @@
+use serde::{Deserialize, Serialize};
+
+/// A company's entry on the Companies House register
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CompanyRecord {
+    /// Eight characters, e.g. "01234567" or "SC123456"
+    pub company_number: String,
+    pub company_name: String,
+    /// Registered office, one line per address line
+    pub registered_office: Option<String>,
+    pub status: CompanyStatus,
+    /// The status as Companies House wrote it, e.g. "Active - Proposal to Strike off"
+    pub status_text: String,
+    /// e.g. "Private Limited Company"
+    pub company_type: Option<String>,
+    /// YYYY-MM-DD
+    pub incorporated_on: Option<String>,
+    /// YYYY-MM-DD
+    pub dissolved_on: Option<String>,
+    pub source: CompanySource,
+    /// When the record was looked up (RFC 3339)
+    pub retrieved_at: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CompanyStatus {
+    #[serde(rename = "active")]
+    Active,
+    /// Still registered but due to be struck off and dissolved
+    #[serde(rename = "proposal_to_strike_off")]
+    ProposalToStrikeOff,
+    #[serde(rename = "dissolved")]
+    Dissolved,
+    #[serde(rename = "liquidation")]
+    Liquidation,
+    #[serde(rename = "administration")]
+    Administration,
+    #[serde(rename = "receivership")]
+    Receivership,
+    #[serde(rename = "voluntary_arrangement")]
+    VoluntaryArrangement,
+    #[serde(rename = "insolvency_proceedings")]
+    InsolvencyProceedings,
+    /// Converted, closed or removed from the register
+    #[serde(rename = "closed")]
+    Closed,
+    #[serde(rename = "unknown")]
+    Unknown,
+}
+
+/// Where a company record came from
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CompanySource {
+    /// The free "Basic Company Data" download; holds the snapshot's file name
+    #[serde(rename = "bulk_snapshot")]
+    BulkSnapshot(String),
+    /// The Companies House public data API
+    #[serde(rename = "live_api")]
+    LiveApi,
+}
+
+impl CompanyStatus {
+    /// Read a status from the bulk data ("Active - Proposal to Strike off",
+    /// "In Administration") or the API ("voluntary-arrangement")
+    pub fn from_text(text: &str) -> Self {
+        let t = text.trim().to_lowercase().replace('-', " ");
+        if t.contains("strike off") {
+            CompanyStatus::ProposalToStrikeOff
+        } else if t.starts_with("dissolved") {
+            CompanyStatus::Dissolved
+        } else if t.contains("liquidation") {
+            CompanyStatus::Liquidation
+        } else if t.contains("administration") {
+            CompanyStatus::Administration
+        } else if t.contains("receiver") {
+            CompanyStatus::Receivership
+        } else if t.contains("voluntary arrangement") {
+            CompanyStatus::VoluntaryArrangement
+        } else if t.contains("insolvency") {
+            CompanyStatus::InsolvencyProceedings
+        } else if t.contains("closed") || t.starts_with("removed") {
+            CompanyStatus::Closed
+        } else if t.starts_with("active") || t == "open" || t == "registered" {
+            CompanyStatus::Active
+        } else {
+            CompanyStatus::Unknown
+        }
+    }
+
+    /// No longer exists, so can't be sued without being restored first
+    pub fn is_dissolved(self) -> bool {
+        matches!(self, CompanyStatus::Dissolved | CompanyStatus::Closed)
+    }
+
+    /// In a formal insolvency process; claims may be stayed or need the
+    /// court's or an office-holder's permission
+    pub fn is_insolvent(self) -> bool {
+        matches!(
+            self,
+            CompanyStatus::Liquidation
+                | CompanyStatus::Administration
+                | CompanyStatus::Receivership
+                | CompanyStatus::VoluntaryArrangement
+                | CompanyStatus::InsolvencyProceedings
+        )
+    }
+}
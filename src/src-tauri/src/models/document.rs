@@ -13,7 +13,14 @@ pub struct DocumentEntry {
     pub description: String,
     pub tags: Vec<String>,
     pub extracted_text: Option<String>,
+    /// Sent date from the file's own headers (RFC 3339), e.g. an email's Date
+    #[serde(default)]
+    pub metadata_date: Option<String>,
     pub added_at: String,
+    /// Set for files picked up automatically (e.g. from a watch-folder inbox)
+    /// until the user has reviewed their details
+    #[serde(default)]
+    pub pending_review: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -52,7 +59,9 @@ impl Default for DocumentEntry {
             description: String::new(),
             tags: Vec::new(),
             extracted_text: None,
+            metadata_date: None,
             added_at: chrono::Utc::now().to_rfc3339(),
+            pending_review: false,
         }
     }
 }
@@ -0,0 +1,90 @@
+use crate::ocr::OcrOptions;
+use serde::{Deserialize, Serialize};
+
+/// A unit of background work for a case, persisted in `.casekit/jobs.json`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Job {
+    pub id: String,
+    pub case_name: String,
+    pub kind: JobKind,
+    pub status: JobStatus,
+    /// 0.0 – 1.0
+    pub progress: f32,
+    /// Human-readable progress note, e.g. "Resolving 3 of 7 citations"
+    pub message: Option<String>,
+    /// Kind-specific output (extracted content, scanned dates, citation resolutions)
+    pub result: Option<serde_json::Value>,
+    pub error: Option<String>,
+    /// Number of times the job has been started (incremented on each run)
+    pub attempts: u32,
+    pub created_at: String,
+    pub started_at: Option<String>,
+    pub finished_at: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum JobKind {
+    /// Text extraction for a case file; falls back to OCR for scanned PDFs and images
+    #[serde(rename = "extraction")]
+    Extraction {
+        relative_path: String,
+        /// Document whose `extracted_text` should be updated with the result
+        document_id: Option<String>,
+        #[serde(default)]
+        ocr_options: Option<OcrOptions>,
+    },
+    /// Force OCR on a PDF or image, even if the PDF has a text layer
+    #[serde(rename = "ocr")]
+    Ocr {
+        relative_path: String,
+        document_id: Option<String>,
+        #[serde(default)]
+        ocr_options: Option<OcrOptions>,
+    },
+    /// Scan every document's extracted text for dates
+    #[serde(rename = "date_scan")]
+    DateScan,
+    /// Find neutral citations in a case file and resolve each against BAILII / FCL
+    #[serde(rename = "citation_audit")]
+    CitationAudit { relative_path: String },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum JobStatus {
+    #[serde(rename = "queued")]
+    Queued,
+    #[serde(rename = "running")]
+    Running,
+    #[serde(rename = "completed")]
+    Completed,
+    #[serde(rename = "failed")]
+    Failed,
+    #[serde(rename = "cancelled")]
+    Cancelled,
+}
+
+impl JobStatus {
+    pub fn is_finished(&self) -> bool {
+        matches!(self, JobStatus::Completed | JobStatus::Failed | JobStatus::Cancelled)
+    }
+}
+
+impl Job {
+    pub fn new(case_name: String, kind: JobKind) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            case_name,
+            kind,
+            status: JobStatus::Queued,
+            progress: 0.0,
+            message: None,
+            result: None,
+            error: None,
+            attempts: 0,
+            created_at: chrono::Utc::now().to_rfc3339(),
+            started_at: None,
+            finished_at: None,
+        }
+    }
+}
@@ -1,3 +1,8 @@
+pub mod bundle;
 pub mod case;
 pub mod chronology;
+pub mod company;
 pub mod document;
+pub mod job;
+pub mod money;
+pub mod party;
@@ -0,0 +1,159 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Sub};
+
+/// An amount of money in whole pence. Serialised as a plain integer so JSON
+/// stays readable and no rounding creeps in at fee band or track boundaries.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Money(i64);
+
+impl Money {
+    pub const ZERO: Money = Money(0);
+
+    pub const fn from_pence(pence: i64) -> Self {
+        Money(pence)
+    }
+
+    pub const fn from_pounds(pounds: i64) -> Self {
+        Money(pounds * 100)
+    }
+
+    pub const fn pence(self) -> i64 {
+        self.0
+    }
+
+    /// Convert a legacy floating-point pounds value, rounding to the nearest penny
+    pub fn from_pounds_f64(pounds: f64) -> Self {
+        Money((pounds * 100.0).round() as i64)
+    }
+
+    /// Parse user input such as "1,234.56", "£99" or "12.5"
+    pub fn parse(input: &str) -> Result<Self, String> {
+        let cleaned: String = input
+            .trim()
+            .trim_start_matches('£')
+            .chars()
+            .filter(|c| *c != ',' && !c.is_whitespace())
+            .collect();
+        let (negative, digits) = match cleaned.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, cleaned.as_str()),
+        };
+
+        let (whole, fraction) = digits.split_once('.').unwrap_or((digits, ""));
+        if (whole.is_empty() && fraction.is_empty())
+            || !whole.chars().all(|c| c.is_ascii_digit())
+            || !fraction.chars().all(|c| c.is_ascii_digit())
+            || fraction.len() > 2
+        {
+            return Err(format!("'{}' is not an amount of money", input));
+        }
+
+        let pounds: i64 = if whole.is_empty() {
+            0
+        } else {
+            whole
+                .parse()
+                .map_err(|_| format!("'{}' is too large", input))?
+        };
+        let pence: i64 = format!("{:0<2}", fraction).parse().unwrap_or(0);
+        let total = pounds
+            .checked_mul(100)
+            .and_then(|p| p.checked_add(pence))
+            .ok_or_else(|| format!("'{}' is too large", input))?;
+        Ok(Money(if negative { -total } else { total }))
+    }
+
+    /// Apply a percentage, rounding down to the penny (as court fees are)
+    pub fn percent_floor(self, percent: f64) -> Self {
+        Money((self.0 as f64 * percent / 100.0).floor() as i64)
+    }
+}
+
+impl fmt::Display for Money {
+    /// "£1,234.56", or "-£5.00" for negative amounts
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let abs = self.0.unsigned_abs();
+        let digits: Vec<char> = (abs / 100).to_string().chars().collect();
+        let groups: Vec<String> = digits
+            .rchunks(3)
+            .rev()
+            .map(|chunk| chunk.iter().collect())
+            .collect();
+        let sign = if self.0 < 0 { "-" } else { "" };
+        write!(f, "{}£{}.{:02}", sign, groups.join(","), abs % 100)
+    }
+}
+
+impl Add for Money {
+    type Output = Money;
+    fn add(self, other: Money) -> Money {
+        Money(self.0 + other.0)
+    }
+}
+
+impl AddAssign for Money {
+    fn add_assign(&mut self, other: Money) {
+        self.0 += other.0;
+    }
+}
+
+impl Sub for Money {
+    type Output = Money;
+    fn sub(self, other: Money) -> Money {
+        Money(self.0 - other.0)
+    }
+}
+
+impl Sum for Money {
+    fn sum<I: Iterator<Item = Money>>(iter: I) -> Money {
+        iter.fold(Money::ZERO, Add::add)
+    }
+}
+
+impl<'a> Sum<&'a Money> for Money {
+    fn sum<I: Iterator<Item = &'a Money>>(iter: I) -> Money {
+        iter.copied().sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_user_input() {
+        assert_eq!(Money::parse("1,234.56"), Ok(Money::from_pence(123_456)));
+        assert_eq!(Money::parse("£99"), Ok(Money::from_pence(9_900)));
+        assert_eq!(Money::parse("12.5"), Ok(Money::from_pence(1_250)));
+        assert_eq!(Money::parse(".75"), Ok(Money::from_pence(75)));
+        assert_eq!(Money::parse("-3"), Ok(Money::from_pence(-300)));
+        assert!(Money::parse("12.345").is_err());
+        assert!(Money::parse("abc").is_err());
+        assert!(Money::parse("").is_err());
+    }
+
+    #[test]
+    fn displays_with_grouping() {
+        assert_eq!(Money::from_pence(123_456).to_string(), "£1,234.56");
+        assert_eq!(Money::from_pence(5).to_string(), "£0.05");
+        assert_eq!(Money::from_pence(-500).to_string(), "-£5.00");
+        assert_eq!(Money::from_pounds(1_000_000).to_string(), "£1,000,000.00");
+    }
+
+    #[test]
+    fn legacy_floats_round_to_the_penny() {
+        // 0.1 + 0.2 style errors must not leak into pence
+        assert_eq!(Money::from_pounds_f64(0.1 + 0.2), Money::from_pence(30));
+        assert_eq!(Money::from_pounds_f64(10_000.004), Money::from_pounds(10_000));
+    }
+
+    #[test]
+    fn sums_and_percentages() {
+        let heads = [Money::from_pence(999), Money::from_pence(1)];
+        assert_eq!(heads.iter().sum::<Money>(), Money::from_pounds(10));
+        assert_eq!(Money::from_pence(1_234_567).percent_floor(5.0), Money::from_pence(61_728));
+    }
+}
@@ -0,0 +1,124 @@
+use crate::models::case::DefendantType;
+use crate::models::company::CompanyRecord;
+use serde::{Deserialize, Serialize};
+
+/// Someone on either side of the case, or involved in it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Party {
+    #[serde(default = "new_id")]
+    pub id: String,
+    pub role: PartyRole,
+    pub party_type: PartyType,
+    /// Full legal name, as it should appear on court forms
+    pub name: String,
+    /// Other names the party goes by: trading names, brands, former names,
+    /// the name of a customer services team
+    #[serde(default)]
+    pub aliases: Vec<String>,
+    /// Addresses the party writes from. An entry like "@acme.co.uk" covers
+    /// the whole domain.
+    #[serde(default)]
+    pub emails: Vec<String>,
+    /// Companies House registration number
+    #[serde(default)]
+    pub company_number: Option<String>,
+    /// Home address, or registered office for a company
+    #[serde(default)]
+    pub address: Option<String>,
+    /// Where documents should be sent, if not the address above (CPR 6.23)
+    #[serde(default)]
+    pub address_for_service: Option<String>,
+    #[serde(default)]
+    pub representative: Option<Representative>,
+    /// The party's entry on the Companies House register, when last checked
+    #[serde(default)]
+    pub companies_house: Option<CompanyRecord>,
+}
+
+/// A solicitor, or other person, acting for a party
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Representative {
+    pub name: String,
+    #[serde(default)]
+    pub firm: Option<String>,
+    /// Their reference for the case
+    #[serde(default)]
+    pub reference: Option<String>,
+    #[serde(default)]
+    pub email: Option<String>,
+    #[serde(default)]
+    pub phone: Option<String>,
+    #[serde(default)]
+    pub address: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PartyRole {
+    #[serde(rename = "claimant")]
+    Claimant,
+    #[serde(rename = "defendant")]
+    Defendant,
+    #[serde(rename = "third_party")]
+    ThirdParty,
+    #[serde(rename = "witness")]
+    Witness,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PartyType {
+    #[serde(rename = "individual")]
+    Individual,
+    #[serde(rename = "company")]
+    Company,
+    #[serde(rename = "sole_trader")]
+    SoleTrader,
+    #[serde(rename = "partnership")]
+    Partnership,
+    #[serde(rename = "other")]
+    Other,
+}
+
+fn new_id() -> String {
+    uuid::Uuid::new_v4().to_string()
+}
+
+impl Party {
+    pub fn new(role: PartyRole, party_type: PartyType, name: &str) -> Self {
+        Self {
+            id: new_id(),
+            role,
+            party_type,
+            name: name.trim().to_string(),
+            aliases: Vec::new(),
+            emails: Vec::new(),
+            company_number: None,
+            address: None,
+            address_for_service: None,
+            representative: None,
+            companies_house: None,
+        }
+    }
+
+    /// Where to send documents: the address for service if one was given,
+    /// then the representative's address, then the party's own address
+    pub fn service_address(&self) -> Option<&str> {
+        [
+            self.address_for_service.as_deref(),
+            self.representative.as_ref().and_then(|r| r.address.as_deref()),
+            self.address.as_deref(),
+        ]
+        .into_iter()
+        .flatten()
+        .find(|a| !a.trim().is_empty())
+    }
+}
+
+impl From<&DefendantType> for PartyType {
+    fn from(t: &DefendantType) -> Self {
+        match t {
+            DefendantType::Company => PartyType::Company,
+            DefendantType::Individual => PartyType::Individual,
+            DefendantType::SoleTrader => PartyType::SoleTrader,
+        }
+    }
+}
@@ -2,44 +2,427 @@
 //!
 //! Detects Tesseract installation at runtime, extracts embedded images
 //! from scanned PDFs, and OCRs them. Also handles direct image files.
+//!
+//! Tesseract is asked for TSV output so that every recognised word comes back
+//! with a confidence score. Runs of low-confidence words are grouped into
+//! regions the UI can highlight for the user to check by hand.
 
+use serde::{Deserialize, Serialize};
+use std::fs;
 use std::path::{Path, PathBuf};
 use std::process::Command;
-use std::fs;
 
-/// Check common Windows install paths for Tesseract
-fn find_tesseract() -> Option<PathBuf> {
-    let candidates = [
-        PathBuf::from(r"C:\Program Files\Tesseract-OCR\tesseract.exe"),
-        PathBuf::from(r"C:\Program Files (x86)\Tesseract-OCR\tesseract.exe"),
-    ];
+/// Optional image clean-up applied before the image is handed to Tesseract
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Preprocessing {
+    /// Straighten pages scanned at a slight angle (up to ±5°)
+    #[serde(default)]
+    pub deskew: bool,
+    /// Convert to pure black and white using an Otsu threshold
+    #[serde(default)]
+    pub binarise: bool,
+    /// Scale factor for small or low-DPI scans (e.g. 2.0). Values ≤ 1.0 are
+    /// ignored and values above 4.0 are treated as 4.0.
+    #[serde(default)]
+    pub upscale: Option<f32>,
+}
+
+/// Largest upscale applied, to keep the image's memory use in bounds
+const MAX_UPSCALE: f32 = 4.0;
+
+impl Preprocessing {
+    fn is_enabled(&self) -> bool {
+        self.deskew || self.binarise || self.upscale_factor().is_some()
+    }
+
+    /// The scale factor to apply, if any, capped at 4.0
+    fn upscale_factor(&self) -> Option<f32> {
+        self.upscale.filter(|f| *f > 1.0).map(|f| f.min(MAX_UPSCALE))
+    }
+}
+
+/// Options controlling how Tesseract is run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OcrOptions {
+    /// Tesseract language packs joined with '+', e.g. "eng" or "eng+cym" for Welsh documents
+    #[serde(default = "default_languages")]
+    pub languages: String,
+    /// Page segmentation mode (Tesseract `--psm`, 0–13). None uses Tesseract's default (3).
+    #[serde(default)]
+    pub page_segmentation_mode: Option<u8>,
+    #[serde(default)]
+    pub preprocessing: Preprocessing,
+    /// Words scoring below this confidence (0–100) are reported as low-confidence
+    #[serde(default = "default_low_confidence_threshold")]
+    pub low_confidence_threshold: f32,
+}
+
+fn default_languages() -> String {
+    "eng".to_string()
+}
+
+fn default_low_confidence_threshold() -> f32 {
+    60.0
+}
+
+impl Default for OcrOptions {
+    fn default() -> Self {
+        Self {
+            languages: default_languages(),
+            page_segmentation_mode: None,
+            preprocessing: Preprocessing::default(),
+            low_confidence_threshold: default_low_confidence_threshold(),
+        }
+    }
+}
+
+impl OcrOptions {
+    /// Reject option values that would be passed straight through to the Tesseract command line
+    fn validate(&self) -> Result<(), String> {
+        let valid_languages = !self.languages.is_empty()
+            && self
+                .languages
+                .split('+')
+                .all(|l| !l.is_empty() && l.chars().all(|c| c.is_ascii_alphanumeric() || c == '_'));
+        if !valid_languages {
+            return Err(format!("Invalid OCR language list: '{}'", self.languages));
+        }
+        if let Some(psm) = self.page_segmentation_mode {
+            if psm > 13 {
+                return Err(format!("Invalid page segmentation mode: {} (must be 0–13)", psm));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A run of adjacent words on one line that Tesseract was unsure about
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OcrRegion {
+    /// 1-based page (or image) number
+    pub page: u32,
+    pub text: String,
+    /// Mean confidence of the words in the region (0–100)
+    pub confidence: f32,
+    pub left: u32,
+    pub top: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Text and confidence information from an OCR run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OcrResult {
+    pub text: String,
+    /// Mean word confidence across the document (0–100), None if no words were recognised
+    pub mean_confidence: Option<f32>,
+    pub low_confidence_regions: Vec<OcrRegion>,
+}
+
+/// A Tesseract installation found on this machine
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TesseractInstall {
+    pub path: PathBuf,
+    /// First line of `tesseract --version`, e.g. "tesseract 5.3.0"
+    pub version: Option<String>,
+    /// Installed language packs from `tesseract --list-langs` (e.g. "eng", "cym", "osd")
+    pub languages: Vec<String>,
+}
+
+#[cfg(windows)]
+const EXECUTABLE: &str = "tesseract.exe";
+#[cfg(not(windows))]
+const EXECUTABLE: &str = "tesseract";
+
+/// Standard install locations for each platform, most likely first
+fn standard_locations() -> Vec<PathBuf> {
+    if cfg!(windows) {
+        vec![
+            PathBuf::from(r"C:\Program Files\Tesseract-OCR\tesseract.exe"),
+            PathBuf::from(r"C:\Program Files (x86)\Tesseract-OCR\tesseract.exe"),
+        ]
+    } else if cfg!(target_os = "macos") {
+        vec![
+            PathBuf::from("/opt/homebrew/bin/tesseract"), // Homebrew on Apple Silicon
+            PathBuf::from("/usr/local/bin/tesseract"),    // Homebrew on Intel
+            PathBuf::from("/opt/local/bin/tesseract"),    // MacPorts
+        ]
+    } else {
+        vec![
+            PathBuf::from("/usr/bin/tesseract"),
+            PathBuf::from("/usr/local/bin/tesseract"),
+            PathBuf::from("/snap/bin/tesseract"),
+            PathBuf::from("/home/linuxbrew/.linuxbrew/bin/tesseract"),
+        ]
+    }
+}
+
+/// A configured path may name the executable itself or the folder it lives in
+fn executable_in(path: &Path) -> PathBuf {
+    if path.is_dir() {
+        path.join(EXECUTABLE)
+    } else {
+        path.to_path_buf()
+    }
+}
+
+/// Candidate executables in priority order: user setting, TESSDATA_PREFIX,
+/// standard install locations. PATH is tried separately as a last resort.
+fn candidate_paths() -> Vec<PathBuf> {
+    let mut candidates = Vec::new();
+
+    if let Ok(settings) = crate::settings::load_settings() {
+        if let Some(configured) = settings.tesseract_path.filter(|p| !p.trim().is_empty()) {
+            candidates.push(executable_in(Path::new(configured.trim())));
+        }
+    }
+
+    // TESSDATA_PREFIX points at the tessdata folder; on Windows and in manual
+    // installs the executable sits beside it or in a sibling bin folder
+    if let Some(prefix) = std::env::var_os("TESSDATA_PREFIX") {
+        let tessdata = PathBuf::from(prefix);
+        for dir in tessdata.ancestors().skip(1).take(2) {
+            candidates.push(dir.join(EXECUTABLE));
+            candidates.push(dir.join("bin").join(EXECUTABLE));
+        }
+    }
+
+    candidates.extend(standard_locations());
+    candidates
+}
+
+/// Run `tesseract <flag>` and return its output. Older releases print the
+/// version to stderr, so fall back to that when stdout is empty.
+fn tesseract_output(path: &Path, flag: &str) -> Option<String> {
+    let output = Command::new(path).arg(flag).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+    if stdout.trim().is_empty() {
+        Some(String::from_utf8_lossy(&output.stderr).to_string())
+    } else {
+        Some(stdout)
+    }
+}
+
+fn parse_version(output: &str) -> Option<String> {
+    output
+        .lines()
+        .map(|l| l.trim())
+        .find(|l| !l.is_empty())
+        .map(|l| l.to_string())
+}
+
+/// Parse `--list-langs` output: a header line followed by one language per line
+fn parse_languages(output: &str) -> Vec<String> {
+    output
+        .lines()
+        .map(|l| l.trim())
+        .filter(|l| !l.is_empty() && !l.starts_with("List of available languages"))
+        .map(|l| l.to_string())
+        .collect()
+}
+
+fn probe(path: PathBuf) -> Option<TesseractInstall> {
+    let version = parse_version(&tesseract_output(&path, "--version")?);
+    let languages = tesseract_output(&path, "--list-langs")
+        .map(|o| parse_languages(&o))
+        .unwrap_or_default();
+    Some(TesseractInstall {
+        path,
+        version,
+        languages,
+    })
+}
+
+/// Locate Tesseract and report its version and installed language packs.
+///
+/// Checked in order: the path set in app settings, TESSDATA_PREFIX, the
+/// standard Windows / Homebrew / Linux locations, then PATH.
+pub fn discover_tesseract() -> Option<TesseractInstall> {
+    candidate_paths()
+        .into_iter()
+        .filter(|p| p.is_file())
+        .find_map(probe)
+        .or_else(|| probe(PathBuf::from("tesseract")))
+}
+
+/// Platform-appropriate installation instructions
+pub fn install_url() -> &'static str {
+    if cfg!(windows) {
+        "https://github.com/UB-Mannheim/tesseract/wiki"
+    } else {
+        "https://tesseract-ocr.github.io/tessdoc/Installation.html"
+    }
+}
+
+fn not_installed_message(what: &str) -> String {
+    format!(
+        "Tesseract OCR is not installed. To extract text from {}, \
+         install Tesseract from {} — CaseKit will detect it automatically, \
+         or you can set its location in Settings.",
+        what,
+        install_url()
+    )
+}
+
+/// Make sure every requested language pack is installed, so a missing pack
+/// gives a clear message rather than a Tesseract error
+fn check_languages(install: &TesseractInstall, options: &OcrOptions) -> Result<(), String> {
+    if install.languages.is_empty() {
+        return Ok(());
+    }
+    let missing: Vec<&str> = options
+        .languages
+        .split('+')
+        .filter(|l| !install.languages.iter().any(|i| i == l))
+        .collect();
+    if missing.is_empty() {
+        Ok(())
+    } else {
+        Err(format!(
+            "Tesseract language pack(s) not installed: {}. Installed: {}",
+            missing.join(", "),
+            install.languages.join(", ")
+        ))
+    }
+}
+
+/// A single word parsed from Tesseract TSV output
+#[derive(Debug, Clone)]
+struct TsvWord {
+    block: u32,
+    par: u32,
+    line: u32,
+    left: u32,
+    top: u32,
+    width: u32,
+    height: u32,
+    conf: f32,
+    text: String,
+}
+
+/// Parse Tesseract TSV output into words, skipping the header and non-word rows.
+///
+/// Columns: level page_num block_num par_num line_num word_num left top width height conf text
+fn parse_tsv(tsv: &str) -> Vec<TsvWord> {
+    let mut words = Vec::new();
+
+    for line in tsv.lines().skip(1) {
+        let cols: Vec<&str> = line.splitn(12, '\t').collect();
+        if cols.len() < 12 || cols[0] != "5" {
+            continue;
+        }
+
+        let text = cols[11].trim();
+        let conf: f32 = cols[10].parse().unwrap_or(-1.0);
+        if text.is_empty() || conf < 0.0 {
+            continue;
+        }
+
+        let num = |i: usize| cols[i].parse::<u32>().unwrap_or(0);
+        words.push(TsvWord {
+            block: num(2),
+            par: num(3),
+            line: num(4),
+            left: num(6),
+            top: num(7),
+            width: num(8),
+            height: num(9),
+            conf,
+            text: text.to_string(),
+        });
+    }
+
+    words
+}
 
-    // Check known paths first (faster than spawning a process)
-    for path in &candidates {
-        if path.exists() {
-            return Some(path.clone());
+/// Rebuild plain text from TSV words: spaces within a line, newlines between
+/// lines, and a blank line between paragraphs or blocks.
+fn words_to_text(words: &[TsvWord]) -> String {
+    let mut text = String::new();
+    let mut prev: Option<&TsvWord> = None;
+
+    for w in words {
+        if let Some(p) = prev {
+            if p.block != w.block || p.par != w.par {
+                text.push_str("\n\n");
+            } else if p.line != w.line {
+                text.push('\n');
+            } else {
+                text.push(' ');
+            }
         }
+        text.push_str(&w.text);
+        prev = Some(w);
     }
 
-    // Also check if it's on PATH
-    if let Ok(output) = Command::new("tesseract").arg("--version").output() {
-        if output.status.success() {
-            return Some(PathBuf::from("tesseract"));
+    text
+}
+
+/// Group consecutive low-confidence words on the same line into regions
+fn low_confidence_regions(words: &[TsvWord], page: u32, threshold: f32) -> Vec<OcrRegion> {
+    let mut regions = Vec::new();
+    let mut current: Vec<&TsvWord> = Vec::new();
+
+    let flush = |current: &mut Vec<&TsvWord>, regions: &mut Vec<OcrRegion>| {
+        if current.is_empty() {
+            return;
+        }
+        let left = current.iter().map(|w| w.left).min().unwrap_or(0);
+        let top = current.iter().map(|w| w.top).min().unwrap_or(0);
+        let right = current.iter().map(|w| w.left + w.width).max().unwrap_or(0);
+        let bottom = current.iter().map(|w| w.top + w.height).max().unwrap_or(0);
+        let confidence = current.iter().map(|w| w.conf).sum::<f32>() / current.len() as f32;
+        regions.push(OcrRegion {
+            page,
+            text: current.iter().map(|w| w.text.as_str()).collect::<Vec<_>>().join(" "),
+            confidence,
+            left,
+            top,
+            width: right - left,
+            height: bottom - top,
+        });
+        current.clear();
+    };
+
+    for w in words {
+        let same_line = current
+            .last()
+            .map(|p| p.block == w.block && p.par == w.par && p.line == w.line)
+            .unwrap_or(true);
+        if w.conf >= threshold || !same_line {
+            flush(&mut current, &mut regions);
+        }
+        if w.conf < threshold {
+            current.push(w);
         }
     }
+    flush(&mut current, &mut regions);
 
-    None
+    regions
 }
 
-/// Run Tesseract OCR on an image file and return the extracted text
-fn run_tesseract(tesseract_path: &Path, image_path: &Path) -> Result<String, String> {
+/// Run Tesseract OCR on an image file and return the parsed TSV words
+fn run_tesseract(
+    tesseract_path: &Path,
+    image_path: &Path,
+    options: &OcrOptions,
+) -> Result<Vec<TsvWord>, String> {
     let output_base = image_path.with_extension("ocr_out");
 
-    let result = Command::new(tesseract_path)
-        .arg(image_path)
+    let mut cmd = Command::new(tesseract_path);
+    cmd.arg(image_path)
         .arg(&output_base)
         .arg("-l")
-        .arg("eng")
+        .arg(&options.languages);
+    if let Some(psm) = options.page_segmentation_mode {
+        cmd.arg("--psm").arg(psm.to_string());
+    }
+    cmd.arg("tsv");
+
+    let result = cmd
         .output()
         .map_err(|e| format!("Failed to run Tesseract: {}", e))?;
 
@@ -48,42 +431,212 @@ fn run_tesseract(tesseract_path: &Path, image_path: &Path) -> Result<String, Str
         return Err(format!("Tesseract failed: {}", stderr));
     }
 
-    // Tesseract writes output to {output_base}.txt
-    let txt_path = output_base.with_extension("txt");
-    let text = fs::read_to_string(&txt_path)
+    // Tesseract writes output to {output_base}.tsv
+    let tsv_path = output_base.with_extension("tsv");
+    let tsv = fs::read_to_string(&tsv_path)
         .map_err(|e| format!("Could not read Tesseract output: {}", e))?;
 
     // Clean up the output file
-    let _ = fs::remove_file(&txt_path);
+    let _ = fs::remove_file(&tsv_path);
+
+    Ok(parse_tsv(&tsv))
+}
+
+// ── Preprocessing ───────────────────────────────────────────────────────────
+
+/// Otsu's method: pick the grey level that best separates ink from paper
+fn otsu_threshold(img: &image::GrayImage) -> u8 {
+    let mut histogram = [0u64; 256];
+    for p in img.pixels() {
+        histogram[p[0] as usize] += 1;
+    }
+
+    let total: u64 = histogram.iter().sum();
+    let sum_all: f64 = histogram
+        .iter()
+        .enumerate()
+        .map(|(i, &c)| i as f64 * c as f64)
+        .sum();
+
+    let mut sum_bg = 0.0;
+    let mut weight_bg = 0u64;
+    let mut best = (0u8, 0.0f64);
+
+    for (t, &count) in histogram.iter().enumerate() {
+        weight_bg += count;
+        if weight_bg == 0 {
+            continue;
+        }
+        let weight_fg = total - weight_bg;
+        if weight_fg == 0 {
+            break;
+        }
+        sum_bg += t as f64 * count as f64;
+        let mean_bg = sum_bg / weight_bg as f64;
+        let mean_fg = (sum_all - sum_bg) / weight_fg as f64;
+        let between = weight_bg as f64 * weight_fg as f64 * (mean_bg - mean_fg).powi(2);
+        if between > best.1 {
+            best = (t as u8, between);
+        }
+    }
+
+    best.0
+}
+
+fn binarise(img: &mut image::GrayImage) {
+    let threshold = otsu_threshold(img);
+    for p in img.pixels_mut() {
+        p[0] = if p[0] > threshold { 255 } else { 0 };
+    }
+}
+
+/// Estimate skew by shearing the page at candidate angles and picking the one
+/// whose row-by-row ink profile is most sharply peaked (text lines aligned).
+fn estimate_skew_degrees(img: &image::GrayImage) -> f32 {
+    let threshold = otsu_threshold(img);
+    let (w, h) = img.dimensions();
+    let step = ((w.max(h) / 800).max(1)) as usize;
+
+    let ink: Vec<(f32, f32)> = (0..h)
+        .step_by(step)
+        .flat_map(|y| (0..w).step_by(step).map(move |x| (x, y)))
+        .filter(|&(x, y)| img.get_pixel(x, y)[0] <= threshold)
+        .map(|(x, y)| (x as f32, y as f32))
+        .collect();
+
+    if ink.is_empty() {
+        return 0.0;
+    }
+
+    let mut best = (0.0f32, f64::MIN);
+    let mut angle = -5.0f32;
+    while angle <= 5.0 {
+        let tan = angle.to_radians().tan();
+        let mut rows = vec![0u32; h as usize + w as usize];
+        let offset = w as f32 * tan.abs();
+        for &(x, y) in &ink {
+            let row = (y - x * tan + offset) as usize;
+            if let Some(r) = rows.get_mut(row / step) {
+                *r += 1;
+            }
+        }
+        let score: f64 = rows.iter().map(|&r| (r as f64).powi(2)).sum();
+        if score > best.1 {
+            best = (angle, score);
+        }
+        angle += 0.25;
+    }
+
+    best.0
+}
+
+/// Rotate about the centre (nearest neighbour), filling uncovered corners with white
+fn rotate(img: &image::GrayImage, degrees: f32) -> image::GrayImage {
+    let (w, h) = img.dimensions();
+    let (sin, cos) = degrees.to_radians().sin_cos();
+    let (cx, cy) = (w as f32 / 2.0, h as f32 / 2.0);
+
+    image::GrayImage::from_fn(w, h, |x, y| {
+        let dx = x as f32 - cx;
+        let dy = y as f32 - cy;
+        let sx = cos * dx + sin * dy + cx;
+        let sy = -sin * dx + cos * dy + cy;
+        if sx >= 0.0 && sy >= 0.0 && (sx as u32) < w && (sy as u32) < h {
+            *img.get_pixel(sx as u32, sy as u32)
+        } else {
+            image::Luma([255])
+        }
+    })
+}
+
+/// Apply the requested preprocessing and write the result next to the source as PNG.
+/// Returns None when no preprocessing is requested or the format can't be decoded
+/// (e.g. JPEG 2000), in which case the original image is OCR'd as-is.
+fn preprocess_image(image_path: &Path, prep: &Preprocessing) -> Option<PathBuf> {
+    if !prep.is_enabled() {
+        return None;
+    }
+
+    let mut img = image::open(image_path).ok()?.to_luma8();
+
+    if let Some(factor) = prep.upscale_factor() {
+        let (w, h) = img.dimensions();
+        img = image::imageops::resize(
+            &img,
+            (w as f32 * factor) as u32,
+            (h as f32 * factor) as u32,
+            image::imageops::FilterType::CatmullRom,
+        );
+    }
+
+    if prep.deskew {
+        let skew = estimate_skew_degrees(&img);
+        if skew.abs() >= 0.25 {
+            img = rotate(&img, -skew);
+        }
+    }
 
-    Ok(text.trim().to_string())
+    if prep.binarise {
+        binarise(&mut img);
+    }
+
+    let out_path = image_path.with_extension("prep.png");
+    img.save(&out_path).ok()?;
+    Some(out_path)
+}
+
+/// Preprocess (if requested) and OCR one image, returning its words
+fn ocr_single_image(
+    tesseract: &Path,
+    image_path: &Path,
+    options: &OcrOptions,
+) -> Result<Vec<TsvWord>, String> {
+    let prepared = preprocess_image(image_path, &options.preprocessing);
+    let words = run_tesseract(tesseract, prepared.as_deref().unwrap_or(image_path), options);
+    if let Some(p) = prepared {
+        let _ = fs::remove_file(p);
+    }
+    words
+}
+
+fn mean_confidence(words: &[TsvWord]) -> Option<f32> {
+    if words.is_empty() {
+        None
+    } else {
+        Some(words.iter().map(|w| w.conf).sum::<f32>() / words.len() as f32)
+    }
 }
 
 /// OCR a direct image file (jpg, png, bmp, tiff, gif)
-pub fn ocr_image_file(image_path: &Path) -> Result<String, String> {
-    let tesseract = find_tesseract().ok_or_else(|| {
-        "Tesseract OCR is not installed. To extract text from scanned documents and images, \
-         install Tesseract from https://github.com/UB-Mannheim/tesseract/wiki — \
-         CaseKit will detect it automatically."
-            .to_string()
-    })?;
+pub fn ocr_image_file(image_path: &Path, options: &OcrOptions) -> Result<OcrResult, String> {
+    options.validate()?;
+
+    let install = discover_tesseract()
+        .ok_or_else(|| not_installed_message("scanned documents and images"))?;
+    check_languages(&install, options)?;
+
+    let words = ocr_single_image(&install.path, image_path, options)?;
 
-    run_tesseract(&tesseract, image_path)
+    Ok(OcrResult {
+        text: words_to_text(&words),
+        mean_confidence: mean_confidence(&words),
+        low_confidence_regions: low_confidence_regions(&words, 1, options.low_confidence_threshold),
+    })
 }
 
 /// Extract embedded images from a scanned PDF using lopdf, OCR each, and combine
-pub fn ocr_scanned_pdf(pdf_path: &Path) -> Result<String, String> {
-    let tesseract = find_tesseract().ok_or_else(|| {
-        "Tesseract OCR is not installed. To extract text from scanned PDFs, \
-         install Tesseract from https://github.com/UB-Mannheim/tesseract/wiki — \
-         CaseKit will detect it automatically."
-            .to_string()
-    })?;
+pub fn ocr_scanned_pdf(pdf_path: &Path, options: &OcrOptions) -> Result<OcrResult, String> {
+    options.validate()?;
+
+    let install = discover_tesseract().ok_or_else(|| not_installed_message("scanned PDFs"))?;
+    check_languages(&install, options)?;
 
     let doc = lopdf::Document::load(pdf_path)
         .map_err(|e| format!("Could not load PDF for image extraction: {}", e))?;
 
     let mut all_text = Vec::new();
+    let mut all_words: Vec<TsvWord> = Vec::new();
+    let mut regions = Vec::new();
     let temp_dir = std::env::temp_dir().join("casekit_ocr");
     let _ = fs::create_dir_all(&temp_dir);
 
@@ -136,9 +689,15 @@ pub fn ocr_scanned_pdf(pdf_path: &Path) -> Result<String, String> {
         }
 
         // OCR the image
-        if let Ok(text) = run_tesseract(&tesseract, &img_path) {
-            if !text.is_empty() {
-                all_text.push(text);
+        if let Ok(words) = ocr_single_image(&install.path, &img_path, options) {
+            if !words.is_empty() {
+                all_text.push(words_to_text(&words));
+                regions.extend(low_confidence_regions(
+                    &words,
+                    image_count + 1,
+                    options.low_confidence_threshold,
+                ));
+                all_words.extend(words);
             }
         }
 
@@ -158,10 +717,113 @@ pub fn ocr_scanned_pdf(pdf_path: &Path) -> Result<String, String> {
         return Err("OCR ran on images but no text was recognised.".to_string());
     }
 
-    Ok(all_text.join("\n\n---\n\n"))
+    Ok(OcrResult {
+        text: all_text.join("\n\n---\n\n"),
+        mean_confidence: mean_confidence(&all_words),
+        low_confidence_regions: regions,
+    })
 }
 
 /// Check if Tesseract is available on this system
 pub fn is_tesseract_available() -> bool {
-    find_tesseract().is_some()
+    discover_tesseract().is_some()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_TSV: &str = "level\tpage_num\tblock_num\tpar_num\tline_num\tword_num\tleft\ttop\twidth\theight\tconf\ttext
+1\t1\t0\t0\t0\t0\t0\t0\t1000\t1000\t-1\t
+4\t1\t1\t1\t1\t0\t10\t10\t300\t20\t-1\t
+5\t1\t1\t1\t1\t1\t10\t10\t80\t20\t96.5\tInvoice
+5\t1\t1\t1\t1\t2\t100\t10\t60\t20\t42.0\tN0.
+5\t1\t1\t1\t1\t3\t170\t10\t60\t20\t38.0\t4Z1
+5\t1\t1\t1\t2\t1\t10\t40\t80\t20\t91.0\tDated
+5\t1\t1\t1\t2\t2\t100\t40\t90\t20\t89.0\t15/03/2024
+5\t1\t2\t1\t1\t1\t10\t90\t50\t20\t55.0\tTotal
+";
+
+    #[test]
+    fn upscale_is_capped_at_four() {
+        let prep = |upscale| Preprocessing { upscale, ..Preprocessing::default() };
+        assert_eq!(prep(Some(2.0)).upscale_factor(), Some(2.0));
+        assert_eq!(prep(Some(8.0)).upscale_factor(), Some(4.0));
+        assert_eq!(prep(Some(1.0)).upscale_factor(), None);
+        assert!(prep(Some(8.0)).is_enabled());
+        assert!(!prep(None).is_enabled());
+    }
+
+    #[test]
+    fn parses_word_rows_only() {
+        let words = parse_tsv(SAMPLE_TSV);
+        assert_eq!(words.len(), 6);
+        assert_eq!(words[0].text, "Invoice");
+        assert_eq!(words[0].conf, 96.5);
+    }
+
+    #[test]
+    fn rebuilds_lines_and_paragraphs() {
+        let words = parse_tsv(SAMPLE_TSV);
+        assert_eq!(words_to_text(&words), "Invoice N0. 4Z1\nDated 15/03/2024\n\nTotal");
+    }
+
+    #[test]
+    fn groups_adjacent_low_confidence_words() {
+        let words = parse_tsv(SAMPLE_TSV);
+        let regions = low_confidence_regions(&words, 1, 60.0);
+        assert_eq!(regions.len(), 2);
+        assert_eq!(regions[0].text, "N0. 4Z1");
+        assert_eq!(regions[0].left, 100);
+        assert_eq!(regions[0].width, 130);
+        assert_eq!(regions[0].confidence, 40.0);
+        assert_eq!(regions[1].text, "Total");
+    }
+
+    #[test]
+    fn rejects_unsafe_language_lists() {
+        let mut options = OcrOptions::default();
+        assert!(options.validate().is_ok());
+        options.languages = "eng+cym".to_string();
+        assert!(options.validate().is_ok());
+        options.languages = "eng --user-words /etc/passwd".to_string();
+        assert!(options.validate().is_err());
+        options.languages = "eng+".to_string();
+        assert!(options.validate().is_err());
+    }
+
+    #[test]
+    fn parses_version_and_languages() {
+        assert_eq!(
+            parse_version("tesseract 5.3.0\n leptonica-1.82.0\n").as_deref(),
+            Some("tesseract 5.3.0")
+        );
+        let langs = parse_languages(
+            "List of available languages in \"/usr/share/tesseract-ocr/5/tessdata/\" (3):\ncym\neng\nosd\n",
+        );
+        assert_eq!(langs, vec!["cym", "eng", "osd"]);
+    }
+
+    #[test]
+    fn reports_missing_language_packs() {
+        let install = TesseractInstall {
+            path: PathBuf::from("tesseract"),
+            version: None,
+            languages: vec!["eng".to_string(), "osd".to_string()],
+        };
+        let mut options = OcrOptions::default();
+        assert!(check_languages(&install, &options).is_ok());
+        options.languages = "eng+cym".to_string();
+        let err = check_languages(&install, &options).unwrap_err();
+        assert!(err.contains("cym"));
+    }
+
+    #[test]
+    fn otsu_separates_ink_from_paper() {
+        let img = image::GrayImage::from_fn(10, 10, |x, _| {
+            if x < 3 { image::Luma([20]) } else { image::Luma([230]) }
+        });
+        let t = otsu_threshold(&img);
+        assert!((20..230).contains(&t));
+    }
 }
@@ -0,0 +1,307 @@
+//! Linking correspondence to the parties in a case.
+//!
+//! Documents record who they are from and to as free text: "Acme Customer
+//! Services <care@acme.co.uk>", "J Smith", "acme appliances limited". Each
+//! name and address in those fields, or in the From:/To:/Cc: lines of an
+//! email's text, is matched against the parties' names, aliases and email
+//! addresses so correspondence can be filtered by party.
+
+use crate::models::document::DocumentEntry;
+use crate::models::party::{Party, PartyType};
+use serde::{Deserialize, Serialize};
+
+/// The parties a document is from and to, by party id
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DocumentParties {
+    pub document_id: String,
+    pub from: Vec<String>,
+    pub to: Vec<String>,
+}
+
+/// One name and/or address from a From or To field
+#[derive(Debug, Clone, PartialEq)]
+pub struct Correspondent {
+    pub name: Option<String>,
+    pub email: Option<String>,
+}
+
+/// Words that don't tell one business from another
+const NOISE_WORDS: &[&str] = &[
+    "the", "ltd", "limited", "plc", "llp", "llc", "inc", "co", "company", "group", "uk", "t", "a", "trading", "as",
+];
+
+/// Free email providers: sharing one of these domains says nothing about who
+/// the sender is
+const WEBMAIL_DOMAINS: &[&str] = &[
+    "gmail.com", "googlemail.com", "hotmail.com", "hotmail.co.uk", "outlook.com", "live.com", "live.co.uk",
+    "msn.com", "yahoo.com", "yahoo.co.uk", "icloud.com", "me.com", "aol.com", "btinternet.com", "sky.com",
+    "virginmedia.com", "talktalk.net", "protonmail.com", "proton.me",
+];
+
+/// Shortest normalised name that may match inside a longer one, so "Acme"
+/// finds "Acme Customer Services" but "Jo" doesn't find "Jo Bloggs Ltd"
+const MIN_CONTAINED_LEN: usize = 4;
+
+/// Lowercase words with punctuation and company suffixes removed:
+/// "Acme Appliances Ltd." and "ACME APPLIANCES LIMITED" both become
+/// "acme appliances"
+pub fn normalise_name(name: &str) -> String {
+    name.to_lowercase()
+        .replace('&', " and ")
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty() && !NOISE_WORDS.contains(w))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn clean_email(s: &str) -> Option<String> {
+    let s = s.trim().trim_matches(|c: char| "<>()[]\"',;:".contains(c)).trim_start_matches("mailto:");
+    let (local, domain) = s.split_once('@')?;
+    (!local.is_empty() && domain.contains('.')).then(|| s.to_lowercase())
+}
+
+fn domain_of(email: &str) -> &str {
+    email.rsplit_once('@').map(|(_, d)| d).unwrap_or("")
+}
+
+/// Split a From/To field into the people in it. Recipients are separated by
+/// semicolons, or by commas when each part has its own address.
+pub fn correspondents(field: &str) -> Vec<Correspondent> {
+    let parts: Vec<&str> = if field.contains(';') || field.matches('@').count() > 1 {
+        field.split([';', ',']).collect()
+    } else {
+        vec![field]
+    };
+
+    let mut found: Vec<Correspondent> = Vec::new();
+    for part in parts {
+        let email = part
+            .split(|c: char| c.is_whitespace() || c == '<' || c == '>')
+            .find_map(clean_email);
+        let name = match part.find('<') {
+            Some(at) => part[..at].to_string(),
+            None if email.is_some() => String::new(),
+            None => part.to_string(),
+        };
+        let name = name.trim().trim_matches(|c: char| c == '"' || c == '\'').trim();
+        let name = (!name.is_empty()).then(|| name.to_string());
+        if name.is_some() || email.is_some() {
+            found.push(Correspondent { name, email });
+        } else if let Some(previous) = found.last_mut() {
+            // "Smith, John <js@example.com>" split at the comma
+            previous.email = previous.email.take().or(email);
+        }
+    }
+    found
+}
+
+/// How well a party fits a correspondent; 0 for not at all
+fn score(party: &Party, who: &Correspondent) -> u8 {
+    let mut best = 0;
+
+    if let Some(email) = &who.email {
+        let domain = domain_of(email);
+        for known in &party.emails {
+            let known = known.trim().to_lowercase();
+            if known == *email {
+                return 4;
+            }
+            let known_domain = known.strip_prefix('@').unwrap_or_else(|| domain_of(&known));
+            let organisation = !matches!(party.party_type, PartyType::Individual);
+            if known_domain == domain
+                && !WEBMAIL_DOMAINS.contains(&domain)
+                && (known.starts_with('@') || organisation)
+            {
+                best = best.max(2);
+            }
+        }
+        if let Some(rep) = party.representative.as_ref().and_then(|r| r.email.as_deref()) {
+            if rep.trim().eq_ignore_ascii_case(email) {
+                best = best.max(3);
+            }
+        }
+        // noreply@acmeappliances.co.uk for "Acme Appliances Ltd"
+        if !WEBMAIL_DOMAINS.contains(&domain) && !matches!(party.party_type, PartyType::Individual) {
+            let label = domain.split('.').next().unwrap_or("");
+            let fits = names(party).any(|n| {
+                let joined = n.replace(' ', "");
+                joined.len() >= MIN_CONTAINED_LEN && joined == label
+            });
+            if fits {
+                best = best.max(2);
+            }
+        }
+    }
+
+    if let Some(name) = who.name.as_deref().map(normalise_name).filter(|n| !n.is_empty()) {
+        for known in names(party) {
+            if known == name {
+                best = best.max(3);
+            } else if contains_words(&name, &known) || contains_words(&known, &name) {
+                best = best.max(1);
+            }
+        }
+    }
+    best
+}
+
+/// The party's name and aliases, normalised
+fn names(party: &Party) -> impl Iterator<Item = String> + '_ {
+    std::iter::once(&party.name)
+        .chain(&party.aliases)
+        .map(|n| normalise_name(n))
+        .filter(|n| !n.is_empty())
+}
+
+/// Whether `needle` appears in `haystack` as whole words
+pub(crate) fn contains_words(haystack: &str, needle: &str) -> bool {
+    needle.len() >= MIN_CONTAINED_LEN
+        && format!(" {} ", haystack).contains(&format!(" {} ", needle))
+}
+
+/// The best-fitting party for a correspondent, if any fits. Ties go to the
+/// party listed first.
+pub fn match_party<'a>(parties: &'a [Party], who: &Correspondent) -> Option<&'a Party> {
+    let mut best: Option<(&Party, u8)> = None;
+    for party in parties {
+        let s = score(party, who);
+        if s > 0 && best.is_none_or(|(_, b)| s > b) {
+            best = Some((party, s));
+        }
+    }
+    best.map(|(p, _)| p)
+}
+
+/// Ids of the parties named in a From/To field, in the order they appear
+pub fn match_field(parties: &[Party], field: &str) -> Vec<String> {
+    let mut ids: Vec<String> = Vec::new();
+    for who in correspondents(field) {
+        if let Some(party) = match_party(parties, &who) {
+            if !ids.contains(&party.id) {
+                ids.push(party.id.clone());
+            }
+        }
+    }
+    ids
+}
+
+/// The value of an email header ("From", "To", "Cc") near the top of the
+/// extracted text
+pub fn header(text: &str, name: &str) -> Option<String> {
+    text.lines().take(40).find_map(|line| {
+        let (key, value) = line.trim().split_once(':')?;
+        (key.trim().eq_ignore_ascii_case(name) && !value.trim().is_empty()).then(|| value.trim().to_string())
+    })
+}
+
+/// Link a document to parties using its From/To fields, falling back to the
+/// headers in its text when those are blank
+pub fn link_document(parties: &[Party], doc: &DocumentEntry) -> DocumentParties {
+    let text = doc.extracted_text.as_deref().unwrap_or("");
+    let field = |value: &Option<String>, headers: &[&str]| -> String {
+        match value.as_deref().map(str::trim).filter(|v| !v.is_empty()) {
+            Some(v) => v.to_string(),
+            None => headers
+                .iter()
+                .filter_map(|h| header(text, h))
+                .collect::<Vec<_>>()
+                .join("; "),
+        }
+    };
+
+    DocumentParties {
+        document_id: doc.id.clone(),
+        from: match_field(parties, &field(&doc.from, &["From"])),
+        to: match_field(parties, &field(&doc.to, &["To", "Cc"])),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::party::{PartyRole, Representative};
+
+    fn parties() -> Vec<Party> {
+        let mut claimant = Party::new(PartyRole::Claimant, PartyType::Individual, "Jane Smith");
+        claimant.id = "jane".to_string();
+        claimant.emails = vec!["jane.smith@gmail.com".to_string()];
+
+        let mut acme = Party::new(PartyRole::Defendant, PartyType::Company, "Acme Appliances Ltd");
+        acme.id = "acme".to_string();
+        acme.aliases = vec!["Acme Customer Care".to_string()];
+        acme.emails = vec!["care@acme.co.uk".to_string()];
+        acme.representative = Some(Representative {
+            name: "P Jones".to_string(),
+            firm: Some("Jones & Co Solicitors".to_string()),
+            email: Some("pjones@joneslaw.co.uk".to_string()),
+            ..Representative::default()
+        });
+
+        let mut fitter = Party::new(PartyRole::Defendant, PartyType::SoleTrader, "Bob Brown");
+        fitter.id = "bob".to_string();
+        fitter.aliases = vec!["Brown's Kitchens".to_string()];
+        fitter.emails = vec!["@brownskitchens.com".to_string()];
+
+        vec![claimant, acme, fitter]
+    }
+
+    #[test]
+    fn normalises_company_names() {
+        assert_eq!(normalise_name("Acme Appliances Ltd."), "acme appliances");
+        assert_eq!(normalise_name("ACME APPLIANCES LIMITED"), "acme appliances");
+        assert_eq!(normalise_name("Bob Brown t/a Brown's Kitchens"), "bob brown brown s kitchens");
+    }
+
+    #[test]
+    fn splits_fields_into_correspondents() {
+        assert_eq!(
+            correspondents("\"Acme Care\" <care@acme.co.uk>"),
+            vec![Correspondent { name: Some("Acme Care".to_string()), email: Some("care@acme.co.uk".to_string()) }]
+        );
+        assert_eq!(correspondents("Smith, Jane").len(), 1);
+        assert_eq!(correspondents("a@x.co.uk, Bob <b@y.com>").len(), 2);
+        assert_eq!(correspondents("Jane Smith; Bob Brown").len(), 2);
+    }
+
+    #[test]
+    fn matches_emails_before_names() {
+        let parties = parties();
+        assert_eq!(match_field(&parties, "Customer Care <care@acme.co.uk>"), vec!["acme"]);
+        assert_eq!(match_field(&parties, "jane.smith@gmail.com"), vec!["jane"]);
+        assert_eq!(match_field(&parties, "pjones@joneslaw.co.uk"), vec!["acme"]);
+        // The same webmail domain isn't enough
+        assert!(match_field(&parties, "someone.else@gmail.com").is_empty());
+        // Domain aliases and company domains
+        assert_eq!(match_field(&parties, "quotes@brownskitchens.com"), vec!["bob"]);
+        assert_eq!(match_field(&parties, "returns@acme.co.uk"), vec!["acme"]);
+        assert_eq!(match_field(&parties, "noreply@acmeappliances.co.uk"), vec!["acme"]);
+    }
+
+    #[test]
+    fn matches_names_and_aliases() {
+        let parties = parties();
+        assert_eq!(match_field(&parties, "ACME APPLIANCES LIMITED"), vec!["acme"]);
+        assert_eq!(match_field(&parties, "Acme Customer Care Team"), vec!["acme"]);
+        assert_eq!(match_field(&parties, "Brown's Kitchens"), vec!["bob"]);
+        assert_eq!(match_field(&parties, "Ms Jane Smith; Acme Appliances"), vec!["jane", "acme"]);
+        assert!(match_field(&parties, "Smithson Ltd").is_empty());
+    }
+
+    #[test]
+    fn falls_back_to_email_headers() {
+        let doc = DocumentEntry {
+            id: "d1".to_string(),
+            extracted_text: Some(
+                "From: Acme Customer Care <care@acme.co.uk>\nTo: jane.smith@gmail.com\nCc: pjones@joneslaw.co.uk\nSubject: Your refund\n\nDear Ms Smith"
+                    .to_string(),
+            ),
+            ..DocumentEntry::default()
+        };
+        let links = link_document(&parties(), &doc);
+        assert_eq!(links.from, vec!["acme"]);
+        assert_eq!(links.to, vec!["jane", "acme"]);
+
+        let doc = DocumentEntry { from: Some("Bob Brown".to_string()), ..doc };
+        assert_eq!(link_document(&parties(), &doc).from, vec!["bob"]);
+    }
+}
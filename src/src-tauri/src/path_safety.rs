@@ -1,12 +1,19 @@
 use std::path::PathBuf;
 
 /// Returns the base CaseKit directory: ~/Documents/CaseKit/
+#[cfg(not(test))]
 pub fn casekit_base() -> Result<PathBuf, String> {
     let doc_dir = dirs::document_dir()
         .ok_or_else(|| "Could not determine Documents directory".to_string())?;
     Ok(doc_dir.join("CaseKit"))
 }
 
+/// Tests keep their cases in a temporary folder, away from the user's documents
+#[cfg(test)]
+pub fn casekit_base() -> Result<PathBuf, String> {
+    Ok(std::env::temp_dir().join(format!("casekit-test-{}", std::process::id())))
+}
+
 /// Sanitise a user-supplied path component (case name, folder, filename).
 /// Rejects any value containing path traversal sequences or path separators.
 pub fn sanitise_path_component(input: &str, label: &str) -> Result<String, String> {
@@ -0,0 +1,1683 @@
+//! Permanent redaction of PDFs for disclosure.
+//!
+//! Drawing a black box over a PDF leaves the text underneath in the file, so
+//! it can still be selected, searched or copied out. Here the text-showing
+//! operators under each redaction are rewritten without the redacted
+//! characters, with a spacing adjustment in their place so the rest of the
+//! line stays where it was. Image pixels under a region are set to black,
+//! and annotations over it are dropped. Only then are the boxes and labels
+//! drawn.
+//!
+//! Glyph positions come from the page's own content stream and font widths.
+//! Glyph heights are estimated from the font size, so a region removes any
+//! character that is a quarter or more inside it.
+//!
+//! Afterwards the copy is checked by extracting its text again: a redacted
+//! term, or a passage removed from a region, that can still be extracted
+//! means the copy is not saved.
+
+use lopdf::content::{Content, Operation};
+use lopdf::{dictionary, Dictionary, Document, Encoding, Object, ObjectId, Stream, StringFormat};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// An area to black out, in PDF points from the bottom-left corner of the page
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RedactionRegion {
+    /// Page number, starting at 1
+    pub page: u32,
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PdfRedactionOptions {
+    #[serde(default)]
+    pub regions: Vec<RedactionRegion>,
+    /// Words or phrases to remove wherever they appear in the text
+    #[serde(default)]
+    pub terms: Vec<String>,
+    /// Printed on each box; "REDACTED" if not given
+    #[serde(default)]
+    pub label: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TermHit {
+    pub term: String,
+    pub occurrences: usize,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PdfRedactionReport {
+    pub pages_changed: Vec<u32>,
+    pub boxes: usize,
+    pub characters_removed: usize,
+    pub term_hits: Vec<TermHit>,
+    pub images_edited: usize,
+    /// Images that could not be edited and were taken off the page entirely
+    pub images_removed: usize,
+    pub annotations_removed: usize,
+    pub warnings: Vec<String>,
+}
+
+/// A redacted copy, before it has been verified
+pub struct RedactedPdf {
+    pub bytes: Vec<u8>,
+    pub report: PdfRedactionReport,
+    /// Text removed from regions, normalised by `key`, with how many times
+    /// each passage was removed
+    removed: BTreeMap<String, usize>,
+    terms: Vec<String>,
+}
+
+const DEFAULT_LABEL: &str = "REDACTED";
+const LABEL_FONT: &[u8] = b"CkRedactLabel";
+const IMAGE_PREFIX: &str = "CkRedactImage";
+
+/// Glyph extents as a fraction of the font size. Real metrics vary by font,
+/// so these err towards covering a little more than the ink.
+const ASCENT: f32 = 0.85;
+const DESCENT: f32 = -0.25;
+/// Share of a glyph, across and up, that must be inside a region for it to
+/// be removed
+const MIN_OVERLAP: f32 = 0.25;
+/// Glyph width, in thousandths of the font size, when a font gives none
+const DEFAULT_WIDTH: f32 = 500.0;
+/// Removed passages shorter than this are too common to check by searching
+const MIN_CHECKED_LEN: usize = 3;
+/// Padding around the box drawn over a matched term, in points
+const TERM_PADDING: f32 = 0.5;
+
+/// Helvetica widths for character codes 32 to 126. The standard 14 fonts
+/// usually come without a /Widths array.
+const HELVETICA_WIDTHS: [u16; 95] = [
+    278, 278, 355, 556, 556, 889, 667, 222, 333, 333, 389, 584, 278, 333, 278, 278, // space to /
+    556, 556, 556, 556, 556, 556, 556, 556, 556, 556, // 0 to 9
+    278, 278, 584, 584, 584, 556, 1015, // : to @
+    667, 667, 722, 722, 667, 611, 778, 722, 278, 500, 667, 556, 833, 722, 778, 667, 778, 722, 667,
+    611, 722, 667, 944, 667, 667, 611, // A to Z
+    278, 278, 278, 469, 556, 222, // [ to `
+    556, 556, 500, 556, 556, 278, 556, 556, 222, 222, 500, 222, 833, 556, 556, 556, 556, 333, 500,
+    278, 556, 500, 722, 500, 500, 500, // a to z
+    334, 260, 334, 584, // { to ~
+];
+
+// ---------------------------------------------------------------------------
+// Geometry
+
+type Matrix = [f32; 6];
+
+const IDENTITY: Matrix = [1.0, 0.0, 0.0, 1.0, 0.0, 0.0];
+
+/// `m` then `n`, in the PDF's row-vector convention
+fn multiply(m: &Matrix, n: &Matrix) -> Matrix {
+    [
+        m[0] * n[0] + m[1] * n[2],
+        m[0] * n[1] + m[1] * n[3],
+        m[2] * n[0] + m[3] * n[2],
+        m[2] * n[1] + m[3] * n[3],
+        m[4] * n[0] + m[5] * n[2] + n[4],
+        m[4] * n[1] + m[5] * n[3] + n[5],
+    ]
+}
+
+fn translate(tx: f32, ty: f32) -> Matrix {
+    [1.0, 0.0, 0.0, 1.0, tx, ty]
+}
+
+fn apply(m: &Matrix, x: f32, y: f32) -> (f32, f32) {
+    (x * m[0] + y * m[2] + m[4], x * m[1] + y * m[3] + m[5])
+}
+
+fn numbers(operands: &[Object]) -> Vec<f32> {
+    operands.iter().filter_map(|o| o.as_float().ok()).collect()
+}
+
+fn matrix_from(operands: &[Object]) -> Option<Matrix> {
+    let n = numbers(operands);
+    (n.len() == 6).then(|| [n[0], n[1], n[2], n[3], n[4], n[5]])
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Rect {
+    x0: f32,
+    y0: f32,
+    x1: f32,
+    y1: f32,
+}
+
+impl Rect {
+    fn new(x0: f32, y0: f32, x1: f32, y1: f32) -> Self {
+        Self {
+            x0: x0.min(x1),
+            y0: y0.min(y1),
+            x1: x0.max(x1),
+            y1: y0.max(y1),
+        }
+    }
+
+    /// Bounding box of `rect` after transforming it by `m`
+    fn transformed(rect: &Rect, m: &Matrix) -> Self {
+        let corners = [
+            apply(m, rect.x0, rect.y0),
+            apply(m, rect.x1, rect.y0),
+            apply(m, rect.x0, rect.y1),
+            apply(m, rect.x1, rect.y1),
+        ];
+        let xs = corners.iter().map(|c| c.0);
+        let ys = corners.iter().map(|c| c.1);
+        Self {
+            x0: xs.clone().fold(f32::INFINITY, f32::min),
+            x1: xs.fold(f32::NEG_INFINITY, f32::max),
+            y0: ys.clone().fold(f32::INFINITY, f32::min),
+            y1: ys.fold(f32::NEG_INFINITY, f32::max),
+        }
+    }
+
+    fn from_array(values: &[f32]) -> Option<Self> {
+        (values.len() == 4).then(|| Self::new(values[0], values[1], values[2], values[3]))
+    }
+
+    fn width(&self) -> f32 {
+        self.x1 - self.x0
+    }
+
+    fn height(&self) -> f32 {
+        self.y1 - self.y0
+    }
+
+    fn intersection(&self, other: &Rect) -> Option<Rect> {
+        let r = Rect {
+            x0: self.x0.max(other.x0),
+            y0: self.y0.max(other.y0),
+            x1: self.x1.min(other.x1),
+            y1: self.y1.min(other.y1),
+        };
+        (r.width() > 0.0 && r.height() > 0.0).then_some(r)
+    }
+
+    fn union(&self, other: &Rect) -> Rect {
+        Rect {
+            x0: self.x0.min(other.x0),
+            y0: self.y0.min(other.y0),
+            x1: self.x1.max(other.x1),
+            y1: self.y1.max(other.y1),
+        }
+    }
+
+    fn padded(&self, by: f32) -> Rect {
+        Rect {
+            x0: self.x0 - by,
+            y0: self.y0 - by,
+            x1: self.x1 + by,
+            y1: self.y1 + by,
+        }
+    }
+
+    /// Whether enough of this glyph box is inside `region` for the glyph to go
+    fn mostly_inside(&self, region: &Rect) -> bool {
+        self.intersection(region).is_some_and(|i| {
+            i.width() >= MIN_OVERLAP * self.width() && i.height() >= MIN_OVERLAP * self.height()
+        })
+    }
+}
+
+impl From<&RedactionRegion> for Rect {
+    fn from(r: &RedactionRegion) -> Self {
+        Rect::new(r.x, r.y, r.x + r.width, r.y + r.height)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Fonts
+
+fn resolve<'a>(doc: &'a Document, object: &'a Object) -> &'a Object {
+    doc.dereference(object).map(|(_, o)| o).unwrap_or(object)
+}
+
+fn dict_get<'a>(doc: &'a Document, dict: &'a Dictionary, key: &[u8]) -> Option<&'a Object> {
+    dict.get(key).ok().map(|o| resolve(doc, o))
+}
+
+fn float_array(doc: &Document, object: Option<&Object>) -> Vec<f32> {
+    object
+        .and_then(|o| o.as_array().ok())
+        .map(|a| a.iter().filter_map(|v| resolve(doc, v).as_float().ok()).collect())
+        .unwrap_or_default()
+}
+
+/// What the redactor needs to know about a font: how to split a string into
+/// character codes, how wide each is and what text it stands for
+struct FontInfo<'a> {
+    two_byte: bool,
+    encoding: Option<Encoding<'a>>,
+    widths: BTreeMap<u32, f32>,
+    missing_width: f32,
+    base_font: String,
+}
+
+impl<'a> FontInfo<'a> {
+    fn load(doc: &'a Document, font: &'a Dictionary) -> Self {
+        let two_byte = font.get(b"Subtype").and_then(Object::as_name).ok() == Some(b"Type0".as_slice());
+        let base_font = font
+            .get(b"BaseFont")
+            .and_then(Object::as_name)
+            .map(|n| String::from_utf8_lossy(n).to_string())
+            .unwrap_or_default();
+        let mut widths = BTreeMap::new();
+        let mut missing_width = DEFAULT_WIDTH;
+
+        if two_byte {
+            let descendant = dict_get(doc, font, b"DescendantFonts")
+                .and_then(|o| o.as_array().ok())
+                .and_then(|a| a.first())
+                .and_then(|o| resolve(doc, o).as_dict().ok());
+            if let Some(cid_font) = descendant {
+                missing_width = dict_get(doc, cid_font, b"DW")
+                    .and_then(|o| o.as_float().ok())
+                    .unwrap_or(1000.0);
+                let w = dict_get(doc, cid_font, b"W")
+                    .and_then(|o| o.as_array().ok())
+                    .cloned()
+                    .unwrap_or_default();
+                let mut i = 0;
+                while i < w.len() {
+                    let Ok(first) = resolve(doc, &w[i]).as_i64() else { break };
+                    match w.get(i + 1).map(|o| resolve(doc, o)) {
+                        Some(Object::Array(list)) => {
+                            for (k, v) in list.iter().enumerate() {
+                                if let Ok(v) = resolve(doc, v).as_float() {
+                                    widths.insert(first as u32 + k as u32, v);
+                                }
+                            }
+                            i += 2;
+                        }
+                        Some(last) => {
+                            let last = last.as_i64().unwrap_or(first).clamp(first, first + 0xFFFF);
+                            let width = w.get(i + 2).and_then(|o| resolve(doc, o).as_float().ok());
+                            if let Some(width) = width {
+                                for code in first..=last {
+                                    widths.insert(code as u32, width);
+                                }
+                            }
+                            i += 3;
+                        }
+                        None => break,
+                    }
+                }
+            }
+        } else {
+            let first = dict_get(doc, font, b"FirstChar")
+                .and_then(|o| o.as_i64().ok())
+                .unwrap_or(0);
+            for (k, width) in float_array(doc, dict_get(doc, font, b"Widths")).into_iter().enumerate() {
+                widths.insert(first as u32 + k as u32, width);
+            }
+            if let Some(width) = dict_get(doc, font, b"FontDescriptor")
+                .and_then(|o| o.as_dict().ok())
+                .and_then(|d| dict_get(doc, d, b"MissingWidth"))
+                .and_then(|o| o.as_float().ok())
+            {
+                missing_width = width;
+            }
+        }
+
+        Self {
+            two_byte,
+            encoding: font
+                .type_is(b"Font")
+                .then(|| font.get_font_encoding(doc).ok())
+                .flatten(),
+            widths,
+            missing_width,
+            base_font,
+        }
+    }
+
+    fn codes<'b>(&self, bytes: &'b [u8]) -> std::slice::Chunks<'b, u8> {
+        bytes.chunks(if self.two_byte { 2 } else { 1 })
+    }
+
+    /// Width in thousandths of the font size
+    fn width(&self, code: &[u8]) -> f32 {
+        let value = code.iter().fold(0u32, |acc, b| acc * 256 + *b as u32);
+        if let Some(width) = self.widths.get(&value) {
+            return *width;
+        }
+        if self.two_byte || !self.widths.is_empty() {
+            return self.missing_width;
+        }
+        if self.base_font.contains("Courier") {
+            return 600.0;
+        }
+        match value {
+            32..=126 => HELVETICA_WIDTHS[value as usize - 32] as f32,
+            _ => DEFAULT_WIDTH,
+        }
+    }
+
+    fn text(&self, code: &[u8]) -> String {
+        match &self.encoding {
+            Some(encoding) => encoding.bytes_to_string(code).unwrap_or_default(),
+            None if self.two_byte => "\u{FFFD}".to_string(),
+            None => code.iter().map(|b| *b as char).collect(),
+        }
+    }
+
+    fn is_word_space(&self, code: &[u8]) -> bool {
+        !self.two_byte && code == [32]
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Reading a page
+
+#[derive(Debug, Clone)]
+struct Glyph {
+    /// Index into `Page::text_ops`
+    text_op: usize,
+    code: Vec<u8>,
+    text: String,
+    bounds: Rect,
+    /// Thousandths of the font size
+    width: f32,
+    /// Character and word spacing applied after the glyph, in text space
+    spacing: f32,
+    removed: bool,
+    by_term: bool,
+}
+
+#[derive(Debug, Clone)]
+enum Element {
+    Glyphs(Vec<usize>),
+    Adjust(f32),
+}
+
+/// A text-showing operator and the glyphs it draws
+#[derive(Debug, Clone)]
+struct TextOp {
+    index: usize,
+    elements: Vec<Element>,
+    font_size: f32,
+    two_byte: bool,
+}
+
+/// An image or form XObject drawn on the page
+#[derive(Debug, Clone)]
+struct Placement {
+    index: usize,
+    id: ObjectId,
+    ctm: Matrix,
+    bounds: Rect,
+    image: bool,
+}
+
+#[derive(Debug, Clone)]
+struct TextState {
+    font: Vec<u8>,
+    size: f32,
+    char_spacing: f32,
+    word_spacing: f32,
+    scale: f32,
+    leading: f32,
+    rise: f32,
+}
+
+#[derive(Debug, Clone)]
+struct GraphicsState {
+    ctm: Matrix,
+    text: TextState,
+}
+
+/// A page's content stream, interpreted far enough to know where each glyph
+/// and image is drawn
+struct Page {
+    operations: Vec<Operation>,
+    glyphs: Vec<Glyph>,
+    text_ops: Vec<TextOp>,
+    placements: Vec<Placement>,
+}
+
+/// XObjects available to a page, by resource name. A page's own resources
+/// replace those it would inherit.
+fn page_xobjects(doc: &Document, page_id: ObjectId) -> BTreeMap<Vec<u8>, ObjectId> {
+    let resources = page_resources(doc, page_id);
+    dict_get(doc, &resources, b"XObject")
+        .and_then(|o| o.as_dict().ok())
+        .map(|d| {
+            d.iter()
+                .filter_map(|(name, o)| o.as_reference().ok().map(|id| (name.clone(), id)))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// A copy of the resources dictionary that applies to a page
+fn page_resources(doc: &Document, page_id: ObjectId) -> Dictionary {
+    let Ok((direct, ids)) = doc.get_page_resources(page_id) else {
+        return Dictionary::new();
+    };
+    direct
+        .or_else(|| ids.first().and_then(|id| doc.get_dictionary(*id).ok()))
+        .cloned()
+        .unwrap_or_default()
+}
+
+fn read_page(doc: &Document, page_id: ObjectId, page_no: u32) -> Result<Page, String> {
+    let content = doc
+        .get_and_decode_page_content(page_id)
+        .map_err(|e| format!("Could not read the content of page {}: {}", page_no, e))?;
+    let fonts: BTreeMap<Vec<u8>, FontInfo> = doc
+        .get_page_fonts(page_id)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|(name, font)| (name, FontInfo::load(doc, font)))
+        .collect();
+    let xobjects = page_xobjects(doc, page_id);
+
+    let mut page = Page {
+        operations: content.operations,
+        glyphs: Vec::new(),
+        text_ops: Vec::new(),
+        placements: Vec::new(),
+    };
+
+    let mut state = GraphicsState {
+        ctm: IDENTITY,
+        text: TextState {
+            font: Vec::new(),
+            size: 0.0,
+            char_spacing: 0.0,
+            word_spacing: 0.0,
+            scale: 1.0,
+            leading: 0.0,
+            rise: 0.0,
+        },
+    };
+    let mut stack: Vec<GraphicsState> = Vec::new();
+    let mut tm = IDENTITY;
+    let mut tlm = IDENTITY;
+
+    for index in 0..page.operations.len() {
+        let op = &page.operations[index];
+        let operands = &op.operands;
+        let n = numbers(operands);
+        let mut shown: Vec<&Object> = Vec::new();
+
+        match op.operator.as_str() {
+            "q" => stack.push(state.clone()),
+            "Q" => {
+                if let Some(saved) = stack.pop() {
+                    state = saved;
+                }
+            }
+            "cm" => {
+                if let Some(m) = matrix_from(operands) {
+                    state.ctm = multiply(&m, &state.ctm);
+                }
+            }
+            "BT" => {
+                tm = IDENTITY;
+                tlm = IDENTITY;
+            }
+            "Tm" => {
+                if let Some(m) = matrix_from(operands) {
+                    tm = m;
+                    tlm = m;
+                }
+            }
+            "Td" | "TD" if n.len() == 2 => {
+                if op.operator == "TD" {
+                    state.text.leading = -n[1];
+                }
+                tlm = multiply(&translate(n[0], n[1]), &tlm);
+                tm = tlm;
+            }
+            "T*" => {
+                tlm = multiply(&translate(0.0, -state.text.leading), &tlm);
+                tm = tlm;
+            }
+            "TL" if n.len() == 1 => state.text.leading = n[0],
+            "Tc" if n.len() == 1 => state.text.char_spacing = n[0],
+            "Tw" if n.len() == 1 => state.text.word_spacing = n[0],
+            "Tz" if n.len() == 1 => state.text.scale = n[0] / 100.0,
+            "Ts" if n.len() == 1 => state.text.rise = n[0],
+            "Tf" if operands.len() == 2 => {
+                if let Ok(name) = operands[0].as_name() {
+                    state.text.font = name.to_vec();
+                }
+                state.text.size = operands[1].as_float().unwrap_or(0.0);
+            }
+            "Tj" => shown.extend(operands.first()),
+            "'" => {
+                tlm = multiply(&translate(0.0, -state.text.leading), &tlm);
+                tm = tlm;
+                shown.extend(operands.first());
+            }
+            "\"" if operands.len() == 3 => {
+                state.text.word_spacing = operands[0].as_float().unwrap_or(0.0);
+                state.text.char_spacing = operands[1].as_float().unwrap_or(0.0);
+                tlm = multiply(&translate(0.0, -state.text.leading), &tlm);
+                tm = tlm;
+                shown.push(&operands[2]);
+            }
+            "TJ" => {
+                if let Some(Object::Array(items)) = operands.first() {
+                    shown.extend(items.iter());
+                }
+            }
+            "Do" => {
+                let Some(id) = operands
+                    .first()
+                    .and_then(|o| o.as_name().ok())
+                    .and_then(|name| xobjects.get(name))
+                else {
+                    continue;
+                };
+                let Ok(Object::Stream(stream)) = doc.get_object(*id) else { continue };
+                let subtype = stream.dict.get(b"Subtype").and_then(Object::as_name).ok();
+                if subtype == Some(b"Image".as_slice()) {
+                    let unit = Rect::new(0.0, 0.0, 1.0, 1.0);
+                    page.placements.push(Placement {
+                        index,
+                        id: *id,
+                        ctm: state.ctm,
+                        bounds: Rect::transformed(&unit, &state.ctm),
+                        image: true,
+                    });
+                } else if subtype == Some(b"Form".as_slice()) {
+                    let bbox = float_array(doc, dict_get(doc, &stream.dict, b"BBox"));
+                    let Some(bbox) = Rect::from_array(&bbox) else { continue };
+                    let matrix = matrix_from(
+                        &dict_get(doc, &stream.dict, b"Matrix")
+                            .and_then(|o| o.as_array().ok())
+                            .cloned()
+                            .unwrap_or_default(),
+                    )
+                    .unwrap_or(IDENTITY);
+                    page.placements.push(Placement {
+                        index,
+                        id: *id,
+                        ctm: state.ctm,
+                        bounds: Rect::transformed(&bbox, &multiply(&matrix, &state.ctm)),
+                        image: false,
+                    });
+                }
+            }
+            _ => {}
+        }
+
+        if !matches!(op.operator.as_str(), "Tj" | "'" | "\"" | "TJ") {
+            continue;
+        }
+
+        let Some(font) = fonts.get(&state.text.font) else {
+            return Err(format!(
+                "Page {} uses a font that is not in its resources, so its text cannot be redacted safely",
+                page_no
+            ));
+        };
+        let text = &state.text;
+        let mut text_op = TextOp {
+            index,
+            elements: Vec::new(),
+            font_size: text.size,
+            two_byte: font.two_byte,
+        };
+        for item in shown {
+            match item {
+                Object::String(bytes, _) => {
+                    let mut ids = Vec::new();
+                    for code in font.codes(bytes) {
+                        let width = font.width(code);
+                        let spacing = text.char_spacing
+                            + if font.is_word_space(code) { text.word_spacing } else { 0.0 };
+                        let trm = multiply(
+                            &multiply(&[text.size * text.scale, 0.0, 0.0, text.size, 0.0, text.rise], &tm),
+                            &state.ctm,
+                        );
+                        let glyph_box = Rect::new(0.0, DESCENT, width / 1000.0, ASCENT);
+                        ids.push(page.glyphs.len());
+                        page.glyphs.push(Glyph {
+                            text_op: page.text_ops.len(),
+                            code: code.to_vec(),
+                            text: font.text(code),
+                            bounds: Rect::transformed(&glyph_box, &trm),
+                            width,
+                            spacing,
+                            removed: false,
+                            by_term: false,
+                        });
+                        let advance = (width / 1000.0 * text.size + spacing) * text.scale;
+                        tm = multiply(&translate(advance, 0.0), &tm);
+                    }
+                    text_op.elements.push(Element::Glyphs(ids));
+                }
+                other => {
+                    if let Ok(adjust) = other.as_float() {
+                        let advance = -adjust / 1000.0 * text.size * text.scale;
+                        tm = multiply(&translate(advance, 0.0), &tm);
+                        text_op.elements.push(Element::Adjust(adjust));
+                    }
+                }
+            }
+        }
+        page.text_ops.push(text_op);
+    }
+
+    Ok(page)
+}
+
+// ---------------------------------------------------------------------------
+// Matching terms
+
+/// How text is compared: lower case, with whitespace removed so a phrase
+/// still matches when the PDF positions each word separately
+fn key(text: &str) -> String {
+    text.chars()
+        .flat_map(char::to_lowercase)
+        .filter(|c| !c.is_whitespace())
+        .collect()
+}
+
+/// Characters for matching, lower case and without whitespace, each with the
+/// piece of text it came from and whether a word starts there. A piece
+/// flagged `true` starts a new word even without a space before it.
+fn word_chars<'a>(pieces: impl Iterator<Item = (&'a str, bool)>) -> Vec<(char, usize, bool)> {
+    let mut chars = Vec::new();
+    let mut prev_alnum = false;
+    for (i, (text, new_word)) in pieces.enumerate() {
+        if new_word {
+            prev_alnum = false;
+        }
+        for c in text.chars() {
+            if c.is_whitespace() {
+                prev_alnum = false;
+                continue;
+            }
+            let boundary = !prev_alnum || !c.is_alphanumeric();
+            for (k, lower) in c.to_lowercase().enumerate() {
+                chars.push((lower, i, boundary && k == 0));
+            }
+            prev_alnum = c.is_alphanumeric();
+        }
+    }
+    chars
+}
+
+/// The page's characters in drawing order, by glyph
+fn page_chars(glyphs: &[Glyph]) -> Vec<(char, usize, bool)> {
+    // Separate text operators with a visible gap between them, or on
+    // different lines, are separate words
+    let new_word = |i: usize| {
+        let Some(prev) = i.checked_sub(1).map(|p| &glyphs[p]) else { return true };
+        let glyph = &glyphs[i];
+        let height = prev.bounds.height().max(0.1);
+        prev.text_op != glyph.text_op
+            && (glyph.bounds.x0 - prev.bounds.x1 > 0.1 * height
+                || (glyph.bounds.y0 - prev.bounds.y0).abs() > 0.5 * height)
+    };
+    word_chars(glyphs.iter().enumerate().map(|(i, g)| (g.text.as_str(), new_word(i))))
+}
+
+/// Start and end (exclusive) in `chars` of each whole-word occurrence of `term`
+fn find_words(chars: &[(char, usize, bool)], term: &str) -> Vec<(usize, usize)> {
+    let wanted: Vec<char> = key(term).chars().collect();
+    let (Some(first), Some(last)) = (wanted.first(), wanted.last()) else {
+        return Vec::new();
+    };
+    let boundary_at = |k: usize| k >= chars.len() || chars[k].2;
+
+    let mut found = Vec::new();
+    let mut start = 0;
+    while start + wanted.len() <= chars.len() {
+        let end = start + wanted.len();
+        if chars[start..end].iter().map(|c| c.0).eq(wanted.iter().copied())
+            && (!first.is_alphanumeric() || boundary_at(start))
+            && (!last.is_alphanumeric() || boundary_at(end))
+        {
+            found.push((start, end));
+            start = end;
+        } else {
+            start += 1;
+        }
+    }
+    found
+}
+
+fn contains_term(text: &str, terms: &[String]) -> bool {
+    let chars = word_chars(std::iter::once((text, true)));
+    terms.iter().any(|t| !find_words(&chars, t).is_empty())
+}
+
+/// Mark the glyphs of every whole-word occurrence of each term as removed,
+/// returning the number of occurrences of each
+fn match_terms(glyphs: &mut [Glyph], terms: &[String]) -> Vec<usize> {
+    let chars = page_chars(glyphs);
+    terms
+        .iter()
+        .map(|term| {
+            let found = find_words(&chars, term);
+            for (start, end) in &found {
+                // Everything between the first and last character goes,
+                // including the spaces between words
+                for glyph in &mut glyphs[chars[*start].1..=chars[end - 1].1] {
+                    glyph.removed = true;
+                    glyph.by_term = true;
+                }
+            }
+            found.len()
+        })
+        .collect()
+}
+
+// ---------------------------------------------------------------------------
+// Rewriting a page
+
+/// Rewrite a text-showing operator without its removed glyphs. Each removed
+/// glyph becomes a TJ adjustment of the same width, so the text after it is
+/// drawn where it was before.
+fn rewrite_text_op(op: &Operation, text_op: &TextOp, glyphs: &[Glyph]) -> Vec<Operation> {
+    let format = if text_op.two_byte { StringFormat::Hexadecimal } else { StringFormat::Literal };
+    let mut array: Vec<Object> = Vec::new();
+    let mut current: Vec<u8> = Vec::new();
+    let mut pending = 0.0f32;
+
+    let flush = |array: &mut Vec<Object>, current: &mut Vec<u8>, pending: &mut f32| {
+        if current.is_empty() {
+            return;
+        }
+        if *pending != 0.0 {
+            array.push(Object::Real(*pending));
+            *pending = 0.0;
+        }
+        array.push(Object::String(std::mem::take(current), format));
+    };
+
+    for element in &text_op.elements {
+        match element {
+            Element::Adjust(n) => {
+                flush(&mut array, &mut current, &mut pending);
+                pending += n;
+            }
+            Element::Glyphs(ids) => {
+                for glyph in ids.iter().map(|id| &glyphs[*id]) {
+                    if !glyph.removed {
+                        current.extend_from_slice(&glyph.code);
+                        continue;
+                    }
+                    flush(&mut array, &mut current, &mut pending);
+                    let spacing = if text_op.font_size != 0.0 {
+                        glyph.spacing * 1000.0 / text_op.font_size
+                    } else {
+                        0.0
+                    };
+                    pending -= glyph.width + spacing;
+                }
+            }
+        }
+    }
+    flush(&mut array, &mut current, &mut pending);
+    if pending != 0.0 {
+        array.push(Object::Real(pending));
+    }
+
+    // ' and " move to the next line (and " sets spacing) before showing text
+    let mut ops = match op.operator.as_str() {
+        "'" => vec![Operation::new("T*", vec![])],
+        "\"" => vec![
+            Operation::new("Tw", vec![op.operands[0].clone()]),
+            Operation::new("Tc", vec![op.operands[1].clone()]),
+            Operation::new("T*", vec![]),
+        ],
+        _ => Vec::new(),
+    };
+    if !array.is_empty() {
+        ops.push(Operation::new("TJ", vec![Object::Array(array)]));
+    }
+    ops
+}
+
+/// Pixel rectangles of an image, counted from its top-left corner, under the
+/// given regions. None unless the image is drawn upright.
+fn pixel_rects(ctm: &Matrix, width: u32, height: u32, regions: &[Rect]) -> Option<Vec<[u32; 4]>> {
+    if ctm[1].abs() > 1e-3 || ctm[2].abs() > 1e-3 || ctm[0] == 0.0 || ctm[3] == 0.0 {
+        return None;
+    }
+    let bounds = Rect::transformed(&Rect::new(0.0, 0.0, 1.0, 1.0), ctm);
+    let rects = regions
+        .iter()
+        .filter_map(|r| r.intersection(&bounds))
+        .map(|r| {
+            let u = Rect::new((r.x0 - ctm[4]) / ctm[0], (r.y0 - ctm[5]) / ctm[3], (r.x1 - ctm[4]) / ctm[0], (r.y1 - ctm[5]) / ctm[3]);
+            let px = |v: f32, size: u32| ((v.clamp(0.0, 1.0) * size as f32) as u32).min(size);
+            [
+                px(u.x0, width),
+                px(1.0 - u.y1, height),
+                (((u.x1.clamp(0.0, 1.0)) * width as f32).ceil() as u32).min(width),
+                (((1.0 - u.y0.clamp(0.0, 1.0)) * height as f32).ceil() as u32).min(height),
+            ]
+        })
+        .collect();
+    Some(rects)
+}
+
+fn components(doc: &Document, dict: &Dictionary) -> Option<usize> {
+    match dict_get(doc, dict, b"ColorSpace")? {
+        Object::Name(name) => match name.as_slice() {
+            b"DeviceGray" | b"G" => Some(1),
+            b"DeviceRGB" | b"RGB" => Some(3),
+            b"DeviceCMYK" | b"CMYK" => Some(4),
+            _ => None,
+        },
+        Object::Array(items) if items.first().and_then(|o| o.as_name().ok()) == Some(b"ICCBased".as_slice()) => {
+            let profile = resolve(doc, items.get(1)?).as_stream().ok()?;
+            profile.dict.get(b"N").and_then(Object::as_i64).ok().map(|n| n as usize)
+        }
+        _ => None,
+    }
+}
+
+/// A copy of an image stream with the given pixel rectangles set to black,
+/// or None if the image is in a format that cannot be edited here
+fn blacken_image(doc: &Document, stream: &Stream, rects: &[[u32; 4]]) -> Option<Stream> {
+    if stream.dict.get(b"ImageMask").and_then(Object::as_bool).unwrap_or(false) {
+        return None;
+    }
+    let filters = stream.filters().unwrap_or_default();
+
+    if filters == ["DCTDecode"] {
+        let decoded = image::load_from_memory_with_format(&stream.content, image::ImageFormat::Jpeg).ok()?;
+        let mut out = Vec::new();
+        let mut encoder = image::codecs::jpeg::JpegEncoder::new_with_quality(&mut out, 90);
+        let colour_space = if decoded.color().channel_count() <= 2 {
+            let mut pixels = decoded.to_luma8();
+            for [x0, y0, x1, y1] in rects {
+                for y in *y0..*y1.min(&pixels.height()) {
+                    for x in *x0..*x1.min(&pixels.width()) {
+                        pixels.put_pixel(x, y, image::Luma([0]));
+                    }
+                }
+            }
+            encoder
+                .encode(&pixels, pixels.width(), pixels.height(), image::ColorType::L8)
+                .ok()?;
+            "DeviceGray"
+        } else {
+            let mut pixels = decoded.to_rgb8();
+            for [x0, y0, x1, y1] in rects {
+                for y in *y0..*y1.min(&pixels.height()) {
+                    for x in *x0..*x1.min(&pixels.width()) {
+                        pixels.put_pixel(x, y, image::Rgb([0, 0, 0]));
+                    }
+                }
+            }
+            encoder
+                .encode(&pixels, pixels.width(), pixels.height(), image::ColorType::Rgb8)
+                .ok()?;
+            "DeviceRGB"
+        };
+        let mut edited = stream.clone();
+        edited.dict.set("ColorSpace", Object::Name(colour_space.as_bytes().to_vec()));
+        edited.dict.set("BitsPerComponent", 8);
+        edited.dict.remove(b"Decode");
+        edited.dict.remove(b"DecodeParms");
+        edited.set_content(out);
+        return Some(edited);
+    }
+
+    if !filters.is_empty() && filters != ["FlateDecode"] {
+        return None;
+    }
+    let comps = components(doc, &stream.dict)?;
+    let bits = stream.dict.get(b"BitsPerComponent").and_then(Object::as_i64).ok()?;
+    let width = stream.dict.get(b"Width").and_then(Object::as_i64).ok()? as usize;
+    let height = stream.dict.get(b"Height").and_then(Object::as_i64).ok()? as usize;
+    if !(bits == 8 || (bits == 1 && comps == 1)) {
+        return None;
+    }
+    let decode = float_array(doc, stream.dict.get(b"Decode").ok());
+    if !decode.is_empty() && comps != 1 {
+        return None;
+    }
+    let inverted = decode.first().is_some_and(|d| *d > 0.5);
+
+    // lopdf refuses to decompress image streams, so decompress a copy
+    // without its subtype
+    let mut plain = if filters.is_empty() {
+        stream.content.clone()
+    } else {
+        let mut copy = stream.clone();
+        copy.dict.remove(b"Subtype");
+        copy.decompressed_content().ok()?
+    };
+    let stride = (width * comps * bits as usize).div_ceil(8);
+    if plain.len() < stride * height {
+        return None;
+    }
+
+    for [x0, y0, x1, y1] in rects {
+        for y in *y0 as usize..(*y1 as usize).min(height) {
+            let row = &mut plain[y * stride..(y + 1) * stride];
+            for x in *x0 as usize..(*x1 as usize).min(width) {
+                if bits == 1 {
+                    let mask = 0x80u8 >> (x % 8);
+                    if inverted {
+                        row[x / 8] |= mask;
+                    } else {
+                        row[x / 8] &= !mask;
+                    }
+                    continue;
+                }
+                let pixel = &mut row[x * comps..(x + 1) * comps];
+                match comps {
+                    1 => pixel[0] = if inverted { 255 } else { 0 },
+                    4 => pixel.copy_from_slice(&[0, 0, 0, 255]),
+                    _ => pixel.fill(0),
+                }
+            }
+        }
+    }
+
+    let mut edited = stream.clone();
+    edited.set_plain_content(plain);
+    let _ = edited.compress();
+    Some(edited)
+}
+
+/// Everything to change on one page, worked out before the document is touched
+#[derive(Default)]
+struct PagePlan {
+    operations: Vec<Operation>,
+    boxes: Vec<Rect>,
+    new_images: Vec<(String, Stream)>,
+    characters_removed: usize,
+    term_hits: Vec<usize>,
+    images_edited: usize,
+    images_removed: usize,
+    forms_removed: usize,
+    removed: Vec<String>,
+}
+
+fn plan_page(
+    doc: &Document,
+    page_id: ObjectId,
+    page_no: u32,
+    regions: &[Rect],
+    terms: &[String],
+) -> Result<PagePlan, String> {
+    let mut page = read_page(doc, page_id, page_no)?;
+    let mut plan = PagePlan {
+        term_hits: match_terms(&mut page.glyphs, terms),
+        boxes: regions.to_vec(),
+        ..PagePlan::default()
+    };
+
+    for glyph in page.glyphs.iter_mut() {
+        if !glyph.removed && regions.iter().any(|r| glyph.bounds.mostly_inside(r)) {
+            glyph.removed = true;
+        }
+    }
+    plan.characters_removed = page
+        .glyphs
+        .iter()
+        .filter(|g| g.removed)
+        .map(|g| g.text.chars().filter(|c| !c.is_whitespace()).count())
+        .sum();
+
+    // One box over each run of term glyphs, and a record of each run of
+    // removed text so the result can be checked
+    let mut replacements: BTreeMap<usize, Vec<Operation>> = BTreeMap::new();
+    for text_op in &page.text_ops {
+        let ids: Vec<usize> = text_op
+            .elements
+            .iter()
+            .flat_map(|e| match e {
+                Element::Glyphs(ids) => ids.clone(),
+                Element::Adjust(_) => Vec::new(),
+            })
+            .collect();
+        if !ids.iter().any(|id| page.glyphs[*id].removed) {
+            continue;
+        }
+
+        let mut term_box: Option<Rect> = None;
+        let mut removed_run = String::new();
+        for id in ids.iter().chain([usize::MAX].iter()) {
+            let glyph = page.glyphs.get(*id);
+            match glyph.filter(|g| g.by_term) {
+                Some(g) => term_box = Some(term_box.map_or(g.bounds, |b| b.union(&g.bounds))),
+                None => plan.boxes.extend(term_box.take().map(|b| b.padded(TERM_PADDING))),
+            }
+            match glyph.filter(|g| g.removed) {
+                Some(g) => removed_run.push_str(&g.text),
+                None => {
+                    let run = key(&std::mem::take(&mut removed_run));
+                    if run.chars().count() >= MIN_CHECKED_LEN {
+                        plan.removed.push(run);
+                    }
+                }
+            }
+        }
+
+        let op = &page.operations[text_op.index];
+        replacements.insert(text_op.index, rewrite_text_op(op, text_op, &page.glyphs));
+    }
+
+    // Images and forms under a region
+    let mut dropped: Vec<usize> = Vec::new();
+    for placement in &page.placements {
+        let under: Vec<Rect> = regions
+            .iter()
+            .filter(|r| r.intersection(&placement.bounds).is_some())
+            .copied()
+            .collect();
+        if under.is_empty() {
+            continue;
+        }
+        if !placement.image {
+            plan.forms_removed += 1;
+            dropped.push(placement.index);
+            continue;
+        }
+
+        let Ok(Object::Stream(stream)) = doc.get_object(placement.id) else { continue };
+        let size = |name: &[u8]| stream.dict.get(name).and_then(Object::as_i64).unwrap_or(0).max(0) as u32;
+        let edited = pixel_rects(&placement.ctm, size(b"Width"), size(b"Height"), &under)
+            .and_then(|rects| blacken_image(doc, stream, &rects));
+        match edited {
+            Some(edited) => {
+                let name = format!("{}{}", IMAGE_PREFIX, plan.new_images.len() + 1);
+                replacements.insert(
+                    placement.index,
+                    vec![Operation::new("Do", vec![Object::Name(name.clone().into_bytes())])],
+                );
+                plan.new_images.push((name, edited));
+                plan.images_edited += 1;
+            }
+            None => {
+                plan.images_removed += 1;
+                dropped.push(placement.index);
+            }
+        }
+    }
+
+    plan.operations = page
+        .operations
+        .into_iter()
+        .enumerate()
+        .filter(|(i, _)| !dropped.contains(i))
+        .flat_map(|(i, op)| replacements.remove(&i).unwrap_or_else(|| vec![op]))
+        .collect();
+    Ok(plan)
+}
+
+/// Operators drawing a black box, with the label centred in white if it fits
+fn box_operations(rect: &Rect, label: &str) -> Vec<Operation> {
+    let mut ops = vec![
+        Operation::new("q", vec![]),
+        Operation::new("g", vec![0.into()]),
+        Operation::new(
+            "re",
+            vec![rect.x0.into(), rect.y0.into(), rect.width().into(), rect.height().into()],
+        ),
+        Operation::new("f", vec![]),
+    ];
+
+    let units: f32 = label
+        .bytes()
+        .map(|b| match b {
+            32..=126 => HELVETICA_WIDTHS[b as usize - 32] as f32,
+            _ => DEFAULT_WIDTH,
+        })
+        .sum();
+    let mut size = (rect.height() * 0.6).min(9.0);
+    if units > 0.0 {
+        size = size.min((rect.width() - 2.0) * 1000.0 / units);
+    }
+    if !label.is_empty() && size >= 4.0 {
+        let text_width = units * size / 1000.0;
+        ops.extend([
+            Operation::new("BT", vec![]),
+            Operation::new("g", vec![1.into()]),
+            Operation::new("Tf", vec![Object::Name(LABEL_FONT.to_vec()), size.into()]),
+            Operation::new(
+                "Td",
+                vec![
+                    (rect.x0 + (rect.width() - text_width) / 2.0).into(),
+                    (rect.y0 + (rect.height() - size * 0.7) / 2.0).into(),
+                ],
+            ),
+            Operation::new("Tj", vec![Object::string_literal(label)]),
+            Operation::new("ET", vec![]),
+        ]);
+    }
+    ops.push(Operation::new("Q", vec![]));
+    ops
+}
+
+/// Labels are drawn in Helvetica, so keep to the characters it has
+fn clean_label(label: Option<&str>) -> String {
+    let label = label.map(str::trim).unwrap_or(DEFAULT_LABEL);
+    label
+        .chars()
+        .map(|c| if (' '..='~').contains(&c) { c } else { '?' })
+        .take(40)
+        .collect()
+}
+
+/// Write the plan into the document: new content, edited images and the
+/// label font in a copy of the page's resources
+fn apply_page(doc: &mut Document, page_id: ObjectId, plan: PagePlan, label: &str) -> Result<(), String> {
+    let mut resources = page_resources(doc, page_id);
+    let mut xobjects = dict_get(doc, &resources, b"XObject")
+        .and_then(|o| o.as_dict().ok())
+        .cloned()
+        .unwrap_or_default();
+    let mut fonts = dict_get(doc, &resources, b"Font")
+        .and_then(|o| o.as_dict().ok())
+        .cloned()
+        .unwrap_or_default();
+
+    for (name, stream) in plan.new_images {
+        let id = doc.add_object(stream);
+        xobjects.set(name, id);
+    }
+    if !xobjects.is_empty() {
+        resources.set("XObject", xobjects);
+    }
+    if !label.is_empty() && !plan.boxes.is_empty() {
+        fonts.set(
+            LABEL_FONT.to_vec(),
+            dictionary! {
+                "Type" => "Font",
+                "Subtype" => "Type1",
+                "BaseFont" => "Helvetica",
+                "Encoding" => "WinAnsiEncoding",
+            },
+        );
+        resources.set("Font", fonts);
+    }
+
+    // The original drawing is wrapped in q/Q so the boxes are drawn in the
+    // page's default coordinates whatever state it leaves behind
+    let mut operations = vec![Operation::new("q", vec![])];
+    operations.extend(plan.operations);
+    operations.push(Operation::new("Q", vec![]));
+    for rect in &plan.boxes {
+        operations.extend(box_operations(rect, label));
+    }
+    let content = Content { operations }
+        .encode()
+        .map_err(|e| format!("Could not write page content: {}", e))?;
+    let mut stream = Stream::new(Dictionary::new(), content);
+    let _ = stream.compress();
+    let content_id = doc.add_object(stream);
+
+    let page = doc
+        .get_dictionary_mut(page_id)
+        .map_err(|e| format!("Could not update page: {}", e))?;
+    page.set("Resources", resources);
+    page.set("Contents", content_id);
+    Ok(())
+}
+
+/// Drop annotations over a region, or whose text contains a term. Their
+/// text is cleared as well, since a form field can still be reached through
+/// the document's form after it is gone from the page.
+fn remove_annotations(doc: &mut Document, page_id: ObjectId, regions: &[Rect], terms: &[String]) -> usize {
+    let Ok(page) = doc.get_dictionary(page_id) else { return 0 };
+    let Some(annots) = dict_get(doc, page, b"Annots").and_then(|o| o.as_array().ok()).cloned() else {
+        return 0;
+    };
+
+    let mut keep = Vec::new();
+    let mut scrub = Vec::new();
+    for annot in annots {
+        let Ok(dict) = resolve(doc, &annot).as_dict() else {
+            keep.push(annot);
+            continue;
+        };
+        let over_region = Rect::from_array(&float_array(doc, dict_get(doc, dict, b"Rect")))
+            .is_some_and(|rect| regions.iter().any(|r| r.intersection(&rect).is_some()));
+        let has_term = [b"Contents".as_slice(), b"V", b"RC", b"TU"].iter().any(|k| {
+            dict_get(doc, dict, k)
+                .and_then(|o| lopdf::decode_text_string(o).ok())
+                .is_some_and(|text| contains_term(&text, terms))
+        });
+        if over_region || has_term {
+            scrub.extend(annot.as_reference().ok());
+        } else {
+            keep.push(annot);
+        }
+    }
+
+    let removed = scrub.len();
+    for id in scrub {
+        if let Ok(dict) = doc.get_dictionary_mut(id) {
+            for k in [b"Contents".as_slice(), b"V", b"RC", b"AP", b"TU"] {
+                dict.remove(k);
+            }
+        }
+    }
+    if removed > 0 {
+        if let Ok(page) = doc.get_dictionary_mut(page_id) {
+            page.set("Annots", keep);
+        }
+    }
+    removed
+}
+
+/// Clear terms from document properties, bookmarks and accessibility text,
+/// returning how many were found
+fn scrub_strings(doc: &mut Document, terms: &[String]) -> usize {
+    const KEYS: [&[u8]; 9] = [b"Title", b"Subject", b"Author", b"Keywords", b"ActualText", b"Alt", b"E", b"TU", b"Contents"];
+    let mut found = 0;
+    for object in doc.objects.values_mut() {
+        let dict = match object {
+            Object::Dictionary(dict) => dict,
+            Object::Stream(stream) => &mut stream.dict,
+            _ => continue,
+        };
+        for k in KEYS {
+            let hit = dict
+                .get(k)
+                .ok()
+                .and_then(|o| lopdf::decode_text_string(o).ok())
+                .is_some_and(|text| contains_term(&text, terms));
+            if hit {
+                dict.set(k, Object::string_literal(""));
+                found += 1;
+            }
+        }
+    }
+
+    // XMP metadata repeats the title and author
+    let metadata = doc
+        .catalog()
+        .ok()
+        .and_then(|c| c.get(b"Metadata").ok())
+        .and_then(|o| o.as_reference().ok());
+    if let Some(id) = metadata {
+        let hit = doc
+            .get_object(id)
+            .and_then(Object::as_stream)
+            .ok()
+            .and_then(|s| s.get_plain_content().ok())
+            .is_some_and(|xml| contains_term(&String::from_utf8_lossy(&xml), terms));
+        if hit {
+            if let Ok(catalog) = doc.catalog_mut() {
+                catalog.remove(b"Metadata");
+            }
+            found += 1;
+        }
+    }
+    found
+}
+
+/// Make a redacted copy of a PDF. The copy should be passed to `verify`
+/// before it is saved.
+pub fn redact(pdf: &[u8], options: &PdfRedactionOptions) -> Result<RedactedPdf, String> {
+    let terms: Vec<String> = options
+        .terms
+        .iter()
+        .map(|t| t.trim().to_string())
+        .filter(|t| !key(t).is_empty())
+        .collect();
+    if terms.is_empty() && options.regions.is_empty() {
+        return Err("Choose at least one area or term to redact".to_string());
+    }
+    let label = clean_label(options.label.as_deref());
+
+    let mut doc = Document::load_mem(pdf).map_err(|e| format!("Could not read PDF: {}", e))?;
+    if doc.is_encrypted() {
+        doc.decrypt("")
+            .map_err(|_| "This PDF is password protected. Remove the password before redacting it.".to_string())?;
+        doc.trailer.remove(b"Encrypt");
+    }
+
+    let pages = doc.get_pages();
+    if let Some(region) = options.regions.iter().find(|r| !pages.contains_key(&r.page)) {
+        return Err(format!("The PDF has no page {}", region.page));
+    }
+
+    let mut report = PdfRedactionReport::default();
+    let mut hits = vec![0; terms.len()];
+    let mut removed: BTreeMap<String, usize> = BTreeMap::new();
+    let mut forms_removed = 0;
+
+    for (page_no, page_id) in pages {
+        let regions: Vec<Rect> = options
+            .regions
+            .iter()
+            .filter(|r| r.page == page_no && r.width > 0.0 && r.height > 0.0)
+            .map(Rect::from)
+            .collect();
+        if regions.is_empty() && terms.is_empty() {
+            continue;
+        }
+        let plan = plan_page(&doc, page_id, page_no, &regions, &terms)?;
+        for (total, found) in hits.iter_mut().zip(&plan.term_hits) {
+            *total += found;
+        }
+        for run in &plan.removed {
+            *removed.entry(run.clone()).or_default() += 1;
+        }
+        let changed = plan.characters_removed > 0
+            || plan.images_edited + plan.images_removed + plan.forms_removed > 0
+            || !plan.boxes.is_empty();
+        report.characters_removed += plan.characters_removed;
+        report.images_edited += plan.images_edited;
+        report.images_removed += plan.images_removed;
+        forms_removed += plan.forms_removed;
+        report.boxes += plan.boxes.len();
+
+        let annotations = remove_annotations(&mut doc, page_id, &regions, &terms);
+        report.annotations_removed += annotations;
+        if changed {
+            apply_page(&mut doc, page_id, plan, &label)?;
+        }
+        if changed || annotations > 0 {
+            report.pages_changed.push(page_no);
+        }
+    }
+
+    report.term_hits = terms
+        .iter()
+        .zip(hits)
+        .map(|(term, occurrences)| TermHit { term: term.clone(), occurrences })
+        .collect();
+    for hit in report.term_hits.iter().filter(|h| h.occurrences == 0) {
+        report.warnings.push(format!(
+            "\"{}\" was not found in the text of the PDF. If the page is a scan, draw a box over it instead.",
+            hit.term
+        ));
+    }
+    if report.images_removed > 0 {
+        report.warnings.push(format!(
+            "{} image(s) under a redaction could not be edited and were removed from the page entirely",
+            report.images_removed
+        ));
+    }
+    if forms_removed > 0 {
+        report.warnings.push(format!(
+            "{} embedded graphic(s) overlapping a redaction were removed in full",
+            forms_removed
+        ));
+    }
+    let scrubbed = if terms.is_empty() { 0 } else { scrub_strings(&mut doc, &terms) };
+    if scrubbed > 0 {
+        report.warnings.push(format!(
+            "Redacted terms were also cleared from {} document propert{}, bookmark(s) or tag(s)",
+            scrubbed,
+            if scrubbed == 1 { "y" } else { "ies" }
+        ));
+    }
+
+    // Replaced content streams and images are still in the object table
+    // until they are pruned, and would be written out with the copy
+    doc.prune_objects();
+    doc.compress();
+    let mut bytes = Vec::new();
+    doc.save_to(&mut bytes)
+        .map_err(|e| format!("Could not write redacted PDF: {}", e))?;
+
+    Ok(RedactedPdf { bytes, report, removed, terms })
+}
+
+/// Whether a stream holds drawing instructions or text, rather than an image
+/// or embedded font program
+fn is_text_stream(dict: &Dictionary) -> bool {
+    let subtype = dict.get(b"Subtype").and_then(Object::as_name).ok();
+    let font_program = [b"Length1".as_slice(), b"Length2", b"Length3"].iter().any(|k| dict.has(k));
+    !font_program && matches!(subtype, None | Some(b"Form") | Some(b"XML"))
+}
+
+/// Check that nothing redacted can be extracted from the copy, returning the
+/// copy's text. `original` is used to tell redacted passages apart from the
+/// same words left elsewhere in the document.
+pub fn verify(original: &[u8], redacted: &RedactedPdf) -> Result<String, String> {
+    let extract = |bytes: &[u8], which: &str| {
+        pdf_extract::extract_text_from_mem(bytes)
+            .map_err(|e| format!("Could not extract text from the {} PDF to check it: {}", which, e))
+    };
+    let text = extract(&redacted.bytes, "redacted")?;
+    let after = key(&text);
+    let mut leaks: Vec<String> = Vec::new();
+
+    for term in &redacted.terms {
+        if contains_term(&text, std::slice::from_ref(term)) {
+            leaks.push(format!("\"{}\" is still in the page text", term));
+        }
+    }
+
+    // Text hidden anywhere else in the file: form content, properties, tags
+    let doc = Document::load_mem(&redacted.bytes).map_err(|e| format!("Could not read redacted PDF: {}", e))?;
+    for object in doc.objects.values() {
+        let raw = match object {
+            Object::String(bytes, _) => String::from_utf8_lossy(bytes).to_string(),
+            Object::Stream(stream) if is_text_stream(&stream.dict) => stream
+                .get_plain_content()
+                .map(|c| String::from_utf8_lossy(&c).to_string())
+                .unwrap_or_default(),
+            Object::Dictionary(dict) => dict
+                .iter()
+                .filter_map(|(_, v)| lopdf::decode_text_string(v).ok())
+                .collect::<Vec<_>>()
+                .join(" "),
+            _ => continue,
+        };
+        for term in &redacted.terms {
+            let message = format!("\"{}\" is still stored in the file", term);
+            if contains_term(&raw, std::slice::from_ref(term)) && !leaks.contains(&message) {
+                leaks.push(message);
+            }
+        }
+    }
+
+    if !redacted.removed.is_empty() {
+        let before = key(&extract(original, "original")?);
+        for (run, times) in &redacted.removed {
+            let allowed = before.matches(run.as_str()).count().saturating_sub(*times);
+            if after.matches(run.as_str()).count() > allowed {
+                leaks.push(format!("text removed from a marked area (\"{}\") can still be extracted", run));
+            }
+        }
+    }
+
+    if leaks.is_empty() {
+        Ok(text)
+    } else {
+        Err(format!(
+            "The redacted copy was not saved because it could not be verified: {}",
+            leaks.join("; ")
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A one-page A4 PDF with the given content stream, Helvetica as /F1 and
+    /// optionally an image as /Im1
+    fn pdf(content: &str, image: Option<Stream>) -> Vec<u8> {
+        let mut doc = Document::with_version("1.5");
+        let pages_id = doc.new_object_id();
+        let font = doc.add_object(dictionary! {
+            "Type" => "Font",
+            "Subtype" => "Type1",
+            "BaseFont" => "Helvetica",
+            "Encoding" => "WinAnsiEncoding",
+        });
+        let mut resources = dictionary! { "Font" => dictionary! { "F1" => font } };
+        if let Some(image) = image {
+            let image = doc.add_object(image);
+            resources.set("XObject", dictionary! { "Im1" => image });
+        }
+        let contents = doc.add_object(Stream::new(dictionary! {}, content.as_bytes().to_vec()));
+        let page_id = doc.add_object(dictionary! {
+            "Type" => "Page",
+            "Parent" => pages_id,
+            "MediaBox" => vec![0.into(), 0.into(), 595.into(), 842.into()],
+            "Resources" => resources,
+            "Contents" => contents,
+        });
+        doc.objects.insert(
+            pages_id,
+            Object::Dictionary(dictionary! {
+                "Type" => "Pages",
+                "Kids" => vec![page_id.into()],
+                "Count" => 1,
+            }),
+        );
+        let catalog = doc.add_object(dictionary! { "Type" => "Catalog", "Pages" => pages_id });
+        let info = doc.add_object(dictionary! { "Title" => Object::string_literal("Letter") });
+        doc.trailer.set("Root", catalog);
+        doc.trailer.set("Info", info);
+        let mut bytes = Vec::new();
+        doc.save_to(&mut bytes).unwrap();
+        bytes
+    }
+
+    const LETTER: &str = "BT /F1 12 Tf 72 700 Td (Claimant: Jane Smith of 1 High Street) Tj \
+                          0 -20 Td [(Account ) -20 (12345678) ( sort code 12-34-56)] TJ ET";
+
+    fn first_page(bytes: &[u8]) -> Page {
+        let doc = Document::load_mem(bytes).unwrap();
+        let page_id = doc.get_pages()[&1];
+        read_page(&doc, page_id, 1).unwrap()
+    }
+
+    fn text_of(page: &Page) -> String {
+        page.glyphs.iter().map(|g| g.text.as_str()).collect()
+    }
+
+    fn options(terms: &[&str], regions: Vec<RedactionRegion>) -> PdfRedactionOptions {
+        PdfRedactionOptions {
+            regions,
+            terms: terms.iter().map(|t| t.to_string()).collect(),
+            label: None,
+        }
+    }
+
+    #[test]
+    fn removes_terms_and_keeps_the_rest_of_the_line_in_place() {
+        let original = pdf(LETTER, None);
+        let redacted = redact(&original, &options(&["jane  smith", "Nobody"], vec![])).unwrap();
+
+        assert_eq!(redacted.report.term_hits[0].occurrences, 1);
+        assert_eq!(redacted.report.term_hits[1].occurrences, 0);
+        assert_eq!(redacted.report.warnings.len(), 1);
+        assert_eq!(redacted.report.boxes, 1);
+        assert_eq!(redacted.report.characters_removed, 9);
+
+        let before = first_page(&original);
+        let after = first_page(&redacted.bytes);
+        let text = text_of(&after);
+        assert!(!text.contains("Jane") && !text.contains("Smith"));
+        assert!(text.contains("Claimant: ") && text.contains(" of 1 High Street"));
+
+        // "of" is drawn exactly where it was
+        let position = |page: &Page| {
+            let i = page.glyphs.windows(2).position(|w| w[0].text == "o" && w[1].text == "f").unwrap();
+            page.glyphs[i].bounds
+        };
+        let (b, a) = (position(&before), position(&after));
+        assert!((b.x0 - a.x0).abs() < 0.01 && (b.y0 - a.y0).abs() < 0.01);
+
+        let extracted = verify(&original, &redacted).unwrap();
+        assert!(extracted.contains("High Street"));
+        assert!(!extracted.contains("Smith"));
+    }
+
+    #[test]
+    fn terms_match_whole_words_only() {
+        let original = pdf("BT /F1 12 Tf 72 700 Td (Ann wrote the Annual report) Tj ET", None);
+        let redacted = redact(&original, &options(&["Ann"], vec![])).unwrap();
+        assert_eq!(redacted.report.term_hits[0].occurrences, 1);
+        assert!(text_of(&first_page(&redacted.bytes)).contains("Annual"));
+    }
+
+    #[test]
+    fn removes_text_inside_a_region() {
+        let original = pdf(LETTER, None);
+        // The second line, baseline at y = 680
+        let region = RedactionRegion { page: 1, x: 60.0, y: 676.0, width: 400.0, height: 14.0 };
+        let redacted = redact(&original, &options(&[], vec![region])).unwrap();
+
+        let text = text_of(&first_page(&redacted.bytes));
+        assert!(text.contains("Jane Smith"));
+        assert!(!text.contains("12345678") && !text.contains("sort"));
+        assert_eq!(redacted.report.pages_changed, vec![1]);
+        verify(&original, &redacted).unwrap();
+    }
+
+    #[test]
+    fn blackens_image_pixels_under_a_region() {
+        let pixels = vec![255u8; 10 * 10 * 3];
+        let mut image = Stream::new(
+            dictionary! {
+                "Type" => "XObject",
+                "Subtype" => "Image",
+                "Width" => 10,
+                "Height" => 10,
+                "ColorSpace" => "DeviceRGB",
+                "BitsPerComponent" => 8,
+            },
+            pixels,
+        );
+        image.compress().unwrap();
+        let original = pdf("q 100 0 0 100 100 100 cm /Im1 Do Q", Some(image));
+
+        // The bottom-left quarter of the image
+        let region = RedactionRegion { page: 1, x: 100.0, y: 100.0, width: 50.0, height: 50.0 };
+        let redacted = redact(&original, &options(&[], vec![region])).unwrap();
+        assert_eq!(redacted.report.images_edited, 1);
+        assert_eq!(redacted.report.images_removed, 0);
+
+        let doc = Document::load_mem(&redacted.bytes).unwrap();
+        let page_id = doc.get_pages()[&1];
+        let xobjects = page_xobjects(&doc, page_id);
+        let id = xobjects[format!("{}1", IMAGE_PREFIX).as_bytes()];
+        let mut stream = doc.get_object(id).unwrap().as_stream().unwrap().clone();
+        stream.dict.remove(b"Subtype");
+        let data = stream.decompressed_content().unwrap();
+        let pixel = |x: usize, y: usize| &data[(y * 10 + x) * 3..(y * 10 + x) * 3 + 3];
+        assert_eq!(pixel(0, 9), [0, 0, 0]);
+        assert_eq!(pixel(4, 5), [0, 0, 0]);
+        assert_eq!(pixel(5, 5), [255, 255, 255]);
+        assert_eq!(pixel(0, 4), [255, 255, 255]);
+    }
+
+    #[test]
+    fn verification_catches_text_left_behind() {
+        let original = pdf(LETTER, None);
+        let mut redacted = redact(&original, &options(&["Jane Smith"], vec![])).unwrap();
+        redacted.bytes = original.clone();
+        let err = verify(&original, &redacted).unwrap_err();
+        assert!(err.contains("Jane Smith"));
+    }
+
+    #[test]
+    fn clears_terms_from_document_properties() {
+        let original = pdf("BT /F1 12 Tf 72 700 Td (Letter) Tj ET", None);
+        let redacted = redact(&original, &options(&["letter"], vec![])).unwrap();
+        let doc = Document::load_mem(&redacted.bytes).unwrap();
+        let info = doc.trailer.get(b"Info").and_then(Object::as_reference).unwrap();
+        let title = doc.get_dictionary(info).unwrap().get(b"Title").unwrap();
+        assert_eq!(lopdf::decode_text_string(title).unwrap(), "");
+        verify(&original, &redacted).unwrap();
+    }
+
+    #[test]
+    fn needs_something_to_redact() {
+        let original = pdf(LETTER, None);
+        assert!(redact(&original, &options(&["  "], vec![])).is_err());
+        let region = RedactionRegion { page: 2, x: 0.0, y: 0.0, width: 10.0, height: 10.0 };
+        assert!(redact(&original, &options(&[], vec![region])).is_err());
+    }
+}
@@ -0,0 +1,538 @@
+//! Pre-action protocol compliance.
+//!
+//! Checks a case against the Practice Direction – Pre-Action Conduct and
+//! Protocols (PD-PAC): letter before claim, a reasonable response period,
+//! exchange of key documents and consideration of ADR. Steps are detected from
+//! the chronology and from letters and emails in the case, and each detection
+//! records the phrase that triggered it so the user can check the evidence.
+
+use crate::models::case::{CaseMetadata, CaseStatus, UserRole};
+use crate::models::chronology::{ChronoDate, ChronologyEntry, ChronologySource, EventType, Significance};
+use crate::models::document::{DocumentEntry, DocumentType};
+use crate::models::party::PartyRole;
+use chrono::{Duration, Months, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+/// Reasonable response period for a simple claim (PD-PAC para 6(b))
+const RESPONSE_DAYS: i64 = 14;
+
+/// Upper end of the reasonable response period for complex claims
+const COMPLEX_RESPONSE_MONTHS: u32 = 3;
+
+const LBC_PHRASES: &[&str] = &[
+    "letter before claim",
+    "letter before action",
+    "letter of claim",
+    "practice direction on pre-action conduct",
+    "practice direction - pre-action conduct",
+    "pre-action conduct",
+];
+
+const RESPONSE_PHRASES: &[&str] = &[
+    "in response to your letter",
+    "in reply to your letter",
+    "we refer to your letter",
+    "thank you for your letter",
+    "response to letter before claim",
+    "letter of response",
+    "replied to",
+    "response received",
+];
+
+const DISCLOSURE_PHRASES: &[&str] = &[
+    "enclose copies",
+    "enclosed copies",
+    "copies of the following",
+    "copies enclosed",
+    "please find enclosed",
+    "please find attached",
+    "i enclose",
+    "we enclose",
+    "key documents",
+];
+
+const ADR_PHRASES: &[&str] = &[
+    "alternative dispute resolution",
+    "mediation",
+    "mediator",
+    "ombudsman",
+    "adr",
+    "arbitration",
+    "without prejudice",
+    "settlement meeting",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum StepStatus {
+    #[serde(rename = "done")]
+    Done,
+    #[serde(rename = "pending")]
+    Pending,
+    #[serde(rename = "overdue")]
+    Overdue,
+    /// Can't start until an earlier step is done
+    #[serde(rename = "not_started")]
+    NotStarted,
+}
+
+/// Where a step was detected
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StepEvidence {
+    pub document_id: Option<String>,
+    pub chronology_id: Option<String>,
+    /// The phrase that matched, e.g. "letter before claim"
+    pub trigger: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProtocolStep {
+    /// Stable key: "letter_before_claim", "response", "document_exchange", "adr", "issue"
+    pub id: String,
+    pub title: String,
+    pub status: StepStatus,
+    pub completed_date: Option<String>,
+    pub due_date: Option<String>,
+    /// PD-PAC paragraph
+    pub reference: String,
+    pub guidance: String,
+    pub evidence: Option<StepEvidence>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Reminder {
+    pub step_id: String,
+    pub title: String,
+    pub due_date: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProtocolReport {
+    /// Only cases at the pre-action stage are expected to be working through the steps
+    pub applicable: bool,
+    /// Complex claims get a response period of up to 3 months rather than 14 days
+    pub complex: bool,
+    pub steps: Vec<ProtocolStep>,
+    pub reminders: Vec<Reminder>,
+}
+
+fn parse_date(s: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(s.trim(), "%Y-%m-%d").ok()
+}
+
+fn ymd(date: NaiveDate) -> String {
+    date.format("%Y-%m-%d").to_string()
+}
+
+fn find_phrase(text: &str, phrases: &[&str]) -> Option<String> {
+    let lower = text.to_lowercase();
+    phrases
+        .iter()
+        .find(|p| {
+            // Short acronyms must stand alone, so "adr" doesn't match "address"
+            if p.len() <= 3 {
+                lower
+                    .split(|c: char| !c.is_alphanumeric())
+                    .any(|w| w == **p)
+            } else {
+                lower.contains(*p)
+            }
+        })
+        .map(|p| p.to_string())
+}
+
+/// Something dated in the case that could show a step was taken
+struct Source<'a> {
+    date: NaiveDate,
+    text: String,
+    from: Option<&'a str>,
+    document_id: Option<String>,
+    chronology_id: Option<String>,
+}
+
+fn sources<'a>(docs: &'a [DocumentEntry], chronology: &'a [ChronologyEntry]) -> Vec<Source<'a>> {
+    let mut out = Vec::new();
+
+    for doc in docs {
+        if !matches!(doc.document_type, DocumentType::Letter | DocumentType::Email) {
+            continue;
+        }
+        if let Some(date) = doc.date.as_deref().and_then(parse_date) {
+            out.push(Source {
+                date,
+                text: format!(
+                    "{}\n{}\n{}",
+                    doc.filename,
+                    doc.description,
+                    doc.extracted_text.as_deref().unwrap_or("")
+                ),
+                from: doc.from.as_deref(),
+                document_id: Some(doc.id.clone()),
+                chronology_id: None,
+            });
+        }
+    }
+
+    for entry in chronology {
+        // Entries generated by this tracker would otherwise count as their own evidence
+        if entry.id.starts_with("protocol-") {
+            continue;
+        }
+        if let Some(date) = entry.date.as_day() {
+            out.push(Source {
+                date,
+                text: entry.description.clone(),
+                from: None,
+                document_id: entry.document_id.clone(),
+                chronology_id: Some(entry.id.clone()),
+            });
+        }
+    }
+
+    out.sort_by_key(|s| s.date);
+    out
+}
+
+fn evidence(source: &Source, trigger: String) -> StepEvidence {
+    StepEvidence {
+        document_id: source.document_id.clone(),
+        chronology_id: source.chronology_id.clone(),
+        trigger,
+    }
+}
+
+/// First source on or after `after` matching any of `phrases`
+fn first_match<'s, 'a>(
+    sources: &'s [Source<'a>],
+    phrases: &[&str],
+    after: Option<NaiveDate>,
+) -> Option<(&'s Source<'a>, String)> {
+    sources
+        .iter()
+        .filter(|s| !matches!(after, Some(a) if s.date < a))
+        .find_map(|s| find_phrase(&s.text, phrases).map(|p| (s, p)))
+}
+
+fn is_complex(case: &CaseMetadata) -> bool {
+    !case.complexity_triggers.is_empty()
+        || case.multiple_parties
+        || case.personal_injury
+        || case.cross_border
+}
+
+fn status_for(done: bool, due: Option<NaiveDate>, today: NaiveDate) -> StepStatus {
+    match (done, due) {
+        (true, _) => StepStatus::Done,
+        (false, Some(due)) if due < today => StepStatus::Overdue,
+        _ => StepStatus::Pending,
+    }
+}
+
+/// Work out which pre-action steps have been taken and what is still due
+pub fn check(
+    case: &CaseMetadata,
+    docs: &[DocumentEntry],
+    chronology: &[ChronologyEntry],
+    today: NaiveDate,
+) -> ProtocolReport {
+    let sources = sources(docs, chronology);
+    let complex = is_complex(case);
+    let claimant = matches!(case.user_role, UserRole::Claimant);
+    let mut steps = Vec::new();
+
+    // 1. Letter before claim
+    let lbc = first_match(&sources, LBC_PHRASES, None);
+    let lbc_date = lbc.as_ref().map(|(s, _)| s.date);
+    steps.push(ProtocolStep {
+        id: "letter_before_claim".to_string(),
+        title: if claimant {
+            "Send a letter before claim".to_string()
+        } else {
+            "Letter before claim received".to_string()
+        },
+        status: if lbc.is_some() { StepStatus::Done } else { StepStatus::Pending },
+        completed_date: lbc_date.map(ymd),
+        due_date: None,
+        reference: "PD-PAC para 6(a)".to_string(),
+        guidance: "The letter should set out the details of the claim concisely, what is wanted and a deadline for response".to_string(),
+        evidence: lbc.as_ref().map(|(s, p)| evidence(s, p.clone())),
+    });
+
+    // 2. Response within a reasonable period
+    let response_due = lbc_date.map(|d| {
+        if complex {
+            d.checked_add_months(Months::new(COMPLEX_RESPONSE_MONTHS))
+                .unwrap_or(d)
+        } else {
+            d + Duration::days(RESPONSE_DAYS)
+        }
+    });
+    let response = lbc_date.and_then(|lbc_date| {
+        let parties = case.parties();
+        sources
+            .iter()
+            .filter(|s| s.date > lbc_date)
+            .find_map(|s| {
+                let from_defendant = s.from.and_then(|f| {
+                    crate::parties::correspondents(f)
+                        .iter()
+                        .filter_map(|who| crate::parties::match_party(&parties, who))
+                        .find(|p| p.role == PartyRole::Defendant)
+                });
+                find_phrase(&s.text, RESPONSE_PHRASES)
+                    .or_else(|| from_defendant.map(|p| format!("From {}", p.name)))
+                    .map(|p| (s, p))
+            })
+    });
+    let responded = response.is_some() || (lbc.is_some() && case.defendant_responded);
+    steps.push(ProtocolStep {
+        id: "response".to_string(),
+        title: if claimant {
+            "Defendant's response".to_string()
+        } else {
+            "Respond to the letter before claim".to_string()
+        },
+        status: if lbc.is_none() {
+            StepStatus::NotStarted
+        } else {
+            status_for(responded, response_due, today)
+        },
+        completed_date: response.as_ref().map(|(s, _)| ymd(s.date)),
+        due_date: response_due.map(ymd),
+        reference: "PD-PAC para 6(b)".to_string(),
+        guidance: if complex {
+            "A reasonable period for a complex claim can be up to 3 months from the letter before claim".to_string()
+        } else {
+            "14 days from the letter before claim is a reasonable period for a straightforward claim".to_string()
+        },
+        evidence: response.as_ref().map(|(s, p)| evidence(s, p.clone())),
+    });
+
+    // 3. Key documents disclosed
+    let disclosure = first_match(&sources, DISCLOSURE_PHRASES, lbc_date);
+    steps.push(ProtocolStep {
+        id: "document_exchange".to_string(),
+        title: "Disclose key documents".to_string(),
+        status: if lbc.is_none() {
+            StepStatus::NotStarted
+        } else {
+            status_for(disclosure.is_some(), response_due, today)
+        },
+        completed_date: disclosure.as_ref().map(|(s, _)| ymd(s.date)),
+        due_date: response_due.map(ymd),
+        reference: "PD-PAC para 6(c)".to_string(),
+        guidance: "Each side should disclose the key documents relevant to the issues in dispute".to_string(),
+        evidence: disclosure.as_ref().map(|(s, p)| evidence(s, p.clone())),
+    });
+
+    // 4. ADR considered
+    let adr = first_match(&sources, ADR_PHRASES, None);
+    steps.push(ProtocolStep {
+        id: "adr".to_string(),
+        title: "Consider ADR".to_string(),
+        status: if lbc.is_none() && adr.is_none() {
+            StepStatus::NotStarted
+        } else {
+            status_for(adr.is_some(), response_due, today)
+        },
+        completed_date: adr.as_ref().map(|(s, _)| ymd(s.date)),
+        due_date: response_due.map(ymd),
+        reference: "PD-PAC paras 8–11".to_string(),
+        guidance: "Litigation should be a last resort. Refusing to consider ADR can lead to a costs penalty, even if you win".to_string(),
+        evidence: adr.as_ref().map(|(s, p)| evidence(s, p.clone())),
+    });
+
+    // 5. Proceedings — not before the response period has run
+    let issued = case.procedural_dates.claim_issued.is_some()
+        || !matches!(case.status, CaseStatus::Intake | CaseStatus::PreAction | CaseStatus::ADR);
+    steps.push(ProtocolStep {
+        id: "issue".to_string(),
+        title: "Earliest date to issue proceedings".to_string(),
+        status: if issued {
+            StepStatus::Done
+        } else if lbc.is_none() {
+            StepStatus::NotStarted
+        } else {
+            StepStatus::Pending
+        },
+        completed_date: case.procedural_dates.claim_issued.clone(),
+        due_date: response_due.map(|d| ymd(d + Duration::days(1))),
+        reference: "PD-PAC para 15".to_string(),
+        guidance: "Issuing before the response period ends, or without following the steps above, risks a costs sanction".to_string(),
+        evidence: None,
+    });
+
+    let reminders = steps
+        .iter()
+        .filter(|s| matches!(s.status, StepStatus::Pending | StepStatus::Overdue) && s.id != "issue")
+        .filter_map(|s| {
+            s.due_date.as_ref().map(|d| Reminder {
+                step_id: s.id.clone(),
+                title: s.title.clone(),
+                due_date: d.clone(),
+            })
+        })
+        .collect();
+
+    ProtocolReport {
+        applicable: matches!(case.status, CaseStatus::PreAction),
+        complex,
+        steps,
+        reminders,
+    }
+}
+
+/// Chronology entries for the steps the tracker has found, and for the end of
+/// the response period. Ids are stable so adding them twice is harmless.
+pub fn chronology_entries(report: &ProtocolReport) -> Vec<ChronologyEntry> {
+    let mut entries = Vec::new();
+
+    for step in &report.steps {
+        if step.id == "issue" {
+            continue;
+        }
+        if let (StepStatus::Done, Some(date)) = (step.status, &step.completed_date) {
+            let document_id = step.evidence.as_ref().and_then(|e| e.document_id.clone());
+            entries.push(ChronologyEntry {
+                id: format!("protocol-{}", step.id),
+                date: ChronoDate::from_text(date),
+                description: format!("{} ({})", step.title, step.reference),
+                source: if document_id.is_some() {
+                    ChronologySource::Document
+                } else {
+                    ChronologySource::Manual
+                },
+                document_id,
+                source_document_path: None,
+                significance: Significance::Key,
+                confidence: None,
+                event_type: match step.id.as_str() {
+                    "letter_before_claim" => Some(EventType::LetterBeforeClaimSent),
+                    "response" => Some(EventType::ResponseReceived),
+                    _ => None,
+                },
+                trigger: None,
+                relative: None,
+            });
+        }
+    }
+
+    if let Some(response) = report.steps.iter().find(|s| s.id == "response") {
+        if let Some(due) = &response.due_date {
+            entries.push(ChronologyEntry {
+                id: "protocol-response-period-ends".to_string(),
+                date: ChronoDate::from_text(due),
+                description: format!("Pre-action response period ends ({})", response.reference),
+                source: ChronologySource::Manual,
+                significance: Significance::Key,
+                ..ChronologyEntry::default()
+            });
+        }
+    }
+
+    entries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    fn letter(date: &str, from: &str, text: &str) -> DocumentEntry {
+        DocumentEntry {
+            document_type: DocumentType::Letter,
+            date: Some(date.to_string()),
+            from: Some(from.to_string()),
+            extracted_text: Some(text.to_string()),
+            ..DocumentEntry::default()
+        }
+    }
+
+    fn pre_action_case() -> CaseMetadata {
+        CaseMetadata {
+            status: CaseStatus::PreAction,
+            defendant_name: "Acme Appliances Ltd".to_string(),
+            ..CaseMetadata::default()
+        }
+    }
+
+    fn step<'a>(report: &'a ProtocolReport, id: &str) -> &'a ProtocolStep {
+        report.steps.iter().find(|s| s.id == id).unwrap()
+    }
+
+    #[test]
+    fn nothing_sent_yet() {
+        let report = check(&pre_action_case(), &[], &[], date("2025-01-10"));
+        assert!(report.applicable);
+        assert_eq!(step(&report, "letter_before_claim").status, StepStatus::Pending);
+        assert_eq!(step(&report, "response").status, StepStatus::NotStarted);
+        assert!(report.reminders.is_empty());
+    }
+
+    #[test]
+    fn response_overdue_after_fourteen_days() {
+        let docs = vec![letter(
+            "2025-01-06",
+            "Jane Smith",
+            "LETTER BEFORE CLAIM\nI enclose copies of the receipt. I am willing to consider mediation.",
+        )];
+        let report = check(&pre_action_case(), &docs, &[], date("2025-01-25"));
+
+        let lbc = step(&report, "letter_before_claim");
+        assert_eq!(lbc.status, StepStatus::Done);
+        assert_eq!(lbc.evidence.as_ref().unwrap().trigger, "letter before claim");
+
+        let response = step(&report, "response");
+        assert_eq!(response.due_date.as_deref(), Some("2025-01-20"));
+        assert_eq!(response.status, StepStatus::Overdue);
+
+        assert_eq!(step(&report, "document_exchange").status, StepStatus::Done);
+        assert_eq!(step(&report, "adr").evidence.as_ref().unwrap().trigger, "mediation");
+        assert_eq!(report.reminders.len(), 1);
+    }
+
+    #[test]
+    fn reply_from_defendant_completes_response() {
+        let docs = vec![
+            letter("2025-01-06", "Jane Smith", "Letter of claim"),
+            letter("2025-01-15", "Acme Appliances Ltd Customer Services", "We do not accept liability."),
+        ];
+        let report = check(&pre_action_case(), &docs, &[], date("2025-01-25"));
+        let response = step(&report, "response");
+        assert_eq!(response.status, StepStatus::Done);
+        assert_eq!(response.completed_date.as_deref(), Some("2025-01-15"));
+    }
+
+    #[test]
+    fn complex_claims_get_three_months() {
+        let mut case = pre_action_case();
+        case.multiple_parties = true;
+        let chronology = vec![ChronologyEntry {
+            date: ChronoDate::from_text("2025-01-06"),
+            description: "Sent letter before action by recorded delivery".to_string(),
+            ..ChronologyEntry::default()
+        }];
+        let report = check(&case, &[], &chronology, date("2025-02-01"));
+        assert!(report.complex);
+        assert_eq!(step(&report, "response").due_date.as_deref(), Some("2025-04-06"));
+        assert_eq!(step(&report, "response").status, StepStatus::Pending);
+    }
+
+    #[test]
+    fn adr_acronym_needs_word_boundary() {
+        assert_eq!(find_phrase("Our address is 1 High St", ADR_PHRASES), None);
+        assert_eq!(find_phrase("Would you consider ADR?", ADR_PHRASES).as_deref(), Some("adr"));
+    }
+
+    #[test]
+    fn generated_entries_have_stable_ids() {
+        let docs = vec![letter("2025-01-06", "Jane Smith", "Letter before claim")];
+        let report = check(&pre_action_case(), &docs, &[], date("2025-01-10"));
+        let entries = chronology_entries(&report);
+        let ids: Vec<&str> = entries.iter().map(|e| e.id.as_str()).collect();
+        assert_eq!(ids, vec!["protocol-letter_before_claim", "protocol-response-period-ends"]);
+        assert_eq!(entries[1].date.as_day(), Some(date("2025-01-20")));
+    }
+}
@@ -0,0 +1,575 @@
+//! Redaction of personal data before text leaves the machine.
+//!
+//! Finds UK bank details (sort codes, account numbers, IBANs), NI and NHS
+//! numbers, card numbers, phone numbers, email addresses, postcodes, dates of
+//! birth and any terms the user lists for the case. Each distinct value is
+//! replaced by a numbered placeholder such as "[REDACTED EMAIL 1]", the same
+//! value getting the same placeholder in every document, so an AI response
+//! can still refer to it and be mapped back.
+//!
+//! The mapping from placeholders to the original values stays in the case
+//! folder. Only a `RedactionLog` — what kinds were removed and how many — is
+//! kept with the AI history.
+//!
+//! Numbers that are easily confused with others (sort codes with dates,
+//! account numbers with references) are only redacted with a keyword such as
+//! "sort code" or "account" nearby. Card, IBAN and NHS numbers must pass
+//! their check digits.
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum PiiKind {
+    #[serde(rename = "sort_code")]
+    SortCode,
+    #[serde(rename = "account_number")]
+    AccountNumber,
+    #[serde(rename = "iban")]
+    Iban,
+    #[serde(rename = "ni_number")]
+    NiNumber,
+    #[serde(rename = "nhs_number")]
+    NhsNumber,
+    #[serde(rename = "card_number")]
+    CardNumber,
+    #[serde(rename = "phone")]
+    Phone,
+    #[serde(rename = "email")]
+    Email,
+    #[serde(rename = "postcode")]
+    Postcode,
+    #[serde(rename = "date_of_birth")]
+    DateOfBirth,
+    /// A term the user asked to redact
+    #[serde(rename = "custom")]
+    Custom,
+}
+
+/// The order kinds are looked for in. Where two overlap, the earlier wins, so
+/// a custom term beats everything and a checked card number beats a phone
+/// number made of the same digits.
+const PRIORITY: [PiiKind; 11] = [
+    PiiKind::Custom,
+    PiiKind::Email,
+    PiiKind::Iban,
+    PiiKind::CardNumber,
+    PiiKind::NhsNumber,
+    PiiKind::NiNumber,
+    PiiKind::DateOfBirth,
+    PiiKind::SortCode,
+    PiiKind::AccountNumber,
+    PiiKind::Phone,
+    PiiKind::Postcode,
+];
+
+/// How far before a number to look for a keyword like "sort code"
+const CONTEXT_CHARS: usize = 40;
+
+const BANK_KEYWORDS: &[&str] = &["sort code", "sort-code", "sortcode", "s/c", "bank", "account", "a/c", "acct", "acc no", "acc. no"];
+
+impl PiiKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            PiiKind::SortCode => "SORT CODE",
+            PiiKind::AccountNumber => "ACCOUNT NUMBER",
+            PiiKind::Iban => "IBAN",
+            PiiKind::NiNumber => "NI NUMBER",
+            PiiKind::NhsNumber => "NHS NUMBER",
+            PiiKind::CardNumber => "CARD NUMBER",
+            PiiKind::Phone => "PHONE",
+            PiiKind::Email => "EMAIL",
+            PiiKind::Postcode => "POSTCODE",
+            PiiKind::DateOfBirth => "DATE OF BIRTH",
+            PiiKind::Custom => "TERM",
+        }
+    }
+}
+
+/// What to look for. With no `kinds`, everything is redacted.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RedactionOptions {
+    #[serde(default)]
+    pub kinds: Option<Vec<PiiKind>>,
+    #[serde(default)]
+    pub custom_terms: Vec<String>,
+}
+
+/// One placeholder and the value it stands for
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RedactionEntry {
+    pub placeholder: String,
+    pub kind: PiiKind,
+    pub original: String,
+    /// How many times the value was replaced
+    pub occurrences: usize,
+}
+
+/// The reversible side of a redaction. Holds the original values, so it is
+/// only ever stored in the case folder.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RedactionMap {
+    pub id: String,
+    pub created_at: String,
+    pub entries: Vec<RedactionEntry>,
+    /// What was searched for, so the log of a request made with this map can
+    /// be rebuilt by the backend
+    #[serde(default)]
+    pub log: Option<RedactionLog>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RedactionCount {
+    pub kind: PiiKind,
+    /// Distinct values redacted
+    pub values: usize,
+    /// Places they were replaced
+    pub occurrences: usize,
+}
+
+/// A record of what was redacted, without the values themselves
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RedactionLog {
+    /// The `RedactionMap` that can reverse it
+    pub map_id: String,
+    pub created_at: String,
+    pub counts: Vec<RedactionCount>,
+    pub total: usize,
+    /// Number of custom terms searched for
+    pub custom_terms: usize,
+    pub kinds_checked: Vec<PiiKind>,
+}
+
+/// Redacted texts, in the order given, with their map and log
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Redacted {
+    pub texts: Vec<String>,
+    pub map: RedactionMap,
+    pub log: RedactionLog,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PiiMatch {
+    pub start: usize,
+    pub end: usize,
+    pub kind: PiiKind,
+}
+
+// ===== Check digits =====
+
+fn digits(s: &str) -> Vec<u32> {
+    s.chars().filter_map(|c| c.to_digit(10)).collect()
+}
+
+fn luhn_valid(number: &[u32]) -> bool {
+    let sum: u32 = number
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(i, &d)| {
+            if i % 2 == 1 {
+                let doubled = d * 2;
+                if doubled > 9 { doubled - 9 } else { doubled }
+            } else {
+                d
+            }
+        })
+        .sum();
+    sum.is_multiple_of(10)
+}
+
+fn nhs_valid(number: &[u32]) -> bool {
+    if number.len() != 10 {
+        return false;
+    }
+    let sum: u32 = number[..9].iter().enumerate().map(|(i, &d)| d * (10 - i as u32)).sum();
+    match 11 - sum % 11 {
+        11 => number[9] == 0,
+        10 => false,
+        check => number[9] == check,
+    }
+}
+
+/// ISO 13616: move the first four characters to the end, turn letters into
+/// numbers (A = 10) and check the result mod 97 is 1
+fn iban_valid(iban: &str) -> bool {
+    let compact: String = iban.chars().filter(|c| !c.is_whitespace()).collect();
+    if !(15..=34).contains(&compact.len()) {
+        return false;
+    }
+    let rearranged = compact[4..].chars().chain(compact[..4].chars());
+    let mut remainder: u32 = 0;
+    for c in rearranged {
+        let Some(value) = c.to_digit(36) else { return false };
+        for d in value.to_string().chars() {
+            remainder = (remainder * 10 + d.to_digit(10).unwrap_or(0)) % 97;
+        }
+    }
+    remainder == 1
+}
+
+// ===== Detection =====
+
+/// Whether one of `keywords` appears shortly before byte `start`
+fn has_context(text: &str, start: usize, keywords: &[&str]) -> bool {
+    let mut from = start.saturating_sub(CONTEXT_CHARS);
+    while !text.is_char_boundary(from) {
+        from -= 1;
+    }
+    let before = text[from..start].to_lowercase();
+    keywords.iter().any(|k| before.contains(k))
+}
+
+/// The longest run at the start of `m` that ends at a number boundary and
+/// passes `valid`, so "4111 1111 1111 1111 2024" still finds the card
+fn longest_valid(text: &str, start: usize, end: usize, valid: impl Fn(&str) -> bool) -> Option<usize> {
+    let candidate = &text[start..end];
+    let mut ends: Vec<usize> = candidate
+        .char_indices()
+        .filter(|&(i, c)| {
+            c.is_ascii_alphanumeric()
+                && candidate[i + 1..].chars().next().is_none_or(|n| !n.is_ascii_alphanumeric())
+        })
+        .map(|(i, _)| i + 1)
+        .collect();
+    ends.reverse();
+    ends.into_iter().find(|&e| valid(&candidate[..e])).map(|e| start + e)
+}
+
+fn find_kind(text: &str, kind: PiiKind, custom_terms: &[String]) -> Vec<(usize, usize)> {
+    let spans = |pattern: &str| -> Vec<(usize, usize)> {
+        Regex::new(pattern)
+            .unwrap()
+            .find_iter(text)
+            .map(|m| (m.start(), m.end()))
+            .collect()
+    };
+
+    match kind {
+        PiiKind::Email => spans(r"(?i)\b[a-z0-9._%+\-]+@[a-z0-9.\-]+\.[a-z]{2,}\b"),
+        PiiKind::Iban => spans(r"\b[A-Z]{2}\d{2}(?: ?[A-Z0-9]){11,30}\b")
+            .into_iter()
+            .filter_map(|(s, e)| longest_valid(text, s, e, iban_valid).map(|e| (s, e)))
+            .collect(),
+        PiiKind::CardNumber => spans(r"\b\d(?:[ \-]?\d){12,22}\b")
+            .into_iter()
+            .filter_map(|(s, e)| {
+                longest_valid(text, s, e, |c| {
+                    let d = digits(c);
+                    (13..=19).contains(&d.len()) && luhn_valid(&d)
+                })
+                .map(|e| (s, e))
+            })
+            .collect(),
+        // NHS numbers start 4 to 9 in England and Wales; anything starting 0
+        // is far more likely to be a phone number
+        PiiKind::NhsNumber => spans(r"\b[1-9]\d{2}[ \-]?\d{3}[ \-]?\d{4}\b")
+            .into_iter()
+            .filter(|&(s, e)| nhs_valid(&digits(&text[s..e])))
+            .collect(),
+        PiiKind::NiNumber => {
+            let re = Regex::new(r"(?i)\b([A-CEGHJ-PR-TW-Z][A-CEGHJ-NPR-TW-Z])\s?\d{2}\s?\d{2}\s?\d{2}\s?[A-D]\b").unwrap();
+            re.captures_iter(text)
+                .filter(|c| !["BG", "GB", "NK", "KN", "TN", "NT", "ZZ"].contains(&c[1].to_uppercase().as_str()))
+                .map(|c| {
+                    let m = c.get(0).unwrap();
+                    (m.start(), m.end())
+                })
+                .collect()
+        }
+        PiiKind::DateOfBirth => {
+            let re = Regex::new(
+                r"(?i)\b(?:d\.?o\.?b\b\.?|date\s+of\s+birth|born(?:\s+on)?)\s*[:\-]?\s*(\d{1,2}[/\-.]\d{1,2}[/\-.]\d{2,4}|\d{1,2}(?:st|nd|rd|th)?\s+[a-z]{3,9}\.?\s+\d{4})",
+            )
+            .unwrap();
+            re.captures_iter(text)
+                .filter_map(|c| c.get(1))
+                .map(|m| (m.start(), m.end()))
+                .collect()
+        }
+        PiiKind::SortCode => spans(r"\b\d{2}[\- ]\d{2}[\- ]\d{2}\b|\b\d{6}\b")
+            .into_iter()
+            .filter(|&(s, _)| has_context(text, s, BANK_KEYWORDS))
+            .collect(),
+        PiiKind::AccountNumber => spans(r"\b\d{7,8}\b")
+            .into_iter()
+            .filter(|&(s, _)| has_context(text, s, BANK_KEYWORDS))
+            .collect(),
+        PiiKind::Phone => spans(r"(?:\+44\s?(?:\(0\)\s?)?|\(?\b0)\d{2,4}\)?(?:[\s\-]?\d{3,4}){1,2}\b")
+            .into_iter()
+            .filter(|&(s, e)| {
+                let m = &text[s..e];
+                let d = digits(m);
+                if m.starts_with("+44") {
+                    // Country code, plus a (0) some people write after it
+                    let national = d.len() - 2 - usize::from(m.contains("(0)"));
+                    (9..=10).contains(&national)
+                } else {
+                    (10..=11).contains(&d.len())
+                }
+            })
+            .collect(),
+        PiiKind::Postcode => spans(r"\b(?:GIR ?0AA|[A-PR-UWYZ][A-HK-Y]?\d[A-Z\d]? ?\d[ABD-HJLNP-UW-Z]{2})\b"),
+        PiiKind::Custom => custom_terms
+            .iter()
+            .map(|t| t.trim())
+            .filter(|t| t.chars().count() >= 2)
+            .flat_map(|term| {
+                let edge = |c: Option<char>| if c.is_some_and(|c| c.is_alphanumeric()) { r"\b" } else { "" };
+                spans(&format!(
+                    "(?i){}{}{}",
+                    edge(term.chars().next()),
+                    regex::escape(term),
+                    edge(term.chars().last())
+                ))
+            })
+            .collect(),
+    }
+}
+
+/// Every piece of personal data in `text`, in order, without overlaps
+pub fn find_pii(text: &str, options: &RedactionOptions) -> Vec<PiiMatch> {
+    let mut found: Vec<PiiMatch> = Vec::new();
+    for kind in PRIORITY {
+        let wanted = kind == PiiKind::Custom || options.kinds.as_ref().is_none_or(|k| k.contains(&kind));
+        if !wanted {
+            continue;
+        }
+        for (start, end) in find_kind(text, kind, &options.custom_terms) {
+            if !found.iter().any(|m| start < m.end && m.start < end) {
+                found.push(PiiMatch { start, end, kind });
+            }
+        }
+    }
+    found.sort_by_key(|m| m.start);
+    found
+}
+
+/// Values that differ only in case, spacing or hyphens are the same value
+fn value_key(kind: PiiKind, original: &str) -> (PiiKind, String) {
+    let key = original
+        .chars()
+        .filter(|c| !c.is_whitespace() && *c != '-')
+        .collect::<String>()
+        .to_lowercase();
+    (kind, key)
+}
+
+/// Redact a set of texts together, so a value gets the same placeholder
+/// wherever it appears
+pub fn redact_all(texts: &[String], options: &RedactionOptions) -> Redacted {
+    let id = uuid::Uuid::new_v4().to_string();
+    let created_at = chrono::Utc::now().to_rfc3339();
+    let mut entries: Vec<RedactionEntry> = Vec::new();
+    let mut by_value: HashMap<(PiiKind, String), usize> = HashMap::new();
+
+    let redacted = texts
+        .iter()
+        .map(|text| {
+            let mut out = String::with_capacity(text.len());
+            let mut last = 0;
+            for m in find_pii(text, options) {
+                let original = &text[m.start..m.end];
+                let index = *by_value.entry(value_key(m.kind, original)).or_insert_with(|| {
+                    let number = entries.iter().filter(|e| e.kind == m.kind).count() + 1;
+                    entries.push(RedactionEntry {
+                        placeholder: format!("[REDACTED {} {}]", m.kind.label(), number),
+                        kind: m.kind,
+                        original: original.to_string(),
+                        occurrences: 0,
+                    });
+                    entries.len() - 1
+                });
+                entries[index].occurrences += 1;
+                out.push_str(&text[last..m.start]);
+                out.push_str(&entries[index].placeholder);
+                last = m.end;
+            }
+            out.push_str(&text[last..]);
+            out
+        })
+        .collect();
+
+    let mut counts: Vec<RedactionCount> = Vec::new();
+    for kind in PRIORITY {
+        let of_kind: Vec<&RedactionEntry> = entries.iter().filter(|e| e.kind == kind).collect();
+        if !of_kind.is_empty() {
+            counts.push(RedactionCount {
+                kind,
+                values: of_kind.len(),
+                occurrences: of_kind.iter().map(|e| e.occurrences).sum(),
+            });
+        }
+    }
+
+    let log = RedactionLog {
+        map_id: id.clone(),
+        created_at: created_at.clone(),
+        total: counts.iter().map(|c| c.occurrences).sum(),
+        counts,
+        custom_terms: options.custom_terms.iter().filter(|t| !t.trim().is_empty()).count(),
+        kinds_checked: PRIORITY
+            .into_iter()
+            .filter(|k| *k != PiiKind::Custom && options.kinds.as_ref().is_none_or(|w| w.contains(k)))
+            .collect(),
+    };
+
+    Redacted {
+        texts: redacted,
+        map: RedactionMap { id, created_at, entries, log: Some(log.clone()) },
+        log,
+    }
+}
+
+/// Replace any original value from `map` still in `text` with its
+/// placeholder, e.g. one typed back in while editing or repeated in the
+/// case details around the documents. Longer values go first, so a name
+/// isn't split by a shorter term inside it.
+pub fn redact_with_map(text: &str, map: &RedactionMap) -> String {
+    let mut entries: Vec<&RedactionEntry> = map.entries.iter().filter(|e| !e.original.trim().is_empty()).collect();
+    entries.sort_by_key(|e| std::cmp::Reverse(e.original.len()));
+    entries.into_iter().fold(text.to_string(), |acc, e| {
+        let edge = |c: Option<char>| if c.is_some_and(|c| c.is_alphanumeric()) { r"\b" } else { "" };
+        let pattern = format!(
+            "(?i){}{}{}",
+            edge(e.original.chars().next()),
+            regex::escape(&e.original),
+            edge(e.original.chars().last())
+        );
+        match Regex::new(&pattern) {
+            Ok(re) => re.replace_all(&acc, regex::NoExpand(&e.placeholder)).into_owned(),
+            Err(_) => acc.replace(&e.original, &e.placeholder),
+        }
+    })
+}
+
+/// The log of what was redacted from `text`, counted from the placeholders
+/// it actually contains rather than taken on trust
+pub fn log_for(text: &str, map: &RedactionMap) -> RedactionLog {
+    let mut counts: Vec<RedactionCount> = Vec::new();
+    for kind in PRIORITY {
+        let found: Vec<usize> = map
+            .entries
+            .iter()
+            .filter(|e| e.kind == kind)
+            .map(|e| text.matches(&e.placeholder).count())
+            .filter(|&n| n > 0)
+            .collect();
+        if !found.is_empty() {
+            counts.push(RedactionCount { kind, values: found.len(), occurrences: found.iter().sum() });
+        }
+    }
+    let searched = map.log.as_ref();
+    RedactionLog {
+        map_id: map.id.clone(),
+        created_at: chrono::Utc::now().to_rfc3339(),
+        total: counts.iter().map(|c| c.occurrences).sum(),
+        counts,
+        custom_terms: searched.map(|l| l.custom_terms).unwrap_or(0),
+        kinds_checked: searched.map(|l| l.kinds_checked.clone()).unwrap_or_default(),
+    }
+}
+
+/// Put the original values back in place of their placeholders
+pub fn restore(text: &str, map: &RedactionMap) -> String {
+    map.entries
+        .iter()
+        .fold(text.to_string(), |acc, e| acc.replace(&e.placeholder, &e.original))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds(text: &str) -> Vec<(PiiKind, String)> {
+        find_pii(text, &RedactionOptions::default())
+            .into_iter()
+            .map(|m| (m.kind, text[m.start..m.end].to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn checks_digits() {
+        assert!(luhn_valid(&digits("4111 1111 1111 1111")));
+        assert!(!luhn_valid(&digits("4111 1111 1111 1112")));
+        assert!(nhs_valid(&digits("943 476 5919")));
+        assert!(!nhs_valid(&digits("943 476 5918")));
+        assert!(iban_valid("GB82 WEST 1234 5698 7654 32"));
+        assert!(!iban_valid("GB82 WEST 1234 5698 7654 33"));
+    }
+
+    #[test]
+    fn finds_bank_details_only_with_context() {
+        assert_eq!(
+            kinds("Please pay to sort code 12-34-56, account number 12345678."),
+            vec![(PiiKind::SortCode, "12-34-56".to_string()), (PiiKind::AccountNumber, "12345678".to_string())]
+        );
+        // A date and an order reference on their own are left alone
+        assert!(kinds("Delivered 12-03-24, order 12345678").is_empty());
+        assert_eq!(kinds("IBAN GB82 WEST 1234 5698 7654 32 please")[0].1, "GB82 WEST 1234 5698 7654 32");
+    }
+
+    #[test]
+    fn finds_identifiers_and_contact_details() {
+        let found = kinds(
+            "Jane (NI AB 12 34 56 C, NHS no 943 476 5919, DOB: 01/02/1980) paid with 4111-1111-1111-1111. \
+             Call 07700 900123 or +44 (0)20 7946 0018, email jane.smith@example.com, LS1 1AA.",
+        );
+        let found: Vec<PiiKind> = found.into_iter().map(|(k, _)| k).collect();
+        assert_eq!(
+            found,
+            vec![
+                PiiKind::NiNumber,
+                PiiKind::NhsNumber,
+                PiiKind::DateOfBirth,
+                PiiKind::CardNumber,
+                PiiKind::Phone,
+                PiiKind::Phone,
+                PiiKind::Email,
+                PiiKind::Postcode,
+            ]
+        );
+        // Invalid prefixes and failed check digits
+        assert!(kinds("GB 12 34 56 A and 4111 1111 1111 1112").is_empty());
+    }
+
+    #[test]
+    fn redacts_consistently_and_reverses() {
+        let texts = vec![
+            "Email jane@example.com about the Acme order. Jane's card 4111 1111 1111 1111.".to_string(),
+            "Reply to JANE@example.com re Flat 4".to_string(),
+        ];
+        let options = RedactionOptions {
+            kinds: None,
+            custom_terms: vec!["Flat 4".to_string(), " ".to_string()],
+        };
+        let redacted = redact_all(&texts, &options);
+
+        assert_eq!(
+            redacted.texts[0],
+            "Email [REDACTED EMAIL 1] about the Acme order. Jane's card [REDACTED CARD NUMBER 1]."
+        );
+        assert_eq!(redacted.texts[1], "Reply to [REDACTED EMAIL 1] re [REDACTED TERM 1]");
+        assert_eq!(redacted.log.total, 4);
+        assert_eq!(redacted.log.custom_terms, 1);
+        assert_eq!(redacted.log.counts[1], RedactionCount { kind: PiiKind::Email, values: 1, occurrences: 2 });
+        assert_eq!(redacted.log.map_id, redacted.map.id);
+        assert_eq!(restore(&redacted.texts[0], &redacted.map), texts[0]);
+
+        // The log never carries the values
+        let log = serde_json::to_string(&redacted.log).unwrap();
+        assert!(!log.contains("example.com") && !log.contains("4111"));
+    }
+
+    #[test]
+    fn only_checks_the_kinds_asked_for() {
+        let options = RedactionOptions {
+            kinds: Some(vec![PiiKind::Email]),
+            custom_terms: Vec::new(),
+        };
+        let redacted = redact_all(&["jane@example.com, 07700 900123".to_string()], &options);
+        assert_eq!(redacted.texts[0], "[REDACTED EMAIL 1], 07700 900123");
+        assert_eq!(redacted.log.kinds_checked, vec![PiiKind::Email]);
+    }
+}
@@ -0,0 +1,464 @@
+//! Consumer Rights Act 2015 remedies.
+//!
+//! Works out which statutory remedies the consumer can still pursue, keyed off
+//! the claim type: goods (Chapter 2), digital content (Chapter 3) or services
+//! (Chapter 4). The purchase date stands in for delivery or supply, the
+//! discovery date for when the fault appeared, and the first complaint for
+//! when the consumer acted on it.
+
+use crate::models::case::{CaseMetadata, ClaimType};
+use chrono::{Days, Months, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+/// s.22(3) — the short-term right to reject lasts 30 days from delivery
+const SHORT_TERM_REJECT_DAYS: u64 = 30;
+
+/// s.19(14) and s.42(9) — faults appearing within 6 months are presumed to
+/// have been there on delivery
+const REVERSE_BURDEN_MONTHS: u32 = 6;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RemedyStatus {
+    #[serde(rename = "available")]
+    Available,
+    /// Only available once something else has happened, e.g. a failed repair
+    #[serde(rename = "conditional")]
+    Conditional,
+    #[serde(rename = "expired")]
+    Expired,
+    /// Not enough dates on the case to tell
+    #[serde(rename = "unknown")]
+    Unknown,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Remedy {
+    /// Stable identifier, e.g. "short_term_reject"
+    pub id: String,
+    pub title: String,
+    /// e.g. "Consumer Rights Act 2015, s.22"
+    pub section: String,
+    pub status: RemedyStatus,
+    /// Last day the remedy (or the presumption) applies, YYYY-MM-DD
+    pub deadline: Option<String>,
+    pub days_remaining: Option<i64>,
+    pub explanation: String,
+    /// What has to be true before the remedy can be used
+    pub conditions: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RemediesReport {
+    pub claim_type: ClaimType,
+    pub remedies: Vec<Remedy>,
+    pub assumptions: Vec<String>,
+}
+
+fn parse_date(s: Option<&str>) -> Option<NaiveDate> {
+    s.and_then(|s| NaiveDate::parse_from_str(s.trim(), "%Y-%m-%d").ok())
+}
+
+fn fmt(date: NaiveDate) -> String {
+    date.format("%-d %B %Y").to_string()
+}
+
+fn remedy(id: &str, title: &str, section: &str, status: RemedyStatus, explanation: String) -> Remedy {
+    Remedy {
+        id: id.to_string(),
+        title: title.to_string(),
+        section: format!("Consumer Rights Act 2015, {}", section),
+        status,
+        deadline: None,
+        days_remaining: None,
+        explanation,
+        conditions: Vec::new(),
+    }
+}
+
+fn with_deadline(mut remedy: Remedy, deadline: NaiveDate, today: NaiveDate) -> Remedy {
+    remedy.deadline = Some(deadline.format("%Y-%m-%d").to_string());
+    remedy.days_remaining = Some((deadline - today).num_days());
+    remedy
+}
+
+fn with_conditions(mut remedy: Remedy, conditions: &[&str]) -> Remedy {
+    remedy.conditions = conditions.iter().map(|c| c.to_string()).collect();
+    remedy
+}
+
+/// The 6-month presumption that a fault was present on delivery or supply.
+/// For goods the period begins the day after delivery (s.19(15)); for digital
+/// content it begins on the day of supply (s.42(10)).
+fn reverse_burden(
+    section: &str,
+    delivered: Option<NaiveDate>,
+    starts_day_after: bool,
+    fault_seen: Option<NaiveDate>,
+    today: NaiveDate,
+) -> Remedy {
+    let title = "Fault presumed present from the start";
+    let Some(delivered) = delivered else {
+        return remedy(
+            "reverse_burden",
+            title,
+            section,
+            RemedyStatus::Unknown,
+            "Add the date of purchase to see whether the 6-month presumption applies".to_string(),
+        );
+    };
+    let ends = delivered
+        .checked_add_months(Months::new(REVERSE_BURDEN_MONTHS))
+        .unwrap_or(NaiveDate::MAX);
+    let last_day = if starts_day_after {
+        ends
+    } else {
+        ends.pred_opt().unwrap_or(ends)
+    };
+
+    let (status, explanation) = match fault_seen {
+        Some(seen) if seen <= last_day => (
+            RemedyStatus::Available,
+            format!(
+                "The fault appeared on {}, within 6 months of {}, so it is presumed to have been there from the start. The trader has to prove otherwise.",
+                fmt(seen),
+                fmt(delivered)
+            ),
+        ),
+        Some(seen) => (
+            RemedyStatus::Expired,
+            format!(
+                "The fault appeared on {}, more than 6 months after {}, so you have to show it was there from the start (expert evidence may help)",
+                fmt(seen),
+                fmt(delivered)
+            ),
+        ),
+        None if today <= last_day => (
+            RemedyStatus::Available,
+            format!(
+                "Any fault that appears by {} is presumed to have been there from the start",
+                fmt(last_day)
+            ),
+        ),
+        None => (
+            RemedyStatus::Expired,
+            "More than 6 months have passed, so you have to show the fault was there from the start".to_string(),
+        ),
+    };
+    with_deadline(remedy("reverse_burden", title, section, status, explanation), last_day, today)
+}
+
+/// Damages sit alongside the statutory remedies and last as long as limitation allows
+fn damages(case: &CaseMetadata, section: &str, today: NaiveDate) -> Remedy {
+    let explanation = "Compensation for losses caused by the breach, such as extra costs or damage to other property. Can be claimed as well as the remedies above, but not twice for the same loss.".to_string();
+    let base = remedy("damages", "Damages", section, RemedyStatus::Available, explanation);
+    match crate::limitation::check(case, today).primary {
+        Some(period) => {
+            let expires = NaiveDate::parse_from_str(&period.expiry_date, "%Y-%m-%d").unwrap_or(today);
+            let mut remedy = with_deadline(base, expires, today);
+            if expires < today {
+                remedy.status = RemedyStatus::Expired;
+            }
+            remedy.conditions = vec![format!("Court claim issued by {} ({})", fmt(expires), period.section)];
+            remedy
+        }
+        None => base,
+    }
+}
+
+fn goods(
+    case: &CaseMetadata,
+    delivered: Option<NaiveDate>,
+    fault_seen: Option<NaiveDate>,
+    complained: Option<NaiveDate>,
+    today: NaiveDate,
+    assumptions: &mut Vec<String>,
+) -> Vec<Remedy> {
+    let mut remedies = Vec::new();
+
+    // Short-term right to reject — a full refund with no deduction for use
+    let title = "Short-term right to reject";
+    remedies.push(match delivered {
+        Some(delivered) => {
+            let deadline = delivered + Days::new(SHORT_TERM_REJECT_DAYS);
+            let (status, explanation) = match complained {
+                Some(c) if c <= deadline => (
+                    RemedyStatus::Available,
+                    format!(
+                        "You complained on {}, within the 30 days. If you made clear you were rejecting the goods, the right was exercised in time and you are owed a full refund within 14 days (s.20).",
+                        fmt(c)
+                    ),
+                ),
+                _ if today <= deadline => (
+                    RemedyStatus::Available,
+                    format!(
+                        "Reject the goods by {} for a full refund, with no deduction for use",
+                        fmt(deadline)
+                    ),
+                ),
+                _ => (
+                    RemedyStatus::Expired,
+                    format!("The 30 days from {} have passed", fmt(delivered)),
+                ),
+            };
+            let remedy = with_deadline(
+                remedy("short_term_reject", title, "ss.20 and 22", status, explanation),
+                deadline,
+                today,
+            );
+            with_conditions(remedy, &["Make clear to the trader that you are rejecting the goods"])
+        }
+        None => remedy(
+            "short_term_reject",
+            title,
+            "ss.20 and 22",
+            RemedyStatus::Unknown,
+            "Add the date of purchase to work out the 30-day deadline".to_string(),
+        ),
+    });
+    assumptions.push("The goods were delivered on the purchase date; the 30 days run from delivery or installation, whichever is later (s.22(6))".to_string());
+    assumptions.push("The goods are not perishable — perishable goods have a shorter rejection period (s.22(4))".to_string());
+
+    remedies.push(reverse_burden("s.19(14)–(15)", delivered, true, fault_seen, today));
+
+    remedies.push(with_conditions(
+        remedy(
+            "repair_or_replacement",
+            "Repair or replacement",
+            "s.23",
+            RemedyStatus::Available,
+            "Ask the trader to repair or replace the goods. They must do so within a reasonable time, without significant inconvenience and at their own cost.".to_string(),
+        ),
+        &["The remedy you choose is not impossible or disproportionate compared with the other (s.23(3))"],
+    ));
+
+    let mut final_tier = with_conditions(
+        remedy(
+            "price_reduction_or_final_reject",
+            "Price reduction or final right to reject",
+            "s.24",
+            RemedyStatus::Conditional,
+            "Keep the goods with an appropriate reduction in price, or reject them for a refund".to_string(),
+        ),
+        &[
+            "One repair or replacement has been tried and the goods still do not conform (s.24(5)(a))",
+            "Or repair and replacement are both impossible (s.24(5)(b))",
+            "Or the trader has not repaired or replaced within a reasonable time, or without significant inconvenience (s.24(5)(c))",
+        ],
+    );
+    if let Some(delivered) = delivered {
+        let no_deduction_until = delivered
+            .checked_add_months(Months::new(REVERSE_BURDEN_MONTHS))
+            .unwrap_or(delivered);
+        final_tier = with_deadline(final_tier, no_deduction_until, today);
+        final_tier.explanation.push_str(&format!(
+            ". A final rejection by {} is refunded in full with no deduction for use, except for motor vehicles (s.24(10)).",
+            fmt(no_deduction_until)
+        ));
+    }
+    remedies.push(final_tier);
+
+    remedies.push(damages(case, "s.19(9)–(11)", today));
+    remedies
+}
+
+fn digital(
+    case: &CaseMetadata,
+    supplied: Option<NaiveDate>,
+    fault_seen: Option<NaiveDate>,
+    today: NaiveDate,
+    assumptions: &mut Vec<String>,
+) -> Vec<Remedy> {
+    assumptions.push("There is no right to reject digital content, unless it came on a physical medium that can be rejected as goods".to_string());
+    assumptions.push("The digital content was supplied on the purchase date".to_string());
+
+    vec![
+        reverse_burden("s.42(9)–(10)", supplied, false, fault_seen, today),
+        with_conditions(
+            remedy(
+                "repair_or_replacement",
+                "Repair or replacement",
+                "s.43",
+                RemedyStatus::Available,
+                "Ask the trader to repair or replace the digital content within a reasonable time and without significant inconvenience".to_string(),
+            ),
+            &["The remedy you choose is not impossible or disproportionate compared with the other (s.43(3))"],
+        ),
+        with_conditions(
+            remedy(
+                "price_reduction",
+                "Price reduction",
+                "s.44",
+                RemedyStatus::Conditional,
+                "An appropriate reduction in price, up to a full refund, paid within 14 days of the trader agreeing it is due".to_string(),
+            ),
+            &[
+                "Repair and replacement are impossible (s.44(3)(a))",
+                "Or the trader has not repaired or replaced within a reasonable time, or without significant inconvenience (s.44(3)(b))",
+            ],
+        ),
+        with_conditions(
+            remedy(
+                "device_damage",
+                "Repair of, or compensation for, a damaged device",
+                "s.46",
+                RemedyStatus::Conditional,
+                "If the trader's digital content damaged your device or other digital content, they must repair it or pay compensation".to_string(),
+            ),
+            &["The damage would not have happened had the trader used reasonable care and skill (s.46(1)(b))"],
+        ),
+        damages(case, "s.42(6)–(7)", today),
+    ]
+}
+
+fn services(case: &CaseMetadata, today: NaiveDate, assumptions: &mut Vec<String>) -> Vec<Remedy> {
+    assumptions.push("The 30-day right to reject and the 6-month presumption do not apply to services".to_string());
+    assumptions.push("The service was performed on the date of purchase".to_string());
+
+    vec![
+        with_conditions(
+            remedy(
+                "repeat_performance",
+                "Repeat performance",
+                "s.55",
+                RemedyStatus::Available,
+                "Ask the trader to put the work right. They must do so within a reasonable time, without significant inconvenience and at their own cost.".to_string(),
+            ),
+            &["Repeat performance is not impossible (s.55(3))"],
+        ),
+        with_conditions(
+            remedy(
+                "price_reduction",
+                "Price reduction",
+                "s.56",
+                RemedyStatus::Conditional,
+                "An appropriate reduction in price, up to a full refund, paid within 14 days of the trader agreeing it is due".to_string(),
+            ),
+            &[
+                "Repeat performance is impossible (s.56(3)(a))",
+                "Or the trader has not repeated the work within a reasonable time, or without significant inconvenience (s.56(3)(b))",
+            ],
+        ),
+        damages(case, "s.54(7)", today),
+    ]
+}
+
+/// Work out the remedies available to the consumer as at `today`
+pub fn assess(case: &CaseMetadata, today: NaiveDate) -> RemediesReport {
+    let purchased = parse_date(case.date_of_purchase.as_deref());
+    let discovered = parse_date(case.date_problem_discovered.as_deref());
+    let complained = parse_date(case.date_first_complained.as_deref());
+    // A complaint is the latest the fault can have appeared
+    let fault_seen = discovered.or(complained);
+
+    let mut assumptions = vec![
+        "The defendant sold to you as a trader and you bought as a consumer (s.2)".to_string(),
+    ];
+    let remedies = match case.claim_type {
+        ClaimType::CraGoods => goods(case, purchased, fault_seen, complained, today, &mut assumptions),
+        ClaimType::CraDigital => digital(case, purchased, fault_seen, today, &mut assumptions),
+        ClaimType::CraServices => services(case, today, &mut assumptions),
+    };
+
+    RemediesReport {
+        claim_type: case.claim_type.clone(),
+        remedies,
+        assumptions,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    fn case(claim_type: ClaimType, purchase: &str, discovered: Option<&str>) -> CaseMetadata {
+        CaseMetadata {
+            claim_type,
+            date_of_purchase: Some(purchase.to_string()),
+            date_problem_discovered: discovered.map(|d| d.to_string()),
+            ..CaseMetadata::default()
+        }
+    }
+
+    fn find<'a>(report: &'a RemediesReport, id: &str) -> &'a Remedy {
+        report.remedies.iter().find(|r| r.id == id).unwrap()
+    }
+
+    #[test]
+    fn short_term_right_to_reject_lasts_30_days() {
+        let c = case(ClaimType::CraGoods, "2025-03-01", Some("2025-03-10"));
+        let open = assess(&c, date("2025-03-20"));
+        let reject = find(&open, "short_term_reject");
+        assert_eq!(reject.status, RemedyStatus::Available);
+        assert_eq!(reject.deadline.as_deref(), Some("2025-03-31"));
+        assert_eq!(reject.days_remaining, Some(11));
+
+        let closed = assess(&c, date("2025-04-01"));
+        assert_eq!(find(&closed, "short_term_reject").status, RemedyStatus::Expired);
+    }
+
+    #[test]
+    fn complaint_within_30_days_keeps_rejection_in_time() {
+        let mut c = case(ClaimType::CraGoods, "2025-03-01", Some("2025-03-05"));
+        c.date_first_complained = Some("2025-03-06".to_string());
+        let report = assess(&c, date("2025-06-01"));
+        assert_eq!(find(&report, "short_term_reject").status, RemedyStatus::Available);
+    }
+
+    #[test]
+    fn reverse_burden_depends_on_when_the_fault_appeared() {
+        let early = assess(&case(ClaimType::CraGoods, "2025-01-15", Some("2025-07-15")), date("2025-09-01"));
+        let burden = find(&early, "reverse_burden");
+        assert_eq!(burden.status, RemedyStatus::Available);
+        assert_eq!(burden.deadline.as_deref(), Some("2025-07-15"));
+
+        let late = assess(&case(ClaimType::CraGoods, "2025-01-15", Some("2025-07-16")), date("2025-09-01"));
+        assert_eq!(find(&late, "reverse_burden").status, RemedyStatus::Expired);
+    }
+
+    #[test]
+    fn final_tier_is_conditional_on_a_failed_repair() {
+        let report = assess(&case(ClaimType::CraGoods, "2025-01-15", None), date("2025-02-01"));
+        let final_tier = find(&report, "price_reduction_or_final_reject");
+        assert_eq!(final_tier.status, RemedyStatus::Conditional);
+        assert!(final_tier.conditions.iter().any(|c| c.contains("s.24(5)(a)")));
+    }
+
+    #[test]
+    fn services_get_repeat_performance_not_rejection() {
+        let report = assess(&case(ClaimType::CraServices, "2025-01-15", None), date("2025-02-01"));
+        let ids: Vec<&str> = report.remedies.iter().map(|r| r.id.as_str()).collect();
+        assert_eq!(ids, vec!["repeat_performance", "price_reduction", "damages"]);
+        assert_eq!(find(&report, "repeat_performance").section, "Consumer Rights Act 2015, s.55");
+    }
+
+    #[test]
+    fn digital_content_has_no_right_to_reject() {
+        let report = assess(&case(ClaimType::CraDigital, "2025-01-15", Some("2025-02-01")), date("2025-03-01"));
+        assert!(report.remedies.iter().all(|r| r.id != "short_term_reject"));
+        let burden = find(&report, "reverse_burden");
+        assert_eq!(burden.status, RemedyStatus::Available);
+        // Digital content: the 6 months begin on the day of supply
+        assert_eq!(burden.deadline.as_deref(), Some("2025-07-14"));
+        assert_eq!(find(&report, "device_damage").section, "Consumer Rights Act 2015, s.46");
+    }
+
+    #[test]
+    fn damages_follow_limitation() {
+        let report = assess(&case(ClaimType::CraGoods, "2018-01-15", None), date("2025-03-01"));
+        let damages = find(&report, "damages");
+        assert_eq!(damages.status, RemedyStatus::Expired);
+        assert_eq!(damages.deadline.as_deref(), Some("2024-01-15"));
+    }
+
+    #[test]
+    fn missing_dates_are_unknown() {
+        let c = CaseMetadata::default();
+        let report = assess(&c, date("2025-03-01"));
+        assert_eq!(find(&report, "short_term_reject").status, RemedyStatus::Unknown);
+        assert_eq!(find(&report, "reverse_burden").status, RemedyStatus::Unknown);
+    }
+}
@@ -0,0 +1,69 @@
+//! Application-wide settings, stored outside any case folder.
+//!
+//! Lives at `<config dir>/CaseKit/settings.json` (e.g. `%APPDATA%\CaseKit` on
+//! Windows, `~/.config/CaseKit` on Linux, `~/Library/Application Support/CaseKit`
+//! on macOS). A missing file means every setting is at its default.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AppSettings {
+    /// Tesseract executable, or the folder containing it, chosen by the user.
+    /// Checked before any of the standard install locations.
+    #[serde(default)]
+    pub tesseract_path: Option<String>,
+    /// Chronology silences longer than this many days are flagged. Defaults to 90.
+    #[serde(default)]
+    pub chronology_gap_days: Option<u32>,
+    /// Downloaded Companies House "Basic Company Data" file (.csv or .zip)
+    #[serde(default)]
+    pub companies_house_snapshot: Option<String>,
+    /// A Companies House API key saved in plain text by an earlier version.
+    /// It is moved into the encrypted key store when settings are loaded and
+    /// never written back.
+    #[serde(default, skip_serializing)]
+    pub companies_house_api_key: Option<String>,
+}
+
+fn settings_path() -> Result<PathBuf, String> {
+    let config_dir = dirs::config_dir()
+        .ok_or_else(|| "Could not determine configuration directory".to_string())?;
+    Ok(config_dir.join("CaseKit").join("settings.json"))
+}
+
+/// Load settings, falling back to defaults if the file is missing
+pub fn load_settings() -> Result<AppSettings, String> {
+    let path = settings_path()?;
+    if !path.exists() {
+        return Ok(AppSettings::default());
+    }
+
+    let content = fs::read_to_string(&path)
+        .map_err(|e| format!("Could not read settings.json: {}", e))?;
+    let mut settings: AppSettings = serde_json::from_str(&content)
+        .map_err(|e| format!("Could not parse settings.json: {}", e))?;
+
+    if let Some(key) = settings.companies_house_api_key.take() {
+        if !key.trim().is_empty() {
+            crate::ai::keys::set_named(crate::ai::keys::COMPANIES_HOUSE, &key)?;
+        }
+        save_settings(&settings)?;
+    }
+    Ok(settings)
+}
+
+pub fn save_settings(settings: &AppSettings) -> Result<(), String> {
+    let path = settings_path()?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Could not create settings folder: {}", e))?;
+    }
+
+    let json = serde_json::to_string_pretty(settings)
+        .map_err(|e| format!("Could not serialise settings: {}", e))?;
+    fs::write(&path, &json)
+        .map_err(|e| format!("Could not write settings.json: {}", e))?;
+    Ok(())
+}
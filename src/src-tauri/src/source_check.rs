@@ -0,0 +1,644 @@
+//! Checks an AI response against the case's own evidence.
+//!
+//! Drafting is meant to be source-locked: every statement of fact should come
+//! from the documents. The response is split into sentences, and each one that
+//! states something is looked for in the documents' extracted text by the
+//! overlap of its meaningful words with a passage. Quotations must appear word
+//! for word, and dates mentioned should be in the chronology.
+//!
+//! Statements of law (sections, Acts, CPR) are not expected to be in the
+//! evidence and are left alone, as are points the model has already marked
+//! "[USER TO CONFIRM]". This is a lexical check: it finds passages that use
+//! the same words, not ones that mean the same thing, so a flag means "look at
+//! this" rather than "this is wrong".
+
+use crate::date_scanner::scan_for_dates;
+use crate::models::chronology::ChronologyEntry;
+use crate::models::document::DocumentEntry;
+use chrono::NaiveDate;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::sync::OnceLock;
+
+/// Sentences with fewer meaningful words than this aren't treated as claims
+const MIN_TERMS: usize = 3;
+/// Passage size, in words, and how far each one moves on from the last
+const WINDOW: usize = 50;
+const STRIDE: usize = 25;
+/// Share of a claim's words that must appear in one passage
+const SUPPORTED_SCORE: f64 = 0.6;
+const PARTIAL_SCORE: f64 = 0.35;
+const EXCERPT_CHARS: usize = 300;
+
+/// Words that say nothing about which passage a claim came from
+const STOP_WORDS: &[&str] = &[
+    "the", "and", "for", "that", "this", "with", "was", "were", "are", "been", "has", "have",
+    "had", "not", "but", "from", "they", "their", "them", "its", "his", "her", "hers", "which",
+    "who", "whom", "what", "when", "where", "would", "could", "should", "will", "shall", "may",
+    "might", "must", "there", "these", "those", "into", "onto", "upon", "also", "any", "all",
+    "such", "than", "then", "very", "more", "most", "only", "other", "some", "our", "your",
+    "you", "she", "him", "did", "does", "being", "about", "after", "before", "because", "while",
+    "whether", "within", "without", "under", "over", "can", "how", "why", "out", "one", "two",
+    "claimant", "defendant", "party", "parties", "document", "documents", "evidence", "states",
+    "stated", "suggests", "shows", "indicates", "appears", "records", "confirms",
+];
+
+/// Words whose trailing full stop doesn't end a sentence
+const ABBREVIATIONS: &[&str] = &[
+    "s", "ss", "eg", "ie", "para", "paras", "no", "nos", "mr", "mrs", "ms", "dr", "ltd", "plc",
+    "co", "reg", "regs", "art", "v", "vs", "cf", "etc", "approx", "st", "pp", "sch", "cl",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ClaimStatus {
+    /// Most of its words appear together in one passage
+    #[serde(rename = "supported")]
+    Supported,
+    /// Some overlap, or the figures don't match the closest passage
+    #[serde(rename = "partial")]
+    Partial,
+    #[serde(rename = "no_source")]
+    NoSource,
+    /// A statement of law, not expected to be in the evidence
+    #[serde(rename = "legal")]
+    Legal,
+    /// Already marked [USER TO CONFIRM] by the model
+    #[serde(rename = "to_confirm")]
+    ToConfirm,
+}
+
+/// The passage that best supports a claim
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SourcePassage {
+    pub document_id: String,
+    pub filename: String,
+    pub path: String,
+    pub excerpt: String,
+    /// Share of the claim's words found in the passage, 0 to 1
+    pub score: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClaimCheck {
+    pub text: String,
+    pub status: ClaimStatus,
+    pub source: Option<SourcePassage>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuoteCheck {
+    pub quote: String,
+    /// The first document containing it word for word
+    pub found_in: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DateCheck {
+    /// The date as written in the response
+    pub text: String,
+    /// yyyy-mm-dd, or yyyy-mm for a month
+    pub date: String,
+    pub in_chronology: bool,
+    pub in_documents: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SourceIssueKind {
+    #[serde(rename = "no_source")]
+    NoSource,
+    #[serde(rename = "quote_not_found")]
+    QuoteNotFound,
+    #[serde(rename = "date_not_in_chronology")]
+    DateNotInChronology,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SourceIssue {
+    pub kind: SourceIssueKind,
+    pub message: String,
+    /// The sentence, quotation or date concerned
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SourceReport {
+    pub checked_at: String,
+    /// Documents with extracted text to check against
+    pub documents_checked: usize,
+    pub claims: Vec<ClaimCheck>,
+    pub quotes: Vec<QuoteCheck>,
+    pub dates: Vec<DateCheck>,
+    /// Everything that needs the user's attention, in the order it appears
+    pub issues: Vec<SourceIssue>,
+    /// Anything that limited the check itself, e.g. redaction placeholders
+    /// that could not be put back
+    #[serde(default)]
+    pub warnings: Vec<String>,
+}
+
+struct Word {
+    text: String,
+    start: usize,
+    end: usize,
+}
+
+/// Lower-cased words with their byte positions. Apostrophes inside a word
+/// are dropped, so "didn't" and "didn’t" both become "didnt".
+fn words(text: &str) -> Vec<Word> {
+    let chars: Vec<(usize, char)> = text.char_indices().collect();
+    let mut out = Vec::new();
+    let mut current = String::new();
+    let (mut start, mut end) = (0, 0);
+    for (i, &(pos, c)) in chars.iter().enumerate() {
+        if c.is_alphanumeric() {
+            if current.is_empty() {
+                start = pos;
+            }
+            current.extend(c.to_lowercase());
+            end = pos + c.len_utf8();
+        } else if (c == '\'' || c == '’')
+            && !current.is_empty()
+            && chars.get(i + 1).is_some_and(|(_, n)| n.is_alphanumeric())
+        {
+            continue;
+        } else if !current.is_empty() {
+            out.push(Word { text: std::mem::take(&mut current), start, end });
+        }
+    }
+    if !current.is_empty() {
+        out.push(Word { text: current, start, end });
+    }
+    out
+}
+
+fn is_term(word: &str) -> bool {
+    word.chars().any(|c| c.is_ascii_digit()) || (word.chars().count() >= 3 && !STOP_WORDS.contains(&word))
+}
+
+fn is_number(word: &str) -> bool {
+    word.chars().all(|c| c.is_ascii_digit())
+}
+
+fn bracketed() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"\[[^\]]*\]").unwrap())
+}
+
+fn list_marker() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"^(?:[#>*\-•]+\s*|\(?[0-9a-zA-Z]{1,3}[.)]\s+)+").unwrap())
+}
+
+fn legal_reference() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(
+            r"\b(?:[A-Z][A-Za-z]+ )+Act(?: \d{4})?\b|\bCPR\b|Civil Procedure Rules|Practice Direction|(?i:\bsection \d+|\bss?\.\s?\d+|\bregulation \d+|\bschedule \d+)",
+        )
+        .unwrap()
+    })
+}
+
+/// Text in double or single quotes, straight or curly. A single quote only
+/// opens after a space or punctuation and only closes before one, and one
+/// between two letters is an apostrophe, so "didn't" and "the buyer's" never
+/// start or end a quotation.
+fn quotation() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(r#""([^"\n]+)"|“([^”\n]+)”|\B'((?:[^'\n]|\b'\b)+?)'\B|\B‘((?:[^’\n]|\b’\b)+?)’\B"#).unwrap()
+    })
+}
+
+/// The text to check: the string values of a JSON response, or the whole
+/// response
+fn segments(response: &str) -> Vec<String> {
+    fn collect(value: &serde_json::Value, out: &mut Vec<String>) {
+        match value {
+            serde_json::Value::String(s) => out.push(s.clone()),
+            serde_json::Value::Array(items) => items.iter().for_each(|v| collect(v, out)),
+            serde_json::Value::Object(map) => map.values().for_each(|v| collect(v, out)),
+            _ => {}
+        }
+    }
+    let trimmed = response.trim().trim_start_matches("```json").trim_matches('`').trim();
+    match serde_json::from_str::<serde_json::Value>(trimmed) {
+        Ok(value) if value.is_object() || value.is_array() => {
+            let mut out = Vec::new();
+            collect(&value, &mut out);
+            out
+        }
+        _ => vec![response.to_string()],
+    }
+}
+
+/// Split text into sentences, one list item or line at a time
+pub fn sentences(text: &str) -> Vec<String> {
+    let mut out = Vec::new();
+    for line in text.lines() {
+        let line = line.replace("**", "");
+        let line = list_marker().replace(line.trim(), "");
+        let chars: Vec<char> = line.chars().collect();
+        let mut current = String::new();
+        for (i, &c) in chars.iter().enumerate() {
+            current.push(c);
+            if !matches!(c, '.' | '!' | '?') || !chars.get(i + 1).is_none_or(|n| n.is_whitespace()) {
+                continue;
+            }
+            let next = chars[i + 1..].iter().find(|n| !n.is_whitespace());
+            if next.is_some_and(|n| n.is_lowercase()) {
+                continue;
+            }
+            let last_word = current[..current.len() - 1]
+                .rsplit(|ch: char| !ch.is_alphanumeric() && ch != '.')
+                .next()
+                .unwrap_or("")
+                .replace('.', "")
+                .to_lowercase();
+            if c == '.' && (last_word.chars().count() == 1 || ABBREVIATIONS.contains(&last_word.as_str())) {
+                continue;
+            }
+            push_sentence(&mut out, &mut current);
+        }
+        push_sentence(&mut out, &mut current);
+    }
+    out
+}
+
+fn push_sentence(out: &mut Vec<String>, current: &mut String) {
+    let sentence = std::mem::take(current).trim().to_string();
+    if !sentence.is_empty() {
+        out.push(sentence);
+    }
+}
+
+/// One document, split into overlapping passages
+struct Source<'a> {
+    doc: &'a DocumentEntry,
+    text: &'a str,
+    words: Vec<Word>,
+    /// Every word, joined by single spaces, for word-for-word matching
+    joined: String,
+    /// First word index and term set of each passage
+    passages: Vec<(usize, HashSet<String>)>,
+}
+
+impl<'a> Source<'a> {
+    fn new(doc: &'a DocumentEntry, text: &'a str) -> Self {
+        let words = words(text);
+        let joined = format!(" {} ", words.iter().map(|w| w.text.as_str()).collect::<Vec<_>>().join(" "));
+        let mut passages = Vec::new();
+        let mut start = 0;
+        loop {
+            let end = (start + WINDOW).min(words.len());
+            let terms = words[start..end].iter().map(|w| w.text.clone()).collect();
+            passages.push((start, terms));
+            if end == words.len() {
+                break;
+            }
+            start += STRIDE;
+        }
+        Self { doc, text, words, joined, passages }
+    }
+
+    fn excerpt(&self, start: usize) -> String {
+        let end = (start + WINDOW).min(self.words.len());
+        if start >= end {
+            return String::new();
+        }
+        let slice = &self.text[self.words[start].start..self.words[end - 1].end];
+        let clean = slice.split_whitespace().collect::<Vec<_>>().join(" ");
+        if clean.chars().count() > EXCERPT_CHARS {
+            format!("{}…", clean.chars().take(EXCERPT_CHARS).collect::<String>())
+        } else {
+            clean
+        }
+    }
+}
+
+fn find_support(terms: &[String], sources: &[Source]) -> (ClaimStatus, Option<SourcePassage>) {
+    let mut best: Option<(f64, &Source, usize, &HashSet<String>)> = None;
+    for source in sources {
+        for (start, passage) in &source.passages {
+            let found = terms.iter().filter(|t| passage.contains(*t)).count();
+            let score = found as f64 / terms.len() as f64;
+            if best.as_ref().is_none_or(|b| score > b.0) {
+                best = Some((score, source, *start, passage));
+            }
+        }
+    }
+    let Some((score, source, start, passage)) = best else {
+        return (ClaimStatus::NoSource, None);
+    };
+    if score < PARTIAL_SCORE {
+        return (ClaimStatus::NoSource, None);
+    }
+    let figures_match = terms.iter().filter(|t| is_number(t)).all(|t| passage.contains(t));
+    let status = if score >= SUPPORTED_SCORE && figures_match {
+        ClaimStatus::Supported
+    } else {
+        ClaimStatus::Partial
+    };
+    let passage = SourcePassage {
+        document_id: source.doc.id.clone(),
+        filename: source.doc.filename.clone(),
+        path: source.doc.path.clone(),
+        excerpt: source.excerpt(start),
+        score: (score * 100.0).round() / 100.0,
+    };
+    (status, Some(passage))
+}
+
+/// The days a scanned date ("2024-03-05" or "2024-03") could be
+fn span_of(date: &str) -> Option<(NaiveDate, NaiveDate)> {
+    if let Ok(day) = NaiveDate::parse_from_str(date, "%Y-%m-%d") {
+        return Some((day, day));
+    }
+    let first = NaiveDate::parse_from_str(&format!("{}-01", date), "%Y-%m-%d").ok()?;
+    let next = first.checked_add_months(chrono::Months::new(1))?;
+    Some((first, next.pred_opt()?))
+}
+
+fn overlaps(a: (NaiveDate, NaiveDate), b: (NaiveDate, NaiveDate)) -> bool {
+    a.0 <= b.1 && b.0 <= a.1
+}
+
+/// Check a response against the documents' extracted text and the chronology
+pub fn check(response: &str, documents: &[DocumentEntry], chronology: &[ChronologyEntry]) -> SourceReport {
+    let sources: Vec<Source> = documents
+        .iter()
+        .filter_map(|d| {
+            let text = d.extracted_text.as_deref().filter(|t| !t.trim().is_empty())?;
+            Some(Source::new(d, text))
+        })
+        .collect();
+
+    let mut claims = Vec::new();
+    let mut quotes: Vec<QuoteCheck> = Vec::new();
+    let mut dates: Vec<DateCheck> = Vec::new();
+    let mut issues = Vec::new();
+
+    let chronology_spans: Vec<(NaiveDate, NaiveDate)> = chronology
+        .iter()
+        .filter_map(|e| Some((e.date.start?, e.date.end.or(e.date.start)?)))
+        .collect();
+    let mut document_spans: Option<Vec<(NaiveDate, NaiveDate)>> = None;
+
+    for sentence in segments(response).iter().flat_map(|s| sentences(s)) {
+        let legal = legal_reference().is_match(&sentence);
+
+        for cap in quotation().captures_iter(&sentence) {
+            let quote = (1..=4).find_map(|i| cap.get(i)).map(|m| m.as_str().trim()).unwrap_or("");
+            let quote_words = words(quote);
+            // Single words and short phrases in quotes are usually terms, not quotations
+            if quote_words.len() < 3 || quotes.iter().any(|q| q.quote == quote) {
+                continue;
+            }
+            let needle = format!(" {} ", quote_words.iter().map(|w| w.text.as_str()).collect::<Vec<_>>().join(" "));
+            let found_in = sources.iter().find(|s| s.joined.contains(&needle)).map(|s| s.doc.filename.clone());
+            if found_in.is_none() {
+                issues.push(SourceIssue {
+                    kind: SourceIssueKind::QuoteNotFound,
+                    message: "This quotation does not appear word for word in any document".to_string(),
+                    text: quote.to_string(),
+                });
+            }
+            quotes.push(QuoteCheck { quote: quote.to_string(), found_in });
+        }
+
+        if !legal {
+            for scanned in scan_for_dates(&sentence) {
+                let Some(span) = span_of(&scanned.date) else { continue };
+                if dates.iter().any(|d| d.date == scanned.date) {
+                    continue;
+                }
+                let in_chronology = chronology_spans.iter().any(|c| overlaps(*c, span));
+                let in_documents = document_spans
+                    .get_or_insert_with(|| {
+                        sources
+                            .iter()
+                            .flat_map(|s| scan_for_dates(s.text))
+                            .filter_map(|d| span_of(&d.date))
+                            .collect()
+                    })
+                    .iter()
+                    .any(|d| overlaps(*d, span));
+                if !in_chronology {
+                    let whereabouts = if in_documents {
+                        "it appears in the documents but is not in the chronology"
+                    } else {
+                        "it is not in the chronology or any document"
+                    };
+                    issues.push(SourceIssue {
+                        kind: SourceIssueKind::DateNotInChronology,
+                        message: format!("{}: {}", scanned.original_text, whereabouts),
+                        text: scanned.original_text.clone(),
+                    });
+                }
+                dates.push(DateCheck {
+                    text: scanned.original_text,
+                    date: scanned.date,
+                    in_chronology,
+                    in_documents,
+                });
+            }
+        }
+
+        let terms: Vec<String> = {
+            let mut seen = HashSet::new();
+            words(&bracketed().replace_all(&sentence, " "))
+                .into_iter()
+                .map(|w| w.text)
+                .filter(|w| is_term(w) && seen.insert(w.clone()))
+                .collect()
+        };
+        if sentence.to_uppercase().contains("USER TO CONFIRM") {
+            claims.push(ClaimCheck { text: sentence, status: ClaimStatus::ToConfirm, source: None });
+            continue;
+        }
+        if terms.len() < MIN_TERMS {
+            continue;
+        }
+        if legal {
+            claims.push(ClaimCheck { text: sentence, status: ClaimStatus::Legal, source: None });
+            continue;
+        }
+        let (status, source) = find_support(&terms, &sources);
+        if status == ClaimStatus::NoSource {
+            issues.push(SourceIssue {
+                kind: SourceIssueKind::NoSource,
+                message: "No passage in the case documents supports this".to_string(),
+                text: sentence.clone(),
+            });
+        }
+        claims.push(ClaimCheck { text: sentence, status, source });
+    }
+
+    SourceReport {
+        checked_at: chrono::Utc::now().to_rfc3339(),
+        documents_checked: sources.len(),
+        claims,
+        quotes,
+        dates,
+        issues,
+        warnings: Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::chronology::{ChronoDate, ChronologySource, Significance};
+
+    fn doc(filename: &str, text: &str) -> DocumentEntry {
+        DocumentEntry {
+            id: format!("id-{}", filename),
+            filename: filename.to_string(),
+            path: format!("01_Correspondence/{}", filename),
+            extracted_text: Some(text.to_string()),
+            ..DocumentEntry::default()
+        }
+    }
+
+    fn entry(date: &str) -> ChronologyEntry {
+        ChronologyEntry {
+            id: date.to_string(),
+            date: ChronoDate::from_text(date),
+            description: String::new(),
+            source: ChronologySource::Manual,
+            document_id: None,
+            source_document_path: None,
+            significance: Significance::Key,
+            confidence: None,
+            event_type: None,
+            trigger: None,
+            relative: None,
+        }
+    }
+
+    fn docs() -> Vec<DocumentEntry> {
+        vec![
+            doc(
+                "email.txt",
+                "Dear Sir, I bought the Zenbook laptop from your Oxford Street store for £849.99. \
+                 The screen stopped working after two weeks and I want a full refund. \
+                 Your manager told me you don't give refunds on opened items.",
+            ),
+            doc("receipt.txt", "Receipt 12345. Zenbook 14 laptop £849.99 paid by card on 3 March 2024."),
+        ]
+    }
+
+    fn status_of(report: &SourceReport, needle: &str) -> ClaimStatus {
+        report.claims.iter().find(|c| c.text.contains(needle)).unwrap().status
+    }
+
+    #[test]
+    fn splits_sentences_without_breaking_abbreviations() {
+        let s = sentences("## Facts\n- The claim under s. 9 of the Act is strong. Mr. Smith paid £849.99 e.g. by card.\n1. Second point! Third?");
+        assert_eq!(
+            s,
+            vec![
+                "Facts",
+                "The claim under s. 9 of the Act is strong.",
+                "Mr. Smith paid £849.99 e.g. by card.",
+                "Second point!",
+                "Third?",
+            ]
+        );
+    }
+
+    #[test]
+    fn supported_partial_and_unsourced_claims() {
+        let response = "The laptop was bought from the Oxford Street store for £849.99. \
+                        The laptop was bought from the Oxford Street store for £999.00. \
+                        The defendant's courier lost a second parcel in Leeds.";
+        let report = check(response, &docs(), &[]);
+        assert_eq!(report.documents_checked, 2);
+        assert_eq!(status_of(&report, "£849.99"), ClaimStatus::Supported);
+        let supported = report.claims.iter().find(|c| c.text.contains("£849.99")).unwrap();
+        assert_eq!(supported.source.as_ref().unwrap().filename, "email.txt");
+        assert!(supported.source.as_ref().unwrap().excerpt.contains("Oxford Street"));
+        // The words match but the figure doesn't
+        assert_eq!(status_of(&report, "£999.00"), ClaimStatus::Partial);
+        assert_eq!(status_of(&report, "courier"), ClaimStatus::NoSource);
+        assert_eq!(
+            report.issues.iter().filter(|i| i.kind == SourceIssueKind::NoSource).count(),
+            1
+        );
+    }
+
+    #[test]
+    fn law_and_user_to_confirm_are_not_flagged() {
+        let response = "Under section 9 of the Consumer Rights Act 2015 goods must be of satisfactory quality. \
+                        The claimant complained by telephone on several occasions [USER TO CONFIRM: dates].";
+        let report = check(response, &docs(), &[]);
+        assert_eq!(status_of(&report, "satisfactory"), ClaimStatus::Legal);
+        assert_eq!(status_of(&report, "telephone"), ClaimStatus::ToConfirm);
+        assert!(report.issues.is_empty());
+    }
+
+    #[test]
+    fn quotes_must_match_word_for_word() {
+        let response = "The manager said \"you don’t give refunds on opened items\". \
+                        The seller wrote “we will always refund faulty goods”. \
+                        The item was described as \"new\".";
+        let report = check(response, &docs(), &[]);
+        assert_eq!(report.quotes.len(), 2);
+        assert_eq!(report.quotes[0].found_in.as_deref(), Some("email.txt"));
+        assert!(report.quotes[1].found_in.is_none());
+        let flagged: Vec<_> = report
+            .issues
+            .iter()
+            .filter(|i| i.kind == SourceIssueKind::QuoteNotFound)
+            .map(|i| i.text.as_str())
+            .collect();
+        assert_eq!(flagged, vec!["we will always refund faulty goods"]);
+    }
+
+    #[test]
+    fn single_quotes_are_quotations_but_apostrophes_are_not() {
+        let response = "The manager's reply was 'you don't give refunds on opened items'. \
+                        The seller wrote ‘we will always refund faulty goods’. \
+                        The buyers' rights weren't explained and the customer's laptop isn't mentioned.";
+        let report = check(response, &docs(), &[]);
+        let quotes: Vec<_> = report.quotes.iter().map(|q| q.quote.as_str()).collect();
+        assert_eq!(quotes, vec!["you don't give refunds on opened items", "we will always refund faulty goods"]);
+        assert_eq!(report.quotes[0].found_in.as_deref(), Some("email.txt"));
+        assert!(report.quotes[1].found_in.is_none());
+    }
+
+    #[test]
+    fn dates_are_checked_against_the_chronology() {
+        let response = "The laptop was paid for by card on 3 March 2024. \
+                        The screen failed on 17 March 2024. \
+                        A refund was requested in April 2024.";
+        let report = check(response, &docs(), &[entry("2024-03-03"), entry("April 2024")]);
+        let missing: Vec<_> = report.dates.iter().filter(|d| !d.in_chronology).collect();
+        assert_eq!(missing.len(), 1);
+        assert_eq!(missing[0].date, "2024-03-17");
+        assert!(!missing[0].in_documents);
+        assert!(report.dates.iter().any(|d| d.date == "2024-03-03" && d.in_documents));
+        assert_eq!(
+            report.issues.iter().filter(|i| i.kind == SourceIssueKind::DateNotInChronology).count(),
+            1
+        );
+    }
+
+    #[test]
+    fn json_responses_are_checked_field_by_field() {
+        let response = r#"{"factualSummary": "The laptop was bought from the Oxford Street store for £849.99.",
+                           "keyUncertainties": ["Whether the courier lost a second parcel in Leeds."]}"#;
+        let report = check(response, &docs(), &[]);
+        assert_eq!(report.claims.len(), 2);
+        assert!(report.claims.iter().all(|c| !c.text.contains('{')));
+        assert_eq!(status_of(&report, "courier"), ClaimStatus::NoSource);
+    }
+
+    #[test]
+    fn no_documents_means_nothing_is_supported() {
+        let report = check("The laptop stopped working after two weeks of use.", &[], &[]);
+        assert_eq!(report.documents_checked, 0);
+        assert_eq!(report.claims[0].status, ClaimStatus::NoSource);
+    }
+}